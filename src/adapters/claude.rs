@@ -1,4 +1,4 @@
-use super::{AdapterError, AgentAdapter, ExtractionSource};
+use super::{command_key, AdapterError, AgentAdapter, ExtractionSource, ToolCall};
 use serde_json::Value;
 use std::io::BufRead;
 use std::path::Path;
@@ -32,6 +32,9 @@ struct RawMetrics {
     cost_estimate_usd: f64,
     session_duration_ms: u64,
     session_output_bytes: u64,
+    session_model: Option<String>,
+    /// Per-model token breakdown from the result event's `modelUsage`.
+    model_tokens: serde_json::Map<String, Value>,
 }
 
 /// Collected text from a session, separated by source type.
@@ -67,9 +70,22 @@ fn parse_claude_jsonl(path: &Path) -> Result<(RawMetrics, CollectedText), Adapte
         };
 
         match v.get("type").and_then(|t| t.as_str()) {
+            Some("system") => {
+                // The init event names the session's primary model
+                if let Some(model) = v.get("model").and_then(|m| m.as_str()) {
+                    m.session_model = Some(model.to_string());
+                }
+            }
             Some("assistant") => {
                 collect_assistant_text(&v, &mut text);
                 count_assistant_turn(&v, &mut m);
+                if m.session_model.is_none() {
+                    m.session_model = v
+                        .get("message")
+                        .and_then(|msg| msg.get("model"))
+                        .and_then(|model| model.as_str())
+                        .map(String::from);
+                }
             }
             Some("result") => extract_result(&v, &mut m),
             _ => {}
@@ -152,27 +168,140 @@ fn extract_result(v: &Value, m: &mut RawMetrics) {
     }
 
     if let Some(model_usage) = v.get("modelUsage").and_then(|u| u.as_object()) {
-        for (_model, stats) in model_usage {
-            m.cost_input_tokens += stats
-                .get("inputTokens")
-                .and_then(|t| t.as_u64())
-                .unwrap_or(0);
-            m.cost_output_tokens += stats
-                .get("outputTokens")
-                .and_then(|t| t.as_u64())
-                .unwrap_or(0);
-            m.cost_cache_read_tokens += stats
-                .get("cacheReadInputTokens")
-                .and_then(|t| t.as_u64())
-                .unwrap_or(0);
-            m.cost_cache_creation_tokens += stats
-                .get("cacheCreationInputTokens")
-                .and_then(|t| t.as_u64())
-                .unwrap_or(0);
+        for (model, stats) in model_usage {
+            let get = |key: &str| stats.get(key).and_then(|t| t.as_u64()).unwrap_or(0);
+            let input = get("inputTokens");
+            let output = get("outputTokens");
+            let cache_read = get("cacheReadInputTokens");
+            let cache_creation = get("cacheCreationInputTokens");
+            m.cost_input_tokens += input;
+            m.cost_output_tokens += output;
+            m.cost_cache_read_tokens += cache_read;
+            m.cost_cache_creation_tokens += cache_creation;
+            let mut entry = serde_json::json!({
+                "input_tokens": input,
+                "output_tokens": output,
+                "cache_read_tokens": cache_read,
+                "cache_creation_tokens": cache_creation,
+            });
+            if let Some(cost) = stats.get("costUSD").filter(|c| c.is_number()) {
+                entry["cost_usd"] = cost.clone();
+            }
+            m.model_tokens.insert(model.clone(), entry);
         }
     }
 }
 
+/// Parse the tool calls of a Claude JSONL file.
+///
+/// `tool_use` blocks in assistant messages are paired with the
+/// `tool_result` blocks that answer them by id. Durations come from the
+/// result's `tool_use_result.durationMs` when the tool reports one, else
+/// from the `timestamp` fields of transcript-style lines.
+fn parse_claude_tool_calls(path: &Path) -> Result<Vec<ToolCall>, AdapterError> {
+    let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+    let mut calls: Vec<ToolCall> = Vec::new();
+    // tool_use id -> (index into calls, call timestamp)
+    let mut pending: std::collections::HashMap<String, (usize, Option<i64>)> =
+        std::collections::HashMap::new();
+    let mut turns: u32 = 0;
+
+    for line in reader.lines() {
+        let line = line?;
+        let v: Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let content = v
+            .get("message")
+            .and_then(|msg| msg.get("content"))
+            .and_then(|c| c.as_array());
+        let ts = line_timestamp_ms(&v);
+
+        match v.get("type").and_then(|t| t.as_str()) {
+            Some("assistant") => {
+                for block in content.into_iter().flatten() {
+                    if block.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+                        continue;
+                    }
+                    let command = block
+                        .get("input")
+                        .and_then(|i| i.get("command"))
+                        .and_then(|c| c.as_str())
+                        .map(command_key);
+                    if let Some(id) = block.get("id").and_then(|i| i.as_str()) {
+                        pending.insert(id.to_string(), (calls.len(), ts));
+                    }
+                    calls.push(ToolCall {
+                        turn: turns,
+                        tool: block
+                            .get("name")
+                            .and_then(|n| n.as_str())
+                            .unwrap_or("unknown")
+                            .to_string(),
+                        command,
+                        ..Default::default()
+                    });
+                }
+                turns += 1;
+            }
+            Some("user") => {
+                for block in content.into_iter().flatten() {
+                    if block.get("type").and_then(|t| t.as_str()) != Some("tool_result") {
+                        continue;
+                    }
+                    let Some((idx, call_ts)) = block
+                        .get("tool_use_id")
+                        .and_then(|i| i.as_str())
+                        .and_then(|id| pending.remove(id))
+                    else {
+                        continue;
+                    };
+                    let call = &mut calls[idx];
+                    let is_error = block
+                        .get("is_error")
+                        .and_then(|e| e.as_bool())
+                        .unwrap_or(false);
+                    call.success = Some(!is_error);
+                    call.output_bytes = Some(tool_result_len(block));
+                    call.duration_ms = v
+                        .get("tool_use_result")
+                        .and_then(|r| r.get("durationMs").or_else(|| r.get("totalDurationMs")))
+                        .and_then(|d| d.as_u64())
+                        .or_else(|| match (call_ts, ts) {
+                            (Some(start), Some(end)) if end >= start => Some((end - start) as u64),
+                            _ => None,
+                        });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(calls)
+}
+
+/// Millisecond timestamp of a transcript line's RFC 3339 `timestamp`.
+fn line_timestamp_ms(v: &Value) -> Option<i64> {
+    let ts = v.get("timestamp")?.as_str()?;
+    chrono::DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|t| t.timestamp_millis())
+}
+
+/// Byte length of a tool_result's content, whether a string or text parts.
+fn tool_result_len(block: &Value) -> u64 {
+    match block.get("content") {
+        Some(Value::String(s)) => s.len() as u64,
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(|p| p.get("text").and_then(|t| t.as_str()))
+            .map(|t| t.len() as u64)
+            .sum(),
+        _ => 0,
+    }
+}
+
 const SUPPORTED_METRICS: &[&str] = &[
     "turns.total",
     "turns.narration_only",
@@ -185,6 +314,8 @@ const SUPPORTED_METRICS: &[&str] = &[
     "cost.estimate_usd",
     "session.output_bytes",
     "session.duration_ms",
+    "session.model",
+    "cost.model_tokens",
 ];
 
 impl AgentAdapter for ClaudeAdapter {
@@ -198,7 +329,7 @@ impl AgentAdapter for ClaudeAdapter {
     ) -> Result<Vec<(String, Value)>, AdapterError> {
         let (m, _) = parse_claude_jsonl(output_path)?;
 
-        let metrics = vec![
+        let mut metrics = vec![
             ("turns.total".into(), Value::from(m.turns_total)),
             (
                 "turns.narration_only".into(),
@@ -235,6 +366,13 @@ impl AgentAdapter for ClaudeAdapter {
             ),
         ];
 
+        if let Some(model) = m.session_model {
+            metrics.push(("session.model".into(), Value::from(model)));
+        }
+        if !m.model_tokens.is_empty() {
+            metrics.push(("cost.model_tokens".into(), Value::Object(m.model_tokens)));
+        }
+
         Ok(metrics)
     }
 
@@ -254,6 +392,10 @@ impl AgentAdapter for ClaudeAdapter {
             ExtractionSource::Raw => text.raw_lines,
         })
     }
+
+    fn extract_tool_calls(&self, output_path: &Path) -> Result<Vec<ToolCall>, AdapterError> {
+        parse_claude_tool_calls(output_path)
+    }
 }
 
 #[cfg(test)]
@@ -415,7 +557,7 @@ mod tests {
         assert!(supported.contains(&"turns.total"));
         assert!(supported.contains(&"cost.estimate_usd"));
         assert!(supported.contains(&"session.output_bytes"));
-        assert_eq!(supported.len(), 11);
+        assert_eq!(supported.len(), 13);
     }
 
     #[test]
@@ -493,5 +635,78 @@ mod tests {
         assert_eq!(get("cost.output_tokens"), 9407 + 947);
         assert!((get("cost.estimate_usd").as_f64().unwrap() - 0.99).abs() < 0.001);
         assert_eq!(get("session.duration_ms"), 229857);
+        assert_eq!(get("session.model"), "claude-opus-4-6");
+        let by_model = get("cost.model_tokens");
+        assert_eq!(by_model["claude-opus-4-6"]["cache_read_tokens"], 939227);
+        assert_eq!(by_model["claude-haiku-4-5-20251001"]["input_tokens"], 47934);
+    }
+
+    #[test]
+    fn extract_model_from_system_init() {
+        let dir = TempDir::new().unwrap();
+        let lines = &[
+            r#"{"type":"system","subtype":"init","model":"claude-sonnet-4-5-20250929"}"#,
+            r#"{"type":"assistant","message":{"model":"claude-haiku-4-5","content":[{"type":"text","text":"hi"}]}}"#,
+        ];
+        let path = write_jsonl(dir.path(), lines);
+        let adapter = ClaudeAdapter::new();
+        let metrics = adapter.extract_builtin_metrics(&path).unwrap();
+        let model = metrics.iter().find(|(k, _)| k == "session.model").unwrap();
+        assert_eq!(model.1, "claude-sonnet-4-5-20250929");
+        // No result event, so no per-model breakdown
+        assert!(!metrics.iter().any(|(k, _)| k == "cost.model_tokens"));
+    }
+
+    #[test]
+    fn no_model_when_not_reported() {
+        let dir = TempDir::new().unwrap();
+        let path = write_jsonl(
+            dir.path(),
+            &[r#"{"type":"assistant","message":{"content":[{"type":"text","text":"hi"}]}}"#],
+        );
+        let adapter = ClaudeAdapter::new();
+        let metrics = adapter.extract_builtin_metrics(&path).unwrap();
+        assert!(!metrics.iter().any(|(k, _)| k == "session.model"));
+    }
+
+    #[test]
+    fn tool_calls_paired_with_results() {
+        let dir = TempDir::new().unwrap();
+        let lines = &[
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Testing"}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Bash","id":"t1","input":{"command":"cd /repo && cargo test --lib"}},{"type":"tool_use","name":"Read","id":"t2","input":{"file_path":"/a"}}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","is_error":true,"content":"error: 2 failed"}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t2","content":[{"type":"text","text":"abc"}]}]},"tool_use_result":{"durationMs":42}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Edit","id":"t3","input":{}}]}}"#,
+        ];
+        let path = write_jsonl(dir.path(), lines);
+        let calls = ClaudeAdapter::new().extract_tool_calls(&path).unwrap();
+
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].turn, 1);
+        assert_eq!(calls[0].tool, "Bash");
+        assert_eq!(calls[0].command.as_deref(), Some("cargo test"));
+        assert_eq!(calls[0].success, Some(false));
+        assert_eq!(calls[0].output_bytes, Some(15));
+        assert_eq!(calls[0].duration_ms, None);
+        assert_eq!(calls[1].command, None);
+        assert_eq!(calls[1].success, Some(true));
+        assert_eq!(calls[1].output_bytes, Some(3));
+        assert_eq!(calls[1].duration_ms, Some(42));
+        // Unanswered call
+        assert_eq!(calls[2].turn, 2);
+        assert_eq!(calls[2].success, None);
+    }
+
+    #[test]
+    fn tool_call_duration_from_timestamps() {
+        let dir = TempDir::new().unwrap();
+        let lines = &[
+            r#"{"type":"assistant","timestamp":"2026-01-01T00:00:00.000Z","message":{"content":[{"type":"tool_use","name":"Bash","id":"t1","input":{"command":"cargo build"}}]}}"#,
+            r#"{"type":"user","timestamp":"2026-01-01T00:00:02.500Z","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"ok"}]}}"#,
+        ];
+        let path = write_jsonl(dir.path(), lines);
+        let calls = ClaudeAdapter::new().extract_tool_calls(&path).unwrap();
+        assert_eq!(calls[0].duration_ms, Some(2500));
     }
 }
@@ -1,4 +1,4 @@
-use super::{AdapterError, AgentAdapter, ExtractionSource};
+use super::{command_key, AdapterError, AgentAdapter, ExtractionSource, ToolCall};
 use serde_json::Value;
 use std::io::BufRead;
 use std::path::Path;
@@ -9,10 +9,16 @@ use std::path::Path;
 /// `item.started`, `item.completed`, etc.
 ///
 /// Supported metrics: turns.total, turns.tool_calls,
+/// cost.input_tokens, cost.output_tokens, cost.cache_read_tokens
+/// (summed from `turn.completed` usage), session.model (when reported),
 /// session.output_bytes, session.exit_code, session.duration_secs.
 ///
+/// Codex reports `input_tokens` inclusive of `cached_input_tokens`; the
+/// cached portion is split out so `cost.input_tokens` means uncached input,
+/// as it does for the Claude adapter.
+///
 /// Not available (gracefully skipped): turns.narration_only,
-/// turns.parallel, cost.*.
+/// turns.parallel, cost.estimate_usd.
 pub struct CodexAdapter;
 
 impl CodexAdapter {
@@ -32,6 +38,10 @@ impl Default for CodexAdapter {
 struct RawMetrics {
     turns_total: u64,
     turns_tool_calls: u64,
+    input_tokens: Option<u64>,
+    cached_input_tokens: u64,
+    output_tokens: Option<u64>,
+    session_model: Option<String>,
     session_output_bytes: u64,
     session_exit_code: Option<i64>,
     session_duration_secs: f64,
@@ -81,9 +91,20 @@ fn parse_codex_jsonl(path: &Path) -> Result<(RawMetrics, CollectedText), Adapter
             last_timestamp = Some(ts);
         }
 
+        // Model is reported on session/thread configuration events
+        if m.session_model.is_none() {
+            m.session_model = v
+                .get("model")
+                .and_then(|model| model.as_str())
+                .map(String::from);
+        }
+
         match v.get("type").and_then(|t| t.as_str()) {
             Some("turn.completed") => {
                 m.turns_total += 1;
+                if let Some(usage) = v.get("usage") {
+                    accumulate_usage(usage, &mut m);
+                }
             }
             Some("item.completed") | Some("item.started") => {
                 if let Some(item) = v.get("item") {
@@ -102,6 +123,20 @@ fn parse_codex_jsonl(path: &Path) -> Result<(RawMetrics, CollectedText), Adapter
     Ok((m, text))
 }
 
+/// Accumulate token usage from a `turn.completed` event's `usage` object.
+fn accumulate_usage(usage: &Value, m: &mut RawMetrics) {
+    let get = |key: &str| usage.get(key).and_then(|t| t.as_u64());
+    if let Some(input) = get("input_tokens") {
+        *m.input_tokens.get_or_insert(0) += input;
+    }
+    if let Some(cached) = get("cached_input_tokens") {
+        m.cached_input_tokens += cached;
+    }
+    if let Some(output) = get("output_tokens") {
+        *m.output_tokens.get_or_insert(0) += output;
+    }
+}
+
 fn collect_item(item: &Value, text: &mut CollectedText, m: &mut RawMetrics) {
     let item_type = item.get("type").and_then(|t| t.as_str()).unwrap_or("");
 
@@ -131,9 +166,101 @@ fn collect_item(item: &Value, text: &mut CollectedText, m: &mut RawMetrics) {
     }
 }
 
+/// Parse the tool calls of a Codex JSONL file.
+///
+/// Each tool-like item is one call, keyed by item id so the
+/// `item.started`/`item.completed` pair is counted once. Durations come
+/// from the two events' timestamps; commands succeed on exit code 0,
+/// other items unless their status is `failed`.
+fn parse_codex_tool_calls(path: &Path) -> Result<Vec<ToolCall>, AdapterError> {
+    let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+    let mut calls: Vec<ToolCall> = Vec::new();
+    // item id -> (index into calls, started timestamp)
+    let mut by_id: std::collections::HashMap<String, (usize, Option<f64>)> =
+        std::collections::HashMap::new();
+    let mut turns: u32 = 0;
+
+    for line in reader.lines() {
+        let line = line?;
+        let v: Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let event = v.get("type").and_then(|t| t.as_str()).unwrap_or("");
+        if event == "turn.completed" {
+            turns += 1;
+            continue;
+        }
+        if event != "item.started" && event != "item.completed" {
+            continue;
+        }
+        let Some(item) = v.get("item") else { continue };
+        let item_type = item.get("type").and_then(|t| t.as_str()).unwrap_or("");
+        if !matches!(
+            item_type,
+            "command_execution" | "file_change" | "mcp_tool_call" | "web_search"
+        ) {
+            continue;
+        }
+        let ts = v.get("timestamp").and_then(|t| t.as_f64());
+        let id = item.get("id").and_then(|i| i.as_str()).map(String::from);
+
+        let idx = match id.as_ref().and_then(|id| by_id.get(id)) {
+            Some(&(idx, _)) => idx,
+            None => {
+                let tool = match item_type {
+                    "mcp_tool_call" => item
+                        .get("tool")
+                        .and_then(|t| t.as_str())
+                        .unwrap_or(item_type),
+                    _ => item_type,
+                };
+                calls.push(ToolCall {
+                    turn: turns,
+                    tool: tool.to_string(),
+                    command: item
+                        .get("command")
+                        .and_then(|c| c.as_str())
+                        .map(|cmd| command_key(cmd.strip_prefix("bash -lc ").unwrap_or(cmd))),
+                    ..Default::default()
+                });
+                if let Some(id) = id.clone() {
+                    by_id.insert(id, (calls.len() - 1, ts));
+                }
+                calls.len() - 1
+            }
+        };
+
+        if event == "item.completed" {
+            let started = id.as_ref().and_then(|id| by_id.get(id)).and_then(|e| e.1);
+            let call = &mut calls[idx];
+            call.success = Some(match item.get("exit_code").and_then(|c| c.as_i64()) {
+                Some(code) => code == 0,
+                None => item.get("status").and_then(|s| s.as_str()) != Some("failed"),
+            });
+            call.output_bytes = item
+                .get("aggregated_output")
+                .and_then(|o| o.as_str())
+                .map(|o| o.len() as u64);
+            call.duration_ms = match (started, ts) {
+                (Some(start), Some(end)) if end >= start => {
+                    Some(((end - start) * 1000.0).round() as u64)
+                }
+                _ => None,
+            };
+        }
+    }
+
+    Ok(calls)
+}
+
 const SUPPORTED_METRICS: &[&str] = &[
     "turns.total",
     "turns.tool_calls",
+    "cost.input_tokens",
+    "cost.output_tokens",
+    "cost.cache_read_tokens",
+    "session.model",
     "session.output_bytes",
     "session.exit_code",
     "session.duration_secs",
@@ -165,6 +292,20 @@ impl AgentAdapter for CodexAdapter {
             ),
         ];
 
+        if let Some(input) = m.input_tokens {
+            let uncached = input.saturating_sub(m.cached_input_tokens);
+            metrics.push(("cost.input_tokens".into(), Value::from(uncached)));
+            metrics.push((
+                "cost.cache_read_tokens".into(),
+                Value::from(m.cached_input_tokens),
+            ));
+        }
+        if let Some(output) = m.output_tokens {
+            metrics.push(("cost.output_tokens".into(), Value::from(output)));
+        }
+        if let Some(model) = m.session_model {
+            metrics.push(("session.model".into(), Value::from(model)));
+        }
         if let Some(code) = m.session_exit_code {
             metrics.push(("session.exit_code".into(), Value::from(code)));
         }
@@ -188,6 +329,10 @@ impl AgentAdapter for CodexAdapter {
             ExtractionSource::Raw => text.raw_lines,
         })
     }
+
+    fn extract_tool_calls(&self, output_path: &Path) -> Result<Vec<ToolCall>, AdapterError> {
+        parse_codex_tool_calls(output_path)
+    }
 }
 
 #[cfg(test)]
@@ -332,9 +477,11 @@ mod tests {
         assert!(supported.contains(&"session.output_bytes"));
         assert!(supported.contains(&"session.exit_code"));
         assert!(supported.contains(&"session.duration_secs"));
-        assert_eq!(supported.len(), 5);
-        // Should NOT contain cost or narration metrics
-        assert!(!supported.contains(&"cost.input_tokens"));
+        assert!(supported.contains(&"cost.input_tokens"));
+        assert!(supported.contains(&"session.model"));
+        assert_eq!(supported.len(), 9);
+        // Should NOT contain reported cost or narration metrics
+        assert!(!supported.contains(&"cost.estimate_usd"));
         assert!(!supported.contains(&"turns.narration_only"));
         assert!(!supported.contains(&"turns.parallel"));
     }
@@ -442,6 +589,8 @@ mod tests {
         assert_eq!(get("turns.tool_calls"), 3); // 2 commands + 1 file_change
         let duration = get("session.duration_secs").as_f64().unwrap();
         assert!((duration - 60.0).abs() < 0.01);
+        assert_eq!(get("cost.input_tokens"), 1300);
+        assert_eq!(get("cost.output_tokens"), 300);
 
         // Check text extraction
         let text = adapter
@@ -459,6 +608,35 @@ mod tests {
         assert_eq!(cmds[1], "cargo test");
     }
 
+    #[test]
+    fn extract_tokens_and_model() {
+        let dir = TempDir::new().unwrap();
+        let lines = &[
+            r#"{"type":"session_configured","model":"gpt-5-codex"}"#,
+            r#"{"type":"turn.completed","usage":{"input_tokens":1000,"cached_input_tokens":600,"output_tokens":50}}"#,
+            r#"{"type":"turn.completed","usage":{"input_tokens":2000,"cached_input_tokens":1500,"output_tokens":70}}"#,
+        ];
+        let path = write_jsonl(dir.path(), lines);
+        let adapter = CodexAdapter::new();
+        let metrics = adapter.extract_builtin_metrics(&path).unwrap();
+        let get = |k: &str| metrics.iter().find(|(key, _)| key == k).unwrap().1.clone();
+        assert_eq!(get("cost.input_tokens"), 900);
+        assert_eq!(get("cost.cache_read_tokens"), 2100);
+        assert_eq!(get("cost.output_tokens"), 120);
+        assert_eq!(get("session.model"), "gpt-5-codex");
+    }
+
+    #[test]
+    fn no_tokens_or_model_when_not_reported() {
+        let dir = TempDir::new().unwrap();
+        let lines = &[r#"{"type":"turn.completed","timestamp":1000.0}"#];
+        let path = write_jsonl(dir.path(), lines);
+        let adapter = CodexAdapter::new();
+        let metrics = adapter.extract_builtin_metrics(&path).unwrap();
+        assert!(!metrics.iter().any(|(k, _)| k.starts_with("cost.")));
+        assert!(!metrics.iter().any(|(k, _)| k == "session.model"));
+    }
+
     #[test]
     fn no_exit_code_when_no_commands() {
         let dir = TempDir::new().unwrap();
@@ -469,4 +647,32 @@ mod tests {
         // exit_code should not be present when no commands executed
         assert!(!metrics.iter().any(|(k, _)| k == "session.exit_code"));
     }
+
+    #[test]
+    fn tool_calls_pair_started_and_completed() {
+        let dir = TempDir::new().unwrap();
+        let lines = &[
+            r#"{"type":"item.started","timestamp":10.0,"item":{"id":"item_0","type":"command_execution","command":"bash -lc cargo test"}}"#,
+            r#"{"type":"item.completed","timestamp":12.5,"item":{"id":"item_0","type":"command_execution","command":"bash -lc cargo test","exit_code":101,"aggregated_output":"failed"}}"#,
+            r#"{"type":"turn.completed","usage":{}}"#,
+            r#"{"type":"item.completed","item":{"id":"item_1","type":"file_change","status":"completed"}}"#,
+            r#"{"type":"item.completed","item":{"id":"item_2","type":"mcp_tool_call","tool":"search","status":"failed"}}"#,
+            r#"{"type":"item.completed","item":{"id":"item_3","type":"agent_message","text":"done"}}"#,
+        ];
+        let path = write_jsonl(dir.path(), lines);
+        let calls = CodexAdapter::new().extract_tool_calls(&path).unwrap();
+
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].turn, 0);
+        assert_eq!(calls[0].tool, "command_execution");
+        assert_eq!(calls[0].command.as_deref(), Some("cargo test"));
+        assert_eq!(calls[0].success, Some(false));
+        assert_eq!(calls[0].duration_ms, Some(2500));
+        assert_eq!(calls[0].output_bytes, Some(6));
+        assert_eq!(calls[1].turn, 1);
+        assert_eq!(calls[1].success, Some(true));
+        assert_eq!(calls[1].duration_ms, None);
+        assert_eq!(calls[2].tool, "search");
+        assert_eq!(calls[2].success, Some(false));
+    }
 }
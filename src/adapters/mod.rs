@@ -50,6 +50,41 @@ impl From<std::io::Error> for AdapterError {
     }
 }
 
+/// A single tool invocation parsed from a session transcript.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ToolCall {
+    /// Zero-based index of the agent turn that issued the call.
+    pub turn: u32,
+    /// Tool name as the agent reports it (e.g. `Bash`, `command_execution`).
+    pub tool: String,
+    /// Normalized shell command (see [`command_key`]) for tools that run one.
+    pub command: Option<String>,
+    /// Wall time between the call and its result, when the format records it.
+    pub duration_ms: Option<u64>,
+    /// Whether the call succeeded; `None` when no result was seen.
+    pub success: Option<bool>,
+    /// Size of the result returned to the agent, when known.
+    pub output_bytes: Option<u64>,
+}
+
+/// The program and subcommand a shell command runs, skipping `cd … &&`
+/// prefixes and `VAR=value` assignments — `cargo test`, `git status`.
+pub fn command_key(command: &str) -> String {
+    let last = command
+        .rsplit(['\n', ';'])
+        .next()
+        .unwrap_or(command)
+        .rsplit("&&")
+        .next()
+        .unwrap_or(command);
+    let words: Vec<&str> = last
+        .split_whitespace()
+        .filter(|w| !w.contains('='))
+        .take(2)
+        .collect();
+    words.join(" ")
+}
+
 /// Normalizes agent-specific output into blacksmith events.
 pub trait AgentAdapter: Send + Sync {
     /// Human-readable adapter name (e.g., "claude", "codex").
@@ -82,6 +117,13 @@ pub trait AgentAdapter: Send + Sync {
         output_path: &Path,
         source: ExtractionSource,
     ) -> Result<Vec<String>, AdapterError>;
+
+    /// Extract every tool invocation from a session output file, in order.
+    ///
+    /// Formats that don't expose individual tool calls return nothing.
+    fn extract_tool_calls(&self, _output_path: &Path) -> Result<Vec<ToolCall>, AdapterError> {
+        Ok(Vec::new())
+    }
 }
 
 /// Known adapter names returned by auto-detection.
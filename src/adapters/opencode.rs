@@ -1,4 +1,4 @@
-use super::{AdapterError, AgentAdapter, ExtractionSource};
+use super::{command_key, AdapterError, AgentAdapter, ExtractionSource, ToolCall};
 use serde_json::Value;
 use std::io::BufRead;
 use std::path::Path;
@@ -11,6 +11,7 @@ use std::path::Path;
 ///
 /// Supported metrics: turns.total, turns.tool_calls,
 /// cost.input_tokens, cost.output_tokens (when available),
+/// session.model, session.provider (from `modelID`/`providerID`),
 /// session.output_bytes, session.exit_code, session.duration_secs.
 ///
 /// OpenCode messages have typed parts:
@@ -39,6 +40,8 @@ struct RawMetrics {
     turns_tool_calls: u64,
     input_tokens: Option<u64>,
     output_tokens: Option<u64>,
+    session_model: Option<String>,
+    session_provider: Option<String>,
     session_output_bytes: u64,
     session_exit_code: Option<i64>,
     session_duration_secs: f64,
@@ -74,21 +77,11 @@ fn parse_opencode_output(path: &Path) -> Result<(RawMetrics, CollectedText), Ada
     // Collect raw lines for ExtractionSource::Raw
     text.raw_lines = lines.iter().filter(|l| !l.is_empty()).cloned().collect();
 
-    // Detect format: if the entire content parses as a single JSON value
-    // that is an array or object with a "messages" key, use single-JSON mode.
-    let full_content: String = lines.join("\n");
-    let messages: Vec<Value> = if let Ok(val) = serde_json::from_str::<Value>(&full_content) {
+    let (wrapper, messages) = split_messages(&lines);
+    if let Some(val) = &wrapper {
         // Extract session-level metadata (tokens, cost) from the wrapper object
-        extract_session_metadata(&val, &mut m);
-        extract_messages_from_value(&val)
-    } else {
-        // JSONL mode: each line is a separate JSON object
-        lines
-            .iter()
-            .filter(|l| !l.is_empty())
-            .filter_map(|l| serde_json::from_str::<Value>(l).ok())
-            .collect()
-    };
+        extract_session_metadata(val, &mut m);
+    }
 
     // Track timestamps for duration calculation
     let mut first_timestamp: Option<f64> = None;
@@ -112,6 +105,26 @@ fn parse_opencode_output(path: &Path) -> Result<(RawMetrics, CollectedText), Ada
     Ok((m, text))
 }
 
+/// Split file lines into messages.
+///
+/// If the entire content parses as a single JSON value (an array, or an
+/// object with a "messages" key), that value is returned as the wrapper
+/// alongside its messages; otherwise each line is a separate message.
+fn split_messages(lines: &[String]) -> (Option<Value>, Vec<Value>) {
+    let full_content: String = lines.join("\n");
+    if let Ok(val) = serde_json::from_str::<Value>(&full_content) {
+        let messages = extract_messages_from_value(&val);
+        return (Some(val), messages);
+    }
+    // JSONL mode: each line is a separate JSON object
+    let messages = lines
+        .iter()
+        .filter(|l| !l.is_empty())
+        .filter_map(|l| serde_json::from_str::<Value>(l).ok())
+        .collect();
+    (None, messages)
+}
+
 /// Extract session-level metadata (tokens, timestamps) from a wrapper object.
 ///
 /// OpenCode session exports may include `prompt_tokens`, `completion_tokens`,
@@ -127,6 +140,7 @@ fn extract_session_metadata(val: &Value, m: &mut RawMetrics) {
 }
 
 fn extract_metadata_from_object(obj: &Value, m: &mut RawMetrics) {
+    record_model(obj, m);
     if let Some(pt) = obj.get("prompt_tokens").and_then(|t| t.as_u64()) {
         *m.input_tokens.get_or_insert(0) += pt;
     }
@@ -198,6 +212,9 @@ fn process_message(
         *m.output_tokens.get_or_insert(0) += ct;
     }
 
+    // First model/provider reported wins; OpenCode tags each assistant message
+    record_model(msg, m);
+
     let role = msg.get("role").and_then(|r| r.as_str()).unwrap_or("");
 
     // Count assistant messages as turns
@@ -213,6 +230,23 @@ fn process_message(
     }
 }
 
+/// Record the session's model and provider from `modelID`/`providerID`
+/// (or plain `model`/`provider`) fields, keeping the first values seen.
+fn record_model(obj: &Value, m: &mut RawMetrics) {
+    let field = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| obj.get(*k).and_then(|v| v.as_str()))
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+    };
+    if m.session_model.is_none() {
+        m.session_model = field(&["modelID", "model"]);
+    }
+    if m.session_provider.is_none() {
+        m.session_provider = field(&["providerID", "provider"]);
+    }
+}
+
 /// Accumulate token usage from a "usage" object.
 fn accumulate_tokens(usage: &Value, m: &mut RawMetrics) {
     if let Some(input) = usage
@@ -313,11 +347,126 @@ fn process_part(
     }
 }
 
+/// Parse the tool calls of an OpenCode output file.
+///
+/// `toolCall` parts in assistant messages are paired with `toolResult`
+/// parts by `callId`. Durations come from the parts' (or their messages')
+/// timestamps, in seconds like the rest of the format.
+fn parse_opencode_tool_calls(path: &Path) -> Result<Vec<ToolCall>, AdapterError> {
+    let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+    let lines: Vec<String> = reader.lines().collect::<Result<_, _>>()?;
+    let (_, messages) = split_messages(&lines);
+
+    let mut calls: Vec<ToolCall> = Vec::new();
+    // call id -> (index into calls, call timestamp)
+    let mut pending: std::collections::HashMap<String, (usize, Option<f64>)> =
+        std::collections::HashMap::new();
+    let mut turns: u32 = 0;
+
+    for msg in &messages {
+        let role = msg.get("role").and_then(|r| r.as_str()).unwrap_or("");
+        let msg_ts = ["created_at", "timestamp"]
+            .iter()
+            .find_map(|k| msg.get(*k).and_then(|t| t.as_f64()));
+
+        for part in msg
+            .get("parts")
+            .and_then(|p| p.as_array())
+            .into_iter()
+            .flatten()
+        {
+            let part_type = part.get("type").and_then(|t| t.as_str()).unwrap_or("");
+            let data = part.get("data").unwrap_or(part);
+            let ts = data.get("timestamp").and_then(|t| t.as_f64()).or(msg_ts);
+
+            match part_type {
+                "toolCall" | "tool_call" if role == "assistant" => {
+                    let name = data
+                        .get("name")
+                        .or_else(|| data.get("command"))
+                        .and_then(|n| n.as_str())
+                        .unwrap_or("unknown");
+                    if let Some(id) = data.get("id").and_then(|i| i.as_str()) {
+                        pending.insert(id.to_string(), (calls.len(), ts));
+                    }
+                    calls.push(ToolCall {
+                        turn: turns,
+                        tool: name.to_string(),
+                        command: tool_command(name, data.get("input")).map(|c| command_key(&c)),
+                        ..Default::default()
+                    });
+                }
+                "toolResult" | "tool_result" => {
+                    let Some((idx, call_ts)) = ["callId", "call_id", "id"]
+                        .iter()
+                        .find_map(|k| data.get(*k).and_then(|i| i.as_str()))
+                        .and_then(|id| pending.remove(id))
+                    else {
+                        continue;
+                    };
+                    let call = &mut calls[idx];
+                    call.success = Some(match data.get("exit_code").and_then(|c| c.as_i64()) {
+                        Some(code) => code == 0,
+                        None => {
+                            !data
+                                .get("is_error")
+                                .or_else(|| data.get("isError"))
+                                .and_then(|e| e.as_bool())
+                                .unwrap_or(false)
+                                && data.get("error").is_none_or(|e| e.is_null())
+                        }
+                    });
+                    call.output_bytes = ["output", "content", "result"]
+                        .iter()
+                        .find_map(|k| data.get(*k).and_then(|o| o.as_str()))
+                        .map(|o| o.len() as u64);
+                    call.duration_ms = match (call_ts, ts) {
+                        (Some(start), Some(end)) if end >= start => {
+                            Some(((end - start) * 1000.0).round() as u64)
+                        }
+                        _ => None,
+                    };
+                }
+                _ => {}
+            }
+        }
+
+        if role == "assistant" {
+            turns += 1;
+        }
+    }
+
+    Ok(calls)
+}
+
+/// The shell command a tool call runs: an input object's `command` field
+/// (the input may be JSON-encoded), or the whole input of a shell tool.
+fn tool_command(name: &str, input: Option<&Value>) -> Option<String> {
+    let input = input?;
+    let parsed;
+    let obj = match input.as_str() {
+        Some(s) => match serde_json::from_str::<Value>(s) {
+            Ok(v) if v.is_object() => {
+                parsed = v;
+                &parsed
+            }
+            _ if matches!(name, "bash" | "shell") => return Some(s.to_string()),
+            _ => return None,
+        },
+        None => input,
+    };
+    obj.get("command")
+        .and_then(|c| c.as_str())
+        .map(String::from)
+}
+
 const SUPPORTED_METRICS: &[&str] = &[
     "turns.total",
     "turns.tool_calls",
     "cost.input_tokens",
     "cost.output_tokens",
+    "session.model",
+    "session.provider",
     "session.output_bytes",
     "session.exit_code",
     "session.duration_secs",
@@ -355,6 +504,12 @@ impl AgentAdapter for OpencodeAdapter {
         if let Some(tokens) = m.output_tokens {
             metrics.push(("cost.output_tokens".into(), Value::from(tokens)));
         }
+        if let Some(model) = m.session_model {
+            metrics.push(("session.model".into(), Value::from(model)));
+        }
+        if let Some(provider) = m.session_provider {
+            metrics.push(("session.provider".into(), Value::from(provider)));
+        }
         if let Some(code) = m.session_exit_code {
             metrics.push(("session.exit_code".into(), Value::from(code)));
         }
@@ -378,6 +533,10 @@ impl AgentAdapter for OpencodeAdapter {
             ExtractionSource::Raw => text.raw_lines,
         })
     }
+
+    fn extract_tool_calls(&self, output_path: &Path) -> Result<Vec<ToolCall>, AdapterError> {
+        parse_opencode_tool_calls(output_path)
+    }
 }
 
 #[cfg(test)]
@@ -472,6 +631,22 @@ mod tests {
         assert_eq!(get("cost.output_tokens"), 50);
     }
 
+    #[test]
+    fn extract_model_and_provider() {
+        let dir = TempDir::new().unwrap();
+        let lines = &[
+            r#"{"role":"user","parts":[{"type":"text","data":{"text":"Fix it"}}],"created_at":999.0}"#,
+            r#"{"role":"assistant","modelID":"claude-sonnet-4-5","providerID":"anthropic","parts":[],"created_at":1000.0}"#,
+            r#"{"role":"assistant","modelID":"gpt-5","providerID":"openai","parts":[],"created_at":1001.0}"#,
+        ];
+        let path = write_jsonl(dir.path(), lines);
+        let adapter = OpencodeAdapter::new();
+        let metrics = adapter.extract_builtin_metrics(&path).unwrap();
+        let get = |k: &str| metrics.iter().find(|(key, _)| key == k).unwrap().1.clone();
+        assert_eq!(get("session.model"), "claude-sonnet-4-5");
+        assert_eq!(get("session.provider"), "anthropic");
+    }
+
     #[test]
     fn no_tokens_when_not_available() {
         let dir = TempDir::new().unwrap();
@@ -567,7 +742,9 @@ mod tests {
         assert!(supported.contains(&"session.output_bytes"));
         assert!(supported.contains(&"session.exit_code"));
         assert!(supported.contains(&"session.duration_secs"));
-        assert_eq!(supported.len(), 7);
+        assert!(supported.contains(&"session.model"));
+        assert!(supported.contains(&"session.provider"));
+        assert_eq!(supported.len(), 9);
         // Should NOT contain narration or parallel metrics
         assert!(!supported.contains(&"turns.narration_only"));
         assert!(!supported.contains(&"turns.parallel"));
@@ -761,4 +938,32 @@ mod tests {
         assert_eq!(cmds.len(), 2);
         assert_eq!(cmds[0], "read_file src/main.rs");
     }
+
+    #[test]
+    fn tool_calls_paired_with_results() {
+        let dir = TempDir::new().unwrap();
+        let lines = &[
+            r#"{"role":"user","parts":[{"type":"text","data":{"text":"go"}}],"created_at":999.0}"#,
+            r#"{"role":"assistant","parts":[{"type":"toolCall","data":{"id":"tc1","name":"bash","input":{"command":"cargo test -q"}}},{"type":"toolCall","data":{"id":"tc2","name":"read_file","input":"src/main.rs"}}],"created_at":1000.0}"#,
+            r#"{"role":"tool","parts":[{"type":"toolResult","data":{"callId":"tc1","exit_code":1,"output":"boom"}}],"created_at":1003.0}"#,
+            r#"{"role":"tool","parts":[{"type":"toolResult","data":{"callId":"tc2","content":"fn main() {}","timestamp":1000.5}}],"created_at":1003.0}"#,
+            r#"{"role":"assistant","parts":[{"type":"tool_call","data":{"id":"tc3","name":"bash","input":"git status"}}],"created_at":1004.0}"#,
+        ];
+        let path = write_jsonl(dir.path(), lines);
+        let calls = OpencodeAdapter::new().extract_tool_calls(&path).unwrap();
+
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].turn, 0);
+        assert_eq!(calls[0].command.as_deref(), Some("cargo test"));
+        assert_eq!(calls[0].success, Some(false));
+        assert_eq!(calls[0].duration_ms, Some(3000));
+        assert_eq!(calls[0].output_bytes, Some(4));
+        assert_eq!(calls[1].tool, "read_file");
+        assert_eq!(calls[1].command, None);
+        assert_eq!(calls[1].success, Some(true));
+        assert_eq!(calls[1].duration_ms, Some(500));
+        assert_eq!(calls[2].turn, 1);
+        assert_eq!(calls[2].command.as_deref(), Some("git status"));
+        assert_eq!(calls[2].success, None);
+    }
 }
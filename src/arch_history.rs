@@ -0,0 +1,481 @@
+//! Architecture metrics history.
+//!
+//! After each successful integration the coordinator computes a
+//! `StructuralReport` and stores a compact snapshot of it: module and file
+//! counts, fan-in hotspots, god files, module cycles and per-module API
+//! surface widths. `blacksmith arch trend` shows how those metrics moved over
+//! the last N integrations and names the bead whose merge introduced a new
+//! cycle or pushed a file over the god-file threshold.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::structural_metrics::StructuralReport;
+
+/// Number of fan-in hotspots kept per snapshot.
+const HOTSPOT_COUNT: usize = 5;
+
+/// A file with high fan-in at the time of a snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hotspot {
+    pub file: String,
+    pub importers: usize,
+    pub score: f64,
+}
+
+/// Architecture metrics recorded for one integration.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Snapshot {
+    pub bead_id: String,
+    pub commit: Option<String>,
+    pub module_count: usize,
+    pub file_count: usize,
+    /// Top fan-in files, highest first.
+    pub hotspots: Vec<Hotspot>,
+    /// Repo-relative paths of god file candidates, sorted.
+    pub god_files: Vec<String>,
+    /// Module cycles, each rendered as `a -> b -> c` with sorted members.
+    pub cycles: Vec<String>,
+    /// Public symbol count per module.
+    pub api_widths: BTreeMap<String, usize>,
+    pub recorded_at: String,
+}
+
+impl Snapshot {
+    /// Summarize a report. Paths are stored relative to `repo_root`.
+    pub fn from_report(
+        report: &StructuralReport,
+        repo_root: &Path,
+        bead_id: &str,
+        commit: Option<&str>,
+    ) -> Self {
+        let rel = |p: &Path| {
+            p.strip_prefix(repo_root)
+                .unwrap_or(p)
+                .to_string_lossy()
+                .to_string()
+        };
+
+        let mut hotspots: Vec<Hotspot> = report
+            .files
+            .values()
+            .filter(|f| f.fan_in_importers > 0)
+            .map(|f| Hotspot {
+                file: rel(&f.path),
+                importers: f.fan_in_importers,
+                score: f.fan_in_score,
+            })
+            .collect();
+        hotspots.sort_by(|a, b| {
+            b.importers
+                .cmp(&a.importers)
+                .then_with(|| a.file.cmp(&b.file))
+        });
+        hotspots.truncate(HOTSPOT_COUNT);
+
+        let mut god_files: Vec<String> = report
+            .files
+            .values()
+            .filter(|f| f.is_god_file)
+            .map(|f| rel(&f.path))
+            .collect();
+        god_files.sort();
+
+        let mut cycles: Vec<String> = report
+            .cycles
+            .iter()
+            .map(|c| {
+                let mut modules = c.modules.clone();
+                modules.sort();
+                modules.join(" -> ")
+            })
+            .collect();
+        cycles.sort();
+
+        Snapshot {
+            bead_id: bead_id.to_string(),
+            commit: commit.map(str::to_string),
+            module_count: report.total_modules,
+            file_count: report.total_files,
+            hotspots,
+            god_files,
+            cycles,
+            api_widths: report
+                .modules
+                .iter()
+                .map(|(name, m)| (name.clone(), m.api_surface_width))
+                .collect(),
+            recorded_at: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        }
+    }
+
+    /// Total public symbols across all modules.
+    pub fn total_api_width(&self) -> usize {
+        self.api_widths.values().sum()
+    }
+
+    /// The module with the widest API surface, if any.
+    pub fn widest_api(&self) -> Option<(&str, usize)> {
+        self.api_widths
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(name, width)| (name.as_str(), *width))
+    }
+}
+
+/// What kind of decay a merge introduced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegressionKind {
+    NewCycle,
+    NewGodFile,
+}
+
+/// A cycle or god file that first appeared in a given integration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Regression {
+    pub bead_id: String,
+    pub commit: Option<String>,
+    pub kind: RegressionKind,
+    /// The cycle (`a -> b`) or file path.
+    pub subject: String,
+}
+
+/// Create the arch_snapshots table if it doesn't exist.
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS arch_snapshots (
+            id            INTEGER PRIMARY KEY AUTOINCREMENT,
+            bead_id       TEXT NOT NULL,
+            commit_hash   TEXT,
+            module_count  INTEGER NOT NULL,
+            file_count    INTEGER NOT NULL,
+            hotspots      TEXT NOT NULL,
+            god_files     TEXT NOT NULL,
+            cycles        TEXT NOT NULL,
+            api_widths    TEXT NOT NULL,
+            recorded_at   TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );",
+    )
+}
+
+/// Record a snapshot.
+pub fn record(conn: &Connection, snapshot: &Snapshot) -> Result<()> {
+    let to_json = |v: serde_json::Result<String>| v.unwrap_or_else(|_| "[]".to_string());
+    conn.execute(
+        "INSERT INTO arch_snapshots
+            (bead_id, commit_hash, module_count, file_count, hotspots, god_files, cycles, api_widths, recorded_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            snapshot.bead_id,
+            snapshot.commit,
+            snapshot.module_count as i64,
+            snapshot.file_count as i64,
+            to_json(serde_json::to_string(&snapshot.hotspots)),
+            to_json(serde_json::to_string(&snapshot.god_files)),
+            to_json(serde_json::to_string(&snapshot.cycles)),
+            to_json(serde_json::to_string(&snapshot.api_widths)),
+            snapshot.recorded_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// The most recent `limit` snapshots, oldest first.
+pub fn recent(conn: &Connection, limit: u32) -> Result<Vec<Snapshot>> {
+    let mut stmt = conn.prepare(
+        "SELECT bead_id, commit_hash, module_count, file_count, hotspots, god_files, cycles,
+                api_widths, recorded_at
+         FROM arch_snapshots
+         ORDER BY id DESC
+         LIMIT ?1",
+    )?;
+    let rows = stmt.query_map(params![limit], |row| {
+        let hotspots: String = row.get(4)?;
+        let god_files: String = row.get(5)?;
+        let cycles: String = row.get(6)?;
+        let api_widths: String = row.get(7)?;
+        Ok(Snapshot {
+            bead_id: row.get(0)?,
+            commit: row.get(1)?,
+            module_count: row.get::<_, i64>(2)? as usize,
+            file_count: row.get::<_, i64>(3)? as usize,
+            hotspots: serde_json::from_str(&hotspots).unwrap_or_default(),
+            god_files: serde_json::from_str(&god_files).unwrap_or_default(),
+            cycles: serde_json::from_str(&cycles).unwrap_or_default(),
+            api_widths: serde_json::from_str(&api_widths).unwrap_or_default(),
+            recorded_at: row.get(8)?,
+        })
+    })?;
+    let mut snapshots = rows.collect::<Result<Vec<_>>>()?;
+    snapshots.reverse();
+    Ok(snapshots)
+}
+
+/// Cycles and god files that appear in a snapshot but not in the one before
+/// it, attributed to the snapshot's bead. `snapshots` must be oldest first;
+/// the first snapshot has no baseline and is never flagged.
+pub fn regressions(snapshots: &[Snapshot]) -> Vec<Regression> {
+    let mut out = Vec::new();
+    for pair in snapshots.windows(2) {
+        let (before, after) = (&pair[0], &pair[1]);
+        let flag = |kind, old: &[String], new: &[String], out: &mut Vec<Regression>| {
+            let old: HashSet<&String> = old.iter().collect();
+            for subject in new.iter().filter(|s| !old.contains(s)) {
+                out.push(Regression {
+                    bead_id: after.bead_id.clone(),
+                    commit: after.commit.clone(),
+                    kind,
+                    subject: subject.clone(),
+                });
+            }
+        };
+        flag(
+            RegressionKind::NewCycle,
+            &before.cycles,
+            &after.cycles,
+            &mut out,
+        );
+        flag(
+            RegressionKind::NewGodFile,
+            &before.god_files,
+            &after.god_files,
+            &mut out,
+        );
+    }
+    out
+}
+
+/// Analyze `repo_root` and record a snapshot for the integration of `bead_id`.
+pub fn record_integration(
+    conn: &Connection,
+    repo_root: &Path,
+    bead_id: &str,
+    commit: Option<&str>,
+) -> Result<Snapshot> {
+    let report = crate::structural_metrics::analyze(repo_root);
+    let snapshot = Snapshot::from_report(&report, repo_root, bead_id, commit);
+    record(conn, &snapshot)?;
+    Ok(snapshot)
+}
+
+fn open_db(db_path: &Path) -> Result<Option<Connection>, String> {
+    if !db_path.exists() {
+        println!("No metrics database found. Run some sessions first.");
+        return Ok(None);
+    }
+    crate::db::open_or_create(db_path)
+        .map(Some)
+        .map_err(|e| format!("Failed to open database: {e}"))
+}
+
+#[derive(Serialize)]
+struct TrendReport<'a> {
+    snapshots: &'a [Snapshot],
+    regressions: &'a [Regression],
+}
+
+/// `blacksmith arch trend [--last N]`
+pub fn handle_trend(db_path: &Path, last: u32, json: bool) -> Result<(), String> {
+    let Some(conn) = open_db(db_path)? else {
+        return Ok(());
+    };
+    let snapshots = recent(&conn, last).map_err(|e| format!("Failed to query snapshots: {e}"))?;
+    let regressions = regressions(&snapshots);
+
+    if json {
+        let report = TrendReport {
+            snapshots: &snapshots,
+            regressions: &regressions,
+        };
+        let out = serde_json::to_string_pretty(&report)
+            .map_err(|e| format!("Failed to serialize trend: {e}"))?;
+        println!("{out}");
+        return Ok(());
+    }
+
+    if snapshots.is_empty() {
+        println!("No architecture snapshots yet. Snapshots are recorded after each integration.");
+        return Ok(());
+    }
+
+    println!(
+        "Architecture trend (last {} integration{})",
+        snapshots.len(),
+        if snapshots.len() == 1 { "" } else { "s" }
+    );
+    println!();
+    println!(
+        "{:<20} {:<9} {:>7} {:>6} {:>6} {:>5} {:>8} {:>8}  TOP HOTSPOT",
+        "BEAD", "COMMIT", "MODULES", "FILES", "CYCLES", "GOD", "API", "FAN-IN"
+    );
+    println!("{}", "-".repeat(100));
+    for s in &snapshots {
+        let top = s.hotspots.first();
+        println!(
+            "{:<20} {:<9} {:>7} {:>6} {:>6} {:>5} {:>8} {:>8}  {}",
+            s.bead_id,
+            s.commit.as_deref().map_or("-", |c| &c[..c.len().min(8)]),
+            s.module_count,
+            s.file_count,
+            s.cycles.len(),
+            s.god_files.len(),
+            s.total_api_width(),
+            top.map_or(0, |h| h.importers),
+            top.map_or("-", |h| h.file.as_str()),
+        );
+    }
+
+    if let (Some(first), Some(last)) = (snapshots.first(), snapshots.last()) {
+        if snapshots.len() > 1 {
+            println!();
+            println!("Change since {}:", first.bead_id);
+            let delta = |name: &str, a: usize, b: usize| {
+                println!(
+                    "  {:<12} {:>5} -> {:<5} ({:+})",
+                    name,
+                    a,
+                    b,
+                    b as i64 - a as i64
+                );
+            };
+            delta("modules", first.module_count, last.module_count);
+            delta("files", first.file_count, last.file_count);
+            delta("cycles", first.cycles.len(), last.cycles.len());
+            delta("god files", first.god_files.len(), last.god_files.len());
+            delta("api width", first.total_api_width(), last.total_api_width());
+            if let Some((module, width)) = last.widest_api() {
+                println!("  widest API: {module} ({width} symbols)");
+            }
+        }
+    }
+
+    println!();
+    if regressions.is_empty() {
+        println!("No new cycles or god files in this window.");
+    } else {
+        println!("Regressions:");
+        for r in &regressions {
+            let what = match r.kind {
+                RegressionKind::NewCycle => "new cycle",
+                RegressionKind::NewGodFile => "new god file",
+            };
+            println!("  {:<20} {:<13} {}", r.bead_id, what, r.subject);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        create_table(&conn).unwrap();
+        conn
+    }
+
+    fn snapshot(bead_id: &str, cycles: &[&str], god_files: &[&str]) -> Snapshot {
+        Snapshot {
+            bead_id: bead_id.to_string(),
+            commit: Some(format!("{bead_id}-sha")),
+            module_count: 3,
+            file_count: 10,
+            hotspots: vec![Hotspot {
+                file: "src/config.rs".to_string(),
+                importers: 4,
+                score: 0.5,
+            }],
+            god_files: god_files.iter().map(|s| s.to_string()).collect(),
+            cycles: cycles.iter().map(|s| s.to_string()).collect(),
+            api_widths: [("crate".to_string(), 2), ("db".to_string(), 7)]
+                .into_iter()
+                .collect(),
+            recorded_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn record_and_read_back_oldest_first() {
+        let conn = setup_db();
+        for id in ["b1", "b2", "b3"] {
+            record(&conn, &snapshot(id, &[], &[])).unwrap();
+        }
+        let snaps = recent(&conn, 2).unwrap();
+        let ids: Vec<&str> = snaps.iter().map(|s| s.bead_id.as_str()).collect();
+        assert_eq!(ids, vec!["b2", "b3"]);
+        assert_eq!(snaps[1], snapshot("b3", &[], &[]));
+    }
+
+    #[test]
+    fn regressions_name_the_introducing_bead() {
+        let snaps = vec![
+            snapshot("b1", &[], &["src/big.rs"]),
+            snapshot("b2", &["a -> b"], &["src/big.rs"]),
+            snapshot("b3", &["a -> b"], &["src/big.rs", "src/huge.rs"]),
+            snapshot("b4", &[], &[]),
+        ];
+        let found = regressions(&snaps);
+        assert_eq!(
+            found,
+            vec![
+                Regression {
+                    bead_id: "b2".to_string(),
+                    commit: Some("b2-sha".to_string()),
+                    kind: RegressionKind::NewCycle,
+                    subject: "a -> b".to_string(),
+                },
+                Regression {
+                    bead_id: "b3".to_string(),
+                    commit: Some("b3-sha".to_string()),
+                    kind: RegressionKind::NewGodFile,
+                    subject: "src/huge.rs".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn first_snapshot_is_not_flagged() {
+        assert!(regressions(&[snapshot("b1", &["a -> b"], &["src/big.rs"])]).is_empty());
+    }
+
+    #[test]
+    fn widest_api_and_total() {
+        let s = snapshot("b1", &[], &[]);
+        assert_eq!(s.total_api_width(), 9);
+        assert_eq!(s.widest_api(), Some(("db", 7)));
+    }
+
+    #[test]
+    fn record_integration_summarizes_repo() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("src");
+        fs::create_dir_all(src.join("a_mod")).unwrap();
+        fs::create_dir_all(src.join("b_mod")).unwrap();
+        fs::write(src.join("main.rs"), "mod a_mod;\nmod b_mod;\nfn main() {}").unwrap();
+        fs::write(
+            src.join("a_mod/mod.rs"),
+            "use crate::b_mod::B;\npub struct A;",
+        )
+        .unwrap();
+        fs::write(
+            src.join("b_mod/mod.rs"),
+            "use crate::a_mod::A;\npub struct B;",
+        )
+        .unwrap();
+
+        let conn = setup_db();
+        let snap = record_integration(&conn, tmp.path(), "b1", Some("abc123")).unwrap();
+        assert_eq!(snap.module_count, 3);
+        assert_eq!(snap.cycles, vec!["a_mod -> b_mod"]);
+        assert_eq!(snap.api_widths["a_mod"], 1);
+        assert!(snap.hotspots.iter().all(|h| h.file.starts_with("src/")));
+        assert_eq!(recent(&conn, 10).unwrap().len(), 1);
+    }
+}
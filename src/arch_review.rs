@@ -0,0 +1,933 @@
+//! Periodic architecture review.
+//!
+//! Runs the structural analysis and signal correlation pipeline, checks each
+//! `RefactorCandidate` against the `[architecture]` thresholds, and turns the
+//! modules that breach them into refactor proposals. With
+//! `refactor_auto_approve` the proposal is filed as a bead straight away (with
+//! an `affected:` set and the rationale); otherwise it waits in the
+//! `refactor_proposals` table for `blacksmith arch approve` or `arch reject`.
+//!
+//! This is step 4 of the architecture analysis pipeline ("Generate proposals").
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::config::ArchitectureConfig;
+use crate::expansion_event::{self, ExpansionEvent};
+use crate::module_detect::{self, Module};
+use crate::signal_correlator::{self, CorrelationReport};
+use crate::structural_metrics::{self, StructuralReport};
+
+/// Upper bound on expansion events loaded for the windowed count.
+const MAX_EVENTS: u32 = 1000;
+
+/// Lifecycle of a refactor proposal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalStatus {
+    /// Waiting for human approval.
+    Pending,
+    /// Filed as a bead (auto-approved or via `blacksmith arch approve`).
+    Filed,
+    /// Rejected via `blacksmith arch reject`; the module is not proposed again
+    /// until another integration changes the architecture.
+    Rejected,
+}
+
+impl ProposalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProposalStatus::Pending => "pending",
+            ProposalStatus::Filed => "filed",
+            ProposalStatus::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(ProposalStatus::Pending),
+            "filed" => Some(ProposalStatus::Filed),
+            "rejected" => Some(ProposalStatus::Rejected),
+            _ => None,
+        }
+    }
+}
+
+/// A module that breached at least one architecture threshold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub module: String,
+    /// Repo-relative globs covering the module's files.
+    pub affected: Vec<String>,
+    /// Human-readable description of each threshold breached.
+    pub breaches: Vec<String>,
+    /// Combined structural + historical score from the correlator.
+    pub score: f64,
+}
+
+impl Finding {
+    /// Bead title for the refactor task.
+    pub fn title(&self) -> String {
+        format!("Refactor {}", self.module)
+    }
+
+    /// Why the refactor is being proposed, one breach per line.
+    pub fn rationale(&self) -> String {
+        let mut out = format!(
+            "Architecture review flagged module `{}` (score {:.1}):\n",
+            self.module, self.score
+        );
+        for breach in &self.breaches {
+            out.push_str(&format!("- {breach}\n"));
+        }
+        out
+    }
+}
+
+/// A stored refactor proposal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proposal {
+    pub id: i64,
+    pub module: String,
+    pub affected: Vec<String>,
+    pub rationale: String,
+    pub score: f64,
+    pub status: ProposalStatus,
+    pub bead_id: Option<String>,
+    pub created_at: String,
+}
+
+impl Proposal {
+    fn title(&self) -> String {
+        format!("Refactor {}", self.module)
+    }
+}
+
+/// What a review run did.
+#[derive(Debug, Default, PartialEq)]
+pub struct ReviewOutcome {
+    /// Refactor candidates reported by the correlator.
+    pub candidates: usize,
+    /// Candidates that breached at least one threshold.
+    pub findings: usize,
+    /// (module, bead id) for proposals filed as beads.
+    pub filed: Vec<(String, String)>,
+    /// Proposal ids queued for human approval.
+    pub queued: Vec<i64>,
+    /// Findings skipped because the module already has a current proposal.
+    pub skipped: usize,
+}
+
+/// Create the refactor_proposals table if it doesn't exist.
+pub fn create_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS refactor_proposals (
+            id         INTEGER PRIMARY KEY,
+            module     TEXT NOT NULL,
+            affected   TEXT NOT NULL,
+            rationale  TEXT NOT NULL,
+            score      REAL NOT NULL,
+            status     TEXT NOT NULL DEFAULT 'pending',
+            bead_id    TEXT,
+            created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_refactor_proposals_module
+            ON refactor_proposals(module);",
+    )
+}
+
+fn insert(
+    conn: &Connection,
+    finding: &Finding,
+    status: ProposalStatus,
+    bead_id: Option<&str>,
+) -> rusqlite::Result<i64> {
+    conn.execute(
+        "INSERT INTO refactor_proposals (module, affected, rationale, score, status, bead_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            finding.module,
+            finding.affected.join(", "),
+            finding.rationale(),
+            finding.score,
+            status.as_str(),
+            bead_id,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+fn map_proposal(row: &rusqlite::Row) -> rusqlite::Result<Proposal> {
+    let affected: String = row.get(2)?;
+    let status: String = row.get(5)?;
+    Ok(Proposal {
+        id: row.get(0)?,
+        module: row.get(1)?,
+        affected: affected
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        rationale: row.get(3)?,
+        score: row.get(4)?,
+        status: ProposalStatus::parse(&status).unwrap_or(ProposalStatus::Pending),
+        bead_id: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+/// Look up a proposal by id.
+pub fn get(conn: &Connection, id: i64) -> rusqlite::Result<Option<Proposal>> {
+    conn.query_row(
+        "SELECT id, module, affected, rationale, score, status, bead_id, created_at
+         FROM refactor_proposals WHERE id = ?1",
+        params![id],
+        map_proposal,
+    )
+    .optional()
+}
+
+/// Proposals in id order. Only pending ones unless `all` is set.
+pub fn list(conn: &Connection, all: bool) -> rusqlite::Result<Vec<Proposal>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, module, affected, rationale, score, status, bead_id, created_at
+         FROM refactor_proposals WHERE ?1 OR status = 'pending'
+         ORDER BY id ASC",
+    )?;
+    let rows = stmt
+        .query_map(params![all], map_proposal)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rows)
+}
+
+/// Whether a module has a proposal that still stands: one awaiting approval,
+/// or one made since the last architecture snapshot (nothing has integrated
+/// since it was filed or rejected).
+fn has_current_proposal(conn: &Connection, module: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM refactor_proposals
+         WHERE module = ?1
+           AND (status = 'pending'
+                OR created_at >= COALESCE((SELECT MAX(recorded_at) FROM arch_snapshots), ''))",
+        params![module],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|n| n > 0)
+}
+
+fn set_status(
+    conn: &Connection,
+    id: i64,
+    status: ProposalStatus,
+    bead_id: Option<&str>,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE refactor_proposals SET status = ?1, bead_id = COALESCE(?2, bead_id) WHERE id = ?3",
+        params![status.as_str(), bead_id, id],
+    )?;
+    Ok(())
+}
+
+/// Repo-relative glob covering everything under a module's directory,
+/// whatever the language, so the scheduler's file locks cover the refactor.
+fn affected_globs(module: &Module, repo_root: &Path) -> Vec<String> {
+    let rel = module
+        .root_path
+        .strip_prefix(repo_root)
+        .unwrap_or(&module.root_path);
+    if rel.as_os_str().is_empty() {
+        return vec!["**".to_string()];
+    }
+    vec![format!("{}/**", rel.to_string_lossy())]
+}
+
+/// Check the correlator's candidates against the configured thresholds.
+///
+/// A candidate becomes a finding when it breaches at least one of:
+/// - `fan_in_threshold`: its most-imported file is imported by more than that
+///   fraction of modules;
+/// - `integration_loop_max`: tasks touching it average at least that many
+///   integration iterations;
+/// - `expansion_event_threshold`: it was touched without being predicted in at
+///   least that many expansion events over the last `expansion_event_window`
+///   tasks;
+/// - `metadata_drift_sensitivity`: its drift count exceeds that multiple of the
+///   average across modules.
+pub fn evaluate(
+    config: &ArchitectureConfig,
+    repo_root: &Path,
+    modules: &HashMap<String, Module>,
+    structural: &StructuralReport,
+    correlation: &CorrelationReport,
+    recent_events: &[ExpansionEvent],
+) -> Vec<Finding> {
+    // Restrict expansion events to the last `expansion_event_window` tasks.
+    let mut window_tasks: HashSet<&str> = HashSet::new();
+    let mut windowed: Vec<&ExpansionEvent> = Vec::new();
+    for event in recent_events {
+        if !window_tasks.contains(event.task_id.as_str()) {
+            if window_tasks.len() >= config.expansion_event_window as usize {
+                break;
+            }
+            window_tasks.insert(&event.task_id);
+        }
+        windowed.push(event);
+    }
+
+    let drift_baseline = if correlation.module_signals.is_empty() {
+        0.0
+    } else {
+        correlation
+            .module_signals
+            .values()
+            .map(|s| s.drift_count as f64)
+            .sum::<f64>()
+            / correlation.module_signals.len() as f64
+    };
+
+    let mut findings = Vec::new();
+    for candidate in &correlation.candidates {
+        let Some(module) = modules.get(&candidate.module) else {
+            continue;
+        };
+        let mut breaches = Vec::new();
+
+        let max_fan_in = module
+            .files
+            .iter()
+            .filter_map(|f| structural.files.get(f))
+            .map(|f| f.fan_in_score)
+            .fold(0.0_f64, f64::max);
+        if max_fan_in > config.fan_in_threshold {
+            breaches.push(format!(
+                "fan-in {:.2} exceeds fan_in_threshold {:.2}",
+                max_fan_in, config.fan_in_threshold
+            ));
+        }
+
+        let iterations = candidate.signals.integration_score;
+        if iterations >= config.integration_loop_max as f64 {
+            breaches.push(format!(
+                "average {:.1} integration iterations reaches integration_loop_max {}",
+                iterations, config.integration_loop_max
+            ));
+        }
+
+        // Only count the module where it was touched without being predicted
+        let expansions = windowed
+            .iter()
+            .filter(|e| {
+                e.actual_modules.contains(&candidate.module)
+                    && !e.predicted_modules.contains(&candidate.module)
+            })
+            .count() as u32;
+        if expansions >= config.expansion_event_threshold {
+            breaches.push(format!(
+                "{} expansion events in the last {} tasks reaches expansion_event_threshold {}",
+                expansions, config.expansion_event_window, config.expansion_event_threshold
+            ));
+        }
+
+        let drift = candidate.signals.drift_count;
+        if drift > 0 && drift as f64 > config.metadata_drift_sensitivity * drift_baseline {
+            breaches.push(format!(
+                "{} metadata drift reports exceed {:.1}x the average of {:.2}",
+                drift, config.metadata_drift_sensitivity, drift_baseline
+            ));
+        }
+
+        if breaches.is_empty() {
+            continue;
+        }
+        findings.push(Finding {
+            module: candidate.module.clone(),
+            affected: affected_globs(module, repo_root),
+            breaches,
+            score: candidate.combined_score,
+        });
+    }
+    findings
+}
+
+/// Record findings as proposals, filing beads for them when `auto_approve` is set.
+///
+/// Modules with a pending proposal, or with any proposal made since the last
+/// architecture snapshot, are skipped. If filing fails, the proposal is queued
+/// as pending instead so it is not lost.
+pub fn apply_findings<F>(
+    conn: &Connection,
+    findings: &[Finding],
+    auto_approve: bool,
+    file: F,
+) -> rusqlite::Result<ReviewOutcome>
+where
+    F: Fn(&str, &str, &[String]) -> Result<String, String>,
+{
+    let mut outcome = ReviewOutcome {
+        findings: findings.len(),
+        ..Default::default()
+    };
+    for finding in findings {
+        if has_current_proposal(conn, &finding.module)? {
+            outcome.skipped += 1;
+            continue;
+        }
+        if auto_approve {
+            match file(&finding.title(), &finding.rationale(), &finding.affected) {
+                Ok(bead_id) => {
+                    insert(conn, finding, ProposalStatus::Filed, Some(&bead_id))?;
+                    outcome.filed.push((finding.module.clone(), bead_id));
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        module = %finding.module,
+                        error = %e,
+                        "failed to file refactor bead, queuing proposal instead"
+                    );
+                }
+            }
+        }
+        let id = insert(conn, finding, ProposalStatus::Pending, None)?;
+        outcome.queued.push(id);
+    }
+    Ok(outcome)
+}
+
+/// Run a full architecture review of `repo_root`.
+pub fn run_review(
+    conn: &Connection,
+    repo_root: &Path,
+    config: &ArchitectureConfig,
+) -> Result<ReviewOutcome, String> {
+    let structural = structural_metrics::analyze(repo_root);
+    let modules = module_detect::detect_modules_from_repo(repo_root);
+    let correlation =
+        signal_correlator::correlate(conn, &structural, Some(config.expansion_event_window))
+            .map_err(|e| format!("signal correlation failed: {e}"))?;
+    let events = expansion_event::get_recent(conn, MAX_EVENTS)
+        .map_err(|e| format!("failed to load expansion events: {e}"))?;
+
+    let findings = evaluate(
+        config,
+        repo_root,
+        &modules,
+        &structural,
+        &correlation,
+        &events,
+    );
+    let mut outcome = apply_findings(conn, &findings, config.refactor_auto_approve, file_bead)
+        .map_err(|e| format!("failed to record refactor proposals: {e}"))?;
+    outcome.candidates = correlation.candidates.len();
+    Ok(outcome)
+}
+
+/// File a refactor task with `bd create`, returning the new bead id.
+pub fn file_bead(title: &str, rationale: &str, affected: &[String]) -> Result<String, String> {
+    let design = format!("affected: {}", affected.join(", "));
+    let output = Command::new("bd")
+        .args([
+            "create",
+            title,
+            "--type=task",
+            "--priority=2",
+            &format!("--description={rationale}"),
+            &format!("--design={design}"),
+            "--json",
+        ])
+        .output()
+        .map_err(|e| format!("failed to run bd create: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "bd create failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    parse_created_id(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "bd create did not report a bead id".to_string())
+}
+
+/// Extract the bead id from `bd create --json` output.
+pub fn parse_created_id(stdout: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(stdout.trim()).ok()?;
+    let obj = match &value {
+        serde_json::Value::Array(items) => items.first()?,
+        other => other,
+    };
+    obj.get("id")?.as_str().map(|s| s.to_string())
+}
+
+fn open_db(db_path: &Path) -> Result<Option<Connection>, String> {
+    if !db_path.exists() {
+        println!("No metrics database found. Run some sessions first.");
+        return Ok(None);
+    }
+    crate::db::open_or_create(db_path)
+        .map(Some)
+        .map_err(|e| format!("Failed to open database: {e}"))
+}
+
+fn require_pending(conn: &Connection, id: i64) -> Result<Proposal, String> {
+    let proposal = get(conn, id)
+        .map_err(|e| format!("Failed to query proposals: {e}"))?
+        .ok_or_else(|| format!("No refactor proposal with id {id}"))?;
+    if proposal.status != ProposalStatus::Pending {
+        return Err(format!(
+            "Proposal {id} is already {}",
+            proposal.status.as_str()
+        ));
+    }
+    Ok(proposal)
+}
+
+/// `blacksmith arch review`: run a review now, regardless of `review_every`.
+pub fn handle_review(
+    db_path: &Path,
+    repo_root: &Path,
+    config: &ArchitectureConfig,
+) -> Result<(), String> {
+    let Some(conn) = open_db(db_path)? else {
+        return Ok(());
+    };
+    let outcome = run_review(&conn, repo_root, config)?;
+    println!(
+        "{} refactor candidate(s), {} over threshold.",
+        outcome.candidates, outcome.findings
+    );
+    for (module, bead_id) in &outcome.filed {
+        println!("  filed {bead_id} for {module}");
+    }
+    for id in &outcome.queued {
+        println!("  queued proposal #{id} for approval");
+    }
+    if outcome.skipped > 0 {
+        println!("  {} module(s) already have a proposal", outcome.skipped);
+    }
+    Ok(())
+}
+
+/// `blacksmith arch proposals [--all]`
+pub fn handle_list(db_path: &Path, all: bool) -> Result<(), String> {
+    let Some(conn) = open_db(db_path)? else {
+        return Ok(());
+    };
+    let proposals = list(&conn, all).map_err(|e| format!("Failed to query proposals: {e}"))?;
+    if proposals.is_empty() {
+        println!("No refactor proposals.");
+        return Ok(());
+    }
+
+    println!(
+        "{:>4} {:<24} {:<9} {:>6} {:<14} AFFECTED",
+        "ID", "MODULE", "STATUS", "SCORE", "BEAD"
+    );
+    println!("{}", "-".repeat(90));
+    for p in &proposals {
+        println!(
+            "{:>4} {:<24} {:<9} {:>6.1} {:<14} {}",
+            p.id,
+            p.module,
+            p.status.as_str(),
+            p.score,
+            p.bead_id.as_deref().unwrap_or("-"),
+            p.affected.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// `blacksmith arch approve <id>`: file the proposal as a bead.
+pub fn handle_approve(db_path: &Path, id: i64) -> Result<(), String> {
+    let Some(conn) = open_db(db_path)? else {
+        return Ok(());
+    };
+    let proposal = require_pending(&conn, id)?;
+    let bead_id = file_bead(&proposal.title(), &proposal.rationale, &proposal.affected)?;
+    set_status(&conn, id, ProposalStatus::Filed, Some(&bead_id)).map_err(|e| e.to_string())?;
+    println!("Filed {bead_id} for {}.", proposal.module);
+    Ok(())
+}
+
+/// `blacksmith arch reject <id>`
+pub fn handle_reject(db_path: &Path, id: i64) -> Result<(), String> {
+    let Some(conn) = open_db(db_path)? else {
+        return Ok(());
+    };
+    let proposal = require_pending(&conn, id)?;
+    set_status(&conn, id, ProposalStatus::Rejected, None).map_err(|e| e.to_string())?;
+    println!(
+        "Rejected proposal {id}; {} will not be proposed again.",
+        proposal.module
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal_correlator::{ModuleSignals, RefactorCandidate, StructuralSmells};
+    use crate::structural_metrics::FileMetrics;
+    use std::cell::RefCell;
+    use std::path::PathBuf;
+
+    fn test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        create_table(&conn).unwrap();
+        crate::arch_history::create_table(&conn).unwrap();
+        conn
+    }
+
+    fn module(name: &str, root: &str, files: &[&str]) -> Module {
+        Module {
+            name: name.to_string(),
+            root_path: PathBuf::from(root),
+            files: files.iter().map(PathBuf::from).collect(),
+            has_entry_point: false,
+            entry_point: None,
+            submodules: Vec::new(),
+        }
+    }
+
+    fn smells() -> StructuralSmells {
+        StructuralSmells {
+            high_fan_in: false,
+            large_module: true,
+            in_cycle: false,
+            has_violations: false,
+            has_god_files: false,
+            wide_api: false,
+            structural_score: 1.0,
+        }
+    }
+
+    fn signals(module: &str, integration_score: f64, drift_count: u32) -> ModuleSignals {
+        ModuleSignals {
+            module: module.to_string(),
+            expansion_score: 0.0,
+            integration_score,
+            drift_count,
+            historical_score: integration_score + drift_count as f64,
+        }
+    }
+
+    fn candidate(module: &str, integration_score: f64, drift_count: u32) -> RefactorCandidate {
+        let signals = signals(module, integration_score, drift_count);
+        RefactorCandidate {
+            module: module.to_string(),
+            smells: smells(),
+            combined_score: 1.0 + signals.historical_score,
+            signals,
+            confidence: 0.5,
+        }
+    }
+
+    fn report(candidates: Vec<RefactorCandidate>, extra: Vec<ModuleSignals>) -> CorrelationReport {
+        let mut module_signals: HashMap<String, ModuleSignals> =
+            extra.into_iter().map(|s| (s.module.clone(), s)).collect();
+        for c in &candidates {
+            module_signals.insert(c.module.clone(), c.signals.clone());
+        }
+        CorrelationReport {
+            module_signals,
+            module_smells: HashMap::new(),
+            candidates,
+            total_expansion_events: 0,
+            total_integration_records: 0,
+            total_drift_reports: 0,
+        }
+    }
+
+    fn structural(fan_in: &[(&str, f64)]) -> StructuralReport {
+        StructuralReport {
+            modules: HashMap::new(),
+            files: fan_in
+                .iter()
+                .map(|(path, score)| {
+                    (
+                        PathBuf::from(path),
+                        FileMetrics {
+                            path: PathBuf::from(path),
+                            line_count: 100,
+                            fan_in_score: *score,
+                            fan_in_importers: 1,
+                            is_god_file: false,
+                            cluster_count: 0,
+                        },
+                    )
+                })
+                .collect(),
+            cycles: Vec::new(),
+            boundary_violations: Vec::new(),
+            total_modules: 0,
+            total_files: fan_in.len(),
+        }
+    }
+
+    fn event(task: &str, modules: &[&str]) -> ExpansionEvent {
+        predicted_event(task, &[], modules)
+    }
+
+    fn predicted_event(task: &str, predicted: &[&str], modules: &[&str]) -> ExpansionEvent {
+        ExpansionEvent {
+            task_id: task.to_string(),
+            predicted_modules: predicted.iter().map(|m| m.to_string()).collect(),
+            actual_modules: modules.iter().map(|m| m.to_string()).collect(),
+            expansion_reason: "test".to_string(),
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    fn modules() -> HashMap<String, Module> {
+        [
+            module("auth", "/repo/src/auth", &["/repo/src/auth/mod.rs"]),
+            module("db", "/repo/src/db", &["/repo/src/db/mod.rs"]),
+        ]
+        .into_iter()
+        .map(|m| (m.name.clone(), m))
+        .collect()
+    }
+
+    #[test]
+    fn evaluate_skips_candidates_under_every_threshold() {
+        let config = ArchitectureConfig::default();
+        let findings = evaluate(
+            &config,
+            Path::new("/repo"),
+            &modules(),
+            &structural(&[("/repo/src/auth/mod.rs", 0.1)]),
+            &report(vec![candidate("auth", 2.0, 0)], Vec::new()),
+            &[],
+        );
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn evaluate_flags_fan_in_and_integration_loops() {
+        let config = ArchitectureConfig::default();
+        let findings = evaluate(
+            &config,
+            Path::new("/repo"),
+            &modules(),
+            &structural(&[("/repo/src/auth/mod.rs", 0.6)]),
+            &report(vec![candidate("auth", 6.0, 0)], Vec::new()),
+            &[],
+        );
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].module, "auth");
+        assert_eq!(findings[0].affected, vec!["src/auth/**".to_string()]);
+        assert_eq!(findings[0].breaches.len(), 2);
+        assert!(findings[0].breaches[0].contains("fan_in_threshold"));
+        assert!(findings[0].breaches[1].contains("integration_loop_max"));
+    }
+
+    #[test]
+    fn evaluate_counts_expansion_events_within_window() {
+        let config = ArchitectureConfig {
+            expansion_event_threshold: 2,
+            expansion_event_window: 2,
+            ..Default::default()
+        };
+        let candidates = || report(vec![candidate("db", 1.0, 0)], Vec::new());
+
+        // Newest first: two recent tasks touched db.
+        let events = vec![
+            event("t3", &["db"]),
+            event("t2", &["db"]),
+            event("t1", &["db"]),
+        ];
+        let findings = evaluate(
+            &config,
+            Path::new("/repo"),
+            &modules(),
+            &structural(&[]),
+            &candidates(),
+            &events,
+        );
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].breaches[0].contains("2 expansion events"));
+
+        // Only one of the last two tasks touched db.
+        let events = vec![
+            event("t3", &["db"]),
+            event("t2", &["auth"]),
+            event("t1", &["db"]),
+        ];
+        let findings = evaluate(
+            &config,
+            Path::new("/repo"),
+            &modules(),
+            &structural(&[]),
+            &candidates(),
+            &events,
+        );
+        assert!(findings.is_empty());
+
+        // t2 predicted db, so only its unpredicted auth change counts.
+        let events = vec![
+            event("t3", &["db"]),
+            predicted_event("t2", &["db"], &["db", "auth"]),
+            event("t1", &["db"]),
+        ];
+        let findings = evaluate(
+            &config,
+            Path::new("/repo"),
+            &modules(),
+            &structural(&[]),
+            &candidates(),
+            &events,
+        );
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn evaluate_flags_metadata_drift_above_baseline() {
+        let config = ArchitectureConfig::default();
+        let quiet: Vec<ModuleSignals> = (0..5).map(|i| signals(&format!("m{i}"), 0.0, 0)).collect();
+        let findings = evaluate(
+            &config,
+            Path::new("/repo"),
+            &modules(),
+            &structural(&[]),
+            &report(vec![candidate("db", 1.0, 4)], quiet),
+            &[],
+        );
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].breaches[0].contains("metadata drift"));
+
+        // With drift spread evenly there is no outlier.
+        let noisy: Vec<ModuleSignals> = (0..5).map(|i| signals(&format!("m{i}"), 0.0, 4)).collect();
+        let findings = evaluate(
+            &config,
+            Path::new("/repo"),
+            &modules(),
+            &structural(&[]),
+            &report(vec![candidate("db", 1.0, 4)], noisy),
+            &[],
+        );
+        assert!(findings.is_empty());
+    }
+
+    fn finding(module: &str) -> Finding {
+        Finding {
+            module: module.to_string(),
+            affected: vec![format!("src/{module}/**")],
+            breaches: vec!["fan-in 0.60 exceeds fan_in_threshold 0.30".to_string()],
+            score: 3.0,
+        }
+    }
+
+    #[test]
+    fn apply_findings_queues_proposals_without_auto_approve() {
+        let conn = test_db();
+        let file = |_: &str, _: &str, _: &[String]| -> Result<String, String> {
+            panic!("must not file beads without auto-approve")
+        };
+        let outcome = apply_findings(&conn, &[finding("auth")], false, file).unwrap();
+        assert_eq!(outcome.queued.len(), 1);
+        assert!(outcome.filed.is_empty());
+
+        let proposal = get(&conn, outcome.queued[0]).unwrap().unwrap();
+        assert_eq!(proposal.module, "auth");
+        assert_eq!(proposal.status, ProposalStatus::Pending);
+        assert_eq!(proposal.affected, vec!["src/auth/**".to_string()]);
+        assert!(proposal.rationale.contains("fan_in_threshold"));
+        assert_eq!(list(&conn, false).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn apply_findings_files_beads_with_auto_approve() {
+        let conn = test_db();
+        let calls = RefCell::new(Vec::new());
+        let file = |title: &str, rationale: &str, affected: &[String]| {
+            calls
+                .borrow_mut()
+                .push((title.to_string(), rationale.to_string(), affected.to_vec()));
+            Ok("bd-42".to_string())
+        };
+        let outcome = apply_findings(&conn, &[finding("auth")], true, file).unwrap();
+        assert_eq!(
+            outcome.filed,
+            vec![("auth".to_string(), "bd-42".to_string())]
+        );
+        assert!(outcome.queued.is_empty());
+
+        let calls = calls.borrow();
+        assert_eq!(calls[0].0, "Refactor auth");
+        assert!(calls[0].1.contains("fan_in_threshold"));
+        assert_eq!(calls[0].2, vec!["src/auth/**".to_string()]);
+
+        let all = list(&conn, true).unwrap();
+        assert_eq!(all[0].status, ProposalStatus::Filed);
+        assert_eq!(all[0].bead_id.as_deref(), Some("bd-42"));
+        assert!(list(&conn, false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn apply_findings_queues_when_filing_fails() {
+        let conn = test_db();
+        let file = |_: &str, _: &str, _: &[String]| Err("bd missing".to_string());
+        let outcome = apply_findings(&conn, &[finding("auth")], true, file).unwrap();
+        assert!(outcome.filed.is_empty());
+        assert_eq!(outcome.queued.len(), 1);
+    }
+
+    #[test]
+    fn apply_findings_reproposes_after_a_new_snapshot() {
+        let conn = test_db();
+        let file = |_: &str, _: &str, _: &[String]| Ok("bd-1".to_string());
+        let outcome = apply_findings(&conn, &[finding("auth")], true, file).unwrap();
+        assert_eq!(outcome.filed.len(), 1);
+
+        // Nothing integrated since the bead was filed
+        let outcome = apply_findings(&conn, &[finding("auth")], true, file).unwrap();
+        assert_eq!(outcome.skipped, 1);
+
+        conn.execute(
+            "UPDATE refactor_proposals SET created_at = '2026-01-01T00:00:00Z'",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO arch_snapshots
+                (bead_id, module_count, file_count, hotspots, god_files, cycles, api_widths,
+                 recorded_at)
+             VALUES ('bd-1', 1, 1, '[]', '[]', '[]', '{}', '2026-01-02T00:00:00Z')",
+            [],
+        )
+        .unwrap();
+        let outcome = apply_findings(&conn, &[finding("auth")], true, file).unwrap();
+        assert_eq!(outcome.skipped, 0);
+        assert_eq!(outcome.filed.len(), 1);
+    }
+
+    #[test]
+    fn apply_findings_skips_modules_with_existing_proposal() {
+        let conn = test_db();
+        let file = |_: &str, _: &str, _: &[String]| Ok("bd-1".to_string());
+        apply_findings(&conn, &[finding("auth")], false, file).unwrap();
+        let id = list(&conn, false).unwrap()[0].id;
+        set_status(&conn, id, ProposalStatus::Rejected, None).unwrap();
+
+        let outcome =
+            apply_findings(&conn, &[finding("auth"), finding("db")], false, file).unwrap();
+        assert_eq!(outcome.skipped, 1);
+        assert_eq!(outcome.queued.len(), 1);
+        assert_eq!(get(&conn, outcome.queued[0]).unwrap().unwrap().module, "db");
+    }
+
+    #[test]
+    fn parse_created_id_handles_object_and_array() {
+        assert_eq!(
+            parse_created_id(r#"{"id": "bd-7", "title": "x"}"#),
+            Some("bd-7".to_string())
+        );
+        assert_eq!(
+            parse_created_id(r#"[{"id": "bd-8"}]"#),
+            Some("bd-8".to_string())
+        );
+        assert_eq!(parse_created_id("Created issue bd-9"), None);
+    }
+}
@@ -0,0 +1,769 @@
+//! Portable export and import of a data directory.
+//!
+//! `blacksmith archive export <file>` writes one zstd-compressed bundle with
+//! the database tables, every session file, the global counter and
+//! `config.toml`. `blacksmith archive import <file>` merges a bundle into an
+//! existing data directory: imported sessions are shifted past the highest
+//! session already present, row ids are reassigned (with `assignment_id`
+//! references following their worker assignment), and improvement refs that
+//! already exist are given fresh ones.
+//!
+//! Inside the zstd stream the bundle is a header line followed by entries,
+//! each a `<name>\t<length>\n` line and `length` raw bytes:
+//! `manifest.json` (metadata and table rows), `config.toml`, and
+//! `sessions/<N>.jsonl.zst`.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+
+use rusqlite::types::Value;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::data_dir::DataDir;
+use crate::db;
+use crate::schema;
+
+const HEADER: &str = "BLACKSMITH-ARCHIVE 1";
+const MANIFEST: &str = "manifest.json";
+const CONFIG: &str = "config.toml";
+
+/// How rows of a table are merged into an existing database.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Merge {
+    /// Insert every row; an `id` column is dropped so the target assigns one.
+    Append,
+    /// Keyed by a natural key (primary or unique); rows already present are
+    /// kept. A surrogate `id` column is dropped as for `Append`.
+    KeepExisting,
+}
+
+/// Exported tables, in import order (worker_assignments before the tables
+/// referencing it).
+const TABLES: &[(&str, Merge)] = &[
+    ("improvements", Merge::Append),
+    ("improvement_impact", Merge::KeepExisting),
+    ("rule_revisions", Merge::Append),
+    ("progress_entries", Merge::Append),
+    ("handoffs", Merge::Append),
+    ("events", Merge::Append),
+    ("observations", Merge::Append),
+    ("worker_assignments", Merge::Append),
+    ("task_file_changes", Merge::Append),
+    ("integration_log", Merge::Append),
+    ("integration_iterations", Merge::Append),
+    ("bead_metrics", Merge::KeepExisting),
+    ("arch_snapshots", Merge::Append),
+    ("refactor_proposals", Merge::Append),
+    ("plan_proposals", Merge::Append),
+    ("expansion_events", Merge::Append),
+    ("test_runs", Merge::Append),
+    ("gate_results", Merge::Append),
+    ("manual_checks", Merge::Append),
+    ("salvage", Merge::KeepExisting),
+    ("test_failures", Merge::Append),
+    ("tool_calls", Merge::Append),
+    ("epochs", Merge::KeepExisting),
+    ("experiment_assignments", Merge::KeepExisting),
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    created_at: String,
+    schema_version: u32,
+    counter: u64,
+    tables: Vec<TableDump>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TableDump {
+    name: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<serde_json::Value>>,
+}
+
+impl TableDump {
+    fn column(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|c| c == name)
+    }
+}
+
+/// Identifier changes applied to imported rows.
+#[derive(Default)]
+struct Remap {
+    session_shift: u64,
+    /// Bundled worker_assignments id -> id assigned on insert.
+    assignment_ids: HashMap<i64, i64>,
+    /// Bundled improvement ref -> fresh ref, for refs that collided.
+    refs: HashMap<String, String>,
+}
+
+/// What an export or import moved.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// Rows per table.
+    pub rows: Vec<(String, usize)>,
+    pub sessions: usize,
+    /// Imported promoted improvements.
+    pub promoted: usize,
+    /// `new - old` session id shift applied on import.
+    pub session_shift: u64,
+    /// Where the bundled config was written, if anywhere.
+    pub config: Option<String>,
+}
+
+/// Write `data_dir` to a bundle at `out`.
+pub fn export(data_dir: &DataDir, out: &Path) -> Result<Summary, String> {
+    let conn =
+        db::open_or_create(&data_dir.db()).map_err(|e| format!("Failed to open database: {e}"))?;
+    let mut summary = Summary::default();
+
+    let mut tables = Vec::new();
+    for (name, _) in TABLES {
+        let dump = dump_table(&conn, name).map_err(|e| format!("Failed to read {name}: {e}"))?;
+        summary.rows.push((name.to_string(), dump.rows.len()));
+        tables.push(dump);
+    }
+    summary.promoted = count_promoted(&tables);
+
+    let manifest = Manifest {
+        created_at: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        schema_version: schema::current_version(&conn).map_err(|e| e.to_string())?,
+        counter: read_counter(data_dir),
+        tables,
+    };
+
+    let file = std::fs::File::create(out)
+        .map_err(|e| format!("Failed to create {}: {e}", out.display()))?;
+    let mut writer = zstd::Encoder::new(file, 3)
+        .map_err(|e| format!("Failed to start compression: {e}"))?
+        .auto_finish();
+    let io_err = |e: std::io::Error| format!("Failed to write {}: {e}", out.display());
+
+    writeln!(writer, "{HEADER}").map_err(io_err)?;
+    let manifest = serde_json::to_vec(&manifest).map_err(|e| e.to_string())?;
+    write_entry(&mut writer, MANIFEST, &manifest).map_err(io_err)?;
+    if let Ok(config) = std::fs::read(data_dir.config()) {
+        write_entry(&mut writer, CONFIG, &config).map_err(io_err)?;
+    }
+    for (session, path) in session_files(&data_dir.sessions_dir()) {
+        let bytes = std::fs::read(&path).map_err(io_err)?;
+        let compressed = if path.extension().is_some_and(|e| e == "zst") {
+            bytes
+        } else {
+            zstd::encode_all(bytes.as_slice(), 3).map_err(io_err)?
+        };
+        write_entry(
+            &mut writer,
+            &format!("sessions/{session}.jsonl.zst"),
+            &compressed,
+        )
+        .map_err(io_err)?;
+        summary.sessions += 1;
+    }
+    Ok(summary)
+}
+
+/// Merge the bundle at `bundle` into `data_dir`.
+pub fn import(data_dir: &DataDir, bundle: &Path) -> Result<Summary, String> {
+    let entries = read_bundle(bundle)?;
+    let manifest: Manifest = entries
+        .iter()
+        .find(|(name, _)| name == MANIFEST)
+        .ok_or_else(|| format!("{} has no {MANIFEST}", bundle.display()))
+        .and_then(|(_, bytes)| {
+            serde_json::from_slice(bytes).map_err(|e| format!("Invalid {MANIFEST}: {e}"))
+        })?;
+    if manifest.schema_version > schema::latest_version() {
+        return Err(format!(
+            "archive has schema version {}, newer than this blacksmith supports ({})",
+            manifest.schema_version,
+            schema::latest_version()
+        ));
+    }
+
+    // `init` writes a default config, which the bundled one should replace.
+    let had_config = data_dir.config().exists();
+    data_dir
+        .init()
+        .map_err(|e| format!("Failed to initialize {}: {e}", data_dir.root().display()))?;
+    let conn =
+        db::open_or_create(&data_dir.db()).map_err(|e| format!("Failed to open database: {e}"))?;
+
+    let bundled_sessions: Vec<(u64, &[u8])> = entries
+        .iter()
+        .filter_map(|(name, bytes)| {
+            let n = name
+                .strip_prefix("sessions/")?
+                .strip_suffix(".jsonl.zst")?
+                .parse()
+                .ok()?;
+            Some((n, bytes.as_slice()))
+        })
+        .collect();
+
+    // Shift imported sessions past everything already in the data dir.
+    let next_free = next_free_session(&conn, data_dir).map_err(|e| e.to_string())?;
+    let first_imported = bundled_sessions
+        .iter()
+        .map(|(n, _)| *n)
+        .chain(session_ids(&manifest.tables))
+        .min();
+    let shift = first_imported.map_or(0, |first| next_free.saturating_sub(first));
+
+    let mut summary = Summary {
+        session_shift: shift,
+        promoted: count_promoted(&manifest.tables),
+        ..Default::default()
+    };
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("Failed to start import: {e}"))?;
+    let mut remap = Remap {
+        session_shift: shift,
+        ..Default::default()
+    };
+    for (name, merge) in TABLES {
+        let Some(dump) = manifest.tables.iter().find(|t| t.name == *name) else {
+            continue;
+        };
+        let count = import_table(&tx, dump, *merge, &mut remap)
+            .map_err(|e| format!("Failed to import {name}: {e}"))?;
+        summary.rows.push((name.to_string(), count));
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit import: {e}"))?;
+
+    let sessions_dir = data_dir.sessions_dir();
+    let mut last_session = None;
+    for (n, bytes) in &bundled_sessions {
+        let new = n + shift;
+        let dest = sessions_dir.join(format!("{new}.jsonl.zst"));
+        std::fs::write(&dest, bytes)
+            .map_err(|e| format!("Failed to write {}: {e}", dest.display()))?;
+        last_session = last_session.max(Some(new));
+        summary.sessions += 1;
+    }
+
+    let counter = read_counter(data_dir)
+        .max(manifest.counter + shift)
+        .max(last_session.map_or(0, |n| n + 1));
+    std::fs::write(data_dir.counter(), counter.to_string())
+        .map_err(|e| format!("Failed to update counter: {e}"))?;
+
+    if let Some((_, config)) = entries.iter().find(|(name, _)| name == CONFIG) {
+        let dest = if had_config {
+            data_dir.root().join("config.imported.toml")
+        } else {
+            data_dir.config()
+        };
+        std::fs::write(&dest, config)
+            .map_err(|e| format!("Failed to write {}: {e}", dest.display()))?;
+        summary.config = Some(dest.display().to_string());
+    }
+
+    Ok(summary)
+}
+
+fn dump_table(conn: &Connection, name: &str) -> rusqlite::Result<TableDump> {
+    let mut stmt = conn.prepare(&format!("SELECT * FROM {name}"))?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let width = columns.len();
+    let rows = stmt
+        .query_map([], |row| {
+            (0..width)
+                .map(|i| row.get::<_, Value>(i).map(to_json))
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(TableDump {
+        name: name.to_string(),
+        columns,
+        rows,
+    })
+}
+
+/// Insert a dumped table's rows, returning how many were inserted.
+fn import_table(
+    conn: &Connection,
+    dump: &TableDump,
+    merge: Merge,
+    remap: &mut Remap,
+) -> rusqlite::Result<usize> {
+    let target: Vec<String> = conn
+        .prepare(&format!(
+            "SELECT name FROM pragma_table_info('{}')",
+            dump.name
+        ))?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    // Columns the bundle and this database share, minus ids reassigned on insert.
+    let columns: Vec<(usize, &str)> = dump
+        .columns
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| target.contains(c) && *c != "id")
+        .map(|(i, c)| (i, c.as_str()))
+        .collect();
+    if columns.is_empty() {
+        return Ok(0);
+    }
+
+    let sql = format!(
+        "INSERT {}INTO {} ({}) VALUES ({})",
+        if merge == Merge::KeepExisting {
+            "OR IGNORE "
+        } else {
+            ""
+        },
+        dump.name,
+        columns
+            .iter()
+            .map(|(_, c)| *c)
+            .collect::<Vec<_>>()
+            .join(", "),
+        (1..=columns.len())
+            .map(|i| format!("?{i}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let old_id = dump.column("id");
+    let mut inserted = 0;
+
+    for row in &dump.rows {
+        let mut values = Vec::with_capacity(columns.len());
+        for (i, column) in &columns {
+            let mut value = from_json(&row[*i]);
+            match (*column, &value) {
+                ("session" | "promoted_session", Value::Integer(n)) => {
+                    value = Value::Integer(n + remap.session_shift as i64);
+                }
+                ("assignment_id", Value::Integer(n)) => {
+                    value = Value::Integer(remap.assignment_ids.get(n).copied().unwrap_or(*n));
+                }
+                ("ref", Value::Text(r)) if dump.name == "improvements" && ref_exists(conn, r)? => {
+                    let fresh = db::next_ref(conn)?;
+                    remap.refs.insert(r.clone(), fresh.clone());
+                    value = Value::Text(fresh);
+                }
+                ("ref", Value::Text(r))
+                    if dump.name == "improvement_impact" || dump.name == "rule_revisions" =>
+                {
+                    if let Some(fresh) = remap.refs.get(r) {
+                        value = Value::Text(fresh.clone());
+                    }
+                }
+                _ => {}
+            }
+            values.push(value);
+        }
+        let changed = conn.execute(&sql, rusqlite::params_from_iter(values))?;
+        inserted += changed;
+        if dump.name == "worker_assignments" && changed > 0 {
+            if let Some(Value::Integer(old)) = old_id.map(|i| from_json(&row[i])) {
+                remap.assignment_ids.insert(old, conn.last_insert_rowid());
+            }
+        }
+    }
+    Ok(inserted)
+}
+
+fn ref_exists(conn: &Connection, r: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM improvements WHERE ref = ?1)",
+        [r],
+        |row| row.get(0),
+    )
+}
+
+fn to_json(value: Value) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Integer(n) => n.into(),
+        Value::Real(f) => f.into(),
+        Value::Text(s) => s.into(),
+        Value::Blob(b) => serde_json::json!({ "blob": b }),
+    }
+}
+
+fn from_json(value: &serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Integer(*b as i64),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => Value::Text(s.clone()),
+        serde_json::Value::Object(o) => match o.get("blob") {
+            Some(serde_json::Value::Array(bytes)) => Value::Blob(
+                bytes
+                    .iter()
+                    .filter_map(|b| b.as_u64().map(|b| b as u8))
+                    .collect(),
+            ),
+            _ => Value::Text(value.to_string()),
+        },
+        serde_json::Value::Array(_) => Value::Text(value.to_string()),
+    }
+}
+
+/// Session ids referenced by the bundled `events` and `observations` rows.
+fn session_ids(tables: &[TableDump]) -> impl Iterator<Item = u64> + '_ {
+    tables
+        .iter()
+        .filter(|t| t.name == "events" || t.name == "observations")
+        .flat_map(|t| {
+            let col = t.column("session");
+            t.rows
+                .iter()
+                .filter_map(move |row| col.and_then(|c| row[c].as_u64()))
+        })
+}
+
+fn count_promoted(tables: &[TableDump]) -> usize {
+    tables
+        .iter()
+        .find(|t| t.name == "improvements")
+        .and_then(|t| t.column("status").map(|c| (t, c)))
+        .map_or(0, |(t, c)| {
+            t.rows
+                .iter()
+                .filter(|row| row[c].as_str() == Some("promoted"))
+                .count()
+        })
+}
+
+/// The lowest session id not used by the counter, the database or a session file.
+fn next_free_session(conn: &Connection, data_dir: &DataDir) -> rusqlite::Result<u64> {
+    let in_db: Option<i64> = conn.query_row(
+        "SELECT MAX(s) FROM (SELECT MAX(session) AS s FROM events
+                             UNION ALL SELECT MAX(session) FROM observations)",
+        [],
+        |row| row.get(0),
+    )?;
+    let in_files = session_files(&data_dir.sessions_dir())
+        .last()
+        .map(|(n, _)| n + 1);
+    Ok(read_counter(data_dir)
+        .max(in_db.map_or(0, |n| n as u64 + 1))
+        .max(in_files.unwrap_or(0)))
+}
+
+/// `(session id, path)` for `N.jsonl` and `N.jsonl.zst` files, sorted by id.
+fn session_files(dir: &Path) -> Vec<(u64, std::path::PathBuf)> {
+    let mut files: Vec<(u64, std::path::PathBuf)> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    let stem = name
+                        .strip_suffix(".jsonl.zst")
+                        .or_else(|| name.strip_suffix(".jsonl"))?;
+                    Some((stem.parse().ok()?, entry.path()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+fn read_counter(data_dir: &DataDir) -> u64 {
+    std::fs::read_to_string(data_dir.counter())
+        .ok()
+        .and_then(|c| c.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+fn write_entry(w: &mut impl Write, name: &str, bytes: &[u8]) -> std::io::Result<()> {
+    writeln!(w, "{name}\t{}", bytes.len())?;
+    w.write_all(bytes)
+}
+
+fn read_bundle(path: &Path) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let file =
+        std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    let decoder = zstd::Decoder::new(file)
+        .map_err(|e| format!("{} is not a zstd file: {e}", path.display()))?;
+    let mut reader = BufReader::new(decoder);
+    let invalid = |what: &str| format!("{} is not a blacksmith archive ({what})", path.display());
+
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|_| invalid("unreadable header"))?;
+    if line.trim_end() != HEADER {
+        return Err(invalid("bad header"));
+    }
+
+    let mut entries = Vec::new();
+    loop {
+        line.clear();
+        let n = reader
+            .read_line(&mut line)
+            .map_err(|e| invalid(&e.to_string()))?;
+        if n == 0 {
+            break;
+        }
+        let (name, len) = line
+            .trim_end()
+            .split_once('\t')
+            .ok_or_else(|| invalid("bad entry header"))?;
+        let len: usize = len.parse().map_err(|_| invalid("bad entry length"))?;
+        let mut bytes = vec![0; len];
+        reader
+            .read_exact(&mut bytes)
+            .map_err(|_| invalid("truncated entry"))?;
+        entries.push((name.to_string(), bytes));
+    }
+    Ok(entries)
+}
+
+fn print_rows(summary: &Summary) {
+    for (table, count) in summary.rows.iter().filter(|(_, n)| *n > 0) {
+        println!("  {table:<24} {count:>6} rows");
+    }
+    println!("  {:<24} {:>6}", "sessions", summary.sessions);
+    if summary.promoted > 0 {
+        println!("  {:<24} {:>6}", "promoted improvements", summary.promoted);
+    }
+}
+
+/// `blacksmith archive export <file>`
+pub fn handle_export(data_dir: &DataDir, out: &Path) -> Result<(), String> {
+    if !data_dir.db().exists() {
+        println!("No metrics database found. Run some sessions first.");
+        return Ok(());
+    }
+    let summary = export(data_dir, out)?;
+    println!(
+        "Exported {} to {}:",
+        data_dir.root().display(),
+        out.display()
+    );
+    print_rows(&summary);
+    Ok(())
+}
+
+/// `blacksmith archive import <file>`
+pub fn handle_import(data_dir: &DataDir, bundle: &Path) -> Result<(), String> {
+    let summary = import(data_dir, bundle)?;
+    println!(
+        "Imported {} into {}:",
+        bundle.display(),
+        data_dir.root().display()
+    );
+    print_rows(&summary);
+    if summary.session_shift > 0 {
+        println!(
+            "Session ids were shifted by {} to avoid collisions.",
+            summary.session_shift
+        );
+    }
+    if let Some(config) = &summary.config {
+        println!("Bundled config written to {config}.");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_dir(root: &Path) -> DataDir {
+        let dd = DataDir::new(root);
+        dd.init().unwrap();
+        dd
+    }
+
+    fn add_session(dd: &DataDir, conn: &Connection, session: i64, content: &str) {
+        std::fs::write(dd.session_file(session as u32), content).unwrap();
+        conn.execute(
+            "INSERT INTO events (session, kind, value) VALUES (?1, 'turns.total', '5')",
+            [session],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO observations (session, ts, data) VALUES (?1, '2026-01-01T00:00:00Z', '{}')",
+            [session],
+        )
+        .unwrap();
+    }
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn round_trip_merges_without_collisions() {
+        let tmp = tempfile::tempdir().unwrap();
+
+        // Source: sessions 0 and 1, an assignment with an integration, R1 promoted.
+        let src = data_dir(&tmp.path().join("src"));
+        let conn = db::open_or_create(&src.db()).unwrap();
+        add_session(&src, &conn, 0, "{\"a\":0}\n");
+        add_session(&src, &conn, 1, "{\"a\":1}\n");
+        std::fs::write(src.counter(), "2").unwrap();
+        let assignment =
+            db::insert_worker_assignment(&conn, 0, "b-src", "/tmp/wt", "completed", None).unwrap();
+        conn.execute(
+            "INSERT INTO integration_log (assignment_id, merged_at, merge_commit)
+             VALUES (?1, '2026-01-01T00:00:00Z', 'abc')",
+            [assignment],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO improvements (ref, category, status, title) VALUES ('R1', 'workflow', 'promoted', 'Batch reads')",
+            [],
+        )
+        .unwrap();
+        db::set_improvement_target(&conn, "R1", "turns.total", "below").unwrap();
+        crate::impact::record_baseline(&conn, "R1", 10).unwrap();
+        conn.execute(
+            "INSERT INTO experiment_assignments (experiment, bead_id, variant)
+             VALUES ('terse', 'b-src', 'short')",
+            [],
+        )
+        .unwrap();
+        drop(conn);
+
+        let bundle = tmp.path().join("run.bsa");
+        let exported = export(&src, &bundle).unwrap();
+        assert_eq!(exported.sessions, 2);
+        assert_eq!(exported.promoted, 1);
+
+        // Target already has sessions 0..=2, an assignment and its own R1.
+        let dst = data_dir(&tmp.path().join("dst"));
+        let conn = db::open_or_create(&dst.db()).unwrap();
+        for s in 0..3 {
+            add_session(&dst, &conn, s, "{\"dst\":true}\n");
+        }
+        std::fs::write(dst.counter(), "3").unwrap();
+        db::insert_worker_assignment(&conn, 1, "b-dst", "/tmp/wt2", "completed", None).unwrap();
+        conn.execute(
+            "INSERT INTO improvements (ref, category, title) VALUES ('R1', 'cost', 'Existing')",
+            [],
+        )
+        .unwrap();
+        drop(conn);
+
+        let summary = import(&dst, &bundle).unwrap();
+        assert_eq!(summary.session_shift, 3);
+        assert_eq!(summary.sessions, 2);
+        assert_eq!(
+            summary.config.as_deref(),
+            Some(
+                dst.root()
+                    .join("config.imported.toml")
+                    .to_string_lossy()
+                    .as_ref()
+            )
+        );
+
+        let conn = db::open_or_create(&dst.db()).unwrap();
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM observations"), 5);
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM events WHERE session IN (3, 4)"),
+            2
+        );
+        let decoded = zstd::decode_all(
+            std::fs::read(dst.sessions_dir().join("4.jsonl.zst"))
+                .unwrap()
+                .as_slice(),
+        )
+        .unwrap();
+        assert_eq!(decoded, b"{\"a\":1}\n");
+        assert_eq!(std::fs::read_to_string(dst.counter()).unwrap(), "5");
+
+        // The integration follows its assignment's new id.
+        let follows = count(
+            &conn,
+            "SELECT COUNT(*) FROM integration_log l
+             JOIN worker_assignments w ON w.id = l.assignment_id
+             WHERE w.bead_id = 'b-src'",
+        );
+        assert_eq!(follows, 1);
+
+        // The imported R1 got a fresh ref.
+        let imported_ref: String = conn
+            .query_row(
+                "SELECT ref FROM improvements WHERE title = 'Batch reads'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(imported_ref, "R2");
+        // Its impact baseline follows the new ref and the shifted sessions.
+        let impact = crate::impact::get(&conn, "R2").unwrap().unwrap();
+        assert_eq!(impact.metric, "turns.total");
+        assert_eq!(impact.promoted_session, 2 + 3);
+        assert_eq!(
+            crate::experiment::recorded_variant(&conn, "terse", "b-src").unwrap(),
+            Some("short".to_string())
+        );
+    }
+
+    #[test]
+    fn import_into_empty_dir_keeps_session_ids() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = data_dir(&tmp.path().join("src"));
+        let conn = db::open_or_create(&src.db()).unwrap();
+        add_session(&src, &conn, 7, "{}\n");
+        drop(conn);
+        let bundle = tmp.path().join("run.bsa");
+        export(&src, &bundle).unwrap();
+
+        let dst = DataDir::new(tmp.path().join("fresh"));
+        let summary = import(&dst, &bundle).unwrap();
+        assert_eq!(summary.session_shift, 0);
+        assert!(dst.sessions_dir().join("7.jsonl.zst").exists());
+        assert_eq!(std::fs::read_to_string(dst.counter()).unwrap(), "8");
+        assert_eq!(summary.config, Some(dst.config().display().to_string()));
+    }
+
+    #[test]
+    fn import_merges_epochs_by_name() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = data_dir(&tmp.path().join("src"));
+        let conn = db::open_or_create(&src.db()).unwrap();
+        crate::epoch::start(&conn, Some("sprint-1"), None).unwrap();
+        crate::epoch::close(&conn, None).unwrap();
+        crate::epoch::start(&conn, Some("sprint-2"), None).unwrap();
+        drop(conn);
+        let bundle = tmp.path().join("run.bsa");
+        export(&src, &bundle).unwrap();
+
+        // Target's own epoch holds id 1 under a different name, and it
+        // already has sprint-2.
+        let dst = data_dir(&tmp.path().join("dst"));
+        let conn = db::open_or_create(&dst.db()).unwrap();
+        crate::epoch::start(&conn, Some("local"), None).unwrap();
+        crate::epoch::close(&conn, None).unwrap();
+        crate::epoch::start(&conn, Some("sprint-2"), None).unwrap();
+        drop(conn);
+
+        import(&dst, &bundle).unwrap();
+        let conn = db::open_or_create(&dst.db()).unwrap();
+        let names: Vec<String> = conn
+            .prepare("SELECT name FROM epochs ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(names, vec!["local", "sprint-2", "sprint-1"]);
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_archives() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bogus = tmp.path().join("bogus.bsa");
+        std::fs::write(&bogus, zstd::encode_all(&b"hello\n"[..], 3).unwrap()).unwrap();
+        let err = import(&DataDir::new(tmp.path().join("dd")), &bogus).unwrap_err();
+        assert!(err.contains("not a blacksmith archive"), "{err}");
+    }
+}
@@ -1,13 +1,15 @@
 //! Boundary violation detector for module imports.
 //!
 //! Detects modules reaching into each other's internals rather than using
-//! public APIs. For each cross-module `use crate::` import, checks whether
+//! public APIs. For each cross-module import (`use crate::` in Rust, named
+//! imports in TypeScript/Python via their `LanguageFrontend`), checks whether
 //! the imported symbol is part of the target module's public API surface.
 //! Non-public symbol accesses are reported as boundary violations.
 
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use crate::lang::{self, SourceIndex};
 use crate::module_detect::Module;
 use crate::public_api::ModuleApi;
 
@@ -28,14 +30,20 @@ pub struct BoundaryViolation {
 
 /// Detect boundary violations across all modules.
 ///
-/// For each file in each module, parses `use crate::` imports, resolves the
-/// target module, and checks whether the imported symbol appears in the target
+/// For each file in each module, parses its imports, resolves the target
+/// module, and checks whether the imported symbol appears in the target
 /// module's public API. If not, it is a boundary violation.
+///
+/// Rust files are parsed for `use crate::` paths; other languages resolve
+/// imports to files through their frontend, and the target module is the one
+/// owning the imported file.
 pub fn detect_boundary_violations(
     modules: &HashMap<String, Module>,
     apis: &HashMap<String, ModuleApi>,
 ) -> Vec<BoundaryViolation> {
     let file_to_module = build_file_to_module(modules);
+    let all_files: Vec<PathBuf> = file_to_module.keys().cloned().collect();
+    let index = SourceIndex::new(&all_files);
     let mut violations = Vec::new();
 
     for (module_name, module) in modules {
@@ -50,16 +58,21 @@ pub fn detect_boundary_violations(
                 .map(|f| f.to_string_lossy().to_string())
                 .unwrap_or_default();
 
-            let imports = extract_cross_module_imports(&content);
-
-            for import in imports {
-                // Resolve which module this import targets
-                let target_module_name =
-                    match resolve_target_module(&import.module_path, modules, &file_to_module) {
-                        Some(name) => name,
-                        None => continue,
-                    };
+            let imports = if file_path.extension().is_some_and(|e| e == "rs") {
+                extract_cross_module_imports(&content)
+                    .into_iter()
+                    .filter_map(|import| {
+                        // Resolve which module this import targets
+                        let target =
+                            resolve_target_module(&import.module_path, modules, &file_to_module)?;
+                        Some((target, import.symbols, import.raw_line))
+                    })
+                    .collect()
+            } else {
+                frontend_imports(file_path, &content, &index, &file_to_module)
+            };
 
+            for (target_module_name, symbols, raw_line) in imports {
                 // Skip intra-module imports
                 if target_module_name == *module_name {
                     continue;
@@ -77,7 +90,7 @@ pub fn detect_boundary_violations(
                     .map(|s| s.name.as_str())
                     .collect();
 
-                for sym_name in &import.symbols {
+                for sym_name in &symbols {
                     // Glob imports and group-level imports without specific symbols can't be checked
                     if sym_name == "*" || sym_name.starts_with('{') {
                         continue;
@@ -89,7 +102,7 @@ pub fn detect_boundary_violations(
                             target_module: target_module_name.clone(),
                             symbol: sym_name.clone(),
                             source_file: file_name.clone(),
-                            import_line: import.raw_line.clone(),
+                            import_line: raw_line.clone(),
                         });
                     }
                 }
@@ -107,6 +120,26 @@ pub fn detect_boundary_violations(
     violations
 }
 
+/// Named imports of a non-Rust file as `(target module, symbols, import line)`.
+fn frontend_imports(
+    file: &Path,
+    content: &str,
+    index: &SourceIndex,
+    file_to_module: &HashMap<PathBuf, String>,
+) -> Vec<(String, Vec<String>, String)> {
+    let Some(frontend) = lang::for_path(file) else {
+        return Vec::new();
+    };
+    frontend
+        .imports(file, content, index)
+        .into_iter()
+        .filter_map(|import| {
+            let target = file_to_module.get(&import.target)?;
+            Some((target.clone(), import.symbols, import.line))
+        })
+        .collect()
+}
+
 /// A parsed cross-module import statement.
 #[derive(Debug, Clone)]
 struct CrossModuleImport {
@@ -1,5 +1,7 @@
 use crate::config::{MetricsTargetsConfig, TargetRule};
 use crate::db;
+use crate::impact;
+use std::collections::BTreeMap;
 use std::path::Path;
 
 /// Handle the `brief` subcommand.
@@ -26,7 +28,7 @@ pub fn handle_brief(
 /// - The database has no improvements and no observations
 ///
 /// Otherwise returns the formatted brief with performance feedback,
-/// target warnings, and/or improvements.
+/// target warnings, flagged promoted rules, and/or improvements.
 pub fn generate_brief(
     db_path: &Path,
     targets_config: Option<&MetricsTargetsConfig>,
@@ -77,6 +79,32 @@ pub fn generate_brief(
         }
     }
 
+    // Gate failures section: which finish gates have been failing lately
+    let gate_warnings = format_gate_failures(&conn)?;
+    if !gate_warnings.is_empty() {
+        if !output.is_empty() {
+            output.push_str("\n\n");
+        }
+        output.push_str(&gate_warnings);
+    }
+
+    // Promoted rules that didn't measurably move their target metric
+    let flagged = impact::flagged(&conn).map_err(|e| format!("Failed to query impact: {e}"))?;
+    if !flagged.is_empty() {
+        if !output.is_empty() {
+            output.push_str("\n\n");
+        }
+        output.push_str("## PROMOTED RULES WITHOUT MEASURABLE BENEFIT (consider removing)\n");
+        for (imp, impact) in &flagged {
+            output.push_str(&format!(
+                "\n{} {}: {}",
+                imp.ref_id,
+                imp.title,
+                impact.describe()
+            ));
+        }
+    }
+
     // Open improvements section
     let open = db::list_improvements(&conn, Some("open"), None)
         .map_err(|e| format!("Failed to query improvements: {e}"))?;
@@ -105,6 +133,39 @@ pub fn generate_brief(
     Ok(output)
 }
 
+/// Number of recent gate results considered for the brief's gate section.
+const GATE_FAILURE_WINDOW: u32 = 50;
+
+/// Format the gate failures section from recent `blacksmith finish` results.
+/// Returns an empty string when no gate has failed in the window.
+fn format_gate_failures(conn: &rusqlite::Connection) -> Result<String, String> {
+    let stats = crate::gate_result::gate_stats(conn, GATE_FAILURE_WINDOW)
+        .map_err(|e| format!("Failed to query gate results: {e}"))?;
+    let failing: Vec<_> = stats.iter().filter(|s| s.failure_rate() > 0.0).collect();
+    if failing.is_empty() {
+        return Ok(String::new());
+    }
+
+    let mut out = String::from("## GATE FAILURES (recent finish runs)\n");
+    for s in failing {
+        let executed = s.runs - s.skipped;
+        out.push_str(&format!(
+            "\n{}: {} of {} failed ({:.0}%)",
+            s.gate,
+            s.failures + s.allowed_failures,
+            executed,
+            s.failure_rate() * 100.0
+        ));
+        if s.allowed_failures > 0 {
+            out.push_str(&format!(", {} allowed", s.allowed_failures));
+        }
+        if s.timeouts > 0 {
+            out.push_str(&format!(", {} timed out", s.timeouts));
+        }
+    }
+    Ok(out)
+}
+
 /// Evaluate a single target rule against one observation's data.
 /// Returns Some(actual_value) if the target is missed, None if it passes or has no data.
 fn evaluate_single_observation(rule: &TargetRule, data: &serde_json::Value) -> Option<f64> {
@@ -294,12 +355,55 @@ fn format_performance_feedback(session: i64, data: &serde_json::Value) -> String
     output.push_str(&format!("  Turns: {}\n", turns));
     output.push_str(&format!("  Narration-only turns: {}%\n", narr_pct));
     output.push_str(&format!("  Parallel tool calls: {}%\n", par_pct));
-    output.push_str(&format!("  Cost: ${:.2}\n", cost));
+    // Reported cost, plus cost computed from the pricing table when known
+    match data["cost.computed_usd"].as_f64() {
+        Some(computed) => output.push_str(&format!(
+            "  Cost: ${:.2} (computed ${:.2})\n",
+            cost, computed
+        )),
+        None => output.push_str(&format!("  Cost: ${:.2}\n", cost)),
+    }
+    if let Some(model) = data["session.model"].as_str() {
+        output.push_str(&format!("  Model: {}\n", model));
+    }
+    output.push_str(&format_cost_by_model(data));
     output.push_str(&format!("  Duration: {}", duration_str));
 
     output
 }
 
+/// One line per model: the cost the agent reported for it
+/// (`cost.model_tokens`) and the cost computed from the pricing table
+/// (`cost.computed_by_model`). Empty unless the session used several models.
+fn format_cost_by_model(data: &serde_json::Value) -> String {
+    let mut models: BTreeMap<&str, (Option<f64>, Option<f64>)> = BTreeMap::new();
+    if let Some(tokens) = data["cost.model_tokens"].as_object() {
+        for (model, usage) in tokens {
+            models.entry(model).or_default().0 = usage["cost_usd"].as_f64();
+        }
+    }
+    if let Some(computed) = data["cost.computed_by_model"].as_object() {
+        for (model, cost) in computed {
+            models.entry(model).or_default().1 = cost.as_f64();
+        }
+    }
+    if models.len() < 2 {
+        return String::new();
+    }
+
+    let mut output = String::from("  Cost by model:\n");
+    for (model, (reported, computed)) in models {
+        let line = match (reported, computed) {
+            (Some(r), Some(c)) => format!("${:.2} (computed ${:.2})", r, c),
+            (Some(r), None) => format!("${:.2}", r),
+            (None, Some(c)) => format!("computed ${:.2}", c),
+            (None, None) => "unknown".to_string(),
+        };
+        output.push_str(&format!("    {}: {}\n", model, line));
+    }
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -368,6 +472,35 @@ mod tests {
         assert!(!text.contains("Promoted item"));
     }
 
+    #[test]
+    fn brief_flags_promoted_rules_without_effect() {
+        let (_dir, path) = test_db_path();
+        let conn = db::open_or_create(&path).unwrap();
+
+        let observe = |session: i64, narration: i64| {
+            let data = format!(r#"{{"turns.narration_only":{narration}}}"#);
+            db::upsert_observation(&conn, session, "2026-01-01T00:00:00Z", None, None, &data)
+                .unwrap();
+        };
+        for (s, v) in [(0, 3), (1, 5), (2, 4)] {
+            observe(s, v);
+        }
+        let r =
+            db::insert_improvement(&conn, "workflow", "No narration", None, None, None).unwrap();
+        db::set_improvement_target(&conn, &r, "turns.narration_only", "below").unwrap();
+        db::update_improvement(&conn, &r, Some("promoted"), None, None, None).unwrap();
+        impact::record_baseline(&conn, &r, 3).unwrap();
+        for (s, v) in [(3, 4), (4, 5), (5, 3)] {
+            observe(s, v);
+        }
+        impact::evaluate_pending(&conn, 3).unwrap();
+
+        drop(conn);
+        let text = generate_brief(&path, None, None).unwrap();
+        assert!(text.contains("## PROMOTED RULES WITHOUT MEASURABLE BENEFIT"));
+        assert!(text.contains("R1 No narration: turns.narration_only 4.00 -> 4.00"));
+    }
+
     #[test]
     fn brief_format_matches_prd() {
         let (_dir, path) = test_db_path();
@@ -404,6 +537,31 @@ mod tests {
         assert!(text.contains("R1 [code-quality] Use ESLint --fix in pre-commit hook"));
     }
 
+    #[test]
+    fn brief_shows_failing_gates() {
+        let (_dir, path) = test_db_path();
+        let conn = db::open_or_create(&path).unwrap();
+        let outcome = |name: &str, status| crate::gates::GateOutcome {
+            name: name.to_string(),
+            status,
+            duration_ms: 10,
+            failed_command: None,
+            output: String::new(),
+            timed_out: false,
+            test_report: None,
+        };
+        use crate::gates::GateStatus;
+        crate::gate_result::record(&conn, "b1", &outcome("check", GateStatus::Passed)).unwrap();
+        crate::gate_result::record(&conn, "b1", &outcome("test", GateStatus::Failed)).unwrap();
+        crate::gate_result::record(&conn, "b2", &outcome("test", GateStatus::Passed)).unwrap();
+        drop(conn);
+
+        let text = generate_brief(&path, None, None).unwrap();
+        assert!(text.contains("## GATE FAILURES"));
+        assert!(text.contains("test: 1 of 2 failed (50%)"));
+        assert!(!text.contains("check:"));
+    }
+
     // ── Performance feedback tests ──────────────────────────────────────
 
     #[test]
@@ -534,6 +692,40 @@ mod tests {
         assert!(result.contains("Duration: 5m00s"));
     }
 
+    #[test]
+    fn format_performance_feedback_shows_computed_cost_and_model() {
+        let data = serde_json::json!({
+            "turns.total": 10,
+            "cost.computed_usd": 2.5,
+            "session.model": "gpt-5-codex",
+            "session.duration_ms": 60000,
+        });
+
+        let result = format_performance_feedback(3, &data);
+        assert!(result.contains("Cost: $0.00 (computed $2.50)"));
+        assert!(result.contains("Model: gpt-5-codex"));
+        assert!(!result.contains("Cost by model"));
+
+        let data = serde_json::json!({
+            "turns.total": 10,
+            "cost.estimate_usd": 1.3,
+            "cost.computed_usd": 1.25,
+            "session.model": "claude-sonnet-4-5",
+            "cost.model_tokens": {
+                "claude-sonnet-4-5": {"input_tokens": 1000, "cost_usd": 1.2},
+                "claude-haiku-4-5": {"input_tokens": 500, "cost_usd": 0.1},
+            },
+            "cost.computed_by_model": {
+                "claude-sonnet-4-5": 1.15,
+                "claude-haiku-4-5": 0.1,
+            },
+        });
+        let result = format_performance_feedback(4, &data);
+        assert!(result.contains("Cost by model:"));
+        assert!(result.contains("    claude-haiku-4-5: $0.10 (computed $0.10)"));
+        assert!(result.contains("    claude-sonnet-4-5: $1.20 (computed $1.15)"));
+    }
+
     // ── Target warnings tests ──────────────────────────────────────────
 
     fn make_rule(
@@ -0,0 +1,360 @@
+//! Spend budgets: daily, per-bead and per-run cost ceilings.
+//!
+//! Spend is summed from ingested observations, so it lags by whatever
+//! sessions are still running. The coordinator checks the limits before each
+//! spawn: a bead over a per-bead limit is skipped, and a daily or per-run
+//! limit drains with `CoordinatorExitReason::BudgetExceeded`;
+//! `blacksmith status` reports what remains.
+
+use crate::config::BudgetConfig;
+use rusqlite::{params, Connection};
+use serde_json::Value;
+
+/// Cost attributed to one session for budget purposes.
+///
+/// Uses the larger of the agent-reported cost (`cost.estimate_usd`) and the
+/// cost computed from the pricing table (`cost.computed_usd`), so a missing
+/// or under-reported figure never lets spend slip past a ceiling.
+pub fn session_cost(data: &Value) -> f64 {
+    let reported = data["cost.estimate_usd"].as_f64().unwrap_or(0.0);
+    let computed = data["cost.computed_usd"].as_f64().unwrap_or(0.0);
+    reported.max(computed)
+}
+
+/// Start of the current UTC day, in observation timestamp format.
+pub fn today_start() -> String {
+    chrono::Utc::now().format("%Y-%m-%dT00:00:00Z").to_string()
+}
+
+/// Total spend of sessions ingested at or after `since` (an RFC 3339 UTC
+/// timestamp in the observations' `%Y-%m-%dT%H:%M:%SZ` format).
+pub fn spend_since(conn: &Connection, since: &str) -> rusqlite::Result<f64> {
+    let mut stmt = conn.prepare("SELECT data FROM observations WHERE ts >= ?1")?;
+    let rows = stmt.query_map(params![since], |row| row.get::<_, String>(0))?;
+    let mut total = 0.0;
+    for data in rows {
+        let data: Value = serde_json::from_str(&data?).unwrap_or_default();
+        total += session_cost(&data);
+    }
+    Ok(total)
+}
+
+/// Spend and session count for one bead.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BeadSpend {
+    pub cost_usd: f64,
+    pub sessions: u32,
+}
+
+/// Sum the cost of all ingested sessions attributed to a bead
+/// (via `session.bead_id` events).
+pub fn bead_spend(conn: &Connection, bead_id: &str) -> rusqlite::Result<BeadSpend> {
+    let mut stmt = conn.prepare(
+        "SELECT data FROM observations WHERE session IN
+            (SELECT session FROM events WHERE kind = 'session.bead_id' AND value = ?1)",
+    )?;
+    let rows = stmt.query_map(params![bead_id], |row| row.get::<_, String>(0))?;
+    let mut spend = BeadSpend::default();
+    for data in rows {
+        let data: Value = serde_json::from_str(&data?).unwrap_or_default();
+        spend.cost_usd += session_cost(&data);
+        spend.sessions += 1;
+    }
+    Ok(spend)
+}
+
+/// Check the run-wide limits (daily and per-run).
+///
+/// `run_started` is the coordinator's start time in observation timestamp
+/// format. Returns a human-readable reason when a limit has been reached.
+pub fn check_run(
+    budget: &BudgetConfig,
+    conn: &Connection,
+    run_started: &str,
+) -> rusqlite::Result<Option<String>> {
+    if budget.daily_usd > 0.0 {
+        let today = spend_since(conn, &today_start())?;
+        if today >= budget.daily_usd {
+            return Ok(Some(format!(
+                "daily budget reached: ${today:.2} spent of ${:.2}",
+                budget.daily_usd
+            )));
+        }
+    }
+    if budget.per_run_usd > 0.0 {
+        let run = spend_since(conn, run_started)?;
+        if run >= budget.per_run_usd {
+            return Ok(Some(format!(
+                "per-run budget reached: ${run:.2} spent of ${:.2}",
+                budget.per_run_usd
+            )));
+        }
+    }
+    Ok(None)
+}
+
+/// Check the per-bead limits (cost and session count) before spawning a
+/// worker for `bead_id`. Returns a human-readable reason when a limit has
+/// been reached.
+pub fn check_bead(
+    budget: &BudgetConfig,
+    conn: &Connection,
+    bead_id: &str,
+) -> rusqlite::Result<Option<String>> {
+    if budget.per_bead_usd <= 0.0 && budget.max_sessions_per_bead == 0 {
+        return Ok(None);
+    }
+    let spend = bead_spend(conn, bead_id)?;
+    if budget.per_bead_usd > 0.0 && spend.cost_usd >= budget.per_bead_usd {
+        return Ok(Some(format!(
+            "per-bead budget reached for {bead_id}: ${:.2} spent of ${:.2}",
+            spend.cost_usd, budget.per_bead_usd
+        )));
+    }
+    if budget.max_sessions_per_bead > 0 && spend.sessions >= budget.max_sessions_per_bead {
+        return Ok(Some(format!(
+            "session limit reached for {bead_id}: {} of {} sessions",
+            spend.sessions, budget.max_sessions_per_bead
+        )));
+    }
+    Ok(None)
+}
+
+/// Format remaining budget for `blacksmith status`.
+///
+/// `run_cost` is the current run's spend from the status file. Returns None
+/// when no limits are configured.
+pub fn format_remaining(budget: &BudgetConfig, conn: &Connection, run_cost: f64) -> Option<String> {
+    if !budget.is_enabled() {
+        return None;
+    }
+    let mut parts = Vec::new();
+    if budget.daily_usd > 0.0 {
+        let today = spend_since(conn, &today_start()).unwrap_or(0.0);
+        parts.push(format!(
+            "today ${:.2} of ${:.2} (${:.2} left)",
+            today,
+            budget.daily_usd,
+            (budget.daily_usd - today).max(0.0)
+        ));
+    }
+    if budget.per_run_usd > 0.0 {
+        parts.push(format!(
+            "run ${:.2} of ${:.2} (${:.2} left)",
+            run_cost,
+            budget.per_run_usd,
+            (budget.per_run_usd - run_cost).max(0.0)
+        ));
+    }
+    if budget.per_bead_usd > 0.0 {
+        parts.push(format!("${:.2}/bead", budget.per_bead_usd));
+    }
+    if budget.max_sessions_per_bead > 0 {
+        parts.push(format!("{} sessions/bead", budget.max_sessions_per_bead));
+    }
+    Some(format!("Budget: {}", parts.join(" | ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use tempfile::TempDir;
+
+    fn test_db() -> (TempDir, Connection) {
+        let dir = TempDir::new().unwrap();
+        let conn = db::open_or_create(&dir.path().join("test.db")).unwrap();
+        (dir, conn)
+    }
+
+    fn observe(conn: &Connection, session: i64, ts: &str, data: &str) {
+        db::upsert_observation(conn, session, ts, Some(60), None, data).unwrap();
+    }
+
+    fn attribute(conn: &Connection, session: i64, bead_id: &str) {
+        db::insert_event_with_ts(
+            conn,
+            "2026-01-01T00:00:00Z",
+            session,
+            "session.bead_id",
+            Some(bead_id),
+            None,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn session_cost_takes_larger_of_reported_and_computed() {
+        let data = serde_json::json!({"cost.estimate_usd": 1.0, "cost.computed_usd": 1.5});
+        assert_eq!(session_cost(&data), 1.5);
+        let data = serde_json::json!({"cost.estimate_usd": 2.0});
+        assert_eq!(session_cost(&data), 2.0);
+        assert_eq!(session_cost(&serde_json::json!({})), 0.0);
+    }
+
+    #[test]
+    fn spend_since_filters_by_timestamp() {
+        let (_dir, conn) = test_db();
+        observe(
+            &conn,
+            1,
+            "2026-01-01T10:00:00Z",
+            r#"{"cost.estimate_usd":1.0}"#,
+        );
+        observe(
+            &conn,
+            2,
+            "2026-01-02T10:00:00Z",
+            r#"{"cost.computed_usd":2.5}"#,
+        );
+        assert_eq!(spend_since(&conn, "2026-01-01T00:00:00Z").unwrap(), 3.5);
+        assert_eq!(spend_since(&conn, "2026-01-02T00:00:00Z").unwrap(), 2.5);
+    }
+
+    #[test]
+    fn bead_spend_sums_attributed_sessions() {
+        let (_dir, conn) = test_db();
+        observe(
+            &conn,
+            1,
+            "2026-01-01T10:00:00Z",
+            r#"{"cost.estimate_usd":1.0}"#,
+        );
+        observe(
+            &conn,
+            2,
+            "2026-01-01T11:00:00Z",
+            r#"{"cost.estimate_usd":2.0}"#,
+        );
+        observe(
+            &conn,
+            3,
+            "2026-01-01T12:00:00Z",
+            r#"{"cost.estimate_usd":4.0}"#,
+        );
+        attribute(&conn, 1, "bd-1");
+        attribute(&conn, 2, "bd-1");
+        attribute(&conn, 3, "bd-2");
+        let spend = bead_spend(&conn, "bd-1").unwrap();
+        assert_eq!(spend.cost_usd, 3.0);
+        assert_eq!(spend.sessions, 2);
+        assert_eq!(bead_spend(&conn, "bd-none").unwrap(), BeadSpend::default());
+    }
+
+    #[test]
+    fn check_run_disabled_by_default() {
+        let (_dir, conn) = test_db();
+        observe(&conn, 1, &today_start(), r#"{"cost.estimate_usd":100.0}"#);
+        let budget = BudgetConfig::default();
+        assert!(check_run(&budget, &conn, "2000-01-01T00:00:00Z")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn check_run_daily_limit() {
+        let (_dir, conn) = test_db();
+        observe(&conn, 1, &today_start(), r#"{"cost.estimate_usd":6.0}"#);
+        let mut budget = BudgetConfig {
+            daily_usd: 10.0,
+            ..Default::default()
+        };
+        assert!(check_run(&budget, &conn, "2000-01-01T00:00:00Z")
+            .unwrap()
+            .is_none());
+        budget.daily_usd = 5.0;
+        let reason = check_run(&budget, &conn, "2000-01-01T00:00:00Z")
+            .unwrap()
+            .unwrap();
+        assert!(reason.contains("daily budget reached"));
+    }
+
+    #[test]
+    fn check_run_per_run_limit_counts_only_this_run() {
+        let (_dir, conn) = test_db();
+        observe(
+            &conn,
+            1,
+            "2026-01-01T09:00:00Z",
+            r#"{"cost.estimate_usd":50.0}"#,
+        );
+        observe(
+            &conn,
+            2,
+            "2026-01-01T11:00:00Z",
+            r#"{"cost.estimate_usd":3.0}"#,
+        );
+        let budget = BudgetConfig {
+            per_run_usd: 5.0,
+            ..Default::default()
+        };
+        assert!(check_run(&budget, &conn, "2026-01-01T10:00:00Z")
+            .unwrap()
+            .is_none());
+        observe(
+            &conn,
+            3,
+            "2026-01-01T12:00:00Z",
+            r#"{"cost.estimate_usd":2.0}"#,
+        );
+        let reason = check_run(&budget, &conn, "2026-01-01T10:00:00Z")
+            .unwrap()
+            .unwrap();
+        assert!(reason.contains("per-run budget reached: $5.00"));
+    }
+
+    #[test]
+    fn check_bead_cost_and_session_limits() {
+        let (_dir, conn) = test_db();
+        observe(
+            &conn,
+            1,
+            "2026-01-01T10:00:00Z",
+            r#"{"cost.estimate_usd":1.0}"#,
+        );
+        observe(
+            &conn,
+            2,
+            "2026-01-01T11:00:00Z",
+            r#"{"cost.estimate_usd":1.0}"#,
+        );
+        attribute(&conn, 1, "bd-1");
+        attribute(&conn, 2, "bd-1");
+
+        let cost_limit = BudgetConfig {
+            per_bead_usd: 2.0,
+            ..Default::default()
+        };
+        let reason = check_bead(&cost_limit, &conn, "bd-1").unwrap().unwrap();
+        assert!(reason.contains("per-bead budget reached for bd-1"));
+        assert!(check_bead(&cost_limit, &conn, "bd-2").unwrap().is_none());
+
+        let session_limit = BudgetConfig {
+            max_sessions_per_bead: 3,
+            ..Default::default()
+        };
+        assert!(check_bead(&session_limit, &conn, "bd-1").unwrap().is_none());
+        observe(&conn, 3, "2026-01-01T12:00:00Z", r#"{}"#);
+        attribute(&conn, 3, "bd-1");
+        let reason = check_bead(&session_limit, &conn, "bd-1").unwrap().unwrap();
+        assert!(reason.contains("session limit reached for bd-1: 3 of 3"));
+    }
+
+    #[test]
+    fn format_remaining_lists_configured_limits() {
+        let (_dir, conn) = test_db();
+        assert!(format_remaining(&BudgetConfig::default(), &conn, 0.0).is_none());
+
+        observe(&conn, 1, &today_start(), r#"{"cost.estimate_usd":4.0}"#);
+        let budget = BudgetConfig {
+            daily_usd: 10.0,
+            per_run_usd: 3.0,
+            max_sessions_per_bead: 5,
+            ..Default::default()
+        };
+        let line = format_remaining(&budget, &conn, 3.5).unwrap();
+        assert!(line.contains("today $4.00 of $10.00 ($6.00 left)"));
+        assert!(line.contains("run $3.50 of $3.00 ($0.00 left)"));
+        assert!(line.contains("5 sessions/bead"));
+    }
+}
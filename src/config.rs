@@ -22,12 +22,17 @@ pub struct HarnessConfig {
     pub metrics: MetricsConfig,
     pub storage: StorageConfig,
     pub workers: WorkersConfig,
+    pub integration: IntegrationConfig,
     pub reconciliation: ReconciliationConfig,
     pub architecture: ArchitectureConfig,
     pub quality_gates: QualityGatesConfig,
     pub improvements: ImprovementsConfig,
     pub serve: ServeConfig,
     pub speck_validate: SpeckValidateConfig,
+    pub pricing: PricingConfig,
+    pub budget: BudgetConfig,
+    pub experiment: ExperimentConfig,
+    pub planning: PlanningConfig,
 }
 
 impl HarnessConfig {
@@ -214,6 +219,9 @@ pub struct AgentConfig {
     pub integration: Option<AgentPhaseConfig>,
     /// Phase-specific config for analysis tasks. Falls back to coding if omitted.
     pub analysis: Option<AgentPhaseConfig>,
+    /// Phase-specific config for planning (bead decomposition) tasks. Falls
+    /// back to coding if omitted.
+    pub planning: Option<AgentPhaseConfig>,
 }
 
 /// Phase-specific agent configuration (used in [agent.coding] and [agent.integration]).
@@ -277,38 +285,24 @@ impl AgentConfig {
     /// Resolve the effective agent config for integration tasks.
     /// Falls back: [agent.integration] → [agent.coding] → flat [agent].
     pub fn resolved_integration(&self) -> ResolvedAgentConfig {
-        match &self.integration {
-            Some(phase) => {
-                let coding = self.resolved_coding();
-                let mut env = coding.env;
-                if let Some(phase_env) = &phase.env {
-                    env.extend(phase_env.iter().map(|(k, v)| (k.clone(), v.clone())));
-                }
-                let mut args = phase.args.clone().unwrap_or(coding.args);
-                if let Some(model) = &phase.model {
-                    args.push("--model".to_string());
-                    args.push(model.clone());
-                }
-                ResolvedAgentConfig {
-                    command: phase
-                        .command
-                        .as_deref()
-                        .unwrap_or(&coding.command)
-                        .to_string(),
-                    args,
-                    adapter: phase.adapter.clone().or(coding.adapter),
-                    prompt_via: phase.prompt_via.clone().unwrap_or(coding.prompt_via),
-                    env,
-                }
-            }
-            None => self.resolved_coding(),
-        }
+        self.resolved_over_coding(self.integration.as_ref())
     }
 
     /// Resolve the effective agent config for analysis tasks.
     /// Falls back: [agent.analysis] → [agent.coding] → flat [agent].
     pub fn resolved_analysis(&self) -> ResolvedAgentConfig {
-        match &self.analysis {
+        self.resolved_over_coding(self.analysis.as_ref())
+    }
+
+    /// Resolve the effective agent config for planning tasks.
+    /// Falls back: [agent.planning] → [agent.coding] → flat [agent].
+    pub fn resolved_planning(&self) -> ResolvedAgentConfig {
+        self.resolved_over_coding(self.planning.as_ref())
+    }
+
+    /// Layer a phase section over the resolved coding config.
+    fn resolved_over_coding(&self, phase: Option<&AgentPhaseConfig>) -> ResolvedAgentConfig {
+        match phase {
             Some(phase) => {
                 let coding = self.resolved_coding();
                 let mut env = coding.env;
@@ -580,6 +574,32 @@ pub struct WorkersConfig {
     pub worktrees_dir: String,
     /// When true, the coordinator polls indefinitely instead of exiting after no-work cycles.
     pub persistent: bool,
+    /// Keep one worktree per worker slot and reset it to the base branch
+    /// between beads instead of creating a fresh worktree each time.
+    pub reuse_worktrees: bool,
+    /// Give each worker slot its own persistent `CARGO_TARGET_DIR` under
+    /// the data directory so incremental builds survive across beads.
+    pub shared_target_dir: bool,
+    /// Directories (relative to the repo root) copied into new worktrees
+    /// before the agent starts, e.g. `["target", "node_modules"]`. Copies use
+    /// copy-on-write clones where the filesystem supports them.
+    pub seed_dirs: Vec<String>,
+    /// Template checkout to seed from. Defaults to the main repository.
+    pub seed_from: Option<PathBuf>,
+    /// Shell commands run in the worktree after provisioning and before the
+    /// agent starts, e.g. `pnpm install --frozen-lockfile`.
+    pub setup: Vec<String>,
+    /// Replay a bead's salvaged work (see `blacksmith salvage`) into its
+    /// worktree when the bead is retried.
+    pub resume_salvage: bool,
+    /// Only schedule beads carrying this label (e.g. `ready-for-agent`), so
+    /// beads a human is still drafting aren't picked up.
+    pub ready_label: Option<String>,
+    /// Only schedule beads not updated for at least this many minutes.
+    /// 0 = no minimum. With `ready_label` also set, either one qualifies.
+    pub ready_after_mins: u64,
+    /// Never schedule beads carrying this label.
+    pub hold_label: String,
 }
 
 impl Default for WorkersConfig {
@@ -589,6 +609,15 @@ impl Default for WorkersConfig {
             base_branch: "main".to_string(),
             worktrees_dir: "worktrees".to_string(),
             persistent: false,
+            reuse_worktrees: false,
+            shared_target_dir: false,
+            seed_dirs: Vec::new(),
+            seed_from: None,
+            setup: Vec::new(),
+            resume_salvage: false,
+            ready_label: None,
+            ready_after_mins: 0,
+            hold_label: "hold".to_string(),
         }
     }
 }
@@ -602,11 +631,27 @@ impl Default for WorkersConfig {
 pub struct ReconciliationConfig {
     /// Run the full test suite every N successful integrations. Default: 3.
     pub every: u32,
+    /// Re-run each failing test in isolation this many times; a test that
+    /// passes on any re-run is classified flaky and not blamed on recent
+    /// beads. 0 = no re-runs. Default: 2.
+    pub flaky_reruns: u32,
+    /// Command to re-run one test, with `{test}` replaced by the test name.
+    /// Default: inferred from the test output format (`cargo test --release
+    /// -- --exact {test}` for libtest, `python -m pytest {test}` for pytest).
+    pub rerun_command: Option<String>,
+    /// Number of recent runs per test considered when classifying a test
+    /// as flaky and in `blacksmith tests flaky`. Default: 20.
+    pub flaky_window: u32,
 }
 
 impl Default for ReconciliationConfig {
     fn default() -> Self {
-        Self { every: 3 }
+        Self {
+            every: 3,
+            flaky_reruns: 2,
+            rerun_command: None,
+            flaky_window: 20,
+        }
     }
 }
 
@@ -639,6 +684,51 @@ pub struct ArchitectureConfig {
     /// automatically approved and queued, or require human confirmation.
     /// Default: false
     pub refactor_auto_approve: bool,
+    /// Run an architecture review in the coordinator after every N
+    /// successful integrations. 0 disables the periodic review. Default: 10
+    pub review_every: u32,
+    /// Fitness rules checked by the integrator on the merged worktree against
+    /// the base commit (`[[architecture.fitness]]`). A violation fails the
+    /// integration and is fed back to the integration agent. Default: none
+    pub fitness: Vec<FitnessRule>,
+}
+
+/// A declarative architecture fitness rule.
+///
+/// ```toml
+/// [[architecture.fitness]]
+/// rule = "forbid_import"   # module `from` must not import module `to`
+/// from = "adapters"
+/// to = "coordinator"
+///
+/// [[architecture.fitness]]
+/// rule = "no_new_cycles"
+///
+/// [[architecture.fitness]]
+/// rule = "max_file_lines"  # no file may grow past `max` lines
+/// max = 800
+///
+/// [[architecture.fitness]]
+/// rule = "max_api_growth"  # `module` may gain at most `max` public symbols per bead
+/// module = "db"
+/// max = 3
+/// ```
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct FitnessRule {
+    /// Rule kind: "forbid_import", "no_new_cycles", "max_file_lines", "max_api_growth"
+    pub rule: String,
+    /// For forbid_import: the importing module
+    #[serde(default)]
+    pub from: Option<String>,
+    /// For forbid_import: the module that must not be imported
+    #[serde(default)]
+    pub to: Option<String>,
+    /// For max_api_growth: the module whose public API is limited
+    #[serde(default)]
+    pub module: Option<String>,
+    /// For max_file_lines / max_api_growth: the limit
+    #[serde(default)]
+    pub max: Option<usize>,
 }
 
 impl Default for ArchitectureConfig {
@@ -650,6 +740,8 @@ impl Default for ArchitectureConfig {
             expansion_event_window: 20,
             metadata_drift_sensitivity: 3.0,
             refactor_auto_approve: false,
+            review_every: 10,
+            fitness: Vec::new(),
         }
     }
 }
@@ -665,6 +757,8 @@ impl ArchitectureConfig {
             expansion_event_window: 30,
             metadata_drift_sensitivity: 5.0,
             refactor_auto_approve: false,
+            review_every: 20,
+            fitness: Vec::new(),
         }
     }
 
@@ -677,6 +771,8 @@ impl ArchitectureConfig {
             expansion_event_window: 15,
             metadata_drift_sensitivity: 2.0,
             refactor_auto_approve: true,
+            review_every: 5,
+            fitness: Vec::new(),
         }
     }
 }
@@ -725,6 +821,9 @@ pub struct QualityGatesConfig {
     pub lint: Vec<String>,
     /// Commands to check formatting. Default: `["cargo fmt --check"]`
     pub format: Vec<String>,
+    /// Named gate pipeline (`[[quality_gates.gates]]`). When non-empty it
+    /// replaces the check/test/lint/format lists above.
+    pub gates: Vec<GateConfig>,
 }
 
 impl Default for QualityGatesConfig {
@@ -734,10 +833,68 @@ impl Default for QualityGatesConfig {
             test: vec!["cargo test --release".to_string()],
             lint: vec!["cargo clippy --fix --allow-dirty".to_string()],
             format: vec!["cargo fmt --check".to_string()],
+            gates: Vec::new(),
         }
     }
 }
 
+impl QualityGatesConfig {
+    /// The ordered gates `blacksmith finish` runs.
+    ///
+    /// Uses `gates` when configured; otherwise builds the legacy fixed
+    /// sequence (check, test, lint, format) from the flat command lists,
+    /// skipping any list that is empty.
+    pub fn pipeline(&self) -> Vec<GateConfig> {
+        if !self.gates.is_empty() {
+            return self.gates.clone();
+        }
+        [
+            ("check", &self.check),
+            ("test", &self.test),
+            ("lint", &self.lint),
+            ("format", &self.format),
+        ]
+        .into_iter()
+        .filter(|(_, commands)| !commands.is_empty())
+        .map(|(name, commands)| GateConfig {
+            name: name.to_string(),
+            commands: commands.clone(),
+            ..Default::default()
+        })
+        .collect()
+    }
+}
+
+/// One named gate in the `[[quality_gates.gates]]` pipeline.
+///
+/// Gates run in declaration order. Consecutive gates sharing a
+/// `parallel_group` run concurrently; the pipeline stops after the first
+/// group (or ungrouped gate) with a failure that is not `allow_failure`.
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct GateConfig {
+    /// Gate name, recorded with each result (e.g. "test", "typecheck").
+    pub name: String,
+    /// Shell commands run in order; all must succeed for the gate to pass.
+    pub commands: Vec<String>,
+    /// Kill the gate after this many seconds. 0 = no timeout (default).
+    pub timeout_secs: u64,
+    /// Directory to run in, relative to the repository root. Default: root.
+    pub working_dir: Option<PathBuf>,
+    /// Extra environment variables for the gate's commands.
+    pub env: HashMap<String, String>,
+    /// Only run when a changed file matches one of these globs. Empty = always run.
+    pub only_if_changed: Vec<String>,
+    /// Record a failure but don't block the bead from closing. Default: false.
+    pub allow_failure: bool,
+    /// Consecutive gates with the same group name run in parallel.
+    pub parallel_group: Option<String>,
+    /// Test report file the gate writes (JUnit XML, TAP or libtest JSON),
+    /// relative to the gate's working directory. Parsed for failing tests
+    /// instead of the command output when the gate fails.
+    pub report: Option<PathBuf>,
+}
+
 /// Configuration for the speck validate pre-integration quality gate.
 ///
 /// When enabled, `speck validate --bead <bead_id> --json` is run after the
@@ -763,6 +920,58 @@ impl Default for SpeckValidateConfig {
     }
 }
 
+/// What integration does when a bead's diff touches files outside its
+/// declared `affected:` globs (including any `.blacksmith-expand` widening).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BoundaryPolicy {
+    /// Record an expansion event and log a warning (default).
+    #[default]
+    Warn,
+    /// Record an expansion event and widen the assignment's affected set to
+    /// cover the files actually touched.
+    Expand,
+    /// Record an expansion event, refuse to integrate and escalate the bead
+    /// to human review.
+    Block,
+}
+
+impl std::fmt::Display for BoundaryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoundaryPolicy::Warn => write!(f, "warn"),
+            BoundaryPolicy::Expand => write!(f, "expand"),
+            BoundaryPolicy::Block => write!(f, "block"),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for BoundaryPolicy {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        match s.as_str() {
+            "warn" => Ok(BoundaryPolicy::Warn),
+            "expand" => Ok(BoundaryPolicy::Expand),
+            "block" => Ok(BoundaryPolicy::Block),
+            other => Err(serde::de::Error::custom(format!(
+                "invalid boundary_policy '{}': expected 'warn', 'expand', or 'block'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Configuration for integrating completed worktrees into the base branch.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct IntegrationConfig {
+    /// How to handle changes outside a bead's declared affected set.
+    /// Default: "warn".
+    pub boundary_policy: BoundaryPolicy,
+}
+
 /// Configuration for the self-improvement promotion cycle.
 ///
 /// Controls how improvements are auto-promoted after a configurable number
@@ -773,7 +982,7 @@ pub struct ImprovementsConfig {
     /// Number of successful sessions before an open improvement is auto-promoted.
     /// Set to 0 to disable auto-promotion. Default: 5
     pub auto_promote_after: u32,
-    /// File to append promoted improvement rules to. Default: "PROMPT.md"
+    /// File holding the managed block of promoted improvement rules. Default: "PROMPT.md"
     pub prompt_file: PathBuf,
     /// Spawn analysis agent every N completed sessions. 0 = disabled (default).
     pub analyze_every: u32,
@@ -789,6 +998,15 @@ pub struct ImprovementsConfig {
     pub analysis_cooldown_sessions: u32,
     /// Hard cap on open improvements injected into analysis prompt. 0 = no cap. Default: 10.
     pub max_open_improvements: u32,
+    /// Sessions in each of the baseline and follow-up windows used to measure a
+    /// promoted improvement's effect on its target metric. 0 = disabled. Default: 10.
+    pub impact_window: u32,
+    /// Character budget for the promoted-rules block; overflow triggers
+    /// consolidation. 0 = unlimited. Default: 4000.
+    pub rules_budget_chars: usize,
+    /// Failure clusters mined from session traces and filed as draft
+    /// improvements for each analysis run. 0 = disabled. Default: 5.
+    pub mine_top: u32,
 }
 
 impl ImprovementsConfig {
@@ -815,10 +1033,159 @@ impl Default for ImprovementsConfig {
             auto_dismiss_after: 30,
             analysis_cooldown_sessions: 0,
             max_open_improvements: 10,
+            impact_window: 10,
+            rules_budget_chars: 4000,
+            mine_top: 5,
         }
     }
 }
 
+/// Per-model token pricing used to compute `cost.computed_usd` at ingestion.
+///
+/// Agents report cost inconsistently (codex and opencode often not at all),
+/// so the harness derives its own figure from token counts. Rates are in
+/// USD per million tokens.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct PricingConfig {
+    pub models: Vec<ModelPricing>,
+}
+
+/// Token rates for one model, optionally scoped to a provider.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ModelPricing {
+    /// Model name or prefix (e.g. "claude-sonnet-4-5", "gpt-5-codex").
+    /// A session model matches when it equals or starts with this value.
+    pub model: String,
+    /// Provider name (e.g. "anthropic", "openai"). When set, only sessions
+    /// reporting the same provider (or no provider) match this entry.
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// USD per million uncached input tokens.
+    pub input: f64,
+    /// USD per million output tokens.
+    pub output: f64,
+    /// USD per million cache-read input tokens. Default: 0.0
+    #[serde(default)]
+    pub cache_read: f64,
+    /// USD per million cache-write (cache creation) input tokens. Default: 0.0
+    #[serde(default)]
+    pub cache_write: f64,
+}
+
+impl PricingConfig {
+    /// Find the pricing entry for a model, preferring the longest matching
+    /// model name. Entries scoped to a different provider are skipped.
+    pub fn rate_for(&self, model: &str, provider: Option<&str>) -> Option<&ModelPricing> {
+        self.models
+            .iter()
+            .filter(|p| model == p.model || model.starts_with(&p.model))
+            .filter(|p| match (&p.provider, provider) {
+                (Some(want), Some(got)) => want.eq_ignore_ascii_case(got),
+                _ => true,
+            })
+            .max_by_key(|p| (p.model.len(), p.provider.is_some()))
+    }
+}
+
+/// Spend ceilings checked by the coordinator before each worker spawn.
+///
+/// Spend is summed from ingested session costs (see [`crate::budget`]).
+/// Every limit defaults to 0, which disables it.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct BudgetConfig {
+    /// Max USD spent per UTC calendar day, across runs. 0 = no limit.
+    pub daily_usd: f64,
+    /// Max USD spent on a single bead across all its sessions. 0 = no limit.
+    pub per_bead_usd: f64,
+    /// Max USD spent by one coordinator run. 0 = no limit.
+    pub per_run_usd: f64,
+    /// Max sessions attempted for a single bead. 0 = no limit.
+    pub max_sessions_per_bead: u32,
+}
+
+impl BudgetConfig {
+    /// Whether any limit is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.daily_usd > 0.0
+            || self.per_bead_usd > 0.0
+            || self.per_run_usd > 0.0
+            || self.max_sessions_per_bead > 0
+    }
+}
+
+/// Bead decomposition before coding (`[planning]`).
+///
+/// A ready bead over any of the size thresholds goes to the
+/// `[agent.planning]` agent instead of a coding worker. The agent proposes
+/// child beads with `affected:` sets and dependencies; they are filed
+/// straight away with `auto_approve`, otherwise they wait for
+/// `blacksmith plan approve` or `plan reject`.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct PlanningConfig {
+    /// Route oversized beads to the planning agent. Default: false.
+    pub enabled: bool,
+    /// Plan beads whose description is longer than this. 0 = ignore. Default: 3000.
+    pub max_description_chars: usize,
+    /// Plan beads declaring more `affected:` globs than this. 0 = ignore. Default: 6.
+    pub max_affected_globs: usize,
+    /// Plan beads that already failed this many attempts. 0 = ignore. Default: 2.
+    pub max_failures: u32,
+    /// File proposed children as beads without waiting for approval. Default: false.
+    pub auto_approve: bool,
+}
+
+impl Default for PlanningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_description_chars: 3000,
+            max_affected_globs: 6,
+            max_failures: 2,
+            auto_approve: false,
+        }
+    }
+}
+
+/// A prompt A/B experiment across workers (`[experiment]`).
+///
+/// Each bead is assigned to one variant by a stable hash of the experiment
+/// name and bead id; the assignment is recorded so a bead keeps its variant
+/// across retries. Sessions are tagged with their variant and compared by
+/// `blacksmith experiment report`.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ExperimentConfig {
+    /// Experiment name. Renaming starts a fresh experiment. Empty = disabled.
+    pub name: String,
+    /// Prompt variants (`[[experiment.variants]]`); the first is the control.
+    pub variants: Vec<PromptVariant>,
+}
+
+impl ExperimentConfig {
+    /// Whether an experiment with at least two variants is configured.
+    pub fn is_enabled(&self) -> bool {
+        !self.name.is_empty() && self.variants.len() >= 2
+    }
+}
+
+/// One arm of a prompt experiment. A variant with no changes is a plain control.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct PromptVariant {
+    pub name: String,
+    /// Markdown section appended to the prompt.
+    pub section: Option<String>,
+    /// Prompt file used instead of `prompt.file` / `session.prompt_file`.
+    pub prompt_file: Option<PathBuf>,
+    /// Refs of promoted improvements whose rules are removed from the prompt.
+    pub improvements_off: Vec<String>,
+    /// Refs of improvements whose rules are added to the prompt.
+    pub improvements_on: Vec<String>,
+}
+
 #[derive(Debug, Deserialize, Clone, Default)]
 #[serde(default)]
 pub struct MetricsConfig {
@@ -998,10 +1365,12 @@ impl HarnessConfig {
         let coding = self.agent.resolved_coding();
         let integration = self.agent.resolved_integration();
         let analysis = self.agent.resolved_analysis();
+        let planning = self.agent.resolved_planning();
         for (label, resolved) in [
             ("coding", &coding),
             ("integration", &integration),
             ("analysis", &analysis),
+            ("planning", &planning),
         ] {
             if !resolved.command.is_empty() {
                 let cmd = Path::new(&resolved.command);
@@ -1070,6 +1439,13 @@ impl HarnessConfig {
             }
         }
 
+        // architecture.fitness rules must name a known rule with its fields
+        for (i, rule) in self.architecture.fitness.iter().enumerate() {
+            if let Err(e) = crate::fitness::Rule::parse(rule) {
+                errors.push(format!("architecture.fitness[{}]: {}", i, e));
+            }
+        }
+
         // metrics.targets.rules must have valid compare and direction values
         let valid_compare = ["pct_of", "pct_sessions", "avg"];
         let valid_direction = ["above", "below"];
@@ -1120,6 +1496,112 @@ impl HarnessConfig {
             ));
         }
 
+        // Pricing rates must be non-negative and model names non-empty
+        for (i, p) in self.pricing.models.iter().enumerate() {
+            if p.model.trim().is_empty() {
+                errors.push(format!("pricing.models[{i}]: model must not be empty"));
+            }
+            for (field, rate) in [
+                ("input", p.input),
+                ("output", p.output),
+                ("cache_read", p.cache_read),
+                ("cache_write", p.cache_write),
+            ] {
+                if rate < 0.0 {
+                    errors.push(format!(
+                        "pricing.models[{i}].{field}: must not be negative, got {rate}"
+                    ));
+                }
+            }
+        }
+
+        // Gate pipeline: names unique and non-empty, commands present,
+        // globs valid, parallel groups contiguous
+        let mut gate_names = std::collections::HashSet::new();
+        let mut closed_groups: std::collections::HashSet<&str> = std::collections::HashSet::new();
+        let mut prev_group: Option<&str> = None;
+        for (i, gate) in self.quality_gates.gates.iter().enumerate() {
+            if gate.name.trim().is_empty() {
+                errors.push(format!("quality_gates.gates[{i}]: name must not be empty"));
+            } else if !gate_names.insert(gate.name.as_str()) {
+                errors.push(format!(
+                    "quality_gates.gates[{i}]: duplicate gate name '{}'",
+                    gate.name
+                ));
+            }
+            if gate.commands.is_empty() {
+                errors.push(format!(
+                    "quality_gates.gates[{i}] ({}): commands must not be empty",
+                    gate.name
+                ));
+            }
+            for pattern in &gate.only_if_changed {
+                if let Err(e) = glob::Pattern::new(pattern) {
+                    errors.push(format!(
+                        "quality_gates.gates[{i}] ({}): invalid only_if_changed glob '{pattern}': {e}",
+                        gate.name
+                    ));
+                }
+            }
+            let group = gate.parallel_group.as_deref();
+            if group != prev_group {
+                if let Some(prev) = prev_group {
+                    closed_groups.insert(prev);
+                }
+                if let Some(g) = group {
+                    if closed_groups.contains(g) {
+                        errors.push(format!(
+                            "quality_gates.gates[{i}] ({}): parallel_group '{g}' must be contiguous",
+                            gate.name
+                        ));
+                    }
+                }
+            }
+            prev_group = group;
+        }
+
+        // Budget limits must be non-negative (0 disables)
+        for (field, limit) in [
+            ("daily_usd", self.budget.daily_usd),
+            ("per_bead_usd", self.budget.per_bead_usd),
+            ("per_run_usd", self.budget.per_run_usd),
+        ] {
+            if limit < 0.0 {
+                errors.push(format!("budget.{field}: must not be negative, got {limit}"));
+            }
+        }
+
+        // Experiment variants need distinct names and existing prompt files
+        if !self.experiment.variants.is_empty() {
+            if self.experiment.name.is_empty() {
+                errors.push("experiment.name: required when variants are defined".to_string());
+            }
+            if self.experiment.variants.len() < 2 {
+                errors.push("experiment.variants: at least two variants are needed".to_string());
+            }
+        }
+        for (i, variant) in self.experiment.variants.iter().enumerate() {
+            if variant.name.is_empty() {
+                errors.push(format!("experiment.variants[{i}]: name must not be empty"));
+            } else if self.experiment.variants[..i]
+                .iter()
+                .any(|v| v.name == variant.name)
+            {
+                errors.push(format!(
+                    "experiment.variants[{i}]: duplicate variant name '{}'",
+                    variant.name
+                ));
+            }
+            if let Some(file) = &variant.prompt_file {
+                if !file.exists() {
+                    errors.push(format!(
+                        "experiment.variants[{i}]: prompt_file not found: {}",
+                        file.display()
+                    ));
+                }
+            }
+        }
+
         errors
     }
 }
@@ -1169,6 +1651,7 @@ impl Default for AgentConfig {
             coding: None,
             integration: None,
             analysis: None,
+            planning: None,
         }
     }
 }
@@ -2467,6 +2950,183 @@ every = 0
         assert_eq!(config.reconciliation.every, 0);
     }
 
+    #[test]
+    fn test_load_reconciliation_flaky_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blacksmith.toml");
+        std::fs::write(
+            &path,
+            r#"
+[reconciliation]
+flaky_reruns = 5
+rerun_command = "npx vitest run -t {test}"
+"#,
+        )
+        .unwrap();
+        let config = HarnessConfig::load(&path).unwrap();
+        assert_eq!(config.reconciliation.every, 3);
+        assert_eq!(config.reconciliation.flaky_reruns, 5);
+        assert_eq!(
+            config.reconciliation.rerun_command.as_deref(),
+            Some("npx vitest run -t {test}")
+        );
+        assert_eq!(config.reconciliation.flaky_window, 20);
+    }
+
+    // --- Integration config tests ---
+
+    #[test]
+    fn test_default_boundary_policy_is_warn() {
+        let config = HarnessConfig::default();
+        assert_eq!(config.integration.boundary_policy, BoundaryPolicy::Warn);
+    }
+
+    #[test]
+    fn test_load_boundary_policy_from_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blacksmith.toml");
+        std::fs::write(
+            &path,
+            r#"
+[integration]
+boundary_policy = "block"
+"#,
+        )
+        .unwrap();
+        let config = HarnessConfig::load(&path).unwrap();
+        assert_eq!(config.integration.boundary_policy, BoundaryPolicy::Block);
+        assert_eq!(config.integration.boundary_policy.to_string(), "block");
+    }
+
+    #[test]
+    fn test_invalid_boundary_policy_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blacksmith.toml");
+        std::fs::write(
+            &path,
+            r#"
+[integration]
+boundary_policy = "ignore"
+"#,
+        )
+        .unwrap();
+        let err = HarnessConfig::load(&path).unwrap_err();
+        assert!(err.to_string().contains("boundary_policy"), "{err}");
+    }
+
+    // --- Worker provisioning tests ---
+
+    #[test]
+    fn test_default_worker_provisioning_is_cold() {
+        let config = HarnessConfig::default();
+        assert!(!config.workers.reuse_worktrees);
+        assert!(!config.workers.shared_target_dir);
+        assert!(config.workers.seed_dirs.is_empty());
+        assert!(config.workers.seed_from.is_none());
+        assert!(config.workers.setup.is_empty());
+        assert!(!config.workers.resume_salvage);
+    }
+
+    #[test]
+    fn test_load_worker_provisioning_from_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blacksmith.toml");
+        std::fs::write(
+            &path,
+            r#"
+[workers]
+max = 3
+reuse_worktrees = true
+shared_target_dir = true
+seed_dirs = ["target", "node_modules"]
+seed_from = "/srv/template"
+setup = ["pnpm install --frozen-lockfile"]
+resume_salvage = true
+"#,
+        )
+        .unwrap();
+        let config = HarnessConfig::load(&path).unwrap();
+        assert_eq!(config.workers.max, 3);
+        assert_eq!(config.workers.base_branch, "main");
+        assert!(config.workers.reuse_worktrees);
+        assert!(config.workers.shared_target_dir);
+        assert_eq!(config.workers.seed_dirs, vec!["target", "node_modules"]);
+        assert_eq!(
+            config.workers.seed_from.as_deref(),
+            Some(Path::new("/srv/template"))
+        );
+        assert_eq!(config.workers.setup, vec!["pnpm install --frozen-lockfile"]);
+        assert!(config.workers.resume_salvage);
+    }
+
+    #[test]
+    fn test_load_worker_readiness_gate_from_toml() {
+        let config = HarnessConfig::default();
+        assert!(config.workers.ready_label.is_none());
+        assert_eq!(config.workers.ready_after_mins, 0);
+        assert_eq!(config.workers.hold_label, "hold");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blacksmith.toml");
+        std::fs::write(
+            &path,
+            r#"
+[workers]
+persistent = true
+ready_label = "ready-for-agent"
+ready_after_mins = 30
+hold_label = "wip"
+"#,
+        )
+        .unwrap();
+        let config = HarnessConfig::load(&path).unwrap();
+        assert_eq!(
+            config.workers.ready_label.as_deref(),
+            Some("ready-for-agent")
+        );
+        assert_eq!(config.workers.ready_after_mins, 30);
+        assert_eq!(config.workers.hold_label, "wip");
+    }
+
+    #[test]
+    fn test_load_planning_config_from_toml() {
+        let config = HarnessConfig::default();
+        assert!(!config.planning.enabled);
+        assert!(!config.planning.auto_approve);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blacksmith.toml");
+        std::fs::write(
+            &path,
+            r#"
+[agent]
+command = "claude"
+
+[agent.planning]
+model = "opus"
+
+[planning]
+enabled = true
+max_description_chars = 1500
+max_failures = 0
+auto_approve = true
+"#,
+        )
+        .unwrap();
+        let config = HarnessConfig::load(&path).unwrap();
+        assert!(config.planning.enabled);
+        assert_eq!(config.planning.max_description_chars, 1500);
+        assert_eq!(config.planning.max_affected_globs, 6);
+        assert_eq!(config.planning.max_failures, 0);
+        assert!(config.planning.auto_approve);
+
+        let resolved = config.agent.resolved_planning();
+        assert_eq!(resolved.command, "claude");
+        assert!(resolved
+            .args
+            .ends_with(&["--model".to_string(), "opus".to_string()]));
+    }
+
     #[test]
     fn test_validate_resolves_agent_for_both_phases() {
         let mut config = valid_config();
@@ -2516,6 +3176,7 @@ every = 0
         assert_eq!(config.architecture.expansion_event_window, 20);
         assert!((config.architecture.metadata_drift_sensitivity - 3.0).abs() < f64::EPSILON);
         assert!(!config.architecture.refactor_auto_approve);
+        assert_eq!(config.architecture.review_every, 10);
     }
 
     #[test]
@@ -2527,6 +3188,7 @@ every = 0
         assert_eq!(preset.expansion_event_window, 30);
         assert!((preset.metadata_drift_sensitivity - 5.0).abs() < f64::EPSILON);
         assert!(!preset.refactor_auto_approve);
+        assert_eq!(preset.review_every, 20);
     }
 
     #[test]
@@ -2538,6 +3200,7 @@ every = 0
         assert_eq!(preset.expansion_event_window, 15);
         assert!((preset.metadata_drift_sensitivity - 2.0).abs() < f64::EPSILON);
         assert!(preset.refactor_auto_approve);
+        assert_eq!(preset.review_every, 5);
     }
 
     #[test]
@@ -2554,6 +3217,7 @@ expansion_event_threshold = 7
 expansion_event_window = 25
 metadata_drift_sensitivity = 4.0
 refactor_auto_approve = true
+review_every = 3
 "#,
         )
         .unwrap();
@@ -2564,6 +3228,56 @@ refactor_auto_approve = true
         assert_eq!(config.architecture.expansion_event_window, 25);
         assert!((config.architecture.metadata_drift_sensitivity - 4.0).abs() < f64::EPSILON);
         assert!(config.architecture.refactor_auto_approve);
+        assert_eq!(config.architecture.review_every, 3);
+    }
+
+    #[test]
+    fn test_load_architecture_fitness_from_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blacksmith.toml");
+        std::fs::write(
+            &path,
+            r#"
+[[architecture.fitness]]
+rule = "forbid_import"
+from = "adapters"
+to = "coordinator"
+
+[[architecture.fitness]]
+rule = "max_api_growth"
+module = "db"
+max = 3
+"#,
+        )
+        .unwrap();
+        let config = HarnessConfig::load(&path).unwrap();
+        let rules = &config.architecture.fitness;
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].rule, "forbid_import");
+        assert_eq!(rules[0].from.as_deref(), Some("adapters"));
+        assert_eq!(rules[0].to.as_deref(), Some("coordinator"));
+        assert_eq!(rules[1].module.as_deref(), Some("db"));
+        assert_eq!(rules[1].max, Some(3));
+        assert!(ArchitectureConfig::default().fitness.is_empty());
+    }
+
+    #[test]
+    fn test_validate_rejects_incomplete_fitness_rule() {
+        let mut config = HarnessConfig::default();
+        config.architecture.fitness = vec![FitnessRule {
+            rule: "max_file_lines".to_string(),
+            from: None,
+            to: None,
+            module: None,
+            max: None,
+        }];
+        let errors = config.validate();
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.starts_with("architecture.fitness[0]") && e.contains("max")),
+            "{errors:?}"
+        );
     }
 
     #[test]
@@ -2713,6 +3427,104 @@ max_iterations = 10
         assert_eq!(config.quality_gates.test, vec!["cargo test --release"]);
     }
 
+    #[test]
+    fn test_quality_gates_pipeline_from_legacy_lists() {
+        let gates = QualityGatesConfig {
+            lint: vec![],
+            ..Default::default()
+        };
+        let pipeline = gates.pipeline();
+        let names: Vec<&str> = pipeline.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, vec!["check", "test", "format"]);
+        assert_eq!(pipeline[0].commands, vec!["cargo check --release"]);
+        assert!(!pipeline[0].allow_failure);
+        assert!(pipeline[0].parallel_group.is_none());
+    }
+
+    #[test]
+    fn test_load_named_gates_from_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blacksmith.toml");
+        std::fs::write(
+            &path,
+            r#"
+[[quality_gates.gates]]
+name = "typecheck"
+commands = ["npx tsc --noEmit"]
+working_dir = "web"
+timeout_secs = 120
+parallel_group = "static"
+
+[[quality_gates.gates]]
+name = "lint"
+commands = ["npx eslint ."]
+only_if_changed = ["web/**/*.ts"]
+allow_failure = true
+parallel_group = "static"
+env = { NODE_ENV = "test" }
+
+[[quality_gates.gates]]
+name = "test"
+commands = ["npm test"]
+"#,
+        )
+        .unwrap();
+        let config = HarnessConfig::load(&path).unwrap();
+        let pipeline = config.quality_gates.pipeline();
+        assert_eq!(pipeline.len(), 3);
+        assert_eq!(pipeline[0].working_dir, Some(PathBuf::from("web")));
+        assert_eq!(pipeline[0].timeout_secs, 120);
+        assert_eq!(pipeline[1].only_if_changed, vec!["web/**/*.ts"]);
+        assert!(pipeline[1].allow_failure);
+        assert_eq!(
+            pipeline[1].env.get("NODE_ENV").map(String::as_str),
+            Some("test")
+        );
+        assert_eq!(pipeline[1].parallel_group.as_deref(), Some("static"));
+        assert_eq!(pipeline[2].timeout_secs, 0);
+        // Legacy lists are ignored once named gates are configured
+        assert!(!pipeline.iter().any(|g| g.name == "check"));
+    }
+
+    #[test]
+    fn test_validate_gate_pipeline() {
+        let gate = |name: &str, group: Option<&str>| GateConfig {
+            name: name.to_string(),
+            commands: vec!["true".to_string()],
+            parallel_group: group.map(String::from),
+            ..Default::default()
+        };
+        let mut config = valid_config();
+        config.quality_gates.gates = vec![
+            gate("a", Some("g1")),
+            gate("b", Some("g1")),
+            gate("c", None),
+            gate("a", Some("g1")),
+            GateConfig {
+                name: "empty".to_string(),
+                only_if_changed: vec!["[".to_string()],
+                ..Default::default()
+            },
+        ];
+        let errors = config.validate();
+        assert!(errors.iter().any(|e| e.contains("duplicate gate name 'a'")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("parallel_group 'g1' must be contiguous")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("(empty): commands must not be empty")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("invalid only_if_changed glob")));
+
+        config.quality_gates.gates = vec![gate("a", Some("g1")), gate("b", Some("g1"))];
+        assert!(!config
+            .validate()
+            .iter()
+            .any(|e| e.contains("quality_gates")));
+    }
+
     // --- Improvements config tests ---
 
     #[test]
@@ -2720,6 +3532,9 @@ max_iterations = 10
         let config = HarnessConfig::default();
         assert_eq!(config.improvements.auto_promote_after, 5);
         assert_eq!(config.improvements.prompt_file, PathBuf::from("PROMPT.md"));
+        assert_eq!(config.improvements.impact_window, 10);
+        assert_eq!(config.improvements.rules_budget_chars, 4000);
+        assert_eq!(config.improvements.mine_top, 5);
     }
 
     #[test]
@@ -2732,12 +3547,18 @@ max_iterations = 10
 [improvements]
 auto_promote_after = 10
 prompt_file = "AGENTS.md"
+impact_window = 6
+rules_budget_chars = 1500
+mine_top = 0
 "#,
         )
         .unwrap();
         let config = HarnessConfig::load(&path).unwrap();
         assert_eq!(config.improvements.auto_promote_after, 10);
         assert_eq!(config.improvements.prompt_file, PathBuf::from("AGENTS.md"));
+        assert_eq!(config.improvements.impact_window, 6);
+        assert_eq!(config.improvements.rules_budget_chars, 1500);
+        assert_eq!(config.improvements.mine_top, 0);
     }
 
     #[test]
@@ -2773,6 +3594,209 @@ auto_promote_after = 0
         assert_eq!(config.improvements.auto_promote_after, 0);
     }
 
+    // --- Pricing config tests ---
+
+    #[test]
+    fn test_default_pricing_is_empty() {
+        let config = HarnessConfig::default();
+        assert!(config.pricing.models.is_empty());
+        assert!(config.pricing.rate_for("claude-sonnet-4-5", None).is_none());
+    }
+
+    #[test]
+    fn test_load_pricing_from_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blacksmith.toml");
+        std::fs::write(
+            &path,
+            r#"
+[[pricing.models]]
+model = "claude-sonnet-4-5"
+provider = "anthropic"
+input = 3.0
+output = 15.0
+cache_read = 0.3
+cache_write = 3.75
+
+[[pricing.models]]
+model = "gpt-5-codex"
+input = 1.25
+output = 10.0
+"#,
+        )
+        .unwrap();
+        let config = HarnessConfig::load(&path).unwrap();
+        assert_eq!(config.pricing.models.len(), 2);
+        let sonnet = &config.pricing.models[0];
+        assert_eq!(sonnet.provider.as_deref(), Some("anthropic"));
+        assert_eq!(sonnet.cache_write, 3.75);
+        let codex = &config.pricing.models[1];
+        assert!(codex.provider.is_none());
+        assert_eq!(codex.cache_read, 0.0);
+    }
+
+    fn price(model: &str, provider: Option<&str>, input: f64) -> ModelPricing {
+        ModelPricing {
+            model: model.to_string(),
+            provider: provider.map(String::from),
+            input,
+            output: 0.0,
+            cache_read: 0.0,
+            cache_write: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_pricing_rate_for_prefers_longest_prefix() {
+        let pricing = PricingConfig {
+            models: vec![
+                price("claude", None, 1.0),
+                price("claude-opus-4", None, 15.0),
+            ],
+        };
+        let rate = pricing.rate_for("claude-opus-4-1-20250805", None).unwrap();
+        assert_eq!(rate.input, 15.0);
+        let rate = pricing.rate_for("claude-haiku-4-5", None).unwrap();
+        assert_eq!(rate.input, 1.0);
+        assert!(pricing.rate_for("gpt-5", None).is_none());
+    }
+
+    #[test]
+    fn test_pricing_rate_for_respects_provider() {
+        let pricing = PricingConfig {
+            models: vec![
+                price("gpt-5", Some("openai"), 1.25),
+                price("gpt-5", Some("openrouter"), 1.5),
+            ],
+        };
+        assert_eq!(
+            pricing.rate_for("gpt-5", Some("OpenRouter")).unwrap().input,
+            1.5
+        );
+        assert_eq!(
+            pricing.rate_for("gpt-5", Some("openai")).unwrap().input,
+            1.25
+        );
+        assert!(pricing.rate_for("gpt-5", Some("azure")).is_none());
+        // Unknown provider: any scoped entry is acceptable
+        assert!(pricing.rate_for("gpt-5", None).is_some());
+    }
+
+    #[test]
+    fn test_validate_negative_pricing_rate() {
+        let mut config = valid_config();
+        config.pricing.models.push(price("gpt-5", None, -1.0));
+        config.pricing.models.push(price(" ", None, 1.0));
+        let errors = config.validate();
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("pricing.models[0].input") && e.contains("negative")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("pricing.models[1]: model must not be empty")));
+    }
+
+    // --- Budget config tests ---
+
+    #[test]
+    fn test_default_budget_is_disabled() {
+        let config = HarnessConfig::default();
+        assert!(!config.budget.is_enabled());
+        assert_eq!(config.budget.max_sessions_per_bead, 0);
+    }
+
+    #[test]
+    fn test_load_budget_from_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blacksmith.toml");
+        std::fs::write(
+            &path,
+            r#"
+[budget]
+daily_usd = 50.0
+per_bead_usd = 5
+max_sessions_per_bead = 4
+"#,
+        )
+        .unwrap();
+        let config = HarnessConfig::load(&path).unwrap();
+        assert!(config.budget.is_enabled());
+        assert_eq!(config.budget.daily_usd, 50.0);
+        assert_eq!(config.budget.per_bead_usd, 5.0);
+        assert_eq!(config.budget.per_run_usd, 0.0);
+        assert_eq!(config.budget.max_sessions_per_bead, 4);
+    }
+
+    #[test]
+    fn test_load_experiment_from_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blacksmith.toml");
+        std::fs::write(
+            &path,
+            r#"
+[experiment]
+name = "terse-replies"
+
+[[experiment.variants]]
+name = "control"
+
+[[experiment.variants]]
+name = "terse"
+section = "Keep narration to one line."
+improvements_off = ["R3"]
+improvements_on = ["R7"]
+"#,
+        )
+        .unwrap();
+        let config = HarnessConfig::load(&path).unwrap();
+        assert!(config.experiment.is_enabled());
+        assert_eq!(config.experiment.variants.len(), 2);
+        let terse = &config.experiment.variants[1];
+        assert_eq!(terse.name, "terse");
+        assert_eq!(
+            terse.section.as_deref(),
+            Some("Keep narration to one line.")
+        );
+        assert_eq!(terse.improvements_off, vec!["R3"]);
+        assert_eq!(terse.improvements_on, vec!["R7"]);
+        assert!(terse.prompt_file.is_none());
+        assert!(!HarnessConfig::default().experiment.is_enabled());
+    }
+
+    #[test]
+    fn test_validate_experiment_variants() {
+        let mut config = valid_config();
+        config.experiment.variants = vec![
+            PromptVariant {
+                name: "a".to_string(),
+                ..Default::default()
+            },
+            PromptVariant {
+                name: "a".to_string(),
+                prompt_file: Some(PathBuf::from("/nonexistent/PROMPT.md")),
+                ..Default::default()
+            },
+        ];
+        let errors = config.validate();
+        assert!(errors.iter().any(|e| e.contains("experiment.name")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("experiment.variants[1]") && e.contains("duplicate")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("experiment.variants[1]") && e.contains("prompt_file")));
+    }
+
+    #[test]
+    fn test_validate_negative_budget() {
+        let mut config = valid_config();
+        config.budget.per_run_usd = -1.0;
+        let errors = config.validate();
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("budget.per_run_usd") && e.contains("negative")));
+    }
+
     // --- Config migration fallback chain tests ---
 
     #[test]
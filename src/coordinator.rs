@@ -6,27 +6,42 @@
 /// for completions. Completed workers are queued for sequential integration into
 /// main (also skipped for max=1).
 use crate::adapters;
-use crate::config::HarnessConfig;
+use crate::arch_history;
+use crate::arch_review;
+use crate::budget;
+use crate::config::{HarnessConfig, WorkersConfig};
 use crate::cycle_detect;
 use crate::data_dir::DataDir;
 use crate::db;
+use crate::dedup;
 use crate::defaults;
 use crate::estimation::{self, BeadNode};
+use crate::expansion_event::{self, ExpansionEvent};
+use crate::experiment;
+use crate::handoff;
+use crate::impact;
 use crate::improve;
 use crate::ingest;
 use crate::integrator::{
-    close_bead_in_bd, CircuitBreaker, IntegrationQueue, TrippedFailure, ValidationCircuitBreaker,
+    close_bead_in_bd, CircuitBreaker, IntegrationQueue, ReconciliationTracker, TrippedFailure,
+    ValidationCircuitBreaker,
 };
+use crate::mining;
+use crate::module_detect;
+use crate::planning::{self, PlanStatus};
 use crate::pool::{PoolError, SessionOutcome, WorkerPool};
 use crate::prompt;
 use crate::ratelimit;
+use crate::salvage;
 use crate::scheduler::{self, InProgressAssignment, ReadyBead};
 use crate::signals::SignalHandler;
 use crate::status::{HarnessState, StatusTracker};
 use crate::worktree;
+use chrono::{DateTime, Utc};
 use rusqlite::Connection;
-use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
 use tokio::time::Duration;
 
 /// Well-known filename that agents write to request affected set expansion.
@@ -58,6 +73,8 @@ pub enum CoordinatorExitReason {
     QuotaExhausted(String),
     /// Too many rapid consecutive session failures (operator intervention needed).
     RapidFailures(String),
+    /// A `[budget]` spend limit was reached; active workers were drained.
+    BudgetExceeded(String),
     /// Fatal error (e.g., database failure).
     Error(String),
 }
@@ -108,16 +125,30 @@ pub async fn run(
     // Load global iteration counter so worker output files use numeric naming
     let counter_path = data_dir.counter();
     let initial_session_id = load_counter(&counter_path);
+    let target_cache_dir = config
+        .workers
+        .shared_target_dir
+        .then(|| data_dir.target_cache_dir());
     let mut pool = WorkerPool::new(
         &config.workers,
         repo_dir.clone(),
         worktrees_dir,
         initial_session_id,
-    );
+    )
+    .with_target_cache(target_cache_dir.clone());
     let output_dir = data_dir.sessions_dir();
     let integration_queue =
         IntegrationQueue::new(repo_dir.clone(), config.workers.base_branch.clone())
-            .with_speck_validate(config.speck_validate.clone());
+            .with_speck_validate(config.speck_validate.clone())
+            .with_boundary_policy(config.integration.boundary_policy)
+            .with_fitness_rules(config.architecture.fitness.clone())
+            .with_reconciliation(config.reconciliation.clone())
+            .with_target_cache(if pool.is_single_agent() {
+                None
+            } else {
+                target_cache_dir
+            });
+    let mut reconciliation_tracker = ReconciliationTracker::new(config.reconciliation.every);
     let mut circuit_breaker = CircuitBreaker::new();
     let mut validation_circuit_breaker =
         ValidationCircuitBreaker::new(config.speck_validate.max_validation_retries);
@@ -141,6 +172,10 @@ pub async fn run(
     let mut total_completed_sessions: u32 = 0;
     let mut draining = false;
     let mut drain_reason: Option<CoordinatorExitReason> = None;
+    // Beads skipped for reaching a per-bead [budget] limit, with the reason
+    let mut over_budget: BTreeMap<String, String> = BTreeMap::new();
+    // Sessions ingested from here on count toward the per-run budget
+    let run_started = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
     const MAX_CONSECUTIVE_NO_WORK: u32 = 3;
     const MAX_CONSECUTIVE_QUOTA_FAILURES: u32 = 2;
 
@@ -182,8 +217,23 @@ pub async fn run(
     recover_orphaned_beads();
 
     // Clean up stale worktrees from previous crash/kill.
-    // No workers are active yet, so every existing worktree is orphaned.
-    match worktree::cleanup_orphans(&repo_dir, pool.worktrees_dir(), &[]) {
+    // No workers are active yet, so every existing worktree is orphaned —
+    // except reusable slot worktrees, which are reset when next assigned.
+    let warm_slots: Vec<PathBuf> = if config.workers.reuse_worktrees {
+        (0..config.workers.max)
+            .map(|id| worktree::slot_path(pool.worktrees_dir(), id))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let salvage_dir = data_dir.salvage_dir();
+    salvage_stale_worktrees(
+        &db_conn,
+        pool.worktrees_dir(),
+        &salvage_dir,
+        &config.workers.base_branch,
+    );
+    match worktree::cleanup_orphans(&repo_dir, pool.worktrees_dir(), &warm_slots) {
         Ok(cleaned) if !cleaned.is_empty() => {
             tracing::info!(
                 count = cleaned.len(),
@@ -226,13 +276,13 @@ pub async fn run(
         // Poll for completed workers
         let outcomes = pool.poll_completed().await;
 
-        // Snapshot which outcomes are analysis agents *before* any resets
-        // clear the bead_id. Used below to exclude them from the session counter.
+        // Snapshot which outcomes are analysis or planning agents *before* any
+        // resets clear the bead_id. Used below to exclude them from the session counter.
         let analysis_worker_ids: Vec<u32> = outcomes
             .iter()
             .filter(|o| {
                 pool.worker_bead_id(o.worker_id)
-                    .map(is_analysis_bead)
+                    .map(|id| is_analysis_bead(id) || planning::planned_bead(id).is_some())
                     .unwrap_or(false)
             })
             .map(|o| o.worker_id)
@@ -243,9 +293,18 @@ pub async fn run(
                 tracing::warn!(error = %e, worker_id = outcome.worker_id, "failed to record outcome");
             }
 
-            // Ingest JSONL metrics from the worker's output file
-            let ingest_result =
-                ingest_worker_metrics(outcome, &db_conn, &extraction_rules, adapter.as_ref());
+            // Attribute the session to its bead, then ingest JSONL metrics
+            // from the worker's output file
+            record_session_bead(outcome, &pool, &db_conn);
+            let ingest_result = ingest_worker_metrics(
+                outcome,
+                &db_conn,
+                &extraction_rules,
+                &config.pricing,
+                adapter.as_ref(),
+            );
+            tag_experiment_session(config, outcome, &pool, &db_conn);
+            capture_handoff(config, outcome, &pool, &db_conn);
 
             let succeeded = outcome.exit_code == Some(0);
             if succeeded {
@@ -261,12 +320,33 @@ pub async fn run(
                     .map(is_analysis_bead)
                     .unwrap_or(false);
 
+                let planned = pool
+                    .worker_bead_id(outcome.worker_id)
+                    .and_then(planning::planned_bead)
+                    .map(str::to_string);
+
                 if is_analysis {
                     tracing::warn!(
                         worker_id = outcome.worker_id,
                         exit_code = ?outcome.exit_code,
                         "analysis agent failed"
                     );
+                } else if let Some(bead_id) = planned {
+                    tracing::warn!(
+                        worker_id = outcome.worker_id,
+                        exit_code = ?outcome.exit_code,
+                        bead_id = %bead_id,
+                        "planning agent failed, bead will be coded as-is"
+                    );
+                    let failure = format!(
+                        "planning session failed (exit code {:?})",
+                        outcome.exit_code
+                    );
+                    if let Err(e) =
+                        planning::record(&db_conn, &planning::Bd, &bead_id, Err(failure), false)
+                    {
+                        tracing::warn!(error = %e, bead_id = %bead_id, "failed to record plan");
+                    }
                 } else {
                     failed_beads += 1;
                     let rapid_failure = is_rapid_session_failure(
@@ -297,11 +377,10 @@ pub async fn run(
                         config.workers.max,
                     );
                     // Check for authentication failure (fatal — no point retrying)
-                    if let Some(auth_msg) =
-                        ratelimit::detect_auth_failure(&outcome.output_file)
-                    {
-                        let api_key_source = ratelimit::extract_api_key_source(&outcome.output_file)
-                            .unwrap_or_else(|| "unknown".to_string());
+                    if let Some(auth_msg) = ratelimit::detect_auth_failure(&outcome.output_file) {
+                        let api_key_source =
+                            ratelimit::extract_api_key_source(&outcome.output_file)
+                                .unwrap_or_else(|| "unknown".to_string());
                         tracing::error!(
                             worker_id = outcome.worker_id,
                             api_key_source = %api_key_source,
@@ -353,6 +432,23 @@ pub async fn run(
                     );
                 }
 
+                // Archive any partial work before the worktree is removed
+                if !pool.is_single_agent() {
+                    if let (Some(wt_path), Some(bead_id)) = (
+                        pool.worker_worktree_path(outcome.worker_id),
+                        pool.worker_bead_id(outcome.worker_id),
+                    ) {
+                        salvage_worktree(
+                            &db_conn,
+                            &salvage_dir,
+                            &wt_path,
+                            bead_id,
+                            &config.workers.base_branch,
+                            &format!("session failed (exit code {:?})", outcome.exit_code),
+                        );
+                    }
+                }
+
                 // Reset failed workers back to idle immediately
                 if let Err(e) = pool.reset_worker(outcome.worker_id) {
                     tracing::warn!(error = %e, worker_id = outcome.worker_id, "failed to reset worker");
@@ -360,6 +456,13 @@ pub async fn run(
             }
         }
 
+        if !outcomes.is_empty() && config.budget.is_enabled() {
+            match budget::spend_since(&db_conn, &run_started) {
+                Ok(cost) => status.set_run_cost(cost),
+                Err(e) => tracing::warn!(error = %e, "failed to compute run spend"),
+            }
+        }
+
         // Track completed sessions for analysis agent trigger.
         // Exclude analysis agent outcomes so they don't count toward the
         // analyze_every interval (otherwise analyze_every=1 would loop forever).
@@ -422,9 +525,17 @@ pub async fn run(
             {
                 let is_analysis = is_analysis_bead(&bead_id);
 
-                // In single-agent mode, the agent committed directly to the main branch.
-                // Skip the integration merge — just close the bead and reset the worker.
-                if pool.is_single_agent() {
+                // Planning agents only leave a plan file behind: record it
+                // (and file the children if auto-approved), nothing to merge.
+                if let Some(planned) = planning::planned_bead(&bead_id) {
+                    pool.set_integrating(worker_id);
+                    finish_planning(config, &db_conn, planned, &worktree_path);
+                    if let Err(e) = pool.reset_worker(worker_id) {
+                        tracing::warn!(error = %e, worker_id, "failed to reset worker after planning");
+                    }
+                } else if pool.is_single_agent() {
+                    // In single-agent mode, the agent committed directly to the main branch.
+                    // Skip the integration merge — just close the bead and reset the worker.
                     pool.set_integrating(worker_id);
 
                     if is_analysis {
@@ -455,6 +566,10 @@ pub async fn run(
 
                         run_auto_promotion(config, &db_conn, &data_dir.db(), completed_beads);
                         dismiss_stale_improvements(config, &db_conn);
+                        evaluate_improvement_impact(config, &db_conn);
+                        let head = git_head(&repo_dir);
+                        record_arch_snapshot(&db_conn, &repo_dir, &bead_id, head.as_deref());
+                        run_architecture_review(config, &db_conn, &repo_dir, completed_beads);
                     }
 
                     if let Err(e) = pool.reset_worker(worker_id) {
@@ -504,6 +619,22 @@ pub async fn run(
                             // Run auto-promotion cycle after successful integration
                             run_auto_promotion(config, &db_conn, &data_dir.db(), completed_beads);
                             dismiss_stale_improvements(config, &db_conn);
+                            evaluate_improvement_impact(config, &db_conn);
+                            record_arch_snapshot(
+                                &db_conn,
+                                &repo_dir,
+                                &bead_id,
+                                result.merge_commit.as_deref(),
+                            );
+                            run_architecture_review(config, &db_conn, &repo_dir, completed_beads);
+
+                            // Full test suite on main every `reconciliation.every` integrations
+                            // (failures are recorded against the flagged beads)
+                            if reconciliation_tracker.record_success(&bead_id) {
+                                let reconciliation = integration_queue
+                                    .run_reconciliation(&mut reconciliation_tracker, &db_conn);
+                                status.set_reconciliation_flagged(reconciliation.flagged_beads);
+                            }
                         }
 
                         // Reset the worker back to idle after successful integration
@@ -535,6 +666,14 @@ pub async fn run(
                             // Validation retries exhausted — escalate to human review
                             failed_beads += 1;
                             handle_tripped_failure(&tripped);
+                            salvage_worktree(
+                                &db_conn,
+                                &salvage_dir,
+                                &worktree_path,
+                                &bead_id,
+                                &config.workers.base_branch,
+                                error_summary,
+                            );
                             // Do NOT reset the worker — worktree is preserved for inspection
                         } else if let Some(tripped) =
                             circuit_breaker.check_tripped(&bead_id, error_summary, &worktree_path)
@@ -542,6 +681,14 @@ pub async fn run(
                             // Integration circuit breaker tripped — escalate to human review
                             failed_beads += 1;
                             handle_tripped_failure(&tripped);
+                            salvage_worktree(
+                                &db_conn,
+                                &salvage_dir,
+                                &worktree_path,
+                                &bead_id,
+                                &config.workers.base_branch,
+                                error_summary,
+                            );
                             // Do NOT reset the worker — worktree is preserved for inspection
                             // Do NOT clean up the worktree
                         } else {
@@ -552,6 +699,14 @@ pub async fn run(
                                 state = %circuit_breaker.state(&bead_id),
                                 "integration failed, retries remain"
                             );
+                            salvage_worktree(
+                                &db_conn,
+                                &salvage_dir,
+                                &worktree_path,
+                                &bead_id,
+                                &config.workers.base_branch,
+                                error_summary,
+                            );
                             // Reset the worker back to idle so it can retry
                             if let Err(e) = pool.reset_worker(worker_id) {
                                 tracing::warn!(error = %e, worker_id, "failed to reset worker after integration failure");
@@ -571,9 +726,9 @@ pub async fn run(
             let in_progress = build_in_progress_list(&pool, &db_conn);
 
             // Query beads, detect cycles, and filter out cycled beads
-            let bead_query = query_ready_beads();
+            let bead_query = query_ready_beads(&config.workers);
             let blocked_count = bead_query.blocked_count;
-            let ready_beads = bead_query.ready;
+            let mut ready_beads = bead_query.ready;
             let current_dependency_filter_counts = (blocked_count, ready_beads.len());
 
             if blocked_count > 0 {
@@ -596,6 +751,10 @@ pub async fn run(
             }
             previous_dependency_filter_counts = Some(current_dependency_filter_counts);
 
+            // Beads over a per-bead [budget] limit are skipped; the rest keep running
+            ready_beads.retain(|b| !over_bead_budget(config, &db_conn, &b.id, &mut over_budget));
+            status.set_over_budget(over_budget.keys().cloned().collect());
+
             if ready_beads.is_empty() && pool.active_count() == 0 {
                 consecutive_no_work += 1;
                 if !config.workers.persistent && consecutive_no_work >= MAX_CONSECUTIVE_NO_WORK {
@@ -615,6 +774,11 @@ pub async fn run(
                 consecutive_no_work = 0;
             }
 
+            // Beads being planned, or whose plan awaits approval, wait
+            if config.planning.enabled {
+                ready_beads.retain(|b| !awaiting_plan(&pool, &db_conn, &b.id));
+            }
+
             // Schedule assignments for idle workers
             let assignable = scheduler::next_assignable_tasks(&ready_beads, &in_progress);
 
@@ -646,12 +810,66 @@ pub async fn run(
             // Assemble the base prompt once (brief + improvements + PROMPT.md).
             // Each worker gets this base prompt with a bead-specific suffix.
             let base_prompt = assemble_base_prompt(config, data_dir);
+            // Experiment variant prompts, built on first use this cycle.
+            let mut variant_prompts: HashMap<String, String> = HashMap::new();
+
+            // Enforce the run-wide [budget] limits before spawning
+            let budget_stop =
+                budget_check(budget::check_run(&config.budget, &db_conn, &run_started));
 
             for bead_id in assignable.iter().take(coding_slots) {
+                if budget_stop.is_some() {
+                    break;
+                }
+
                 // Find the bead to get its info for prompting and affected set
                 let bead = ready_beads.iter().find(|b| b.id == *bead_id);
+
+                // Oversized beads go to the planning agent first
+                if let Some(reason) = bead.and_then(|b| plan_reason(config, &db_conn, b)) {
+                    let planning_id = planning::planning_bead_id(bead_id);
+                    let planning_prompt = planning::prompt(data_dir.root(), bead_id, &reason);
+                    let resolved_planning = config.agent.resolved_planning();
+                    match pool
+                        .spawn_worker(
+                            &planning_id,
+                            Some(".beads/**"),
+                            &resolved_planning,
+                            &planning_prompt,
+                            &output_dir,
+                            &db_conn,
+                        )
+                        .await
+                    {
+                        Ok((worker_id, _)) => {
+                            save_counter(&counter_path, pool.next_session_id());
+                            tracing::info!(
+                                worker_id,
+                                bead_id,
+                                "bead is oversized, spawned planning agent"
+                            );
+                        }
+                        Err(PoolError::NoIdleWorker) => break,
+                        Err(e) => {
+                            tracing::warn!(error = %e, bead_id, "failed to spawn planning agent");
+                        }
+                    }
+                    continue;
+                }
+
                 let prompt = match bead {
-                    Some(b) => format!("{}\n\nWork on bead: {}", base_prompt, b.id),
+                    Some(b) if config.experiment.is_enabled() => {
+                        let variant_prompt = experiment_prompt(
+                            config,
+                            data_dir,
+                            &db_conn,
+                            &base_prompt,
+                            &b.id,
+                            &mut variant_prompts,
+                        );
+                        bead_prompt(&db_conn, &variant_prompt, &b.id)
+                    }
+                    Some(b) => bead_prompt(&db_conn, &base_prompt, &b.id),
                     None => continue,
                 };
 
@@ -700,9 +918,24 @@ pub async fn run(
                 }
             }
 
+            if let Some(reason) = budget_stop {
+                eprintln!();
+                eprintln!("BUDGET: {reason}.");
+                eprintln!("        Draining active workers; raise [budget] limits to continue.");
+                eprintln!();
+                tracing::warn!(
+                    active_workers = pool.active_count(),
+                    "{reason}, draining active workers"
+                );
+                draining = true;
+                drain_reason = Some(CoordinatorExitReason::BudgetExceeded(reason));
+                status.update(HarnessState::ShuttingDown);
+            }
+
             // Spawn analysis agent if conditions are met and an idle slot is available
             // (scheduled after coding beads so coding gets priority)
-            if pool.idle_count() > 0
+            if !draining
+                && pool.idle_count() > 0
                 && should_spawn_analysis(config, total_completed_sessions, &pool, &db_conn)
             {
                 let ts = chrono_timestamp();
@@ -769,6 +1002,112 @@ pub async fn run(
     }
 }
 
+/// Whether a bead reached a per-bead `[budget]` limit. Such a bead is only
+/// skipped, not the whole run; it is logged the first time it is found over
+/// its limit and kept in `over_budget` for the status file.
+fn over_bead_budget(
+    config: &HarnessConfig,
+    db_conn: &Connection,
+    bead_id: &str,
+    over_budget: &mut BTreeMap<String, String>,
+) -> bool {
+    match budget_check(budget::check_bead(&config.budget, db_conn, bead_id)) {
+        Some(reason) => {
+            if !over_budget.contains_key(bead_id) {
+                tracing::warn!(bead_id, "{reason}, skipping bead");
+            }
+            over_budget.insert(bead_id.to_string(), reason);
+            true
+        }
+        None => {
+            over_budget.remove(bead_id);
+            false
+        }
+    }
+}
+
+/// Unwrap a budget check, treating a query failure as "within budget" so a
+/// metrics DB hiccup does not halt work.
+fn budget_check(result: rusqlite::Result<Option<String>>) -> Option<String> {
+    result.unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "budget check failed, continuing");
+        None
+    })
+}
+
+// ── Planning agent ──────────────────────────────────────────────────────
+
+/// Whether a bead is being planned or has a plan awaiting approval.
+fn awaiting_plan(pool: &WorkerPool, db_conn: &Connection, bead_id: &str) -> bool {
+    let planning_id = planning::planning_bead_id(bead_id);
+    if pool
+        .snapshot()
+        .iter()
+        .any(|(_, _, id)| *id == Some(planning_id.as_str()))
+    {
+        return true;
+    }
+    matches!(
+        planning::latest_for(db_conn, bead_id),
+        Ok(Some(plan)) if plan.status == PlanStatus::Pending
+    )
+}
+
+/// Why a bead should be planned before coding, if it should: planning is
+/// enabled, it has never been planned, and it is over a `[planning]` threshold.
+fn plan_reason(config: &HarnessConfig, db_conn: &Connection, bead: &ReadyBead) -> Option<String> {
+    if !config.planning.enabled {
+        return None;
+    }
+    if !matches!(planning::latest_for(db_conn, &bead.id), Ok(None)) {
+        return None;
+    }
+    let failures = planning::failed_attempts(db_conn, &bead.id).unwrap_or(0);
+    planning::oversize_reason(&config.planning, bead, failures)
+}
+
+/// Record the plan a finished planning agent left in its worktree, filing
+/// the children right away when `[planning] auto_approve` is set.
+fn finish_planning(
+    config: &HarnessConfig,
+    db_conn: &Connection,
+    bead_id: &str,
+    worktree_path: &std::path::Path,
+) {
+    let plan = planning::take_plan_file(worktree_path);
+    match planning::record(
+        db_conn,
+        &planning::Bd,
+        bead_id,
+        plan,
+        config.planning.auto_approve,
+    ) {
+        Ok(plan) => match plan.status {
+            PlanStatus::Filed => tracing::info!(
+                bead_id,
+                children = %plan.filed.join(", "),
+                "filed planned child beads"
+            ),
+            PlanStatus::Pending => tracing::info!(
+                bead_id,
+                plan_id = plan.id,
+                children = plan.children.len(),
+                "plan awaiting approval (blacksmith plan approve {})",
+                plan.id
+            ),
+            PlanStatus::Declined => {
+                tracing::info!(bead_id, "planning agent kept the bead as-is")
+            }
+            PlanStatus::Failed | PlanStatus::Rejected => tracing::warn!(
+                bead_id,
+                reason = plan.note.as_deref().unwrap_or(""),
+                "planning produced no plan, bead will be coded as-is"
+            ),
+        },
+        Err(e) => tracing::warn!(error = %e, bead_id, "failed to record plan"),
+    }
+}
+
 // ── Analysis agent ──────────────────────────────────────────────────────
 
 /// Check if a bead ID identifies an analysis agent run.
@@ -836,15 +1175,7 @@ fn assemble_analysis_prompt(
 
     // Query open improvements
     let improvements = db::list_improvements(db_conn, Some("open"), None).unwrap_or_default();
-    let improvements_text = if improvements.is_empty() {
-        "(none)".to_string()
-    } else {
-        improvements
-            .iter()
-            .map(|imp| format!("- [{}] {}: {}", imp.ref_id, imp.category, imp.title))
-            .collect::<Vec<_>>()
-            .join("\n")
-    };
+    let improvements_text = dedup::format_clustered(&improvements);
 
     // Query recently promoted improvements for outcome-based gating
     let promoted = db::list_improvements(db_conn, Some("promoted"), None).unwrap_or_default();
@@ -860,6 +1191,17 @@ fn assemble_analysis_prompt(
 
     let open_count = improvements.len();
 
+    // Mine recorded tool calls and gate results for recurring failures
+    let failure_clusters = mining::analysis_evidence(
+        db_conn,
+        config.improvements.analyze_sessions,
+        config.improvements.mine_top as usize,
+    )
+    .unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "failed to mine session traces");
+        "(failure mining failed)".to_string()
+    });
+
     // Count total sessions
     let session_count = observations.len();
 
@@ -878,6 +1220,7 @@ fn assemble_analysis_prompt(
         .replace("{{recent_metrics}}", &metrics_table)
         .replace("{{open_improvements}}", &improvements_text)
         .replace("{{promoted_improvements}}", &promoted_text)
+        .replace("{{failure_clusters}}", &failure_clusters)
         .replace("{{session_count}}", &session_count.to_string())
         .replace("{{max_improvements}}", &max_improvements.to_string())
         .replace("{{open_count}}", &open_count.to_string())
@@ -931,6 +1274,70 @@ fn chrono_timestamp() -> String {
 ///
 /// Falls back to an empty string if prompt assembly fails (e.g., missing prompt file).
 fn assemble_base_prompt(config: &HarnessConfig, data_dir: &DataDir) -> String {
+    assemble_prompt_with(config, data_dir, &config.prompt)
+}
+
+/// The prompt for `bead_id` under the configured experiment: its variant's
+/// prompt file (if any) assembled in place of the base, with the variant's
+/// rule toggles and extra section applied. Falls back to the base prompt if
+/// the assignment can't be recorded.
+fn experiment_prompt(
+    config: &HarnessConfig,
+    data_dir: &DataDir,
+    db_conn: &rusqlite::Connection,
+    base_prompt: &str,
+    bead_id: &str,
+    cache: &mut HashMap<String, String>,
+) -> String {
+    let variant = match experiment::variant_for(db_conn, &config.experiment, bead_id) {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::warn!(error = %e, bead_id, "failed to assign experiment variant");
+            return base_prompt.to_string();
+        }
+    };
+    if let Some(prompt) = cache.get(&variant.name) {
+        return prompt.clone();
+    }
+    let base = match &variant.prompt_file {
+        Some(file) => {
+            let prompt_config = crate::config::PromptConfig {
+                file: Some(file.clone()),
+                ..config.prompt.clone()
+            };
+            assemble_prompt_with(config, data_dir, &prompt_config)
+        }
+        None => base_prompt.to_string(),
+    };
+    let prompt = experiment::apply(db_conn, variant, &base).unwrap_or_else(|e| {
+        tracing::warn!(error = %e, variant = %variant.name, "failed to apply experiment variant");
+        base
+    });
+    tracing::debug!(bead_id, variant = %variant.name, "experiment variant prompt");
+    cache.insert(variant.name.clone(), prompt.clone());
+    prompt
+}
+
+/// The prompt for one bead: the base prompt, the bead's latest handoff (if
+/// an earlier session left one) and the bead assignment.
+fn bead_prompt(db_conn: &Connection, base_prompt: &str, bead_id: &str) -> String {
+    match handoff::prompt_section(db_conn, bead_id) {
+        Ok(Some(section)) => format!("{base_prompt}\n\n{section}\n\nWork on bead: {bead_id}"),
+        Ok(None) => format!("{base_prompt}\n\nWork on bead: {bead_id}"),
+        Err(e) => {
+            tracing::warn!(error = %e, bead_id, "failed to load handoff");
+            format!("{base_prompt}\n\nWork on bead: {bead_id}")
+        }
+    }
+}
+
+/// Assemble the base prompt with an explicit `[prompt]` section, so experiment
+/// variants can swap in an alternate prompt file.
+fn assemble_prompt_with(
+    config: &HarnessConfig,
+    data_dir: &DataDir,
+    prompt_config: &crate::config::PromptConfig,
+) -> String {
     // Compile adapter to determine supported metrics
     let resolved_agent = config.agent.resolved_coding();
     let adapter_name =
@@ -949,7 +1356,7 @@ fn assemble_base_prompt(config: &HarnessConfig, data_dir: &DataDir) -> String {
     };
 
     match prompt::assemble(
-        &config.prompt,
+        prompt_config,
         &config.session.prompt_file,
         &data_dir.db(),
         targets_opt,
@@ -963,10 +1370,79 @@ fn assemble_base_prompt(config: &HarnessConfig, data_dir: &DataDir) -> String {
     }
 }
 
+/// Snapshot architecture metrics for the integration of `bead_id`, for
+/// `blacksmith arch trend`.
+fn record_arch_snapshot(
+    db_conn: &Connection,
+    repo_dir: &std::path::Path,
+    bead_id: &str,
+    commit: Option<&str>,
+) {
+    match arch_history::record_integration(db_conn, repo_dir, bead_id, commit) {
+        Ok(snapshot) => tracing::debug!(
+            bead_id,
+            modules = snapshot.module_count,
+            cycles = snapshot.cycles.len(),
+            god_files = snapshot.god_files.len(),
+            "recorded architecture snapshot"
+        ),
+        Err(e) => tracing::warn!(error = %e, bead_id, "failed to record architecture snapshot"),
+    }
+}
+
+/// The commit checked out in `repo_dir`, if it can be determined.
+fn git_head(repo_dir: &std::path::Path) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(repo_dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Run the periodic architecture review every `architecture.review_every`
+/// successful integrations.
+///
+/// Refactor candidates that breach the `[architecture]` thresholds are filed as
+/// beads when `refactor_auto_approve` is set, otherwise queued as proposals for
+/// `blacksmith arch approve`.
+fn run_architecture_review(
+    config: &HarnessConfig,
+    db_conn: &Connection,
+    repo_dir: &std::path::Path,
+    completed_beads: u32,
+) {
+    let every = config.architecture.review_every;
+    if every == 0 || completed_beads == 0 || !completed_beads.is_multiple_of(every) {
+        return;
+    }
+
+    match arch_review::run_review(db_conn, repo_dir, &config.architecture) {
+        Ok(outcome) => {
+            for (module, bead_id) in &outcome.filed {
+                tracing::info!(module = %module, bead_id = %bead_id, "filed refactor bead");
+            }
+            tracing::info!(
+                candidates = outcome.candidates,
+                findings = outcome.findings,
+                filed = outcome.filed.len(),
+                queued = outcome.queued.len(),
+                "architecture review complete"
+            );
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "architecture review failed");
+        }
+    }
+}
+
 /// Run the self-improvement auto-promotion cycle after a successful integration.
 ///
 /// Checks if any open improvements have been active for at least `auto_promote_after`
-/// successful sessions. If so, promotes them and appends to the configured prompt file.
+/// successful sessions. If so, promotes them into the rules block of the configured prompt file.
 fn run_auto_promotion(
     config: &HarnessConfig,
     db_conn: &Connection,
@@ -1011,23 +1487,13 @@ fn run_auto_promotion(
             "auto-promoting improvement"
         );
 
-        // Promote in DB
-        if let Err(e) = improve::handle_promote(db_path, &imp.ref_id) {
+        // Promote in DB and rewrite the rules block in PROMPT.md
+        if let Err(e) = improve::handle_promote(db_path, &imp.ref_id, &config.improvements) {
             tracing::warn!(
                 error = %e,
                 ref_id = %imp.ref_id,
                 "failed to auto-promote improvement"
             );
-            continue;
-        }
-
-        // Append to PROMPT.md
-        if let Err(e) = append_promoted_to_prompt(&config.improvements.prompt_file, imp) {
-            tracing::warn!(
-                error = %e,
-                ref_id = %imp.ref_id,
-                "failed to append promoted improvement to prompt file"
-            );
         }
     }
 }
@@ -1080,6 +1546,33 @@ fn dismiss_stale_improvements(config: &HarnessConfig, db_conn: &Connection) {
     }
 }
 
+/// Compare promoted improvements against their baselines once their
+/// follow-up window is complete, logging the verdicts.
+fn evaluate_improvement_impact(config: &HarnessConfig, db_conn: &Connection) {
+    let decided = match impact::evaluate_pending(db_conn, config.improvements.impact_window) {
+        Ok(decided) => decided,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to evaluate improvement impact");
+            return;
+        }
+    };
+    for impact in decided {
+        if impact.verdict.flagged() {
+            tracing::warn!(
+                ref_id = %impact.ref_id,
+                impact = %impact.describe(),
+                "promoted improvement had no measurable benefit; consider removing it"
+            );
+        } else {
+            tracing::info!(
+                ref_id = %impact.ref_id,
+                impact = %impact.describe(),
+                "evaluated promoted improvement"
+            );
+        }
+    }
+}
+
 /// Count sessions (observations) recorded after the given ISO timestamp.
 fn sessions_since_improvement(db_conn: &Connection, created_at: &str) -> i64 {
     db_conn
@@ -1091,34 +1584,6 @@ fn sessions_since_improvement(db_conn: &Connection, created_at: &str) -> i64 {
         .unwrap_or_default()
 }
 
-/// Append a promoted improvement's rule to the prompt file.
-fn append_promoted_to_prompt(
-    prompt_path: &std::path::Path,
-    imp: &db::Improvement,
-) -> Result<(), String> {
-    use std::io::Write;
-    let mut file = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(prompt_path)
-        .map_err(|e| format!("failed to open {}: {}", prompt_path.display(), e))?;
-
-    let body_text = imp.body.as_deref().unwrap_or(&imp.title);
-    write!(
-        file,
-        "\n\n<!-- Promoted from {} [{}] -->\n- {}",
-        imp.ref_id, imp.category, body_text
-    )
-    .map_err(|e| format!("failed to write to {}: {}", prompt_path.display(), e))?;
-
-    tracing::info!(
-        ref_id = %imp.ref_id,
-        prompt_file = %prompt_path.display(),
-        "appended promoted improvement to prompt file"
-    );
-    Ok(())
-}
-
 /// Build the list of in-progress assignments from the worker pool's current state.
 ///
 /// Reads affected_globs from the database for each coding worker so the scheduler
@@ -1173,7 +1638,7 @@ fn parse_comma_separated_globs(s: &str) -> Vec<String> {
 /// Per the spec, expansion is always granted (optimistic concurrency).
 /// Conflicts with other in-progress tasks are resolved at integration time.
 fn check_and_process_expand_files(pool: &WorkerPool, db_conn: &Connection) {
-    for (worker_id, state, _bead_id) in pool.snapshot() {
+    for (worker_id, state, bead_id) in pool.snapshot() {
         if state != crate::pool::WorkerState::Coding {
             continue;
         }
@@ -1233,7 +1698,7 @@ fn check_and_process_expand_files(pool: &WorkerPool, db_conn: &Connection) {
             .map(|g| parse_comma_separated_globs(&g))
             .unwrap_or_default();
 
-        let mut merged = existing;
+        let mut merged = existing.clone();
         for g in &new_globs {
             if !merged.contains(g) {
                 merged.push(g.clone());
@@ -1252,6 +1717,16 @@ fn check_and_process_expand_files(pool: &WorkerPool, db_conn: &Connection) {
                     merged = %merged_str,
                     "expanded affected set for worker"
                 );
+                if let Some(bead_id) = bead_id {
+                    record_expand_file_event(
+                        db_conn,
+                        &worktree_path,
+                        bead_id,
+                        &existing,
+                        &merged,
+                        &new_globs,
+                    );
+                }
             }
             Ok(false) => {
                 tracing::warn!(
@@ -1282,6 +1757,33 @@ fn check_and_process_expand_files(pool: &WorkerPool, db_conn: &Connection) {
     }
 }
 
+/// Record an expansion event for a `.blacksmith-expand` request: the modules
+/// covered by the affected set before and after the agent widened it.
+fn record_expand_file_event(
+    db_conn: &Connection,
+    worktree_path: &std::path::Path,
+    bead_id: &str,
+    predicted_globs: &[String],
+    expanded_globs: &[String],
+    new_globs: &[String],
+) {
+    let modules = module_detect::detect_modules_from_repo(worktree_path);
+    let event = ExpansionEvent {
+        task_id: bead_id.to_string(),
+        predicted_modules: module_detect::modules_matching(
+            &modules,
+            worktree_path,
+            predicted_globs,
+        ),
+        actual_modules: module_detect::modules_matching(&modules, worktree_path, expanded_globs),
+        expansion_reason: format!("Agent requested expansion: {}", new_globs.join(", ")),
+        timestamp: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+    };
+    if let Err(e) = expansion_event::record(db_conn, &event) {
+        tracing::warn!(bead_id, error = %e, "failed to record expansion event");
+    }
+}
+
 /// Close a bead directly without a merge step (for single-agent mode).
 ///
 /// Runs `bd close` and `bd sync` since the agent already committed to the main branch.
@@ -1604,12 +2106,49 @@ fn parse_orphaned_bead_ids(json_str: &str) -> Vec<String> {
     }
 }
 
+/// Why an open bead is not being scheduled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Unscheduled {
+    /// Part of a dependency cycle.
+    Cycle,
+    /// An epic whose children are still open.
+    EpicWithOpenChildren,
+    /// Waiting on these open beads.
+    Blocked(Vec<String>),
+    /// Carries the `workers.hold_label` label.
+    Held(String),
+    /// Fails the `workers` readiness gate, with the reason.
+    NotReady(String),
+    /// Its affected files overlap those locked by this in-progress bead.
+    LockConflict(String),
+    /// A planning agent proposed splitting it; the plan awaits approval.
+    AwaitingPlan(i64),
+}
+
+impl fmt::Display for Unscheduled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unscheduled::Cycle => write!(f, "dependency cycle"),
+            Unscheduled::EpicWithOpenChildren => write!(f, "epic with open children"),
+            Unscheduled::Blocked(deps) => write!(f, "blocked by {}", deps.join(", ")),
+            Unscheduled::Held(label) => write!(f, "held (label '{label}')"),
+            Unscheduled::NotReady(reason) => write!(f, "not ready: {reason}"),
+            Unscheduled::LockConflict(holder) => {
+                write!(f, "affected files locked by {holder}")
+            }
+            Unscheduled::AwaitingPlan(id) => write!(f, "plan {id} awaiting approval"),
+        }
+    }
+}
+
 /// Result of querying beads: ready beads for scheduling and the full graph for cycle detection.
 struct BeadQuery {
     /// Beads available for scheduling (after filtering out cycled ones).
     ready: Vec<ReadyBead>,
     /// Number of open beads blocked by unresolved dependencies.
     blocked_count: usize,
+    /// Open beads filtered out, with the reason.
+    excluded: Vec<(String, Unscheduled)>,
     /// Detected dependency cycles (each cycle is a list of bead IDs).
     /// Used by CLI status output (simple-agent-harness-cqf) and in tests.
     #[allow(dead_code)]
@@ -1621,19 +2160,20 @@ struct BeadQuery {
 /// Shells out to `bd list --status=open --json` to get all open beads with dependencies.
 /// Runs cycle detection on every scheduling pass (cycles can be created or broken mid-run).
 /// Cycled beads are filtered out of the scheduling pool.
-fn query_ready_beads() -> BeadQuery {
+fn query_ready_beads(workers: &WorkersConfig) -> BeadQuery {
     match std::process::Command::new("bd")
         .args(["list", "--status=open", "--json"])
         .output()
     {
         Ok(output) if output.status.success() => {
-            parse_and_filter_beads(&String::from_utf8_lossy(&output.stdout))
+            parse_and_filter_beads(&String::from_utf8_lossy(&output.stdout), workers)
         }
         _ => {
             tracing::debug!("bd command not available or failed, no beads to schedule");
             BeadQuery {
                 ready: Vec::new(),
                 blocked_count: 0,
+                excluded: Vec::new(),
                 cycles: Vec::new(),
             }
         }
@@ -1641,9 +2181,13 @@ fn query_ready_beads() -> BeadQuery {
 }
 
 /// Parse JSON bead data, detect cycles, filter out cycled beads, and return schedulable beads.
-fn parse_and_filter_beads(json_str: &str) -> BeadQuery {
+///
+/// Beads that pass the dependency checks must also pass the `[workers]`
+/// readiness gate (see [`readiness_block`]).
+fn parse_and_filter_beads(json_str: &str, workers: &WorkersConfig) -> BeadQuery {
     let (ready_beads, bead_nodes) = parse_ready_beads_json(json_str);
     let _open_ids_all: HashSet<String> = ready_beads.iter().map(|b| b.id.clone()).collect();
+    let mut excluded: Vec<(String, Unscheduled)> = Vec::new();
 
     // Run cycle detection on the dependency graph
     let cycles = cycle_detect::detect_cycles(&bead_nodes);
@@ -1667,10 +2211,11 @@ fn parse_and_filter_beads(json_str: &str) -> BeadQuery {
             .flat_map(|c| c.iter().map(|s| s.as_str()))
             .collect();
 
-        after_cycle = ready_beads
+        let (cycled, rest): (Vec<ReadyBead>, Vec<ReadyBead>) = ready_beads
             .into_iter()
-            .filter(|b| !cycled_ids.contains(b.id.as_str()))
-            .collect();
+            .partition(|b| cycled_ids.contains(b.id.as_str()));
+        excluded.extend(cycled.into_iter().map(|b| (b.id, Unscheduled::Cycle)));
+        after_cycle = rest;
 
         let excluded_count = cycled_ids.len();
         tracing::warn!(
@@ -1698,16 +2243,15 @@ fn parse_and_filter_beads(json_str: &str) -> BeadQuery {
     }
 
     let before_epic_count = after_cycle.len();
-    let after_epic: Vec<ReadyBead> = after_cycle
-        .into_iter()
-        .filter(|b| {
-            if b.issue_type.eq_ignore_ascii_case("epic") {
-                !epics_with_open_children.contains(&b.id)
-            } else {
-                true
-            }
-        })
-        .collect();
+    let (epics, after_epic): (Vec<ReadyBead>, Vec<ReadyBead>) =
+        after_cycle.into_iter().partition(|b| {
+            b.issue_type.eq_ignore_ascii_case("epic") && epics_with_open_children.contains(&b.id)
+        });
+    excluded.extend(
+        epics
+            .into_iter()
+            .map(|b| (b.id, Unscheduled::EpicWithOpenChildren)),
+    );
     let epic_filtered_count = before_epic_count - after_epic.len();
     if epic_filtered_count > 0 {
         tracing::info!(
@@ -1726,25 +2270,169 @@ fn parse_and_filter_beads(json_str: &str) -> BeadQuery {
         .collect();
 
     let before_dep_count = after_epic.len();
-    let truly_ready: Vec<ReadyBead> = after_epic
-        .into_iter()
-        .filter(|b| {
-            deps_map
-                .get(b.id.as_str())
-                .map(|deps| deps.iter().all(|d| !open_ids.contains(d)))
-                .unwrap_or(true)
-        })
-        .collect();
+    let mut truly_ready: Vec<ReadyBead> = Vec::new();
+    for b in after_epic {
+        let open_deps: Vec<String> = deps_map
+            .get(b.id.as_str())
+            .map(|deps| {
+                deps.iter()
+                    .filter(|d| open_ids.contains(*d))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        if open_deps.is_empty() {
+            truly_ready.push(b);
+        } else {
+            excluded.push((b.id, Unscheduled::Blocked(open_deps)));
+        }
+    }
 
     let blocked_count = before_dep_count - truly_ready.len();
 
+    // --- Readiness gate ---
+    // Applied after dependency filtering so a held or still-drafted bead
+    // keeps blocking its dependents.
+    let now = Utc::now();
+    let before_gate_count = truly_ready.len();
+    let mut gated_ready = Vec::new();
+    for b in truly_ready {
+        match readiness_block(&b, workers, now) {
+            Some(reason) => excluded.push((b.id, reason)),
+            None => gated_ready.push(b),
+        }
+    }
+    let gated_count = before_gate_count - gated_ready.len();
+    if gated_count > 0 {
+        tracing::debug!(
+            gated = gated_count,
+            remaining = gated_ready.len(),
+            "held back {gated_count} beads by the readiness gate"
+        );
+    }
+
     BeadQuery {
-        ready: truly_ready,
+        ready: gated_ready,
         blocked_count,
+        excluded,
         cycles,
     }
 }
 
+/// Check a bead against the `[workers]` readiness gate.
+///
+/// Beads carrying `hold_label` are never scheduled. When `ready_label` or
+/// `ready_after_mins` is configured, a bead needs the label or to have gone
+/// unedited that long; a bead without an update time counts as settled.
+fn readiness_block(
+    bead: &ReadyBead,
+    workers: &WorkersConfig,
+    now: DateTime<Utc>,
+) -> Option<Unscheduled> {
+    let has_label = |label: &str| bead.labels.iter().any(|l| l.eq_ignore_ascii_case(label));
+    if !workers.hold_label.is_empty() && has_label(&workers.hold_label) {
+        return Some(Unscheduled::Held(workers.hold_label.clone()));
+    }
+
+    let ready_label = workers.ready_label.as_deref().filter(|l| !l.is_empty());
+    let min_age = workers.ready_after_mins;
+    if ready_label.is_none() && min_age == 0 {
+        return None;
+    }
+    if ready_label.is_some_and(has_label) {
+        return None;
+    }
+
+    let mut reasons = Vec::new();
+    if let Some(label) = ready_label {
+        reasons.push(format!("missing label '{label}'"));
+    }
+    if min_age > 0 {
+        let age = bead.updated_at.map(|t| (now - t).num_minutes());
+        match age {
+            Some(age) if age < min_age as i64 => {
+                reasons.push(format!("updated {age}m ago, ready after {min_age}m"));
+            }
+            _ => return None,
+        }
+    }
+    Some(Unscheduled::NotReady(reasons.join(" and ")))
+}
+
+/// Open beads the coordinator would not schedule right now, with the reason.
+///
+/// Lock conflicts are checked against the active assignments recorded in
+/// the database at `db_path`.
+pub fn unscheduled_beads(workers: &WorkersConfig, db_path: &Path) -> Vec<(String, Unscheduled)> {
+    let mut query = query_ready_beads(workers);
+    let mut unscheduled = query.excluded;
+
+    let conn = if db_path.exists() {
+        db::open_or_create(db_path).ok()
+    } else {
+        None
+    };
+    let active = conn
+        .as_ref()
+        .and_then(|conn| db::active_worker_assignments(conn).ok())
+        .unwrap_or_default();
+    if let Some(conn) = &conn {
+        query
+            .ready
+            .retain(|bead| match planning::latest_for(conn, &bead.id) {
+                Ok(Some(plan)) if plan.status == PlanStatus::Pending => {
+                    unscheduled.push((bead.id.clone(), Unscheduled::AwaitingPlan(plan.id)));
+                    false
+                }
+                _ => true,
+            });
+    }
+    let in_progress: Vec<InProgressAssignment> = active
+        .into_iter()
+        .map(|wa| InProgressAssignment {
+            bead_id: wa.bead_id,
+            affected_globs: wa
+                .affected_globs
+                .as_deref()
+                .map(parse_comma_separated_globs),
+        })
+        .collect();
+
+    let assignable: HashSet<String> = scheduler::next_assignable_tasks(&query.ready, &in_progress)
+        .into_iter()
+        .collect();
+    for bead in &query.ready {
+        if assignable.contains(&bead.id) {
+            continue;
+        }
+        if let Some(holder) = lock_holder(bead, &in_progress) {
+            unscheduled.push((
+                bead.id.clone(),
+                Unscheduled::LockConflict(holder.bead_id.clone()),
+            ));
+        }
+    }
+    unscheduled
+}
+
+/// The in-progress assignment whose lock keeps `bead` from being scheduled.
+///
+/// A missing affected set, on either side, overlaps everything; an explicit
+/// glob overlap is preferred when there is one.
+fn lock_holder<'a>(
+    bead: &ReadyBead,
+    in_progress: &'a [InProgressAssignment],
+) -> Option<&'a InProgressAssignment> {
+    in_progress
+        .iter()
+        .filter(|a| a.bead_id != bead.id)
+        .filter(|a| match (&bead.affected_globs, &a.affected_globs) {
+            (Some(globs), Some(locked)) => scheduler::globs_overlap(globs, locked),
+            _ => true,
+        })
+        .min_by_key(|a| bead.affected_globs.is_none() || a.affected_globs.is_none())
+}
+
 fn should_log_dependency_filter_info(
     previous_counts: Option<(usize, usize)>,
     current_counts: (usize, usize),
@@ -1842,12 +2530,34 @@ fn parse_ready_beads_json(json_str: &str) -> (Vec<ReadyBead>, Vec<BeadNode>) {
                     })
                     .collect();
 
+                let labels: Vec<String> = b
+                    .get("labels")
+                    .and_then(|v| v.as_array())
+                    .map(|labels| {
+                        labels
+                            .iter()
+                            .filter_map(|l| l.as_str().map(|s| s.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let updated_at = b
+                    .get("updated_at")
+                    .and_then(|v| v.as_str())
+                    .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                    .map(|t| t.with_timezone(&Utc));
+
                 ready.push(ReadyBead {
                     id: id.clone(),
                     priority,
                     issue_type,
                     parent_child_ids,
                     affected_globs,
+                    labels,
+                    updated_at,
+                    description_len: b
+                        .get("description")
+                        .and_then(|d| d.as_str())
+                        .map_or(0, str::len),
                 });
 
                 nodes.push(BeadNode { id, depends_on });
@@ -1913,6 +2623,82 @@ fn handle_tripped_failure(tripped: &TrippedFailure) {
     }
 }
 
+/// Archive a failed worktree's work under the salvage directory.
+///
+/// Best-effort: failures are logged and never block the coordinator.
+fn salvage_worktree(
+    db_conn: &Connection,
+    salvage_dir: &std::path::Path,
+    wt_path: &std::path::Path,
+    bead_id: &str,
+    base_branch: &str,
+    reason: &str,
+) {
+    match salvage::capture(db_conn, salvage_dir, wt_path, bead_id, base_branch, reason) {
+        Ok(Some(record)) => {
+            tracing::info!(
+                bead_id,
+                branch = %record.branch,
+                commits = record.commits,
+                "salvaged worktree"
+            );
+        }
+        Ok(None) => {}
+        Err(e) => {
+            tracing::warn!(bead_id, error = %e, "failed to salvage worktree");
+        }
+    }
+}
+
+/// Salvage work left behind in worktrees by a previous run, before orphan
+/// cleanup removes them.
+///
+/// Each worktree is matched to the most recent assignment that ran in it;
+/// directories with no recorded assignment are left to orphan cleanup.
+fn salvage_stale_worktrees(
+    db_conn: &Connection,
+    worktrees_dir: &std::path::Path,
+    salvage_dir: &std::path::Path,
+    base_branch: &str,
+) {
+    let Ok(entries) = std::fs::read_dir(worktrees_dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|e| e.path()) {
+        if !path.is_dir() {
+            continue;
+        }
+        let assignment = match db::latest_worker_assignment_by_worktree(
+            db_conn,
+            &path.to_string_lossy(),
+        ) {
+            Ok(Some(a)) => a,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "failed to look up worktree assignment");
+                continue;
+            }
+        };
+        let reason = match assignment.status.as_str() {
+            "coding" | "integrating" | "completed" => {
+                "interrupted before integration (coordinator restart)".to_string()
+            }
+            status => match assignment.failure_notes {
+                Some(notes) => format!("{status}: {notes}"),
+                None => status.to_string(),
+            },
+        };
+        salvage_worktree(
+            db_conn,
+            salvage_dir,
+            &path,
+            &assignment.bead_id,
+            base_branch,
+            &reason,
+        );
+    }
+}
+
 /// Extension trait for StopFileStatus (same as in runner.rs).
 trait StopFileStatusExt {
     fn is_detected(&self) -> bool;
@@ -1939,6 +2725,95 @@ fn save_counter(path: &std::path::Path, value: u64) {
     }
 }
 
+/// Record which bead a finished coding session worked on, so ingestion,
+/// budgets, experiment reports and gate mining don't have to guess it from
+/// git history or the transcript.
+fn record_session_bead(outcome: &SessionOutcome, pool: &WorkerPool, db_conn: &Connection) {
+    let Some(bead_id) = pool.worker_bead_id(outcome.worker_id) else {
+        return;
+    };
+    if is_analysis_bead(bead_id) || planning::planned_bead(bead_id).is_some() {
+        return;
+    }
+    if let Err(e) = db::insert_event(
+        db_conn,
+        outcome.session_id as i64,
+        "session.bead_id",
+        Some(bead_id),
+        None,
+    ) {
+        tracing::warn!(error = %e, bead_id, "failed to record session bead");
+    }
+}
+
+/// Tag a finished coding session with its bead's experiment variant, if the
+/// bead was assigned one.
+fn tag_experiment_session(
+    config: &HarnessConfig,
+    outcome: &SessionOutcome,
+    pool: &WorkerPool,
+    db_conn: &Connection,
+) {
+    if !config.experiment.is_enabled() {
+        return;
+    }
+    let Some(bead_id) = pool.worker_bead_id(outcome.worker_id) else {
+        return;
+    };
+    if is_analysis_bead(bead_id) || planning::planned_bead(bead_id).is_some() {
+        return;
+    }
+    let name = &config.experiment.name;
+    let result =
+        experiment::recorded_variant(db_conn, name, bead_id).and_then(|variant| match variant {
+            Some(v) => experiment::tag_session(db_conn, outcome.session_id as i64, name, &v),
+            None => Ok(()),
+        });
+    if let Err(e) = result {
+        tracing::warn!(error = %e, bead_id, "failed to tag experiment variant");
+    }
+}
+
+/// Record a handoff for a finished coding session's bead, unless the agent
+/// already wrote one during the session with `blacksmith progress add --bead`.
+fn capture_handoff(
+    config: &HarnessConfig,
+    outcome: &SessionOutcome,
+    pool: &WorkerPool,
+    db_conn: &Connection,
+) {
+    let Some(bead_id) = pool.worker_bead_id(outcome.worker_id) else {
+        return;
+    };
+    if is_analysis_bead(bead_id) || planning::planned_bead(bead_id).is_some() {
+        return;
+    }
+    let started =
+        chrono::Utc::now() - chrono::Duration::from_std(outcome.duration).unwrap_or_default();
+    let since = started.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let files = pool
+        .worker_worktree_path(outcome.worker_id)
+        .map(|wt| handoff::changed_files(&wt, &config.workers.base_branch))
+        .unwrap_or_default();
+
+    let result = handoff::agent_wrote_since(db_conn, bead_id, &since).and_then(|written| {
+        if written {
+            return Ok(());
+        }
+        let captured = handoff::capture(
+            db_conn,
+            bead_id,
+            outcome.session_id as i64,
+            outcome.exit_code,
+            files,
+        )?;
+        handoff::record(db_conn, &captured).map(|_| ())
+    });
+    if let Err(e) = result {
+        tracing::warn!(error = %e, bead_id, "failed to capture handoff");
+    }
+}
+
 /// Ingest JSONL metrics from a worker's output file into the metrics DB.
 ///
 /// Called after each worker completion (success or failure), same as the serial runner does.
@@ -1947,6 +2822,7 @@ fn ingest_worker_metrics(
     outcome: &SessionOutcome,
     db_conn: &Connection,
     extraction_rules: &[crate::config::CompiledRule],
+    pricing: &crate::config::PricingConfig,
     adapter: &dyn crate::adapters::AgentAdapter,
 ) -> Option<ingest::IngestResult> {
     match ingest::ingest_session_with_rules(
@@ -1955,6 +2831,7 @@ fn ingest_worker_metrics(
         &outcome.output_file,
         outcome.exit_code,
         extraction_rules,
+        pricing,
         adapter,
     ) {
         Ok(m) => {
@@ -1963,6 +2840,7 @@ fn ingest_worker_metrics(
                 session = outcome.session_id,
                 turns = m.turns_total,
                 cost_usd = format!("{:.4}", m.cost_estimate_usd),
+                computed_cost_usd = ?m.cost_computed_usd,
                 "JSONL metrics ingested"
             );
             Some(m)
@@ -2174,7 +3052,9 @@ mod tests {
                 base_branch: "main".to_string(),
                 worktrees_dir: "worktrees".to_string(),
                 persistent: false,
+                ..Default::default()
             },
+            integration: IntegrationConfig::default(),
             reconciliation: ReconciliationConfig::default(),
             architecture: ArchitectureConfig::default(),
             quality_gates: QualityGatesConfig::default(),
@@ -2190,6 +3070,10 @@ mod tests {
             },
             serve: ServeConfig::default(),
             speck_validate: crate::config::SpeckValidateConfig::default(),
+            pricing: crate::config::PricingConfig::default(),
+            budget: crate::config::BudgetConfig::default(),
+            experiment: crate::config::ExperimentConfig::default(),
+            planning: crate::config::PlanningConfig::default(),
         }
     }
 
@@ -2283,7 +3167,7 @@ mod tests {
             {"id": "a", "priority": 1, "dependencies": []},
             {"id": "b", "priority": 2, "dependencies": []}
         ]"#;
-        let result = parse_and_filter_beads(json);
+        let result = parse_and_filter_beads(json, &WorkersConfig::default());
         assert_eq!(result.ready.len(), 2);
         assert_eq!(result.blocked_count, 0);
         assert!(result.cycles.is_empty());
@@ -2296,7 +3180,7 @@ mod tests {
             {"id": "a", "priority": 1, "dependencies": [{"depends_on_id": "b", "type": "blocks"}]},
             {"id": "b", "priority": 2, "dependencies": []}
         ]"#;
-        let result = parse_and_filter_beads(json);
+        let result = parse_and_filter_beads(json, &WorkersConfig::default());
         assert_eq!(result.ready.len(), 1);
         assert_eq!(result.blocked_count, 1);
         assert_eq!(result.ready[0].id, "b");
@@ -2310,7 +3194,7 @@ mod tests {
             {"id": "a", "priority": 1, "dependencies": [{"depends_on_id": "c", "type": "blocks"}]},
             {"id": "b", "priority": 2, "dependencies": []}
         ]"#;
-        let result = parse_and_filter_beads(json);
+        let result = parse_and_filter_beads(json, &WorkersConfig::default());
         assert_eq!(result.ready.len(), 2);
         assert_eq!(result.blocked_count, 0);
         assert!(result.cycles.is_empty());
@@ -2324,7 +3208,7 @@ mod tests {
             {"id": "b", "priority": 2, "dependencies": [{"depends_on_id": "c", "type": "blocks"}]},
             {"id": "c", "priority": 3, "dependencies": []}
         ]"#;
-        let result = parse_and_filter_beads(json);
+        let result = parse_and_filter_beads(json, &WorkersConfig::default());
         assert_eq!(result.ready.len(), 1);
         assert_eq!(result.blocked_count, 2);
         assert_eq!(result.ready[0].id, "c");
@@ -2338,7 +3222,7 @@ mod tests {
             {"id": "b", "priority": 2, "dependencies": [{"depends_on_id": "a", "type": "blocks"}]},
             {"id": "c", "priority": 3, "dependencies": []}
         ]"#;
-        let result = parse_and_filter_beads(json);
+        let result = parse_and_filter_beads(json, &WorkersConfig::default());
         // a and b are in a cycle, only c should remain
         assert_eq!(result.ready.len(), 1);
         assert_eq!(result.blocked_count, 0);
@@ -2414,7 +3298,7 @@ mod tests {
             {"id": "epic-1", "issue_type": "epic", "priority": 1, "dependencies": [{"depends_on_id": "task-1", "type": "parent-child"}]},
             {"id": "task-1", "issue_type": "task", "priority": 2, "dependencies": []}
         ]"#;
-        let result = parse_and_filter_beads(json);
+        let result = parse_and_filter_beads(json, &WorkersConfig::default());
         assert_eq!(result.ready.len(), 1);
         assert_eq!(result.ready[0].id, "task-1");
     }
@@ -2425,12 +3309,123 @@ mod tests {
             {"id": "epic-1", "issue_type": "epic", "priority": 1, "dependencies": [{"depends_on_id": "task-closed", "type": "parent-child"}]},
             {"id": "task-1", "issue_type": "task", "priority": 2, "dependencies": []}
         ]"#;
-        let result = parse_and_filter_beads(json);
+        let result = parse_and_filter_beads(json, &WorkersConfig::default());
         assert_eq!(result.ready.len(), 2);
         assert!(result.ready.iter().any(|b| b.id == "epic-1"));
         assert!(result.ready.iter().any(|b| b.id == "task-1"));
     }
 
+    #[test]
+    fn test_parse_and_filter_beads_records_exclusion_reasons() {
+        let json = r#"[
+            {"id": "a", "dependencies": [{"depends_on_id": "b", "type": "blocks"}]},
+            {"id": "b", "dependencies": [{"depends_on_id": "a", "type": "blocks"}]},
+            {"id": "c", "dependencies": [{"depends_on_id": "d", "type": "blocks"}]},
+            {"id": "d", "dependencies": []},
+            {"id": "epic-1", "issue_type": "epic", "dependencies": []},
+            {"id": "task-1", "dependencies": [{"depends_on_id": "epic-1", "type": "parent-child"}]}
+        ]"#;
+        let result = parse_and_filter_beads(json, &WorkersConfig::default());
+        let reason = |id: &str| {
+            result
+                .excluded
+                .iter()
+                .find(|(b, _)| b == id)
+                .map(|(_, r)| r.clone())
+        };
+        assert_eq!(reason("a"), Some(Unscheduled::Cycle));
+        assert_eq!(reason("b"), Some(Unscheduled::Cycle));
+        assert_eq!(
+            reason("c"),
+            Some(Unscheduled::Blocked(vec!["d".to_string()]))
+        );
+        assert_eq!(reason("epic-1"), Some(Unscheduled::EpicWithOpenChildren));
+        assert_eq!(reason("d"), None);
+        assert_eq!(
+            Unscheduled::Blocked(vec!["d".to_string()]).to_string(),
+            "blocked by d"
+        );
+    }
+
+    #[test]
+    fn test_parse_and_filter_beads_excludes_held_beads() {
+        let json = r#"[
+            {"id": "a", "labels": ["hold"], "dependencies": []},
+            {"id": "b", "dependencies": [{"depends_on_id": "a", "type": "blocks"}]},
+            {"id": "c", "labels": ["backend"], "dependencies": []}
+        ]"#;
+        let result = parse_and_filter_beads(json, &WorkersConfig::default());
+        assert_eq!(result.ready.len(), 1);
+        assert_eq!(result.ready[0].id, "c");
+        // A held bead still blocks its dependents
+        assert!(result
+            .excluded
+            .contains(&("a".to_string(), Unscheduled::Held("hold".to_string()))));
+        assert!(result
+            .excluded
+            .contains(&("b".to_string(), Unscheduled::Blocked(vec!["a".to_string()]))));
+    }
+
+    #[test]
+    fn test_parse_and_filter_beads_requires_ready_label_or_age() {
+        let fresh = Utc::now().to_rfc3339();
+        let json = format!(
+            r#"[
+            {{"id": "labelled", "labels": ["ready-for-agent"], "updated_at": "{fresh}", "dependencies": []}},
+            {{"id": "settled", "updated_at": "2020-01-01T00:00:00Z", "dependencies": []}},
+            {{"id": "drafting", "updated_at": "{fresh}", "dependencies": []}}
+        ]"#
+        );
+
+        let label_only = WorkersConfig {
+            ready_label: Some("ready-for-agent".to_string()),
+            ..Default::default()
+        };
+        let result = parse_and_filter_beads(&json, &label_only);
+        let ready: Vec<&str> = result.ready.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ready, vec!["labelled"]);
+        assert!(result.excluded.contains(&(
+            "drafting".to_string(),
+            Unscheduled::NotReady("missing label 'ready-for-agent'".to_string())
+        )));
+
+        let label_or_age = WorkersConfig {
+            ready_label: Some("ready-for-agent".to_string()),
+            ready_after_mins: 30,
+            ..Default::default()
+        };
+        let result = parse_and_filter_beads(&json, &label_or_age);
+        let ready: Vec<&str> = result.ready.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ready, vec!["labelled", "settled"]);
+        let (_, reason) = &result.excluded[0];
+        assert_eq!(result.excluded[0].0, "drafting");
+        assert!(reason.to_string().contains("ready after 30m"), "{reason}");
+    }
+
+    #[test]
+    fn test_plan_reason_only_for_unplanned_oversized_beads() {
+        let dir = tempdir().unwrap();
+        let conn = test_db(dir.path());
+        let mut config = test_config(dir.path());
+        config.planning.max_description_chars = 20;
+
+        let json = r#"[
+            {"id": "big", "priority": 1, "description": "a description well over twenty chars"},
+            {"id": "small", "priority": 1, "description": "short"}
+        ]"#;
+        let (beads, _) = parse_ready_beads_json(json);
+        assert_eq!(plan_reason(&config, &conn, &beads[0]), None);
+
+        config.planning.enabled = true;
+        let reason = plan_reason(&config, &conn, &beads[0]).unwrap();
+        assert!(reason.contains("description is 36 chars"), "{reason}");
+        assert_eq!(plan_reason(&config, &conn, &beads[1]), None);
+
+        // Once a plan is recorded the bead is not planned again
+        planning::record(&conn, &planning::Bd, "big", Ok(Vec::new()), false).unwrap();
+        assert_eq!(plan_reason(&config, &conn, &beads[0]), None);
+    }
+
     #[test]
     fn test_parse_open_epic_hierarchy_extracts_parent_child_edges() {
         let json = r#"[
@@ -2495,6 +3490,7 @@ mod tests {
             base_branch: "main".to_string(),
             worktrees_dir: "worktrees".to_string(),
             persistent: false,
+            ..Default::default()
         };
         let pool = WorkerPool::new(&config, dir.path().to_path_buf(), wt_dir, 0);
         let db_path = dir.path().join("test.db");
@@ -2685,6 +3681,7 @@ mod tests {
             base_branch: "main".to_string(),
             worktrees_dir: "worktrees".to_string(),
             persistent: false,
+            ..Default::default()
         };
         let mut pool = WorkerPool::new(&workers_config, repo.to_path_buf(), wt_dir, 0);
 
@@ -2720,6 +3717,61 @@ mod tests {
         assert!(!fake_worktree.join(EXPAND_FILE_NAME).exists());
     }
 
+    #[test]
+    fn test_expand_file_records_expansion_event() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db_conn = db::open_or_create(&db_path).unwrap();
+
+        let fake_worktree = dir.path().join("worker-0");
+        for module in ["db", "config"] {
+            let module_dir = fake_worktree.join("src").join(module);
+            std::fs::create_dir_all(&module_dir).unwrap();
+            std::fs::write(module_dir.join("mod.rs"), "").unwrap();
+        }
+
+        let assignment_id = db::insert_worker_assignment(
+            &db_conn,
+            0,
+            "beads-expand-event",
+            &fake_worktree.to_string_lossy(),
+            "coding",
+            Some("src/db/**"),
+        )
+        .unwrap();
+
+        let workers_config = WorkersConfig {
+            max: 1,
+            ..Default::default()
+        };
+        let mut pool = WorkerPool::new(
+            &workers_config,
+            dir.path().to_path_buf(),
+            dir.path().to_path_buf(),
+            0,
+        );
+        pool.set_worker_state_for_test(
+            0,
+            crate::pool::WorkerState::Coding,
+            Some(assignment_id),
+            Some("beads-expand-event".to_string()),
+            Some(fake_worktree.clone()),
+        );
+
+        std::fs::write(fake_worktree.join(EXPAND_FILE_NAME), "src/config/**\n").unwrap();
+        check_and_process_expand_files(&pool, &db_conn);
+
+        let events = expansion_event::get_recent(&db_conn, 10).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].task_id, "beads-expand-event");
+        assert_eq!(events[0].predicted_modules, vec!["db".to_string()]);
+        assert_eq!(
+            events[0].actual_modules,
+            vec!["config".to_string(), "db".to_string()]
+        );
+        assert!(events[0].expansion_reason.contains("src/config/**"));
+    }
+
     #[test]
     fn test_expand_file_no_duplicates() {
         let dir = tempdir().unwrap();
@@ -2744,6 +3796,7 @@ mod tests {
             base_branch: "main".to_string(),
             worktrees_dir: "worktrees".to_string(),
             persistent: false,
+            ..Default::default()
         };
         let mut pool = WorkerPool::new(
             &workers_config,
@@ -2804,6 +3857,7 @@ mod tests {
             base_branch: "main".to_string(),
             worktrees_dir: "worktrees".to_string(),
             persistent: false,
+            ..Default::default()
         };
         let mut pool = WorkerPool::new(
             &workers_config,
@@ -2858,6 +3912,7 @@ mod tests {
             base_branch: "main".to_string(),
             worktrees_dir: "worktrees".to_string(),
             persistent: false,
+            ..Default::default()
         };
         let mut pool = WorkerPool::new(
             &workers_config,
@@ -2913,6 +3968,7 @@ mod tests {
             base_branch: "main".to_string(),
             worktrees_dir: "worktrees".to_string(),
             persistent: false,
+            ..Default::default()
         };
         let mut pool = WorkerPool::new(
             &workers_config,
@@ -2993,6 +4049,54 @@ mod tests {
         assert_eq!(imp.status, "open");
     }
 
+    #[test]
+    fn test_lock_holder_treats_missing_globs_as_overlapping() {
+        let assignment = |id: &str, globs: Option<&[&str]>| InProgressAssignment {
+            bead_id: id.to_string(),
+            affected_globs: globs.map(|g| g.iter().map(|s| s.to_string()).collect()),
+        };
+        let json = r#"[{"id":"a","title":"A","priority":1,"design":"affected: src/db/**"},
+                       {"id":"b","title":"B","priority":1}]"#;
+        let (beads, _) = parse_ready_beads_json(json);
+        let (scoped, unscoped) = (&beads[0], &beads[1]);
+
+        let holding = [assignment("x", None), assignment("y", Some(&["src/db/**"]))];
+        assert_eq!(lock_holder(scoped, &holding).unwrap().bead_id, "y");
+        assert_eq!(lock_holder(unscoped, &holding).unwrap().bead_id, "x");
+
+        let unrelated = [assignment("z", Some(&["docs/**"]))];
+        assert!(lock_holder(scoped, &unrelated).is_none());
+        assert_eq!(lock_holder(unscoped, &unrelated).unwrap().bead_id, "z");
+        let unscoped_holder = [assignment("x", None)];
+        assert_eq!(lock_holder(scoped, &unscoped_holder).unwrap().bead_id, "x");
+    }
+
+    #[test]
+    fn test_over_bead_budget_skips_only_that_bead() {
+        let dir = tempdir().unwrap();
+        let db_conn = test_db(dir.path());
+        db::upsert_observation(&db_conn, 1, "2026-02-16T10:00:00Z", None, None, "{}").unwrap();
+        db::insert_event(&db_conn, 1, "session.bead_id", Some("a"), None).unwrap();
+
+        let mut config = test_config(dir.path());
+        config.budget.max_sessions_per_bead = 1;
+
+        let json = r#"[{"id":"a","title":"A","priority":1},{"id":"b","title":"B","priority":1}]"#;
+        let (mut beads, _) = parse_ready_beads_json(json);
+        let mut over_budget = BTreeMap::new();
+        beads.retain(|b| !over_bead_budget(&config, &db_conn, &b.id, &mut over_budget));
+
+        let ids: Vec<&str> = beads.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, vec!["b"]);
+        assert!(over_budget.contains_key("a"));
+        // A per-bead limit alone never drains the run
+        let started = "2026-02-16T00:00:00Z";
+        assert_eq!(
+            budget::check_run(&config.budget, &db_conn, started).unwrap(),
+            None
+        );
+    }
+
     #[test]
     fn test_run_auto_promotion_not_ready_yet() {
         let dir = tempdir().unwrap();
@@ -3109,51 +4213,6 @@ mod tests {
         assert_eq!(imp.status, "open"); // not promoted
     }
 
-    #[test]
-    fn test_append_promoted_to_prompt_creates_file() {
-        let dir = tempdir().unwrap();
-        let prompt_path = dir.path().join("PROMPT.md");
-
-        let imp = db::Improvement {
-            ref_id: "R1".to_string(),
-            created: "2026-02-16".to_string(),
-            category: "workflow".to_string(),
-            status: "open".to_string(),
-            title: "Batch reads".to_string(),
-            body: Some("Always batch independent file reads".to_string()),
-            context: None,
-            tags: None,
-        };
-
-        append_promoted_to_prompt(&prompt_path, &imp).unwrap();
-
-        let content = std::fs::read_to_string(&prompt_path).unwrap();
-        assert!(content.contains("Promoted from R1 [workflow]"));
-        assert!(content.contains("Always batch independent file reads"));
-    }
-
-    #[test]
-    fn test_append_promoted_to_prompt_uses_title_when_no_body() {
-        let dir = tempdir().unwrap();
-        let prompt_path = dir.path().join("PROMPT.md");
-
-        let imp = db::Improvement {
-            ref_id: "R2".to_string(),
-            created: "2026-02-16".to_string(),
-            category: "cost".to_string(),
-            status: "open".to_string(),
-            title: "Skip redundant lints".to_string(),
-            body: None,
-            context: None,
-            tags: None,
-        };
-
-        append_promoted_to_prompt(&prompt_path, &imp).unwrap();
-
-        let content = std::fs::read_to_string(&prompt_path).unwrap();
-        assert!(content.contains("Skip redundant lints"));
-    }
-
     #[test]
     fn test_sessions_since_improvement() {
         let dir = tempdir().unwrap();
@@ -3226,6 +4285,7 @@ mod tests {
             base_branch: "main".to_string(),
             worktrees_dir: "worktrees".to_string(),
             persistent: false,
+            ..Default::default()
         };
         let pool = WorkerPool::new(&workers_config, dir.path().to_path_buf(), wt_dir, 0);
 
@@ -3238,6 +4298,7 @@ mod tests {
             base_branch: "main".to_string(),
             worktrees_dir: "worktrees".to_string(),
             persistent: false,
+            ..Default::default()
         };
         let multi_pool = WorkerPool::new(
             &multi_config,
@@ -3260,6 +4321,26 @@ mod tests {
         assert!(prompt.is_empty());
     }
 
+    #[test]
+    fn test_bead_prompt_includes_latest_handoff() {
+        let dir = tempdir().unwrap();
+        let conn = test_db(dir.path());
+        assert_eq!(bead_prompt(&conn, "BASE", "b1"), "BASE\n\nWork on bead: b1");
+
+        let handoff = handoff::Handoff {
+            bead_id: "b1".to_string(),
+            source: handoff::SOURCE_AGENT.to_string(),
+            remaining: vec!["wire up the CLI".to_string()],
+            ..Default::default()
+        };
+        handoff::record(&conn, &handoff).unwrap();
+        let prompt = bead_prompt(&conn, "BASE", "b1");
+        assert!(prompt.starts_with("BASE\n\n## Handoff from the previous session"));
+        assert!(prompt.contains("- wire up the CLI"));
+        assert!(prompt.ends_with("\n\nWork on bead: b1"));
+        assert_eq!(bead_prompt(&conn, "BASE", "b2"), "BASE\n\nWork on bead: b2");
+    }
+
     #[test]
     fn test_assemble_base_prompt_includes_prompt_file() {
         let dir = tempdir().unwrap();
@@ -3364,6 +4445,7 @@ mod tests {
             base_branch: "main".to_string(),
             worktrees_dir: "worktrees".to_string(),
             persistent: false,
+            ..Default::default()
         };
         WorkerPool::new(&workers_config, dir.to_path_buf(), wt_dir, 0)
     }
@@ -3639,6 +4721,7 @@ mod tests {
             base_branch: "main".to_string(),
             worktrees_dir: "worktrees".to_string(),
             persistent: false,
+            ..Default::default()
         };
         let pool = WorkerPool::new(&workers_config, dir.path().to_path_buf(), wt_dir, 0);
 
@@ -3682,6 +4765,7 @@ mod tests {
             base_branch: "main".to_string(),
             worktrees_dir: "worktrees".to_string(),
             persistent: false,
+            ..Default::default()
         };
         let pool = WorkerPool::new(&workers_config, dir.path().to_path_buf(), wt_dir, 0);
 
@@ -4038,6 +5122,7 @@ mod tests {
         let prompt = assemble_analysis_prompt(&config, &data_dir, &db_conn);
 
         assert!(!prompt.contains("{{promoted_improvements}}"));
+        assert!(!prompt.contains("{{failure_clusters}}"));
         assert!(!prompt.contains("{{max_improvements}}"));
         assert!(!prompt.contains("{{open_count}}"));
         assert!(!prompt.contains("{{backlog_threshold}}"));
@@ -45,6 +45,16 @@ impl DataDir {
         self.root.join("worktrees")
     }
 
+    /// Path to the per-worker-slot `CARGO_TARGET_DIR` cache.
+    pub fn target_cache_dir(&self) -> PathBuf {
+        self.root.join("target-cache")
+    }
+
+    /// Path to the directory holding patches salvaged from failed worktrees.
+    pub fn salvage_dir(&self) -> PathBuf {
+        self.root.join("salvage")
+    }
+
     /// Path to the singleton lock file.
     pub fn lock(&self) -> PathBuf {
         self.root.join("lock")
@@ -178,6 +188,11 @@ mod tests {
         assert_eq!(dd.counter(), PathBuf::from(".blacksmith/counter"));
         assert_eq!(dd.sessions_dir(), PathBuf::from(".blacksmith/sessions"));
         assert_eq!(dd.worktrees_dir(), PathBuf::from(".blacksmith/worktrees"));
+        assert_eq!(dd.salvage_dir(), PathBuf::from(".blacksmith/salvage"));
+        assert_eq!(
+            dd.target_cache_dir(),
+            PathBuf::from(".blacksmith/target-cache")
+        );
         assert_eq!(
             dd.session_file(42),
             PathBuf::from(".blacksmith/sessions/42.jsonl")
@@ -3,123 +3,31 @@ use std::path::Path;
 
 /// Opens (or creates) the blacksmith SQLite database at the given path.
 ///
-/// Creates the improvements table and indexes if they don't already exist.
-/// Returns an open connection ready for use.
+/// Applies any pending versioned schema migrations (see `schema`), backing
+/// up an existing database first, then creates the per-feature tables if
+/// they don't already exist. Returns an open connection ready for use.
 pub fn open_or_create(path: &Path) -> Result<Connection> {
     let conn = Connection::open(path)?;
 
     // Enable WAL mode for better concurrent read performance
     conn.execute_batch("PRAGMA journal_mode=WAL;")?;
 
-    conn.execute_batch(
-        "CREATE TABLE IF NOT EXISTS improvements (
-            id         INTEGER PRIMARY KEY AUTOINCREMENT,
-            ref        TEXT UNIQUE,
-            created    TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
-            resolved   TEXT,
-            category   TEXT NOT NULL,
-            status     TEXT NOT NULL DEFAULT 'open',
-            title      TEXT NOT NULL,
-            body       TEXT,
-            context    TEXT,
-            tags       TEXT,
-            meta       TEXT
-        );
-
-        CREATE INDEX IF NOT EXISTS idx_improvements_status ON improvements(status);
-        CREATE INDEX IF NOT EXISTS idx_improvements_category ON improvements(category);
-
-        CREATE TABLE IF NOT EXISTS progress_entries (
-            id         INTEGER PRIMARY KEY AUTOINCREMENT,
-            created    TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
-            bead_id    TEXT,
-            body       TEXT NOT NULL
-        );
-
-        CREATE INDEX IF NOT EXISTS idx_progress_entries_bead_id ON progress_entries(bead_id);
-        CREATE INDEX IF NOT EXISTS idx_progress_entries_created ON progress_entries(created);
-
-        CREATE TABLE IF NOT EXISTS events (
-            id        INTEGER PRIMARY KEY AUTOINCREMENT,
-            ts        TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
-            session   INTEGER NOT NULL,
-            kind      TEXT NOT NULL,
-            value     TEXT,
-            tags      TEXT
-        );
-
-        CREATE INDEX IF NOT EXISTS idx_events_session ON events(session);
-        CREATE INDEX IF NOT EXISTS idx_events_kind ON events(kind);
-        CREATE INDEX IF NOT EXISTS idx_events_ts ON events(ts);
-
-        CREATE TABLE IF NOT EXISTS observations (
-            session   INTEGER PRIMARY KEY,
-            ts        TEXT NOT NULL,
-            duration  INTEGER,
-            outcome   TEXT,
-            data      TEXT NOT NULL
-        );
-
-        -- Coordinator tables for multi-agent state
-
-        CREATE TABLE IF NOT EXISTS worker_assignments (
-            id             INTEGER PRIMARY KEY,
-            worker_id      INTEGER NOT NULL,
-            bead_id        TEXT NOT NULL,
-            worktree_path  TEXT NOT NULL,
-            status         TEXT NOT NULL,
-            affected_globs TEXT,
-            started_at     TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
-            completed_at   TEXT,
-            failure_notes  TEXT
-        );
-
-        CREATE INDEX IF NOT EXISTS idx_worker_assignments_status ON worker_assignments(status);
-        CREATE INDEX IF NOT EXISTS idx_worker_assignments_bead_id ON worker_assignments(bead_id);
-
-        CREATE TABLE IF NOT EXISTS task_file_changes (
-            assignment_id  INTEGER NOT NULL REFERENCES worker_assignments(id),
-            file_path      TEXT NOT NULL,
-            change_type    TEXT NOT NULL
-        );
-
-        CREATE INDEX IF NOT EXISTS idx_task_file_changes_assignment ON task_file_changes(assignment_id);
-
-        CREATE TABLE IF NOT EXISTS integration_log (
-            id                        INTEGER PRIMARY KEY,
-            assignment_id             INTEGER NOT NULL REFERENCES worker_assignments(id),
-            merged_at                 TEXT NOT NULL,
-            merge_commit              TEXT NOT NULL,
-            manifest_entries_applied  TEXT,
-            cross_task_imports        TEXT,
-            reconciliation_run        BOOLEAN DEFAULT 0
-        );
-
-        CREATE INDEX IF NOT EXISTS idx_integration_log_assignment ON integration_log(assignment_id);
-
-        CREATE TABLE IF NOT EXISTS integration_iterations (
-            id              INTEGER PRIMARY KEY,
-            assignment_id   INTEGER NOT NULL REFERENCES worker_assignments(id),
-            bead_id         TEXT NOT NULL,
-            iteration_count INTEGER NOT NULL,
-            modules         TEXT,
-            recorded_at     TEXT NOT NULL
-        );
-
-        CREATE INDEX IF NOT EXISTS idx_integration_iterations_bead ON integration_iterations(bead_id);
-
-        CREATE TABLE IF NOT EXISTS bead_metrics (
-            bead_id               TEXT PRIMARY KEY,
-            sessions              INTEGER NOT NULL DEFAULT 0,
-            wall_time_secs        REAL NOT NULL DEFAULT 0,
-            total_turns           INTEGER NOT NULL DEFAULT 0,
-            total_output_tokens   INTEGER DEFAULT 0,
-            integration_time_secs REAL DEFAULT 0,
-            completed_at          TEXT
-        );",
-    )?;
+    crate::schema::migrate(path, &conn)?;
 
+    crate::arch_history::create_table(&conn)?;
+    crate::arch_review::create_table(&conn)?;
+    crate::epoch::create_table(&conn)?;
     crate::expansion_event::create_table(&conn)?;
+    crate::experiment::create_table(&conn)?;
+    crate::flaky::create_table(&conn)?;
+    crate::gate_result::create_table(&conn)?;
+    crate::handoff::create_table(&conn)?;
+    crate::impact::create_table(&conn)?;
+    crate::planning::create_table(&conn)?;
+    crate::rules::create_table(&conn)?;
+    crate::salvage::create_table(&conn)?;
+    crate::test_report::create_table(&conn)?;
+    crate::tool_call::create_table(&conn)?;
 
     Ok(conn)
 }
@@ -156,7 +64,7 @@ pub fn insert_improvement(
 }
 
 /// A row from the improvements table.
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct Improvement {
     pub ref_id: String,
     pub created: String,
@@ -256,6 +164,33 @@ pub fn get_improvement_meta(conn: &Connection, ref_id: &str) -> Result<Option<St
     )
 }
 
+/// Set the observation metric an improvement targets and which way it should
+/// move ("below" to lower it, "above" to raise it). Returns false if no such ref.
+pub fn set_improvement_target(
+    conn: &Connection,
+    ref_id: &str,
+    metric: &str,
+    direction: &str,
+) -> Result<bool> {
+    let changed = conn.execute(
+        "UPDATE improvements SET target_metric = ?2, target_direction = ?3 WHERE ref = ?1",
+        rusqlite::params![ref_id, metric, direction],
+    )?;
+    Ok(changed > 0)
+}
+
+/// Fetch an improvement's `(target_metric, target_direction)`, if it declares one.
+pub fn get_improvement_target(conn: &Connection, ref_id: &str) -> Result<Option<(String, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT target_metric, COALESCE(target_direction, 'below') FROM improvements \
+         WHERE ref = ?1 AND target_metric IS NOT NULL",
+    )?;
+    let mut rows = stmt.query_map(rusqlite::params![ref_id], |row| {
+        Ok((row.get(0)?, row.get(1)?))
+    })?;
+    rows.next().transpose()
+}
+
 /// Update an improvement's fields by ref. Only non-None values are updated.
 pub fn update_improvement(
     conn: &Connection,
@@ -969,6 +904,23 @@ pub fn find_failed_assignment_by_bead(
     }
 }
 
+/// Find the most recent worker assignment that ran in the given worktree.
+pub fn latest_worker_assignment_by_worktree(
+    conn: &Connection,
+    worktree_path: &str,
+) -> Result<Option<WorkerAssignment>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, worker_id, bead_id, worktree_path, status, affected_globs, \
+         started_at, completed_at, failure_notes FROM worker_assignments \
+         WHERE worktree_path = ?1 ORDER BY id DESC LIMIT 1",
+    )?;
+    let mut rows = stmt.query_map(rusqlite::params![worktree_path], map_worker_assignment)?;
+    match rows.next() {
+        Some(row) => Ok(Some(row?)),
+        None => Ok(None),
+    }
+}
+
 fn map_integration_log_view(row: &rusqlite::Row) -> Result<IntegrationLogView> {
     Ok(IntegrationLogView {
         id: row.get(0)?,
@@ -2799,6 +2751,25 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn latest_worker_assignment_by_worktree_returns_most_recent() {
+        let (_dir, conn) = test_db();
+
+        insert_worker_assignment(&conn, 0, "beads-a", "/tmp/wt-0", "completed", None).unwrap();
+        let latest =
+            insert_worker_assignment(&conn, 0, "beads-b", "/tmp/wt-0", "coding", None).unwrap();
+        insert_worker_assignment(&conn, 1, "beads-c", "/tmp/wt-1", "coding", None).unwrap();
+
+        let found = latest_worker_assignment_by_worktree(&conn, "/tmp/wt-0")
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, latest);
+        assert_eq!(found.bead_id, "beads-b");
+        assert!(latest_worker_assignment_by_worktree(&conn, "/tmp/wt-9")
+            .unwrap()
+            .is_none());
+    }
+
     #[test]
     fn find_failed_assignment_returns_most_recent() {
         let (_dir, conn) = test_db();
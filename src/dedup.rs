@@ -0,0 +1,290 @@
+//! Near-duplicate detection for improvements.
+//!
+//! A small local TF-IDF index over improvement titles and bodies — no
+//! network, no embeddings. Titles count twice, since they name the problem
+//! while bodies tend to share boilerplate. Cosine similarity between the
+//! weighted term vectors drives three things: the warning or rejection at
+//! `improve add`, `improve merge` suggestions, and the pre-clustered list of
+//! open improvements handed to the analysis agent.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::db::Improvement;
+
+/// At or above this similarity `improve add` warns about a likely duplicate.
+pub const WARN_SIMILARITY: f64 = 0.5;
+/// At or above this similarity `improve add` refuses without `--force`.
+pub const REJECT_SIMILARITY: f64 = 0.8;
+
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "with", "that", "this", "from", "into", "are", "was", "not", "but",
+    "when", "then", "than", "use", "should", "before", "after", "each", "all", "any", "its",
+    "instead", "only", "more", "less",
+];
+
+fn tokens(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|w| w.len() >= 3 && !STOP_WORDS.contains(&w.as_str()))
+        .collect()
+}
+
+fn document(title: &str, body: Option<&str>) -> Vec<String> {
+    let mut terms = tokens(title);
+    terms.extend(tokens(title));
+    if let Some(body) = body {
+        terms.extend(tokens(body));
+    }
+    terms
+}
+
+/// A candidate duplicate and how similar it is (cosine, 0.0–1.0).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    pub ref_id: String,
+    pub title: String,
+    pub score: f64,
+}
+
+/// TF-IDF vectors for a set of improvements.
+pub struct Index {
+    refs: Vec<(String, String)>,
+    vectors: Vec<HashMap<String, f64>>,
+    doc_freq: HashMap<String, usize>,
+}
+
+impl Index {
+    pub fn build(items: &[Improvement]) -> Self {
+        let docs: Vec<Vec<String>> = items
+            .iter()
+            .map(|imp| document(&imp.title, imp.body.as_deref()))
+            .collect();
+        let mut doc_freq: HashMap<String, usize> = HashMap::new();
+        for doc in &docs {
+            for term in doc.iter().collect::<HashSet<_>>() {
+                *doc_freq.entry(term.clone()).or_default() += 1;
+            }
+        }
+        let mut index = Index {
+            refs: items
+                .iter()
+                .map(|imp| (imp.ref_id.clone(), imp.title.clone()))
+                .collect(),
+            vectors: Vec::new(),
+            doc_freq,
+        };
+        index.vectors = docs.iter().map(|doc| index.vector(doc)).collect();
+        index
+    }
+
+    /// Smoothed inverse document frequency; unseen terms get the maximum.
+    fn idf(&self, term: &str) -> f64 {
+        let n = self.refs.len() as f64;
+        let df = self.doc_freq.get(term).copied().unwrap_or(0) as f64;
+        ((1.0 + n) / (1.0 + df)).ln() + 1.0
+    }
+
+    /// Unit-length TF-IDF vector of a document.
+    fn vector(&self, doc: &[String]) -> HashMap<String, f64> {
+        let mut tf: HashMap<String, f64> = HashMap::new();
+        for term in doc {
+            *tf.entry(term.clone()).or_default() += 1.0;
+        }
+        for (term, weight) in tf.iter_mut() {
+            *weight *= self.idf(term);
+        }
+        let norm = tf.values().map(|w| w * w).sum::<f64>().sqrt();
+        if norm > 0.0 {
+            tf.values_mut().for_each(|w| *w /= norm);
+        }
+        tf
+    }
+
+    fn cosine(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> f64 {
+        let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+        small
+            .iter()
+            .filter_map(|(term, w)| large.get(term).map(|v| w * v))
+            .sum()
+    }
+
+    /// Indexed improvements at least `threshold` similar to the given
+    /// title and body, most similar first.
+    pub fn similar(&self, title: &str, body: Option<&str>, threshold: f64) -> Vec<Match> {
+        let query = self.vector(&document(title, body));
+        let mut matches: Vec<Match> = self
+            .vectors
+            .iter()
+            .zip(&self.refs)
+            .map(|(v, (ref_id, title))| Match {
+                ref_id: ref_id.clone(),
+                title: title.clone(),
+                score: Self::cosine(&query, v),
+            })
+            .filter(|m| m.score >= threshold)
+            .collect();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        matches
+    }
+
+    /// Groups of indexed improvements linked by pairwise similarity of at
+    /// least `threshold`, each in index order; singletons are omitted.
+    pub fn clusters(&self, threshold: f64) -> Vec<Vec<usize>> {
+        let n = self.vectors.len();
+        let mut parent: Vec<usize> = (0..n).collect();
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        for i in 0..n {
+            for j in i + 1..n {
+                if Self::cosine(&self.vectors[i], &self.vectors[j]) >= threshold {
+                    let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                    parent[b.max(a)] = a.min(b);
+                }
+            }
+        }
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut slot: HashMap<usize, usize> = HashMap::new();
+        for i in 0..n {
+            let r = root(&mut parent, i);
+            match slot.get(&r) {
+                Some(&g) => groups[g].push(i),
+                None => {
+                    slot.insert(r, groups.len());
+                    groups.push(vec![i]);
+                }
+            }
+        }
+        groups.retain(|g| g.len() > 1);
+        groups
+    }
+}
+
+/// Render improvements for the analysis prompt with likely duplicates
+/// grouped together, so they can be merged rather than re-filed.
+pub fn format_clustered(items: &[Improvement]) -> String {
+    if items.is_empty() {
+        return "(none)".to_string();
+    }
+    let line = |imp: &Improvement| format!("- [{}] {}: {}", imp.ref_id, imp.category, imp.title);
+    let clusters = Index::build(items).clusters(WARN_SIMILARITY);
+    let clustered: HashSet<usize> = clusters.iter().flatten().copied().collect();
+
+    let mut out: Vec<String> = items
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !clustered.contains(i))
+        .map(|(_, imp)| line(imp))
+        .collect();
+    for (n, cluster) in clusters.iter().enumerate() {
+        let refs: Vec<&str> = cluster.iter().map(|&i| items[i].ref_id.as_str()).collect();
+        out.push(String::new());
+        out.push(format!(
+            "Likely duplicates (group {}) — merge with `blacksmith improve merge {} {}`:",
+            n + 1,
+            refs[0],
+            refs[1]
+        ));
+        out.extend(cluster.iter().map(|&i| line(&items[i])));
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn imp(ref_id: &str, title: &str, body: Option<&str>) -> Improvement {
+        Improvement {
+            ref_id: ref_id.to_string(),
+            created: "2026-01-01T00:00:00Z".to_string(),
+            category: "workflow".to_string(),
+            status: "open".to_string(),
+            title: title.to_string(),
+            body: body.map(str::to_string),
+            context: None,
+            tags: None,
+        }
+    }
+
+    fn corpus() -> Vec<Improvement> {
+        vec![
+            imp(
+                "R1",
+                "Batch independent file reads",
+                Some("Issue independent Read calls in a single turn"),
+            ),
+            imp(
+                "R2",
+                "Skip CSS tests for Rust-only changes",
+                Some("Run cargo test only when no stylesheets changed"),
+            ),
+            imp(
+                "R3",
+                "Reduce narration-only turns",
+                Some("Do not send turns that only narrate the plan"),
+            ),
+        ]
+    }
+
+    #[test]
+    fn near_duplicate_scores_high() {
+        let index = Index::build(&corpus());
+        let matches = index.similar(
+            "Batch independent file reads together",
+            Some("Issue independent Read calls in one turn"),
+            WARN_SIMILARITY,
+        );
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].ref_id, "R1");
+        assert!(
+            matches[0].score >= REJECT_SIMILARITY,
+            "{}",
+            matches[0].score
+        );
+    }
+
+    #[test]
+    fn unrelated_text_scores_low() {
+        let index = Index::build(&corpus());
+        let matches = index.similar("Pin the toolchain version", None, 0.0);
+        assert!(matches.iter().all(|m| m.score < WARN_SIMILARITY));
+    }
+
+    #[test]
+    fn identical_documents_score_one() {
+        let items = corpus();
+        let index = Index::build(&items);
+        let matches = index.similar(&items[1].title, items[1].body.as_deref(), 0.99);
+        assert_eq!(matches[0].ref_id, "R2");
+        assert!((matches[0].score - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn clusters_group_duplicates() {
+        let mut items = corpus();
+        items.push(imp(
+            "R4",
+            "Batch file reads",
+            Some("Independent Read calls belong in a single turn"),
+        ));
+        let clusters = Index::build(&items).clusters(WARN_SIMILARITY);
+        assert_eq!(clusters, vec![vec![0, 3]]);
+
+        let text = format_clustered(&items);
+        assert!(text.starts_with("- [R2]"));
+        assert!(text.contains(
+            "Likely duplicates (group 1) — merge with `blacksmith improve merge R1 R4`:\n- [R1]"
+        ));
+        assert!(text.ends_with("- [R4] workflow: Batch file reads"));
+    }
+
+    #[test]
+    fn format_clustered_empty() {
+        assert_eq!(format_clustered(&[]), "(none)");
+    }
+}
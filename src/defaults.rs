@@ -1 +1,2 @@
 pub const ANALYSIS_PROMPT: &str = include_str!("../defaults/ANALYSIS_PROMPT.md");
+pub const PLANNING_PROMPT: &str = include_str!("../defaults/PLANNING_PROMPT.md");
@@ -0,0 +1,771 @@
+//! Epochs: named windows of integrated work with a QA digest.
+//!
+//! `blacksmith epoch start` marks the beginning of a sprint and `epoch close`
+//! its end, each recording the repo HEAD. `epoch report` digests everything
+//! that landed in between for a human QA pass: beads closed and rolled back,
+//! merge commits, files and modules changed, gate and verify results, spend,
+//! and the `## Verify` steps `finish` skipped as prose, which nobody has run.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::process::Command;
+
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use serde::Serialize;
+
+use crate::module_detect::{self, Module};
+
+/// Upper bound used as the end of an epoch that is still open.
+const OPEN_END: &str = "9999-12-31T23:59:59Z";
+
+/// Create the epochs and manual_checks tables if they don't exist.
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS epochs (
+            id           INTEGER PRIMARY KEY AUTOINCREMENT,
+            name         TEXT NOT NULL UNIQUE,
+            started_at   TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            closed_at    TEXT,
+            start_commit TEXT,
+            end_commit   TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS manual_checks (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            bead_id     TEXT NOT NULL,
+            step        TEXT NOT NULL,
+            recorded_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_manual_checks_recorded ON manual_checks(recorded_at);",
+    )
+}
+
+/// A start/close mark pair.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Epoch {
+    pub name: String,
+    pub started_at: String,
+    /// `None` while the epoch is open.
+    pub closed_at: Option<String>,
+    pub start_commit: Option<String>,
+    pub end_commit: Option<String>,
+}
+
+impl Epoch {
+    fn end(&self) -> &str {
+        self.closed_at.as_deref().unwrap_or(OPEN_END)
+    }
+}
+
+fn map_epoch(row: &rusqlite::Row) -> Result<Epoch> {
+    Ok(Epoch {
+        name: row.get(0)?,
+        started_at: row.get(1)?,
+        closed_at: row.get(2)?,
+        start_commit: row.get(3)?,
+        end_commit: row.get(4)?,
+    })
+}
+
+const EPOCH_COLUMNS: &str = "name, started_at, closed_at, start_commit, end_commit";
+
+/// The epoch that is currently open, if any.
+pub fn current(conn: &Connection) -> Result<Option<Epoch>> {
+    conn.query_row(
+        &format!(
+            "SELECT {EPOCH_COLUMNS} FROM epochs WHERE closed_at IS NULL ORDER BY id DESC LIMIT 1"
+        ),
+        [],
+        map_epoch,
+    )
+    .optional()
+}
+
+/// Look up an epoch by name, or the most recent one when `name` is `None`.
+pub fn find(conn: &Connection, name: Option<&str>) -> Result<Option<Epoch>> {
+    match name {
+        Some(name) => conn
+            .query_row(
+                &format!("SELECT {EPOCH_COLUMNS} FROM epochs WHERE name = ?1"),
+                params![name],
+                map_epoch,
+            )
+            .optional(),
+        None => conn
+            .query_row(
+                &format!("SELECT {EPOCH_COLUMNS} FROM epochs ORDER BY id DESC LIMIT 1"),
+                [],
+                map_epoch,
+            )
+            .optional(),
+    }
+}
+
+/// Open a new epoch at `commit`. Only one epoch can be open at a time; the
+/// name defaults to `epoch-N`.
+pub fn start(conn: &Connection, name: Option<&str>, commit: Option<&str>) -> Result<Epoch, String> {
+    if let Some(open) = current(conn).map_err(|e| e.to_string())? {
+        return Err(format!(
+            "Epoch '{}' is still open. Close it with `blacksmith epoch close` first.",
+            open.name
+        ));
+    }
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => {
+            let count: i64 = conn
+                .query_row("SELECT COUNT(*) FROM epochs", [], |row| row.get(0))
+                .map_err(|e| e.to_string())?;
+            format!("epoch-{}", count + 1)
+        }
+    };
+    if find(conn, Some(&name))
+        .map_err(|e| e.to_string())?
+        .is_some()
+    {
+        return Err(format!("An epoch named '{name}' already exists"));
+    }
+    conn.execute(
+        "INSERT INTO epochs (name, start_commit) VALUES (?1, ?2)",
+        params![name, commit],
+    )
+    .map_err(|e| e.to_string())?;
+    find(conn, Some(&name))
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Epoch '{name}' vanished after insert"))
+}
+
+/// Close the open epoch at `commit`.
+pub fn close(conn: &Connection, commit: Option<&str>) -> Result<Epoch, String> {
+    let open = current(conn)
+        .map_err(|e| e.to_string())?
+        .ok_or("No open epoch. Start one with `blacksmith epoch start`.")?;
+    conn.execute(
+        "UPDATE epochs SET closed_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), end_commit = ?1
+         WHERE name = ?2",
+        params![commit, open.name],
+    )
+    .map_err(|e| e.to_string())?;
+    find(conn, Some(&open.name))
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Epoch '{}' vanished after close", open.name))
+}
+
+/// Record the `## Verify` steps of a bead that need a human to check them.
+pub fn record_manual_steps(conn: &Connection, bead_id: &str, steps: &[String]) -> Result<()> {
+    let mut stmt = conn.prepare("INSERT INTO manual_checks (bead_id, step) VALUES (?1, ?2)")?;
+    for step in steps {
+        stmt.execute(params![bead_id, step])?;
+    }
+    Ok(())
+}
+
+/// A verification step recorded for manual QA.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ManualStep {
+    pub bead_id: String,
+    pub step: String,
+}
+
+/// Manual steps recorded between two timestamps (inclusive), once each even
+/// when `finish` recorded them on several attempts.
+pub fn manual_steps_between(conn: &Connection, from: &str, to: &str) -> Result<Vec<ManualStep>> {
+    let mut stmt = conn.prepare(
+        "SELECT bead_id, step FROM manual_checks
+         WHERE recorded_at >= ?1 AND recorded_at <= ?2
+         GROUP BY bead_id, step
+         ORDER BY MIN(id)",
+    )?;
+    let rows = stmt.query_map(params![from, to], |row| {
+        Ok(ManualStep {
+            bead_id: row.get(0)?,
+            step: row.get(1)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// A bead integrated during the epoch.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IntegratedBead {
+    pub bead_id: String,
+    pub merged_at: String,
+    pub merge_commit: String,
+    pub rolled_back: bool,
+}
+
+/// Gate outcomes over the epoch, per gate.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GateSummary {
+    pub gate: String,
+    pub runs: u64,
+    pub passed: u64,
+    pub failed: u64,
+}
+
+/// Everything that landed during an epoch.
+#[derive(Debug, Clone, Serialize)]
+pub struct Digest {
+    pub epoch: Epoch,
+    /// Integrations in merge order, rolled-back ones included.
+    pub beads: Vec<IntegratedBead>,
+    pub files: Vec<String>,
+    /// Changed file count per module (see `module_detect`).
+    pub modules: BTreeMap<String, usize>,
+    pub gates: Vec<GateSummary>,
+    /// Beads whose deliverable verification failed at least once.
+    pub verify_failures: Vec<String>,
+    pub manual_steps: Vec<ManualStep>,
+    pub sessions: u64,
+    pub cost_usd: f64,
+}
+
+impl Digest {
+    /// Beads that were integrated and stayed integrated.
+    pub fn closed(&self) -> impl Iterator<Item = &IntegratedBead> {
+        self.beads.iter().filter(|b| !b.rolled_back)
+    }
+
+    /// Beads whose integration was rolled back.
+    pub fn rolled_back(&self) -> impl Iterator<Item = &IntegratedBead> {
+        self.beads.iter().filter(|b| b.rolled_back)
+    }
+}
+
+/// Build the digest of an epoch. Changed files come from `git diff` between
+/// the epoch's commits (HEAD for an open epoch) in `repo_dir`.
+pub fn digest(conn: &Connection, epoch: &Epoch, repo_dir: &Path) -> Result<Digest> {
+    let (from, to) = (epoch.started_at.as_str(), epoch.end());
+
+    let mut stmt = conn.prepare(
+        "SELECT wa.bead_id, il.merged_at, il.merge_commit, wa.status
+         FROM integration_log il
+         JOIN worker_assignments wa ON wa.id = il.assignment_id
+         WHERE il.merged_at >= ?1 AND il.merged_at <= ?2
+         ORDER BY il.merged_at, il.id",
+    )?;
+    let beads = stmt
+        .query_map(params![from, to], |row| {
+            Ok(IntegratedBead {
+                bead_id: row.get(0)?,
+                merged_at: row.get(1)?,
+                merge_commit: row.get(2)?,
+                rolled_back: row.get::<_, String>(3)? == "rolled_back",
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT gate, COUNT(*),
+                SUM(CASE WHEN status = 'passed' THEN 1 ELSE 0 END),
+                SUM(CASE WHEN status = 'failed' THEN 1 ELSE 0 END)
+         FROM gate_results
+         WHERE recorded_at >= ?1 AND recorded_at <= ?2
+         GROUP BY gate
+         ORDER BY MIN(id)",
+    )?;
+    let gates = stmt
+        .query_map(params![from, to], |row| {
+            Ok(GateSummary {
+                gate: row.get(0)?,
+                runs: row.get::<_, i64>(1)? as u64,
+                passed: row.get::<_, i64>(2)? as u64,
+                failed: row.get::<_, i64>(3)? as u64,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT bead_id FROM gate_results
+         WHERE gate = 'verify' AND status = 'failed'
+           AND recorded_at >= ?1 AND recorded_at <= ?2
+         ORDER BY bead_id",
+    )?;
+    let verify_failures = stmt
+        .query_map(params![from, to], |row| row.get(0))?
+        .collect::<Result<Vec<String>>>()?;
+
+    let mut stmt = conn.prepare("SELECT data FROM observations WHERE ts >= ?1 AND ts <= ?2")?;
+    let mut sessions = 0;
+    let mut cost_usd = 0.0;
+    for data in stmt.query_map(params![from, to], |row| row.get::<_, String>(0))? {
+        let data: serde_json::Value = serde_json::from_str(&data?).unwrap_or_default();
+        sessions += 1;
+        cost_usd += crate::budget::session_cost(&data);
+    }
+
+    let files = match &epoch.start_commit {
+        Some(start) => changed_files(
+            repo_dir,
+            start,
+            epoch.end_commit.as_deref().unwrap_or("HEAD"),
+        ),
+        None => Vec::new(),
+    };
+    let detected = module_detect::detect_modules_from_repo(repo_dir);
+    let mut modules = BTreeMap::new();
+    for file in &files {
+        *modules
+            .entry(module_of(&detected, repo_dir, file))
+            .or_insert(0) += 1;
+    }
+
+    // Steps from finish attempts that never landed, or were rolled back,
+    // have nothing to check
+    let mut manual_steps = manual_steps_between(conn, from, to)?;
+    manual_steps.retain(|s| {
+        beads
+            .iter()
+            .any(|b| b.bead_id == s.bead_id && !b.rolled_back)
+    });
+
+    Ok(Digest {
+        epoch: epoch.clone(),
+        beads,
+        files,
+        modules,
+        gates,
+        verify_failures,
+        manual_steps,
+        sessions,
+        cost_usd,
+    })
+}
+
+/// The detected module a changed file belongs to: the module listing it,
+/// else the one whose directory most closely contains it (non-source or
+/// deleted files), else `.`.
+fn module_of(modules: &HashMap<String, Module>, repo_dir: &Path, file: &str) -> String {
+    let path = repo_dir.join(file);
+    if let Some(module) = modules.values().find(|m| m.files.contains(&path)) {
+        return module.name.clone();
+    }
+    modules
+        .values()
+        .filter(|m| path.starts_with(&m.root_path))
+        .max_by_key(|m| m.root_path.components().count())
+        .map_or_else(|| ".".to_string(), |m| m.name.clone())
+}
+
+fn changed_files(repo_dir: &Path, from: &str, to: &str) -> Vec<String> {
+    let output = Command::new("git")
+        .args(["diff", "--name-only", &format!("{from}..{to}")])
+        .current_dir(repo_dir)
+        .output();
+    match output {
+        Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout)
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn git_head(repo_dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(repo_dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn short(commit: &str) -> &str {
+    &commit[..commit.len().min(8)]
+}
+
+/// Render a digest as the Markdown QA report.
+pub fn render_markdown(digest: &Digest) -> String {
+    let epoch = &digest.epoch;
+    let mut out = format!("# Epoch {}\n\n", epoch.name);
+    out.push_str(&format!(
+        "{} → {}",
+        epoch.started_at,
+        epoch.closed_at.as_deref().unwrap_or("open")
+    ));
+    if let Some(start) = &epoch.start_commit {
+        let end = epoch.end_commit.as_deref().map_or("HEAD", short);
+        out.push_str(&format!(" (`{}..{}`)", short(start), end));
+    }
+    out.push_str("\n\n## Summary\n\n");
+    out.push_str(&format!("- Beads closed: {}\n", digest.closed().count()));
+    out.push_str(&format!(
+        "- Rolled back: {}\n",
+        digest.rolled_back().count()
+    ));
+    out.push_str(&format!(
+        "- Files changed: {} across {} modules\n",
+        digest.files.len(),
+        digest.modules.len()
+    ));
+    out.push_str(&format!(
+        "- Sessions: {} (${:.2})\n",
+        digest.sessions, digest.cost_usd
+    ));
+    out.push_str(&format!(
+        "- Manual verification steps: {}\n",
+        digest.manual_steps.len()
+    ));
+
+    if digest.closed().next().is_some() {
+        out.push_str("\n## Beads closed\n\n");
+        for bead in digest.closed() {
+            out.push_str(&format!(
+                "- {} — merged {} as `{}`\n",
+                bead.bead_id,
+                bead.merged_at,
+                short(&bead.merge_commit)
+            ));
+        }
+    }
+
+    if digest.rolled_back().next().is_some() {
+        out.push_str("\n## Rolled back\n\n");
+        for bead in digest.rolled_back() {
+            out.push_str(&format!(
+                "- {} — merge `{}` was reverted\n",
+                bead.bead_id,
+                short(&bead.merge_commit)
+            ));
+        }
+    }
+
+    if !digest.modules.is_empty() {
+        out.push_str("\n## Modules changed\n\n");
+        for (module, count) in &digest.modules {
+            out.push_str(&format!(
+                "- `{module}` ({count} file{})\n",
+                if *count == 1 { "" } else { "s" }
+            ));
+        }
+    }
+
+    if !digest.gates.is_empty() {
+        out.push_str("\n## Gates\n\n| Gate | Runs | Passed | Failed |\n|---|---|---|---|\n");
+        for gate in &digest.gates {
+            out.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                gate.gate, gate.runs, gate.passed, gate.failed
+            ));
+        }
+    }
+
+    if !digest.verify_failures.is_empty() {
+        out.push_str("\n## Verify failures\n\n");
+        for bead in &digest.verify_failures {
+            out.push_str(&format!("- {bead}\n"));
+        }
+    }
+
+    if !digest.manual_steps.is_empty() {
+        out.push_str("\n## Manual verification\n\n");
+        out.push_str("These `## Verify` steps read as prose and were not run by `finish`.\n\n");
+        for step in &digest.manual_steps {
+            out.push_str(&format!("- [ ] {}: {}\n", step.bead_id, step.step));
+        }
+    }
+
+    out
+}
+
+fn open_db(db_path: &Path) -> Result<Option<Connection>, String> {
+    if !db_path.exists() {
+        println!("No metrics database found. Run some sessions first.");
+        return Ok(None);
+    }
+    crate::db::open_or_create(db_path)
+        .map(Some)
+        .map_err(|e| format!("Failed to open database: {e}"))
+}
+
+/// Handle `blacksmith epoch start [NAME]`.
+pub fn handle_start(db_path: &Path, repo_dir: &Path, name: Option<&str>) -> Result<(), String> {
+    let conn =
+        crate::db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
+    let epoch = start(&conn, name, git_head(repo_dir).as_deref())?;
+    println!(
+        "Started epoch '{}' at {}",
+        epoch.name,
+        epoch.start_commit.as_deref().map_or("(no commit)", short)
+    );
+    Ok(())
+}
+
+/// Handle `blacksmith epoch close`.
+pub fn handle_close(db_path: &Path, repo_dir: &Path) -> Result<(), String> {
+    let Some(conn) = open_db(db_path)? else {
+        return Ok(());
+    };
+    let epoch = close(&conn, git_head(repo_dir).as_deref())?;
+    println!(
+        "Closed epoch '{}' at {}. Run `blacksmith epoch report` for the QA digest.",
+        epoch.name,
+        epoch.end_commit.as_deref().map_or("(no commit)", short)
+    );
+    Ok(())
+}
+
+/// Handle `blacksmith epoch report [NAME] [--json]`.
+pub fn handle_report(
+    db_path: &Path,
+    repo_dir: &Path,
+    name: Option<&str>,
+    json: bool,
+) -> Result<(), String> {
+    let Some(conn) = open_db(db_path)? else {
+        return Ok(());
+    };
+    let epoch = find(&conn, name)
+        .map_err(|e| format!("Failed to query epochs: {e}"))?
+        .ok_or_else(|| match name {
+            Some(name) => format!("No epoch named '{name}'"),
+            None => "No epochs yet. Start one with `blacksmith epoch start`.".to_string(),
+        })?;
+    let digest =
+        digest(&conn, &epoch, repo_dir).map_err(|e| format!("Failed to build digest: {e}"))?;
+
+    if json {
+        let out = serde_json::to_string_pretty(&digest)
+            .map_err(|e| format!("Failed to serialize digest: {e}"))?;
+        println!("{out}");
+    } else {
+        print!("{}", render_markdown(&digest));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> (tempfile::TempDir, Connection) {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = crate::db::open_or_create(&dir.path().join("blacksmith.db")).unwrap();
+        (dir, conn)
+    }
+
+    fn set_window(conn: &Connection, name: &str, from: &str, to: Option<&str>) {
+        conn.execute(
+            "UPDATE epochs SET started_at = ?1, closed_at = ?2 WHERE name = ?3",
+            params![from, to, name],
+        )
+        .unwrap();
+    }
+
+    fn integrate(conn: &Connection, bead: &str, at: &str, commit: &str, status: &str) {
+        let id =
+            crate::db::insert_worker_assignment(conn, 0, bead, "/tmp/wt", status, None).unwrap();
+        crate::db::insert_integration_log(conn, id, at, commit, None, None, false).unwrap();
+    }
+
+    fn gate(conn: &Connection, bead: &str, gate: &str, status: &str, at: &str) {
+        conn.execute(
+            "INSERT INTO gate_results (bead_id, gate, status, duration_ms, recorded_at)
+             VALUES (?1, ?2, ?3, 10, ?4)",
+            params![bead, gate, status, at],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn start_close_and_find() {
+        let (_dir, conn) = setup_db();
+        assert!(find(&conn, None).unwrap().is_none());
+        assert!(close(&conn, None).is_err());
+
+        let first = start(&conn, None, Some("abc")).unwrap();
+        assert_eq!(first.name, "epoch-1");
+        assert_eq!(first.start_commit.as_deref(), Some("abc"));
+        assert!(start(&conn, Some("other"), None)
+            .unwrap_err()
+            .contains("still open"));
+
+        let closed = close(&conn, Some("def")).unwrap();
+        assert!(closed.closed_at.is_some());
+        assert_eq!(closed.end_commit.as_deref(), Some("def"));
+        assert!(current(&conn).unwrap().is_none());
+
+        assert!(start(&conn, Some("epoch-1"), None)
+            .unwrap_err()
+            .contains("already exists"));
+        start(&conn, Some("sprint-2"), None).unwrap();
+        assert_eq!(find(&conn, None).unwrap().unwrap().name, "sprint-2");
+        assert_eq!(
+            find(&conn, Some("epoch-1"))
+                .unwrap()
+                .unwrap()
+                .end_commit
+                .as_deref(),
+            Some("def")
+        );
+    }
+
+    #[test]
+    fn digest_covers_only_the_epoch_window() {
+        let (dir, conn) = setup_db();
+        start(&conn, Some("s1"), None).unwrap();
+        close(&conn, None).unwrap();
+        set_window(
+            &conn,
+            "s1",
+            "2026-01-02T00:00:00Z",
+            Some("2026-01-03T00:00:00Z"),
+        );
+
+        integrate(
+            &conn,
+            "bd-old",
+            "2026-01-01T12:00:00Z",
+            "0000",
+            "integrated",
+        );
+        integrate(
+            &conn,
+            "bd-a",
+            "2026-01-02T10:00:00Z",
+            "aaaa1111bbbb",
+            "integrated",
+        );
+        integrate(
+            &conn,
+            "bd-b",
+            "2026-01-02T11:00:00Z",
+            "cccc2222dddd",
+            "rolled_back",
+        );
+
+        gate(&conn, "bd-old", "test", "failed", "2026-01-01T12:00:00Z");
+        gate(&conn, "bd-a", "test", "passed", "2026-01-02T09:00:00Z");
+        gate(&conn, "bd-b", "test", "failed", "2026-01-02T09:30:00Z");
+        gate(&conn, "bd-b", "verify", "failed", "2026-01-02T09:31:00Z");
+
+        let data = r#"{"cost.estimate_usd": 1.5}"#;
+        crate::db::upsert_observation(&conn, 1, "2026-01-01T09:00:00Z", None, None, data).unwrap();
+        crate::db::upsert_observation(&conn, 2, "2026-01-02T09:00:00Z", None, None, data).unwrap();
+        crate::db::upsert_observation(&conn, 3, "2026-01-02T10:00:00Z", None, None, data).unwrap();
+
+        // Recorded on two finish attempts; bd-b was rolled back, bd-c never landed
+        let step = ["open the page and check it".to_string()];
+        record_manual_steps(&conn, "bd-a", &step).unwrap();
+        record_manual_steps(&conn, "bd-a", &step).unwrap();
+        record_manual_steps(&conn, "bd-b", &step).unwrap();
+        record_manual_steps(&conn, "bd-c", &step).unwrap();
+        conn.execute(
+            "UPDATE manual_checks SET recorded_at = '2026-01-02T09:00:00Z'",
+            [],
+        )
+        .unwrap();
+
+        let epoch = find(&conn, Some("s1")).unwrap().unwrap();
+        let digest = digest(&conn, &epoch, dir.path()).unwrap();
+
+        let closed: Vec<_> = digest.closed().map(|b| b.bead_id.as_str()).collect();
+        assert_eq!(closed, vec!["bd-a"]);
+        let rolled: Vec<_> = digest.rolled_back().map(|b| b.bead_id.as_str()).collect();
+        assert_eq!(rolled, vec!["bd-b"]);
+        assert_eq!(
+            digest.gates[0],
+            GateSummary {
+                gate: "test".to_string(),
+                runs: 2,
+                passed: 1,
+                failed: 1,
+            }
+        );
+        assert_eq!(digest.gates[1].gate, "verify");
+        assert_eq!(digest.verify_failures, vec!["bd-b"]);
+        assert_eq!(digest.sessions, 2);
+        assert!((digest.cost_usd - 3.0).abs() < 1e-9);
+        assert_eq!(
+            digest.manual_steps,
+            vec![ManualStep {
+                bead_id: "bd-a".to_string(),
+                step: "open the page and check it".to_string(),
+            }]
+        );
+        assert!(digest.files.is_empty());
+    }
+
+    #[test]
+    fn render_markdown_lists_sections() {
+        let mut modules = BTreeMap::new();
+        modules.insert("src".to_string(), 2);
+        modules.insert(".".to_string(), 1);
+        let digest = Digest {
+            epoch: Epoch {
+                name: "s1".to_string(),
+                started_at: "2026-01-02T00:00:00Z".to_string(),
+                closed_at: None,
+                start_commit: Some("1234567890".to_string()),
+                end_commit: None,
+            },
+            beads: vec![
+                IntegratedBead {
+                    bead_id: "bd-a".to_string(),
+                    merged_at: "2026-01-02T10:00:00Z".to_string(),
+                    merge_commit: "aaaa1111bbbb".to_string(),
+                    rolled_back: false,
+                },
+                IntegratedBead {
+                    bead_id: "bd-b".to_string(),
+                    merged_at: "2026-01-02T11:00:00Z".to_string(),
+                    merge_commit: "cccc2222dddd".to_string(),
+                    rolled_back: true,
+                },
+            ],
+            files: vec![
+                "README.md".to_string(),
+                "src/a.rs".to_string(),
+                "src/b.rs".to_string(),
+            ],
+            modules,
+            gates: Vec::new(),
+            verify_failures: Vec::new(),
+            manual_steps: vec![ManualStep {
+                bead_id: "bd-a".to_string(),
+                step: "open the page and check it".to_string(),
+            }],
+            sessions: 2,
+            cost_usd: 3.0,
+        };
+        let md = render_markdown(&digest);
+        assert!(md.starts_with("# Epoch s1\n"));
+        assert!(md.contains("2026-01-02T00:00:00Z → open (`12345678..HEAD`)"));
+        assert!(md.contains("- Files changed: 3 across 2 modules"));
+        assert!(md.contains("- Sessions: 2 ($3.00)"));
+        assert!(md.contains("- bd-a — merged 2026-01-02T10:00:00Z as `aaaa1111`"));
+        assert!(md.contains("## Rolled back\n\n- bd-b — merge `cccc2222` was reverted"));
+        assert!(md.contains("- `src` (2 files)"));
+        assert!(md.contains("- `.` (1 file)"));
+        assert!(!md.contains("## Gates"));
+        assert!(md.contains("- [ ] bd-a: open the page and check it"));
+    }
+
+    #[test]
+    fn module_of_uses_detected_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        let adapters = dir.path().join("src/adapters");
+        std::fs::create_dir_all(&adapters).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "mod adapters;").unwrap();
+        std::fs::write(adapters.join("mod.rs"), "pub mod claude;").unwrap();
+        std::fs::write(adapters.join("claude.rs"), "pub fn run() {}").unwrap();
+        let modules = module_detect::detect_modules_from_repo(dir.path());
+
+        assert_eq!(
+            module_of(&modules, dir.path(), "src/adapters/claude.rs"),
+            "adapters"
+        );
+        assert_eq!(module_of(&modules, dir.path(), "src/main.rs"), "crate");
+        // Not a source file, but inside the adapters module's directory
+        assert_eq!(
+            module_of(&modules, dir.path(), "src/adapters/README.md"),
+            "adapters"
+        );
+        assert_eq!(module_of(&modules, dir.path(), "Cargo.toml"), ".");
+    }
+}
@@ -0,0 +1,525 @@
+//! Prompt A/B experiments across workers.
+//!
+//! With `[experiment]` configured, every coding bead is assigned to one
+//! prompt variant — by a stable hash of the experiment name and bead id, so
+//! the split needs no coordination and survives restarts — and the
+//! assignment is recorded in `experiment_assignments`. The variant reshapes
+//! the bead's prompt (extra section, alternate prompt file, promoted rules
+//! toggled), and each of the bead's sessions gets an `experiment.variant`
+//! event and observation field. `blacksmith experiment report` compares the
+//! variants' beads on success rate, turns, cost and integration iterations
+//! against the control (the first variant), with 95% confidence intervals.
+
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use serde::Serialize;
+
+use crate::config::{ExperimentConfig, PromptVariant};
+use crate::db;
+use crate::impact::{self, Window};
+use crate::rules;
+
+/// Create the experiment_assignments table if it doesn't exist.
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS experiment_assignments (
+            experiment  TEXT NOT NULL,
+            bead_id     TEXT NOT NULL,
+            variant     TEXT NOT NULL,
+            assigned_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            PRIMARY KEY (experiment, bead_id)
+        );",
+    )
+}
+
+/// FNV-1a, so assignments don't depend on the std hasher's seed or version.
+fn stable_hash(s: &str) -> u64 {
+    s.bytes().fold(0xcbf2_9ce4_8422_2325, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The variant `bead_id` hashes to.
+pub fn assign<'a>(experiment: &'a ExperimentConfig, bead_id: &str) -> &'a PromptVariant {
+    let hash = stable_hash(&format!("{}/{bead_id}", experiment.name));
+    &experiment.variants[(hash % experiment.variants.len() as u64) as usize]
+}
+
+/// The variant recorded for `bead_id` in `experiment`, if any.
+pub fn recorded_variant(
+    conn: &Connection,
+    experiment: &str,
+    bead_id: &str,
+) -> Result<Option<String>> {
+    conn.query_row(
+        "SELECT variant FROM experiment_assignments WHERE experiment = ?1 AND bead_id = ?2",
+        params![experiment, bead_id],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// The variant for `bead_id`: the recorded one when the bead was assigned
+/// before (and that variant still exists), otherwise a fresh assignment,
+/// which is recorded.
+pub fn variant_for<'a>(
+    conn: &Connection,
+    experiment: &'a ExperimentConfig,
+    bead_id: &str,
+) -> Result<&'a PromptVariant> {
+    if let Some(name) = recorded_variant(conn, &experiment.name, bead_id)? {
+        if let Some(variant) = experiment.variants.iter().find(|v| v.name == name) {
+            return Ok(variant);
+        }
+    }
+    let variant = assign(experiment, bead_id);
+    conn.execute(
+        "INSERT OR REPLACE INTO experiment_assignments (experiment, bead_id, variant)
+         VALUES (?1, ?2, ?3)",
+        params![experiment.name, bead_id, variant.name],
+    )?;
+    Ok(variant)
+}
+
+/// Apply `variant`'s rule toggles and extra section to `prompt`.
+///
+/// Promoted rules are the `<!-- Promoted from R<n> [...] -->` blocks appended
+/// to the prompt file; `improvements_off` removes them and `improvements_on`
+/// appends the named improvements in the same form.
+pub fn apply(conn: &Connection, variant: &PromptVariant, prompt: &str) -> Result<String> {
+    let mut out = if variant.improvements_off.is_empty() {
+        prompt.to_string()
+    } else {
+        rules::remove_rules(prompt, &variant.improvements_off)
+    };
+    for ref_id in &variant.improvements_on {
+        let marker = format!("<!-- Promoted from {ref_id} ");
+        if out.contains(&marker) {
+            continue;
+        }
+        match db::get_improvement(conn, ref_id)? {
+            Some(imp) => {
+                out.push_str("\n\n");
+                out.push_str(&rules::render_rule(&imp));
+            }
+            None => {
+                tracing::warn!(ref_id, variant = %variant.name, "experiment improvement not found")
+            }
+        }
+    }
+    if let Some(section) = &variant.section {
+        out.push_str("\n\n");
+        out.push_str(section.trim_end());
+    }
+    Ok(out)
+}
+
+/// Tag a session with its bead's variant: an `experiment.variant` event
+/// (tagged `experiment=<name>`) and the same field in its observation.
+pub fn tag_session(conn: &Connection, session: i64, experiment: &str, variant: &str) -> Result<()> {
+    db::insert_event(
+        conn,
+        session,
+        "experiment.variant",
+        Some(variant),
+        Some(&format!("experiment={experiment}")),
+    )?;
+    if let Some(obs) = db::get_observation(conn, session)? {
+        let mut data: serde_json::Value =
+            serde_json::from_str(&obs.data).unwrap_or_else(|_| serde_json::json!({}));
+        if let Some(map) = data.as_object_mut() {
+            map.insert("experiment.variant".to_string(), variant.into());
+        }
+        db::upsert_observation(
+            conn,
+            session,
+            &obs.ts,
+            obs.duration,
+            obs.outcome.as_deref(),
+            &data.to_string(),
+        )?;
+    }
+    Ok(())
+}
+
+/// One bead's outcome within an experiment.
+#[derive(Debug, Clone, PartialEq)]
+struct BeadOutcome {
+    /// None while the bead is neither integrated nor failed.
+    success: Option<bool>,
+    turns: Option<f64>,
+    cost_usd: Option<f64>,
+    integration_iterations: Option<f64>,
+}
+
+fn bead_outcome(conn: &Connection, bead_id: &str) -> Result<BeadOutcome> {
+    let (assignments, integrated, failed): (i64, i64, i64) = conn.query_row(
+        "SELECT COUNT(*),
+                COALESCE(SUM(status = 'integrated'), 0),
+                COALESCE(SUM(status = 'failed'), 0)
+         FROM worker_assignments WHERE bead_id = ?1",
+        [bead_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    let success = if integrated > 0 {
+        Some(true)
+    } else if assignments > 0 && failed == assignments {
+        Some(false)
+    } else {
+        None
+    };
+    let turns: Option<i64> = conn
+        .query_row(
+            "SELECT total_turns FROM bead_metrics WHERE bead_id = ?1",
+            [bead_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let cost_usd: Option<f64> = conn.query_row(
+        "SELECT SUM(CAST(value AS REAL)) FROM events
+         WHERE kind = 'cost.estimate_usd' AND session IN
+            (SELECT session FROM events WHERE kind = 'session.bead_id' AND value = ?1)",
+        [bead_id],
+        |row| row.get(0),
+    )?;
+    let integration_iterations: Option<i64> = conn.query_row(
+        "SELECT MAX(iteration_count) FROM integration_iterations WHERE bead_id = ?1",
+        [bead_id],
+        |row| row.get(0),
+    )?;
+    Ok(BeadOutcome {
+        success,
+        turns: turns.map(|t| t as f64),
+        cost_usd,
+        integration_iterations: integration_iterations.map(|i| i as f64),
+    })
+}
+
+/// A metric's summary for one variant and, for non-control variants, the
+/// difference from the control with its 95% CI.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricStats {
+    pub n: usize,
+    pub mean: f64,
+    /// `mean - control mean`.
+    pub diff: Option<f64>,
+    pub ci_low: Option<f64>,
+    pub ci_high: Option<f64>,
+    /// The CI excludes zero.
+    pub significant: bool,
+}
+
+/// Per-variant results of an experiment.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VariantReport {
+    pub variant: String,
+    pub beads: usize,
+    /// Fraction of finished beads that integrated.
+    pub success_rate: MetricStats,
+    pub turns: MetricStats,
+    pub cost_usd: MetricStats,
+    pub integration_iterations: MetricStats,
+}
+
+fn metric_stats(values: &[f64], control: Option<&[f64]>) -> MetricStats {
+    let window = Window::of(values);
+    let comparison = control.and_then(|c| impact::compare(&Window::of(c), &window));
+    MetricStats {
+        n: window.n,
+        mean: window.mean,
+        diff: comparison.map(|c| c.effect),
+        ci_low: comparison.map(|c| c.ci_low),
+        ci_high: comparison.map(|c| c.ci_high),
+        significant: comparison.is_some_and(|c| c.ci_low > 0.0 || c.ci_high < 0.0),
+    }
+}
+
+/// Compare the variants of `experiment` in `variant_order` (control first;
+/// variants seen in the database but not listed follow in name order).
+pub fn report(
+    conn: &Connection,
+    experiment: &str,
+    variant_order: &[String],
+) -> Result<Vec<VariantReport>> {
+    let mut stmt = conn.prepare(
+        "SELECT variant, bead_id FROM experiment_assignments WHERE experiment = ?1
+         ORDER BY variant, bead_id",
+    )?;
+    let rows: Vec<(String, String)> = stmt
+        .query_map([experiment], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_>>()?;
+
+    let mut variants: Vec<String> = variant_order
+        .iter()
+        .filter(|v| rows.iter().any(|(r, _)| r == *v))
+        .cloned()
+        .collect();
+    for (v, _) in &rows {
+        if !variants.contains(v) {
+            variants.push(v.clone());
+        }
+    }
+
+    // Per variant: success (0/1), turns, cost, integration iterations.
+    let mut samples: Vec<[Vec<f64>; 4]> = Vec::new();
+    let mut bead_counts = Vec::new();
+    for variant in &variants {
+        let mut s: [Vec<f64>; 4] = Default::default();
+        let beads: Vec<&str> = rows
+            .iter()
+            .filter(|(v, _)| v == variant)
+            .map(|(_, b)| b.as_str())
+            .collect();
+        for bead in &beads {
+            let o = bead_outcome(conn, bead)?;
+            let values = [
+                o.success.map(|ok| if ok { 1.0 } else { 0.0 }),
+                o.turns,
+                o.cost_usd,
+                o.integration_iterations,
+            ];
+            for (sample, value) in s.iter_mut().zip(values) {
+                sample.extend(value);
+            }
+        }
+        bead_counts.push(beads.len());
+        samples.push(s);
+    }
+
+    Ok(variants
+        .iter()
+        .enumerate()
+        .map(|(i, variant)| {
+            let control = (i > 0).then(|| &samples[0]);
+            let stats = |m: usize| metric_stats(&samples[i][m], control.map(|c| c[m].as_slice()));
+            VariantReport {
+                variant: variant.clone(),
+                beads: bead_counts[i],
+                success_rate: stats(0),
+                turns: stats(1),
+                cost_usd: stats(2),
+                integration_iterations: stats(3),
+            }
+        })
+        .collect())
+}
+
+fn format_stat(stats: &MetricStats, precision: usize) -> String {
+    let mut out = format!("{:.*}", precision, stats.mean);
+    if let (Some(diff), Some(lo), Some(hi)) = (stats.diff, stats.ci_low, stats.ci_high) {
+        out.push_str(&format!(
+            " ({diff:+.precision$} [{lo:.precision$}, {hi:.precision$}]{})",
+            if stats.significant { " *" } else { "" }
+        ));
+    }
+    out
+}
+
+/// `blacksmith experiment report`
+pub fn handle_report(
+    db_path: &Path,
+    config: &ExperimentConfig,
+    name: Option<&str>,
+    json: bool,
+) -> std::result::Result<(), String> {
+    let name = name.unwrap_or(&config.name);
+    if name.is_empty() {
+        return Err("no experiment configured; set [experiment] name or pass --name".to_string());
+    }
+    if !db_path.exists() {
+        println!("No metrics database found. Run some sessions first.");
+        return Ok(());
+    }
+    let conn = db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
+    let order: Vec<String> = config.variants.iter().map(|v| v.name.clone()).collect();
+    let reports =
+        report(&conn, name, &order).map_err(|e| format!("Failed to build report: {e}"))?;
+
+    if json {
+        let out = serde_json::to_string_pretty(&reports).map_err(|e| e.to_string())?;
+        println!("{out}");
+        return Ok(());
+    }
+    if reports.is_empty() {
+        println!("No beads assigned to experiment '{name}' yet.");
+        return Ok(());
+    }
+
+    println!("Experiment '{name}' (control: {})", reports[0].variant);
+    println!(
+        "{:<16} {:>5}  {:<28} {:<28} {:<28} INTEGRATION ITERATIONS",
+        "VARIANT", "BEADS", "SUCCESS RATE", "TURNS", "COST (USD)"
+    );
+    for r in &reports {
+        println!(
+            "{:<16} {:>5}  {:<28} {:<28} {:<28} {}",
+            r.variant,
+            r.beads,
+            format_stat(&r.success_rate, 2),
+            format_stat(&r.turns, 1),
+            format_stat(&r.cost_usd, 2),
+            format_stat(&r.integration_iterations, 1),
+        );
+    }
+    println!();
+    println!("Differences are vs the control with 95% CIs; * = CI excludes zero.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn experiment(variants: &[&str]) -> ExperimentConfig {
+        ExperimentConfig {
+            name: "exp".to_string(),
+            variants: variants
+                .iter()
+                .map(|v| PromptVariant {
+                    name: v.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    fn setup() -> (tempfile::TempDir, Connection) {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = db::open_or_create(&dir.path().join("blacksmith.db")).unwrap();
+        (dir, conn)
+    }
+
+    #[test]
+    fn assignment_is_deterministic_and_uses_every_variant() {
+        let exp = experiment(&["control", "terse"]);
+        let first: Vec<&str> = (0..40)
+            .map(|i| assign(&exp, &format!("beads-{i}")).name.as_str())
+            .collect();
+        let again: Vec<&str> = (0..40)
+            .map(|i| assign(&exp, &format!("beads-{i}")).name.as_str())
+            .collect();
+        assert_eq!(first, again);
+        assert!(first.contains(&"control") && first.contains(&"terse"));
+    }
+
+    #[test]
+    fn recorded_assignment_sticks() {
+        let (_dir, conn) = setup();
+        let exp = experiment(&["control", "terse"]);
+        let assigned = variant_for(&conn, &exp, "beads-1").unwrap().name.clone();
+
+        // Reordering variants would change the hash bucket, not the recorded choice.
+        let reordered = experiment(&["terse", "control", "verbose"]);
+        let again = variant_for(&conn, &reordered, "beads-1").unwrap();
+        assert_eq!(again.name, assigned);
+        assert_eq!(
+            recorded_variant(&conn, "exp", "beads-1").unwrap(),
+            Some(assigned)
+        );
+    }
+
+    #[test]
+    fn apply_toggles_rules_and_appends_section() {
+        let (_dir, conn) = setup();
+        db::insert_improvement(
+            &conn,
+            "workflow",
+            "Batch reads",
+            Some("Read files in batches"),
+            None,
+            None,
+        )
+        .unwrap();
+        let prompt = "# Prompt\n\nDo the work.\n\n<!-- Promoted from R3 [cost] -->\n- Skip CSS tests\n\n<!-- Promoted from R4 [workflow] -->\n- Keep it short\n";
+        let variant = PromptVariant {
+            name: "v".to_string(),
+            section: Some("## Extra\nBe terse.".to_string()),
+            improvements_off: vec!["R3".to_string()],
+            improvements_on: vec!["R1".to_string(), "R4".to_string()],
+            ..Default::default()
+        };
+
+        let out = apply(&conn, &variant, prompt).unwrap();
+        assert!(!out.contains("R3") && !out.contains("Skip CSS tests"));
+        assert!(
+            out.contains("Do the work.\n\n<!-- Promoted from R4 [workflow] -->\n- Keep it short")
+        );
+        // R4 is already present, so it isn't appended twice.
+        assert_eq!(out.matches("Promoted from R4").count(), 1);
+        assert!(out.contains("<!-- Promoted from R1 [workflow] -->\n- Read files in batches"));
+        assert!(out.ends_with("## Extra\nBe terse."));
+    }
+
+    #[test]
+    fn tag_session_marks_event_and_observation() {
+        let (_dir, conn) = setup();
+        db::upsert_observation(
+            &conn,
+            7,
+            "2026-01-01T00:00:00Z",
+            Some(30),
+            None,
+            r#"{"turns.total":12}"#,
+        )
+        .unwrap();
+        tag_session(&conn, 7, "exp", "terse").unwrap();
+
+        let events = db::all_events(&conn, Some(7)).unwrap();
+        let tag = events
+            .iter()
+            .find(|e| e.kind == "experiment.variant")
+            .unwrap();
+        assert_eq!(tag.value.as_deref(), Some("terse"));
+        assert_eq!(tag.tags.as_deref(), Some("experiment=exp"));
+        let obs = db::get_observation(&conn, 7).unwrap().unwrap();
+        let data: serde_json::Value = serde_json::from_str(&obs.data).unwrap();
+        assert_eq!(data["experiment.variant"], "terse");
+        assert_eq!(data["turns.total"], 12);
+        assert_eq!(obs.duration, Some(30));
+    }
+
+    fn finished_bead(conn: &Connection, bead: &str, variant: &str, integrated: bool, turns: i64) {
+        conn.execute(
+            "INSERT INTO experiment_assignments (experiment, bead_id, variant) VALUES ('exp', ?1, ?2)",
+            params![bead, variant],
+        )
+        .unwrap();
+        let status = if integrated { "integrated" } else { "failed" };
+        db::insert_worker_assignment(conn, 0, bead, "/tmp/wt", status, None).unwrap();
+        db::upsert_bead_metrics(conn, bead, 1, 60.0, turns, None, None, None).unwrap();
+    }
+
+    #[test]
+    fn report_compares_variants_to_control() {
+        let (_dir, conn) = setup();
+        for (i, turns) in [50, 55, 60, 52].iter().enumerate() {
+            finished_bead(&conn, &format!("c-{i}"), "control", i != 3, *turns);
+        }
+        for (i, turns) in [20, 22, 25, 21].iter().enumerate() {
+            finished_bead(&conn, &format!("t-{i}"), "terse", true, *turns);
+        }
+        // Still running: counted as a bead, excluded from the success rate.
+        conn.execute(
+            "INSERT INTO experiment_assignments (experiment, bead_id, variant) VALUES ('exp', 't-run', 'terse')",
+            [],
+        )
+        .unwrap();
+
+        let reports = report(&conn, "exp", &["control".to_string(), "terse".to_string()]).unwrap();
+        assert_eq!(reports.len(), 2);
+        let (control, terse) = (&reports[0], &reports[1]);
+        assert_eq!(control.variant, "control");
+        assert_eq!(control.beads, 4);
+        assert!((control.success_rate.mean - 0.75).abs() < 1e-9);
+        assert!(control.turns.diff.is_none());
+
+        assert_eq!(terse.beads, 5);
+        assert_eq!(terse.success_rate.n, 4);
+        assert!((terse.success_rate.mean - 1.0).abs() < 1e-9);
+        assert!(terse.turns.diff.unwrap() < -25.0);
+        assert!(terse.turns.significant);
+        assert!(!terse.success_rate.significant);
+        assert_eq!(terse.cost_usd.n, 0);
+    }
+}
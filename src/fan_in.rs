@@ -20,7 +20,7 @@ pub struct FanInEntry {
     pub score: f64,
 }
 
-/// Compute fan-in scores for all files in a Rust codebase.
+/// Compute fan-in scores for all source files in a codebase.
 ///
 /// Returns entries sorted by score descending (highest fan-in first).
 #[cfg(test)]
@@ -4,18 +4,11 @@
 //! quality gates before allowing a bead to be closed. This prevents agents
 //! from closing beads without actually completing the work.
 
-use crate::config::QualityGatesConfig;
+use crate::config::{GateConfig, QualityGatesConfig};
+use crate::{epoch, gate_result, gates, test_report};
 use std::path::Path;
 use std::process::Command;
 
-/// Result of running a single gate command.
-#[derive(Debug)]
-struct GateResult {
-    command: String,
-    success: bool,
-    output: String,
-}
-
 /// Result of the entire finish operation.
 #[derive(Debug)]
 pub struct FinishResult {
@@ -23,63 +16,126 @@ pub struct FinishResult {
     pub message: String,
 }
 
-/// Run a single shell command, returning its success status and combined output.
-fn run_gate_command(cmd: &str, working_dir: &Path) -> GateResult {
-    let result = Command::new("sh")
-        .args(["-c", cmd])
-        .current_dir(working_dir)
-        .output();
+/// Run a single shell command with no timeout or extra environment.
+fn run_gate_command(cmd: &str, working_dir: &Path) -> gates::CommandResult {
+    gates::run_command(cmd, working_dir, &Default::default(), None)
+}
+
+/// Run the gate pipeline, recording each gate's outcome against the bead.
+///
+/// Returns the failing gate's message (and name) on the first blocking
+/// failure. Recording is best-effort: a missing or unwritable DB never
+/// blocks a finish.
+fn run_gates(
+    bead_id: &str,
+    gates: &[GateConfig],
+    working_dir: &Path,
+    db_path: Option<&Path>,
+) -> Result<(), (String, String)> {
+    let needs_changes = gates.iter().any(|g| !g.only_if_changed.is_empty());
+    let changed = if needs_changes {
+        gates::changed_files(working_dir)
+    } else {
+        None
+    };
 
-    match result {
-        Ok(output) => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let combined = format!("{}{}", stdout, stderr);
-            GateResult {
-                command: cmd.to_string(),
-                success: output.status.success(),
-                output: combined,
+    let outcomes = gates::run_pipeline(gates, working_dir, changed.as_deref());
+
+    if let Some(db_path) = db_path {
+        match crate::db::open_or_create(db_path) {
+            Ok(conn) => {
+                for outcome in &outcomes {
+                    if let Err(e) = gate_result::record(&conn, bead_id, outcome) {
+                        eprintln!(
+                            "Warning: failed to record {} gate result: {e}",
+                            outcome.name
+                        );
+                    }
+                    if let Some(report) = &outcome.test_report {
+                        if let Err(e) =
+                            test_report::record(&conn, bead_id, &outcome.name, &report.failures)
+                        {
+                            eprintln!(
+                                "Warning: failed to record {} test failures: {e}",
+                                outcome.name
+                            );
+                        }
+                    }
+                }
             }
+            Err(e) => eprintln!("Warning: failed to open DB for gate results: {e}"),
         }
-        Err(e) => GateResult {
-            command: cmd.to_string(),
-            success: false,
-            output: format!("Failed to execute: {e}"),
-        },
+    }
+
+    match gates::first_failure(&outcomes) {
+        Some(failed) => Err((failed.name.clone(), failed.failure_message())),
+        None => Ok(()),
     }
 }
 
-/// Run all commands in a gate. All must succeed for the gate to pass.
-fn run_gate(name: &str, commands: &[String], working_dir: &Path) -> Result<(), String> {
-    if commands.is_empty() {
-        return Ok(());
-    }
-
-    for cmd in commands {
-        eprintln!("  Running {name} gate: {cmd}");
-        let result = run_gate_command(cmd, working_dir);
-        if !result.success {
-            return Err(format!(
-                "{name} gate failed: {}\n{}",
-                result.command,
-                result
-                    .output
-                    .lines()
-                    .take(50)
-                    .collect::<Vec<_>>()
-                    .join("\n")
-            ));
+/// What deliverable verification found.
+#[derive(Debug, Default)]
+struct Verification {
+    /// Whether there was a bead description to verify against.
+    checked: bool,
+    failures: Vec<String>,
+    /// `## Verify` lines written as prose, left for a human to check.
+    manual_steps: Vec<String>,
+    duration_ms: u64,
+}
+
+impl Verification {
+    fn result(&self) -> Result<(), String> {
+        if self.failures.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Bead deliverable verification failed:\n  {}",
+                self.failures.join("\n  ")
+            ))
         }
     }
-    Ok(())
 }
 
-/// Verify bead deliverables by checking affected files and running verify commands.
-///
-/// Parses the bead description for:
-/// - `## Affected files` section: checks that files marked `(new)` exist
-/// - `## Verify` section: runs `Run:` commands
-fn verify_deliverables(bead_id: &str, working_dir: &Path) -> Result<(), String> {
+/// Record a verification as a `verify` gate result, plus its manual steps
+/// for the epoch QA digest. Best-effort, like gate recording.
+fn record_verification(bead_id: &str, verification: &Verification, db_path: Option<&Path>) {
+    let Some(db_path) = db_path else {
+        return;
+    };
+    if !verification.checked {
+        return;
+    }
+    let conn = match crate::db::open_or_create(db_path) {
+        Ok(conn) => conn,
+        Err(e) => {
+            eprintln!("Warning: failed to open DB for verify result: {e}");
+            return;
+        }
+    };
+    let outcome = gates::GateOutcome {
+        name: "verify".to_string(),
+        status: if verification.failures.is_empty() {
+            gates::GateStatus::Passed
+        } else {
+            gates::GateStatus::Failed
+        },
+        duration_ms: verification.duration_ms,
+        failed_command: None,
+        output: verification.failures.join("\n"),
+        timed_out: false,
+        test_report: None,
+    };
+    if let Err(e) = gate_result::record(&conn, bead_id, &outcome) {
+        eprintln!("Warning: failed to record verify result: {e}");
+    }
+    if let Err(e) = epoch::record_manual_steps(&conn, bead_id, &verification.manual_steps) {
+        eprintln!("Warning: failed to record manual verify steps: {e}");
+    }
+}
+
+/// Verify bead deliverables against the bead's description.
+fn verify_deliverables(bead_id: &str, working_dir: &Path) -> Verification {
     // Fetch bead description via bd show --json
     let output = Command::new("bd")
         .args(["show", bead_id, "--allow-stale", "--json"])
@@ -99,18 +155,29 @@ fn verify_deliverables(bead_id: &str, working_dir: &Path) -> Result<(), String>
         }
         _ => {
             eprintln!("  Could not fetch bead description — skipping deliverable verification");
-            return Ok(());
+            return Verification::default();
         }
     };
 
+    verify_description(&description, working_dir)
+}
+
+/// Verify deliverables described by a bead description.
+///
+/// Parses the description for:
+/// - `## Affected files` section: checks that files marked `(new)` exist
+/// - `## Verify` section: runs `Run:` commands
+fn verify_description(description: &str, working_dir: &Path) -> Verification {
     if description.is_empty() {
-        return Ok(());
+        return Verification::default();
     }
 
+    let started = std::time::Instant::now();
     let mut failures = Vec::new();
+    let mut manual_steps = Vec::new();
 
     // Check ## Affected files section for (new) files that must exist
-    if let Some(affected) = extract_section(&description, "## Affected files") {
+    if let Some(affected) = extract_section(description, "## Affected files") {
         for line in affected.lines() {
             let trimmed = line.trim();
             if trimmed.is_empty() {
@@ -140,7 +207,7 @@ fn verify_deliverables(bead_id: &str, working_dir: &Path) -> Result<(), String>
     }
 
     // Run ## Verify commands
-    if let Some(verify) = extract_section(&description, "## Verify") {
+    if let Some(verify) = extract_section(description, "## Verify") {
         for line in verify.lines() {
             let trimmed = line.trim().trim_start_matches('-').trim();
             if let Some(cmd) = trimmed.strip_prefix("Run:") {
@@ -151,6 +218,7 @@ fn verify_deliverables(bead_id: &str, working_dir: &Path) -> Result<(), String>
                 // Skip non-executable prose commands (e.g., "manually inspect ...")
                 if looks_like_prose(&cmd) {
                     eprintln!("  Skipping non-executable verify line: {cmd}");
+                    manual_steps.push(cmd);
                     continue;
                 }
                 eprintln!("  Running verify command: {cmd}");
@@ -164,13 +232,11 @@ fn verify_deliverables(bead_id: &str, working_dir: &Path) -> Result<(), String>
         }
     }
 
-    if failures.is_empty() {
-        Ok(())
-    } else {
-        Err(format!(
-            "Bead deliverable verification failed:\n  {}",
-            failures.join("\n  ")
-        ))
+    Verification {
+        checked: true,
+        failures,
+        manual_steps,
+        duration_ms: started.elapsed().as_millis() as u64,
     }
 }
 
@@ -281,10 +347,8 @@ fn looks_like_prose(cmd: &str) -> bool {
 }
 
 /// Run the full finish protocol:
-/// 0a. Check gate (compilation)
-/// 0b. Test gate
-/// 0c. Lint gate (e.g. clippy --fix)
-/// 0d. Format gate (e.g. fmt --check)
+/// 0a. Quality gate pipeline (check, test, lint, format by default;
+///     see `QualityGatesConfig::pipeline`), results recorded in `db_path`
 /// 0e. Deliverable verification
 /// 1. Append PROGRESS.txt to log
 /// 2. Stage files + git commit
@@ -296,6 +360,7 @@ pub fn handle_finish(
     commit_msg: &str,
     files: &[String],
     gates_config: &QualityGatesConfig,
+    db_path: Option<&Path>,
 ) -> FinishResult {
     let working_dir = match std::env::current_dir() {
         Ok(d) => d,
@@ -310,61 +375,32 @@ pub fn handle_finish(
     // --- Step 0: Quality gates ---
     eprintln!("=== blacksmith finish: closing {bead_id} ===\n");
 
-    // 0a. Check gate
-    eprintln!("[0a] Running check gate...");
-    if let Err(e) = run_gate("check", &gates_config.check, &working_dir) {
-        eprintln!("\n=== CHECK GATE FAILED ===");
-        eprintln!("Bead {bead_id} will NOT be closed. Fix compilation errors first.");
+    // 0a-0d. Gate pipeline (check, test, lint, format or [[quality_gates.gates]])
+    let pipeline = gates_config.pipeline();
+    eprintln!(
+        "[0a] Running {} quality gate(s): {}",
+        pipeline.len(),
+        pipeline
+            .iter()
+            .map(|g| g.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    if let Err((gate, message)) = run_gates(bead_id, &pipeline, &working_dir, db_path) {
+        eprintln!("\n=== {} GATE FAILED ===", gate.to_uppercase());
+        eprintln!("Bead {bead_id} will NOT be closed. Fix the {gate} gate first.");
         return FinishResult {
             success: false,
-            message: e,
+            message,
         };
     }
-    eprintln!("[0a] Check gate passed\n");
-
-    // 0b. Test gate
-    eprintln!("[0b] Running test gate...");
-    if let Err(e) = run_gate("test", &gates_config.test, &working_dir) {
-        eprintln!("\n=== TEST GATE FAILED ===");
-        eprintln!("Bead {bead_id} will NOT be closed. Fix failing tests first.");
-        return FinishResult {
-            success: false,
-            message: e,
-        };
-    }
-    eprintln!("[0b] Test gate passed\n");
-
-    // 0c. Lint gate
-    if !gates_config.lint.is_empty() {
-        eprintln!("[0c] Running lint gate...");
-        if let Err(e) = run_gate("lint", &gates_config.lint, &working_dir) {
-            eprintln!("\n=== LINT GATE FAILED ===");
-            eprintln!("Bead {bead_id} will NOT be closed. Fix lint errors first.");
-            return FinishResult {
-                success: false,
-                message: e,
-            };
-        }
-        eprintln!("[0c] Lint gate passed\n");
-    }
-
-    // 0d. Format gate
-    if !gates_config.format.is_empty() {
-        eprintln!("[0d] Running format gate...");
-        if let Err(e) = run_gate("format", &gates_config.format, &working_dir) {
-            eprintln!("\n=== FORMAT GATE FAILED ===");
-            eprintln!("Bead {bead_id} will NOT be closed. Fix formatting errors first.");
-            return FinishResult {
-                success: false,
-                message: e,
-            };
-        }
-        eprintln!("[0d] Format gate passed\n");
-    }
+    eprintln!("[0a] Quality gates passed\n");
 
     // 0e. Deliverable verification
     eprintln!("[0e] Verifying bead deliverables...");
-    if let Err(e) = verify_deliverables(bead_id, &working_dir) {
+    let verification = verify_deliverables(bead_id, &working_dir);
+    record_verification(bead_id, &verification, db_path);
+    if let Err(e) = verification.result() {
         eprintln!("\n=== DELIVERABLE VERIFICATION FAILED ===");
         eprintln!("Bead {bead_id} will NOT be closed.");
         return FinishResult {
@@ -633,27 +669,82 @@ mod tests {
         assert!(!result.success);
     }
 
+    fn gate(name: &str, commands: &[&str]) -> GateConfig {
+        GateConfig {
+            name: name.to_string(),
+            commands: commands.iter().map(|c| c.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
     #[test]
-    fn test_run_gate_empty_commands() {
+    fn test_run_gates_empty_commands() {
         let dir = tempfile::tempdir().unwrap();
-        assert!(run_gate("empty", &[], dir.path()).is_ok());
+        assert!(run_gates("b", &[gate("empty", &[])], dir.path(), None).is_ok());
+        assert!(run_gates("b", &[], dir.path(), None).is_ok());
     }
 
     #[test]
-    fn test_run_gate_passes() {
+    fn test_run_gates_passes() {
         let dir = tempfile::tempdir().unwrap();
-        let commands = vec!["true".to_string(), "echo ok".to_string()];
-        assert!(run_gate("test", &commands, dir.path()).is_ok());
+        let gates = [gate("test", &["true", "echo ok"])];
+        assert!(run_gates("b", &gates, dir.path(), None).is_ok());
     }
 
     #[test]
-    fn test_run_gate_fails_on_first_failure() {
+    fn test_run_gates_fails_on_first_failure() {
         let dir = tempfile::tempdir().unwrap();
-        let commands = vec!["exit 1".to_string(), "echo should-not-run".to_string()];
-        let err = run_gate("test", &commands, dir.path()).unwrap_err();
+        let gates = [gate("test", &["exit 1", "echo should-not-run"])];
+        let (name, err) = run_gates("b", &gates, dir.path(), None).unwrap_err();
+        assert_eq!(name, "test");
         assert!(err.contains("test gate failed"));
     }
 
+    #[test]
+    fn test_run_gates_records_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("blacksmith.db");
+        let mut lint = gate("lint", &["exit 1"]);
+        lint.allow_failure = true;
+        let gates = [gate("check", &["true"]), lint, gate("test", &["exit 2"])];
+        assert!(run_gates("bead-7", &gates, dir.path(), Some(&db_path)).is_err());
+
+        let conn = crate::db::open_or_create(&db_path).unwrap();
+        let mut stats = gate_result::gate_stats(&conn, 0).unwrap();
+        stats.sort_by(|a, b| a.gate.cmp(&b.gate));
+        let summary: Vec<(&str, u64, u64, u64)> = stats
+            .iter()
+            .map(|s| (s.gate.as_str(), s.runs, s.failures, s.allowed_failures))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("check", 1, 0, 0), ("lint", 1, 0, 1), ("test", 1, 1, 0)]
+        );
+    }
+
+    #[test]
+    fn test_run_gates_records_test_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("blacksmith.db");
+        let cmd = "echo 'test auth::login ... FAILED'; echo 'test result: FAILED.'; exit 101";
+        let gates = [gate("test", &[cmd])];
+        let (_, message) = run_gates("bead-8", &gates, dir.path(), Some(&db_path)).unwrap_err();
+        assert!(message.contains("- auth::login"));
+
+        let conn = crate::db::open_or_create(&db_path).unwrap();
+        let (bead, source, name): (String, String, String) = conn
+            .query_row(
+                "SELECT bead_id, source, test_name FROM test_failures",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            (bead.as_str(), source.as_str(), name.as_str()),
+            ("bead-8", "test", "auth::login")
+        );
+    }
+
     #[test]
     fn test_strip_verify_prose() {
         // Plain command — no change
@@ -703,6 +794,48 @@ mod tests {
         assert_eq!(sanitize_verify_command(" `` "), "");
     }
 
+    #[test]
+    fn test_verify_description_collects_manual_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        let description =
+            "## Verify\n- Run: true\n- Run: manually inspect the YAML file\n- Run: `false`\n";
+        let verification = verify_description(description, dir.path());
+        assert!(verification.checked);
+        assert_eq!(
+            verification.manual_steps,
+            vec!["manually inspect the YAML file"]
+        );
+        assert_eq!(verification.failures, vec!["Verify command failed: false"]);
+        assert!(verification.result().is_err());
+
+        assert!(!verify_description("", dir.path()).checked);
+    }
+
+    #[test]
+    fn test_record_verification_stores_gate_and_manual_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("blacksmith.db");
+        let verification = Verification {
+            checked: true,
+            failures: Vec::new(),
+            manual_steps: vec!["open the dashboard and check the chart".to_string()],
+            duration_ms: 12,
+        };
+        record_verification("bd-1", &verification, Some(&db_path));
+
+        let conn = crate::db::open_or_create(&db_path).unwrap();
+        let stats = gate_result::gate_stats(&conn, 0).unwrap();
+        assert_eq!(stats[0].gate, "verify");
+        assert_eq!(stats[0].failures, 0);
+        let steps = epoch::manual_steps_between(&conn, "", "9999").unwrap();
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].bead_id, "bd-1");
+
+        // Nothing to verify against: nothing recorded
+        record_verification("bd-2", &Verification::default(), Some(&db_path));
+        assert_eq!(gate_result::gate_stats(&conn, 0).unwrap()[0].runs, 1);
+    }
+
     #[test]
     fn test_looks_like_prose() {
         assert!(looks_like_prose("manually inspect the YAML file"));
@@ -735,9 +868,10 @@ mod tests {
             test: vec![],
             lint: vec![],
             format: vec![],
+            gates: vec![],
         };
         // This tests the gate failure path (we can't test the full flow without git/bd)
-        let result = handle_finish("test-bead", "test message", &[], &gates);
+        let result = handle_finish("test-bead", "test message", &[], &gates, None);
         assert!(!result.success);
         assert!(result.message.contains("check gate failed"));
     }
@@ -749,8 +883,9 @@ mod tests {
             test: vec!["exit 1".to_string()],
             lint: vec![],
             format: vec![],
+            gates: vec![],
         };
-        let result = handle_finish("test-bead", "test message", &[], &gates);
+        let result = handle_finish("test-bead", "test message", &[], &gates, None);
         assert!(!result.success);
         assert!(result.message.contains("test gate failed"));
     }
@@ -762,8 +897,9 @@ mod tests {
             test: vec!["true".to_string()],
             lint: vec!["exit 1".to_string()],
             format: vec![],
+            gates: vec![],
         };
-        let result = handle_finish("test-bead", "test message", &[], &gates);
+        let result = handle_finish("test-bead", "test message", &[], &gates, None);
         assert!(!result.success);
         assert!(result.message.contains("lint gate failed"));
     }
@@ -775,8 +911,9 @@ mod tests {
             test: vec!["true".to_string()],
             lint: vec!["true".to_string()],
             format: vec!["exit 1".to_string()],
+            gates: vec![],
         };
-        let result = handle_finish("test-bead", "test message", &[], &gates);
+        let result = handle_finish("test-bead", "test message", &[], &gates, None);
         assert!(!result.success);
         assert!(result.message.contains("format gate failed"));
     }
@@ -790,8 +927,9 @@ mod tests {
             test: vec!["true".to_string()],
             lint: vec![],
             format: vec![],
+            gates: vec![],
         };
-        let result = handle_finish("test-bead", "test message", &[], &gates);
+        let result = handle_finish("test-bead", "test message", &[], &gates, None);
         // Will fail at deliverable verification or git, but NOT at lint/format gates
         assert!(
             !result.message.contains("lint gate failed")
@@ -0,0 +1,431 @@
+//! Architecture fitness rules evaluated as an integration gate.
+//!
+//! Rules come from `[[architecture.fitness]]` in config. The integrator
+//! analyzes the merged worktree and a checkout of the base commit, and every
+//! rule is a ratchet against the base: only imports, cycles, oversized files
+//! and API growth introduced by the bead count as violations, so pre-existing
+//! debt does not block unrelated work.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::FitnessRule;
+use crate::import_graph;
+use crate::module_detect;
+use crate::structural_metrics;
+
+/// A validated fitness rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule {
+    /// Files in module `from` must not import files in module `to`.
+    ForbidImport { from: String, to: String },
+    /// No module-level cycle may be introduced.
+    NoNewCycles,
+    /// No file may grow beyond `max` lines.
+    MaxFileLines { max: usize },
+    /// The public API of `module` may grow by at most `max` symbols.
+    MaxApiGrowth { module: String, max: usize },
+}
+
+impl Rule {
+    /// Validate a config entry.
+    pub fn parse(rule: &FitnessRule) -> Result<Self, String> {
+        let field = |value: &Option<String>, name: &str| {
+            value
+                .clone()
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| format!("rule '{}' requires '{name}'", rule.rule))
+        };
+        let max = || {
+            rule.max
+                .ok_or_else(|| format!("rule '{}' requires 'max'", rule.rule))
+        };
+        match rule.rule.as_str() {
+            "forbid_import" => Ok(Rule::ForbidImport {
+                from: field(&rule.from, "from")?,
+                to: field(&rule.to, "to")?,
+            }),
+            "no_new_cycles" => Ok(Rule::NoNewCycles),
+            "max_file_lines" => Ok(Rule::MaxFileLines { max: max()? }),
+            "max_api_growth" => Ok(Rule::MaxApiGrowth {
+                module: field(&rule.module, "module")?,
+                max: max()?,
+            }),
+            other => Err(format!(
+                "unknown rule '{other}', expected one of: forbid_import, no_new_cycles, \
+                 max_file_lines, max_api_growth"
+            )),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Rule::ForbidImport { .. } => "forbid_import",
+            Rule::NoNewCycles => "no_new_cycles",
+            Rule::MaxFileLines { .. } => "max_file_lines",
+            Rule::MaxApiGrowth { .. } => "max_api_growth",
+        }
+    }
+}
+
+/// A rule broken by the merged tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.rule, self.message)
+    }
+}
+
+/// The facts about one tree that rules are evaluated against. Paths are
+/// relative to the tree root so base and head can be compared.
+#[derive(Debug, Default)]
+pub struct TreeFacts {
+    module_of: HashMap<String, String>,
+    imports: HashSet<(String, String)>,
+    cycles: HashSet<String>,
+    file_lines: HashMap<String, usize>,
+    api_widths: HashMap<String, usize>,
+}
+
+impl TreeFacts {
+    /// Analyze the tree rooted at `root`.
+    pub fn collect(root: &Path) -> Self {
+        let rel = |p: &Path| {
+            p.strip_prefix(root)
+                .unwrap_or(p)
+                .to_string_lossy()
+                .to_string()
+        };
+        let report = structural_metrics::analyze(root);
+        let modules = module_detect::detect_modules_from_repo(root);
+
+        let module_of = modules
+            .iter()
+            .flat_map(|(name, m)| m.files.iter().map(move |f| (rel(f), name.clone())))
+            .collect();
+        let imports = import_graph::build_import_graph(root)
+            .iter()
+            .flat_map(|(file, deps)| deps.iter().map(move |d| (rel(file), rel(d))))
+            .collect();
+        let cycles = report
+            .cycles
+            .iter()
+            .map(|c| {
+                let mut modules = c.modules.clone();
+                modules.sort();
+                modules.join(" -> ")
+            })
+            .collect();
+        let file_lines = report
+            .files
+            .values()
+            .map(|f| (rel(&f.path), f.line_count))
+            .collect();
+        let api_widths = report
+            .modules
+            .values()
+            .map(|m| (m.name.clone(), m.api_surface_width))
+            .collect();
+
+        TreeFacts {
+            module_of,
+            imports,
+            cycles,
+            file_lines,
+            api_widths,
+        }
+    }
+}
+
+/// Whether `name` is `module` or one of its submodules.
+fn in_module(name: &str, module: &str) -> bool {
+    name == module
+        || name
+            .strip_prefix(module)
+            .is_some_and(|rest| rest.starts_with("::"))
+}
+
+/// Evaluate `rules` on `head`, reporting only what changed relative to `base`.
+pub fn evaluate(rules: &[Rule], base: &TreeFacts, head: &TreeFacts) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for rule in rules {
+        let mut messages: Vec<String> = Vec::new();
+        match rule {
+            Rule::ForbidImport { from, to } => {
+                for (source, target) in &head.imports {
+                    let (Some(src_mod), Some(dst_mod)) =
+                        (head.module_of.get(source), head.module_of.get(target))
+                    else {
+                        continue;
+                    };
+                    if in_module(src_mod, from)
+                        && in_module(dst_mod, to)
+                        && !base.imports.contains(&(source.clone(), target.clone()))
+                    {
+                        messages.push(format!(
+                            "{source} imports {target}: module '{from}' must not import '{to}'"
+                        ));
+                    }
+                }
+            }
+            Rule::NoNewCycles => {
+                for cycle in head.cycles.difference(&base.cycles) {
+                    messages.push(format!("new module cycle: {cycle}"));
+                }
+            }
+            Rule::MaxFileLines { max } => {
+                for (file, &lines) in &head.file_lines {
+                    let before = base.file_lines.get(file).copied().unwrap_or(0);
+                    if lines > *max && lines > before {
+                        messages.push(format!(
+                            "{file} has {lines} lines (limit {max}, was {before})"
+                        ));
+                    }
+                }
+            }
+            Rule::MaxApiGrowth { module, max } => {
+                let before = base.api_widths.get(module).copied().unwrap_or(0);
+                let after = head.api_widths.get(module).copied().unwrap_or(0);
+                if after > before + max {
+                    messages.push(format!(
+                        "public API of '{module}' grew by {} symbols ({before} -> {after}; limit {max} per bead)",
+                        after - before
+                    ));
+                }
+            }
+        }
+        messages.sort();
+        violations.extend(messages.into_iter().map(|message| Violation {
+            rule: rule.name(),
+            message,
+        }));
+    }
+    violations
+}
+
+/// Evaluate configured rules on `worktree` against `base_ref` in `repo_dir`.
+///
+/// The base commit is checked out into a temporary detached worktree for
+/// analysis and removed afterwards.
+pub fn check(
+    rules: &[FitnessRule],
+    repo_dir: &Path,
+    base_ref: &str,
+    worktree: &Path,
+) -> Result<Vec<Violation>, String> {
+    let rules = rules
+        .iter()
+        .map(Rule::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+    if rules.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let head = TreeFacts::collect(worktree);
+    let base = with_checkout(repo_dir, base_ref, TreeFacts::collect)?;
+    Ok(evaluate(&rules, &base, &head))
+}
+
+/// Run `f` on a temporary detached checkout of `git_ref`.
+fn with_checkout<T>(
+    repo_dir: &Path,
+    git_ref: &str,
+    f: impl FnOnce(&Path) -> T,
+) -> Result<T, String> {
+    let tmp = tempfile::Builder::new()
+        .prefix("blacksmith-fitness-")
+        .tempdir()
+        .map_err(|e| format!("failed to create temp dir: {e}"))?;
+    let output = Command::new("git")
+        .args(["worktree", "add", "--detach"])
+        .arg(tmp.path())
+        .arg(git_ref)
+        .current_dir(repo_dir)
+        .output()
+        .map_err(|e| format!("failed to run git worktree add: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git worktree add {git_ref} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let result = f(tmp.path());
+    if let Err(e) = crate::worktree::remove(repo_dir, tmp.path()) {
+        tracing::warn!(error = %e, "failed to remove fitness base checkout");
+    }
+    Ok(result)
+}
+
+/// One violation per line, for failure reasons and agent prompts.
+pub fn describe(violations: &[Violation]) -> String {
+    violations
+        .iter()
+        .map(|v| format!("- {v}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn rule(kind: &str) -> FitnessRule {
+        FitnessRule {
+            rule: kind.to_string(),
+            from: None,
+            to: None,
+            module: None,
+            max: None,
+        }
+    }
+
+    fn project(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        for (path, content) in files {
+            let full = tmp.path().join("src").join(path);
+            fs::create_dir_all(full.parent().unwrap()).unwrap();
+            fs::write(&full, content).unwrap();
+        }
+        tmp
+    }
+
+    const BASE: &[(&str, &str)] = &[
+        (
+            "main.rs",
+            "mod adapters;\nmod coordinator;\nmod db;\nfn main() {}",
+        ),
+        ("adapters/mod.rs", "pub struct Adapter;"),
+        (
+            "coordinator/mod.rs",
+            "use crate::adapters::Adapter;\npub struct Coordinator;",
+        ),
+        ("db/mod.rs", "pub fn open() {}"),
+    ];
+
+    fn head_with(changes: &[(&str, &str)]) -> tempfile::TempDir {
+        let mut files: HashMap<&str, &str> = BASE.iter().copied().collect();
+        files.extend(changes.iter().copied());
+        project(&files.into_iter().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn parse_requires_rule_fields() {
+        assert_eq!(Rule::parse(&rule("no_new_cycles")), Ok(Rule::NoNewCycles));
+        let err = Rule::parse(&rule("forbid_import")).unwrap_err();
+        assert!(err.contains("'from'"), "{err}");
+        let err = Rule::parse(&rule("max_api_growth")).unwrap_err();
+        assert!(err.contains("'module'"), "{err}");
+        let err = Rule::parse(&rule("no_god_files")).unwrap_err();
+        assert!(err.contains("unknown rule"), "{err}");
+        let mut lines = rule("max_file_lines");
+        lines.max = Some(500);
+        assert_eq!(Rule::parse(&lines), Ok(Rule::MaxFileLines { max: 500 }));
+    }
+
+    #[test]
+    fn forbid_import_flags_only_new_edges() {
+        let base = project(BASE);
+        let head = head_with(&[(
+            "adapters/mod.rs",
+            "use crate::coordinator::Coordinator;\npub struct Adapter;",
+        )]);
+        let rules = vec![Rule::ForbidImport {
+            from: "adapters".to_string(),
+            to: "coordinator".to_string(),
+        }];
+        let base_facts = TreeFacts::collect(base.path());
+        let violations = evaluate(&rules, &base_facts, &TreeFacts::collect(head.path()));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, "forbid_import");
+        assert!(
+            violations[0]
+                .message
+                .starts_with("src/adapters/mod.rs imports src/coordinator/mod.rs"),
+            "{}",
+            violations[0]
+        );
+
+        // The same edge already present in the base is not the bead's fault.
+        assert!(evaluate(
+            &rules,
+            &TreeFacts::collect(head.path()),
+            &TreeFacts::collect(head.path())
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn no_new_cycles_reports_introduced_cycle() {
+        let base = project(BASE);
+        let head = head_with(&[(
+            "adapters/mod.rs",
+            "use crate::coordinator::Coordinator;\npub struct Adapter;",
+        )]);
+        let violations = evaluate(
+            &[Rule::NoNewCycles],
+            &TreeFacts::collect(base.path()),
+            &TreeFacts::collect(head.path()),
+        );
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].message,
+            "new module cycle: adapters -> coordinator"
+        );
+    }
+
+    #[test]
+    fn max_file_lines_ignores_files_that_did_not_grow() {
+        let big = "fn f() {}\n".repeat(30);
+        let bigger = "fn f() {}\n".repeat(40);
+        let base = head_with(&[("db/mod.rs", &big)]);
+        let rules = [Rule::MaxFileLines { max: 20 }];
+
+        let same = head_with(&[("db/mod.rs", &big)]);
+        let base_facts = TreeFacts::collect(base.path());
+        assert!(evaluate(&rules, &base_facts, &TreeFacts::collect(same.path())).is_empty());
+
+        let grown = head_with(&[("db/mod.rs", &bigger)]);
+        let violations = evaluate(&rules, &base_facts, &TreeFacts::collect(grown.path()));
+        assert_eq!(
+            violations[0].message,
+            "src/db/mod.rs has 40 lines (limit 20, was 30)"
+        );
+    }
+
+    #[test]
+    fn max_api_growth_limits_new_public_symbols() {
+        let base = project(BASE);
+        let head = head_with(&[(
+            "db/mod.rs",
+            "pub fn open() {}\npub fn close() {}\npub fn flush() {}\npub struct Pool;",
+        )]);
+        let rule = |max| Rule::MaxApiGrowth {
+            module: "db".to_string(),
+            max,
+        };
+        let base_facts = TreeFacts::collect(base.path());
+        let head_facts = TreeFacts::collect(head.path());
+        assert!(evaluate(&[rule(3)], &base_facts, &head_facts).is_empty());
+        let violations = evaluate(&[rule(2)], &base_facts, &head_facts);
+        assert_eq!(
+            violations[0].message,
+            "public API of 'db' grew by 3 symbols (1 -> 4; limit 2 per bead)"
+        );
+    }
+
+    #[test]
+    fn in_module_matches_submodules() {
+        assert!(in_module("adapters", "adapters"));
+        assert!(in_module("adapters::claude", "adapters"));
+        assert!(!in_module("adapters_old", "adapters"));
+    }
+}
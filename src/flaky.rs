@@ -0,0 +1,393 @@
+//! Flaky-test detection for reconciliation.
+//!
+//! When the full suite fails on main, each failing test is re-run in
+//! isolation. A test that passes on any re-run is flaky: it is recorded as
+//! such and excluded from blame, so the recently integrated beads are only
+//! flagged for genuinely broken tests. Every suite failure and re-run result
+//! is kept in `test_runs`, which feeds `blacksmith tests flaky`.
+
+use crate::config::ReconciliationConfig;
+use crate::test_report::{ReportFormat, TestReport};
+use rusqlite::{params, Connection, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Create the test_runs table if it doesn't exist.
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS test_runs (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            test_name   TEXT NOT NULL,
+            passed      INTEGER NOT NULL,
+            rerun       INTEGER NOT NULL DEFAULT 0,
+            recorded_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_test_runs_test ON test_runs(test_name);",
+    )
+}
+
+/// Record one result for a test. `rerun` marks isolated re-runs, as opposed
+/// to failures seen in a full-suite run.
+pub fn record_run(conn: &Connection, test_name: &str, passed: bool, rerun: bool) -> Result<()> {
+    conn.execute(
+        "INSERT INTO test_runs (test_name, passed, rerun) VALUES (?1, ?2, ?3)",
+        params![test_name, passed, rerun],
+    )?;
+    Ok(())
+}
+
+/// Verdict for a failing test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Passed on re-run (or has a flaky history and couldn't be re-run).
+    Flaky,
+    /// Failed every re-run.
+    Broken,
+}
+
+/// Pass/fail history for one test over its most recent runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestHistory {
+    pub test_name: String,
+    /// Full-suite failures.
+    pub suite_failures: u64,
+    pub reruns: u64,
+    pub rerun_passes: u64,
+    pub last_seen: String,
+}
+
+impl TestHistory {
+    /// A test with any passing re-run in its window has proven flaky.
+    pub fn is_flaky(&self) -> bool {
+        self.rerun_passes > 0
+    }
+
+    /// Fraction of re-runs that passed.
+    pub fn flake_rate(&self) -> f64 {
+        if self.reruns == 0 {
+            0.0
+        } else {
+            self.rerun_passes as f64 / self.reruns as f64
+        }
+    }
+}
+
+const HISTORY_SELECT: &str = "SELECT test_name,
+        SUM(CASE WHEN rerun = 0 AND passed = 0 THEN 1 ELSE 0 END),
+        SUM(rerun),
+        SUM(CASE WHEN rerun = 1 AND passed = 1 THEN 1 ELSE 0 END),
+        MAX(recorded_at)
+     FROM (
+        SELECT *, ROW_NUMBER() OVER (PARTITION BY test_name ORDER BY id DESC) AS rn
+        FROM test_runs
+     )
+     WHERE rn <= ?1";
+
+fn history_from_row(row: &rusqlite::Row) -> Result<TestHistory> {
+    Ok(TestHistory {
+        test_name: row.get(0)?,
+        suite_failures: row.get::<_, i64>(1)? as u64,
+        reruns: row.get::<_, i64>(2)? as u64,
+        rerun_passes: row.get::<_, i64>(3)? as u64,
+        last_seen: row.get(4)?,
+    })
+}
+
+/// History of one test over its last `window` runs.
+pub fn history(conn: &Connection, test_name: &str, window: u32) -> Result<Option<TestHistory>> {
+    let sql = format!("{HISTORY_SELECT} AND test_name = ?2 GROUP BY test_name");
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query_map(params![window, test_name], history_from_row)?;
+    rows.next().transpose()
+}
+
+/// All tests that have proven flaky within their last `window` runs,
+/// most flaky first.
+pub fn flaky_tests(conn: &Connection, window: u32) -> Result<Vec<TestHistory>> {
+    let sql = format!("{HISTORY_SELECT} GROUP BY test_name");
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params![window], history_from_row)?;
+    let mut tests: Vec<TestHistory> = rows
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .filter(TestHistory::is_flaky)
+        .collect();
+    tests.sort_by(|a, b| {
+        b.flake_rate()
+            .partial_cmp(&a.flake_rate())
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.last_seen.cmp(&a.last_seen))
+    });
+    Ok(tests)
+}
+
+/// The command that re-runs a single test, if one is configured or can be
+/// inferred from the report format.
+pub fn rerun_command(
+    config: &ReconciliationConfig,
+    format: ReportFormat,
+    test_name: &str,
+) -> Option<String> {
+    let template = match (&config.rerun_command, format) {
+        (Some(cmd), _) => cmd.as_str(),
+        (None, ReportFormat::Libtest | ReportFormat::LibtestJson) => {
+            "cargo test --release -- --exact {test}"
+        }
+        (None, ReportFormat::Pytest) => "python -m pytest {test}",
+        (None, _) => return None,
+    };
+    Some(template.replace("{test}", &shell_quote(test_name)))
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Re-run each failing test in `report` in isolation and classify it.
+///
+/// Records the suite failure and every re-run in `test_runs`. Tests that
+/// can't be re-run (no command, or re-runs disabled) fall back to their
+/// history: known-flaky tests stay flaky, anything else is broken.
+pub fn classify_failures(
+    conn: &Connection,
+    config: &ReconciliationConfig,
+    report: &TestReport,
+    repo_dir: &Path,
+) -> Vec<(String, Verdict)> {
+    let mut verdicts = Vec::new();
+    for failure in &report.failures {
+        let name = &failure.name;
+        if let Err(e) = record_run(conn, name, false, false) {
+            tracing::warn!(test = %name, error = %e, "failed to record test run");
+        }
+
+        let command = rerun_command(config, report.format, name);
+        let mut rerun_passed = false;
+        if let Some(cmd) = command.as_deref().filter(|_| config.flaky_reruns > 0) {
+            for attempt in 1..=config.flaky_reruns {
+                let passed = Command::new("sh")
+                    .args(["-c", cmd])
+                    .current_dir(repo_dir)
+                    .output()
+                    .map(|o| o.status.success())
+                    .unwrap_or(false);
+                tracing::info!(test = %name, attempt, passed, "re-ran failing test in isolation");
+                if let Err(e) = record_run(conn, name, passed, true) {
+                    tracing::warn!(test = %name, error = %e, "failed to record test re-run");
+                }
+                if passed {
+                    rerun_passed = true;
+                    break;
+                }
+            }
+        }
+
+        let verdict = if rerun_passed {
+            Verdict::Flaky
+        } else if command.is_none() || config.flaky_reruns == 0 {
+            let known_flaky = history(conn, name, config.flaky_window)
+                .ok()
+                .flatten()
+                .is_some_and(|h| h.is_flaky());
+            if known_flaky {
+                Verdict::Flaky
+            } else {
+                Verdict::Broken
+            }
+        } else {
+            Verdict::Broken
+        };
+        verdicts.push((name.clone(), verdict));
+    }
+    verdicts
+}
+
+/// Handle `blacksmith tests flaky`.
+pub fn handle_flaky(db_path: &Path, window: u32) -> Result<(), String> {
+    if !db_path.exists() {
+        println!("No metrics database found. Run some sessions first.");
+        return Ok(());
+    }
+    let conn =
+        crate::db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
+    let tests =
+        flaky_tests(&conn, window).map_err(|e| format!("Failed to query test runs: {e}"))?;
+
+    if tests.is_empty() {
+        println!("No flaky tests detected.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<50} {:>8} {:>8} {:>10} LAST SEEN",
+        "TEST", "FAILURES", "RERUNS", "FLAKE RATE"
+    );
+    println!("{}", "-".repeat(100));
+    for t in &tests {
+        let name = if t.test_name.chars().count() > 49 {
+            let tail: String = t
+                .test_name
+                .chars()
+                .rev()
+                .take(46)
+                .collect::<Vec<_>>()
+                .into_iter()
+                .rev()
+                .collect();
+            format!("...{tail}")
+        } else {
+            t.test_name.clone()
+        };
+        println!(
+            "{:<50} {:>8} {:>8} {:>9.0}% {}",
+            name,
+            t.suite_failures,
+            format!("{}/{}", t.rerun_passes, t.reruns),
+            t.flake_rate() * 100.0,
+            t.last_seen
+        );
+    }
+    println!(
+        "\n{} flaky test(s) (passed on isolated re-run within the last {} runs); \
+         excluded from reconciliation blame.",
+        tests.len(),
+        window
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_report::TestFailure;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        create_table(&conn).unwrap();
+        conn
+    }
+
+    fn report(names: &[&str]) -> TestReport {
+        TestReport {
+            format: ReportFormat::Libtest,
+            passed: 0,
+            failed: names.len() as u64,
+            failures: names
+                .iter()
+                .map(|n| TestFailure {
+                    name: n.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    fn config(reruns: u32, cmd: &str) -> ReconciliationConfig {
+        ReconciliationConfig {
+            flaky_reruns: reruns,
+            rerun_command: Some(cmd.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn history_counts_recent_window() {
+        let conn = setup_db();
+        record_run(&conn, "a", false, false).unwrap();
+        record_run(&conn, "a", true, true).unwrap();
+        record_run(&conn, "a", false, false).unwrap();
+        record_run(&conn, "a", false, true).unwrap();
+        record_run(&conn, "b", false, false).unwrap();
+
+        let a = history(&conn, "a", 20).unwrap().unwrap();
+        assert_eq!((a.suite_failures, a.reruns, a.rerun_passes), (2, 2, 1));
+        assert!(a.is_flaky());
+        assert!((a.flake_rate() - 0.5).abs() < 1e-9);
+
+        // Only the last 2 runs: the passing re-run falls out of the window
+        let recent = history(&conn, "a", 2).unwrap().unwrap();
+        assert!(!recent.is_flaky());
+
+        assert!(history(&conn, "missing", 20).unwrap().is_none());
+        let flaky = flaky_tests(&conn, 20).unwrap();
+        assert_eq!(flaky.len(), 1);
+        assert_eq!(flaky[0].test_name, "a");
+    }
+
+    #[test]
+    fn rerun_command_inferred_from_format() {
+        let cfg = ReconciliationConfig::default();
+        assert_eq!(
+            rerun_command(&cfg, ReportFormat::Libtest, "a::b").as_deref(),
+            Some("cargo test --release -- --exact 'a::b'")
+        );
+        assert_eq!(
+            rerun_command(&cfg, ReportFormat::Pytest, "t.py::test_x[it's]").as_deref(),
+            Some(r"python -m pytest 't.py::test_x[it'\''s]'")
+        );
+        assert!(rerun_command(&cfg, ReportFormat::JUnit, "x").is_none());
+        assert_eq!(
+            rerun_command(&config(1, "run {test}"), ReportFormat::JUnit, "x").as_deref(),
+            Some("run 'x'")
+        );
+    }
+
+    #[test]
+    fn classify_passing_rerun_is_flaky() {
+        let conn = setup_db();
+        let dir = tempfile::tempdir().unwrap();
+        // Fails the first re-run, passes the second
+        let cmd = "if [ -f seen ]; then exit 0; else touch seen; exit 1; fi";
+        let verdicts = classify_failures(&conn, &config(3, cmd), &report(&["t"]), dir.path());
+        assert_eq!(verdicts, vec![("t".to_string(), Verdict::Flaky)]);
+        let h = history(&conn, "t", 20).unwrap().unwrap();
+        assert_eq!((h.suite_failures, h.reruns, h.rerun_passes), (1, 2, 1));
+    }
+
+    #[test]
+    fn classify_always_failing_is_broken() {
+        let conn = setup_db();
+        let dir = tempfile::tempdir().unwrap();
+        let verdicts = classify_failures(&conn, &config(2, "exit 1"), &report(&["t"]), dir.path());
+        assert_eq!(verdicts, vec![("t".to_string(), Verdict::Broken)]);
+        assert_eq!(history(&conn, "t", 20).unwrap().unwrap().reruns, 2);
+    }
+
+    #[test]
+    fn classify_without_reruns_uses_history() {
+        let conn = setup_db();
+        let dir = tempfile::tempdir().unwrap();
+        record_run(&conn, "known", true, true).unwrap();
+        let verdicts = classify_failures(
+            &conn,
+            &config(0, "exit 0"),
+            &report(&["known", "new"]),
+            dir.path(),
+        );
+        assert_eq!(
+            verdicts,
+            vec![
+                ("known".to_string(), Verdict::Flaky),
+                ("new".to_string(), Verdict::Broken)
+            ]
+        );
+    }
+
+    #[test]
+    fn handle_flaky_reports() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blacksmith.db");
+        handle_flaky(&path, 20).unwrap();
+        let conn = crate::db::open_or_create(&path).unwrap();
+        handle_flaky(&path, 20).unwrap();
+        record_run(
+            &conn,
+            "very::long::module::path::that::goes::on::and::on::test_name",
+            true,
+            true,
+        )
+        .unwrap();
+        handle_flaky(&path, 20).unwrap();
+    }
+}
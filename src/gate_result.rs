@@ -0,0 +1,192 @@
+//! Per-gate results from `blacksmith finish`.
+//!
+//! Every gate in the finish pipeline records one row per run against the
+//! bead, so `metrics gates` and the brief can show which gates fail most
+//! often and how long they take.
+
+use crate::gates::{GateOutcome, GateStatus};
+use rusqlite::{params, Connection, Result};
+
+/// Create the gate_results table if it doesn't exist.
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS gate_results (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            bead_id     TEXT NOT NULL,
+            gate        TEXT NOT NULL,
+            status      TEXT NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            timed_out   INTEGER NOT NULL DEFAULT 0,
+            recorded_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_gate_results_bead ON gate_results(bead_id);
+        CREATE INDEX IF NOT EXISTS idx_gate_results_gate ON gate_results(gate);",
+    )
+}
+
+/// Record the outcome of one gate run for a bead.
+pub fn record(conn: &Connection, bead_id: &str, outcome: &GateOutcome) -> Result<()> {
+    conn.execute(
+        "INSERT INTO gate_results (bead_id, gate, status, duration_ms, timed_out)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            bead_id,
+            outcome.name,
+            outcome.status.as_str(),
+            outcome.duration_ms as i64,
+            outcome.timed_out,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Aggregate results for one gate.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct GateStats {
+    pub gate: String,
+    pub runs: u64,
+    pub failures: u64,
+    pub allowed_failures: u64,
+    pub skipped: u64,
+    pub timeouts: u64,
+    /// Average duration of runs that were not skipped.
+    pub avg_duration_ms: f64,
+}
+
+impl GateStats {
+    /// Fraction of executed (non-skipped) runs that failed, allowed or not.
+    pub fn failure_rate(&self) -> f64 {
+        let executed = self.runs - self.skipped;
+        if executed == 0 {
+            0.0
+        } else {
+            (self.failures + self.allowed_failures) as f64 / executed as f64
+        }
+    }
+}
+
+/// Per-gate stats over the most recent `last` results (all when 0),
+/// ordered by failure rate, highest first.
+pub fn gate_stats(conn: &Connection, last: u32) -> Result<Vec<GateStats>> {
+    let limit: i64 = if last == 0 { -1 } else { last as i64 };
+    let mut stmt = conn.prepare(
+        "SELECT gate, status, duration_ms, timed_out FROM (
+            SELECT * FROM gate_results ORDER BY id DESC LIMIT ?1
+         ) ORDER BY id",
+    )?;
+    let rows = stmt.query_map(params![limit], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, bool>(3)?,
+        ))
+    })?;
+
+    let mut stats: Vec<GateStats> = Vec::new();
+    for row in rows {
+        let (gate, status, duration_ms, timed_out) = row?;
+        let idx = match stats.iter().position(|s| s.gate == gate) {
+            Some(i) => i,
+            None => {
+                stats.push(GateStats {
+                    gate,
+                    runs: 0,
+                    failures: 0,
+                    allowed_failures: 0,
+                    skipped: 0,
+                    timeouts: 0,
+                    avg_duration_ms: 0.0,
+                });
+                stats.len() - 1
+            }
+        };
+        let entry = &mut stats[idx];
+        entry.runs += 1;
+        match GateStatus::parse(&status) {
+            Some(GateStatus::Failed) => entry.failures += 1,
+            Some(GateStatus::AllowedFailure) => entry.allowed_failures += 1,
+            Some(GateStatus::Skipped) => {
+                entry.skipped += 1;
+                continue;
+            }
+            _ => {}
+        }
+        if timed_out {
+            entry.timeouts += 1;
+        }
+        // Running mean over executed runs
+        let executed = (entry.runs - entry.skipped) as f64;
+        entry.avg_duration_ms += (duration_ms as f64 - entry.avg_duration_ms) / executed;
+    }
+
+    stats.sort_by(|a, b| {
+        b.failure_rate()
+            .partial_cmp(&a.failure_rate())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        create_table(&conn).unwrap();
+        conn
+    }
+
+    fn outcome(name: &str, status: GateStatus, duration_ms: u64) -> GateOutcome {
+        GateOutcome {
+            name: name.to_string(),
+            status,
+            duration_ms,
+            failed_command: None,
+            output: String::new(),
+            timed_out: false,
+            test_report: None,
+        }
+    }
+
+    #[test]
+    fn gate_stats_aggregates_per_gate() {
+        let conn = setup_db();
+        record(&conn, "b1", &outcome("test", GateStatus::Failed, 300)).unwrap();
+        record(&conn, "b1", &outcome("check", GateStatus::Passed, 100)).unwrap();
+        record(&conn, "b2", &outcome("check", GateStatus::Passed, 200)).unwrap();
+        record(&conn, "b2", &outcome("test", GateStatus::Passed, 100)).unwrap();
+        record(&conn, "b3", &outcome("test", GateStatus::Skipped, 0)).unwrap();
+
+        let stats = gate_stats(&conn, 0).unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].gate, "test");
+        assert_eq!(stats[0].runs, 3);
+        assert_eq!(stats[0].failures, 1);
+        assert_eq!(stats[0].skipped, 1);
+        assert!((stats[0].failure_rate() - 0.5).abs() < 1e-9);
+        assert!((stats[0].avg_duration_ms - 200.0).abs() < 1e-9);
+        assert_eq!(stats[1].gate, "check");
+        assert_eq!(stats[1].failure_rate(), 0.0);
+        assert!((stats[1].avg_duration_ms - 150.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn gate_stats_limits_to_recent_results() {
+        let conn = setup_db();
+        record(&conn, "b1", &outcome("test", GateStatus::Failed, 10)).unwrap();
+        record(&conn, "b2", &outcome("test", GateStatus::Passed, 10)).unwrap();
+
+        let stats = gate_stats(&conn, 1).unwrap();
+        assert_eq!(stats[0].runs, 1);
+        assert_eq!(stats[0].failures, 0);
+    }
+
+    #[test]
+    fn gate_stats_empty() {
+        let conn = setup_db();
+        assert!(gate_stats(&conn, 0).unwrap().is_empty());
+    }
+}
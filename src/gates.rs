@@ -0,0 +1,534 @@
+//! Quality gate pipeline for `blacksmith finish`.
+//!
+//! Runs the named gates from `QualityGatesConfig::pipeline()` in order.
+//! Consecutive gates sharing a `parallel_group` form one stage and run on
+//! separate threads; the pipeline stops after the first stage containing a
+//! blocking failure. Each gate gets its own working directory, environment,
+//! timeout and `only_if_changed` filter.
+
+use crate::config::GateConfig;
+use crate::test_report::{self, TestReport};
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How a gate finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GateStatus {
+    Passed,
+    /// Failed and blocks the finish.
+    Failed,
+    /// Failed, but the gate is `allow_failure`.
+    AllowedFailure,
+    /// Not run: no changed file matched `only_if_changed`.
+    Skipped,
+}
+
+impl GateStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GateStatus::Passed => "passed",
+            GateStatus::Failed => "failed",
+            GateStatus::AllowedFailure => "allowed_failure",
+            GateStatus::Skipped => "skipped",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "passed" => Some(GateStatus::Passed),
+            "failed" => Some(GateStatus::Failed),
+            "allowed_failure" => Some(GateStatus::AllowedFailure),
+            "skipped" => Some(GateStatus::Skipped),
+            _ => None,
+        }
+    }
+}
+
+/// Result of running one gate.
+#[derive(Debug, Clone)]
+pub struct GateOutcome {
+    pub name: String,
+    pub status: GateStatus,
+    pub duration_ms: u64,
+    /// The command that failed, if any.
+    pub failed_command: Option<String>,
+    /// Combined stdout/stderr of the failing command (empty on success).
+    pub output: String,
+    pub timed_out: bool,
+    /// Failing tests parsed from the gate's report file or output.
+    pub test_report: Option<TestReport>,
+}
+
+impl GateOutcome {
+    /// Summary of a failing gate for the finish error message. Lists the
+    /// failing tests when they could be parsed, else the raw output.
+    pub fn failure_message(&self) -> String {
+        let command = self.failed_command.as_deref().unwrap_or("");
+        let reason = if self.timed_out { " (timed out)" } else { "" };
+        let detail = match &self.test_report {
+            Some(report) if !report.failures.is_empty() => {
+                report.summary(test_report::SUMMARY_MAX_FAILURES)
+            }
+            _ => self.output.lines().take(50).collect::<Vec<_>>().join("\n"),
+        };
+        format!("{} gate failed{reason}: {command}\n{detail}", self.name)
+    }
+}
+
+/// Result of running a single shell command.
+#[derive(Debug)]
+pub struct CommandResult {
+    pub success: bool,
+    pub output: String,
+    pub timed_out: bool,
+}
+
+/// Run a shell command with optional environment and timeout.
+///
+/// The command runs in its own process group so a timeout kills the whole
+/// tree (e.g. `sh -c "cargo test"` and its test binaries), not just `sh`.
+pub fn run_command(
+    cmd: &str,
+    working_dir: &Path,
+    env: &std::collections::HashMap<String, String>,
+    timeout: Option<Duration>,
+) -> CommandResult {
+    let child = Command::new("sh")
+        .args(["-c", cmd])
+        .current_dir(working_dir)
+        .envs(env)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn();
+
+    let mut child = match child {
+        Ok(c) => c,
+        Err(e) => {
+            return CommandResult {
+                success: false,
+                output: format!("Failed to execute: {e}"),
+                timed_out: false,
+            }
+        }
+    };
+
+    let stdout = child.stdout.take().map(read_to_string_thread);
+    let stderr = child.stderr.take().map(read_to_string_thread);
+
+    let start = Instant::now();
+    let mut timed_out = false;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) => {}
+            Err(_) => break None,
+        }
+        if timeout.is_some_and(|t| start.elapsed() >= t) {
+            timed_out = true;
+            let _ = nix::sys::signal::killpg(
+                nix::unistd::Pid::from_raw(child.id() as i32),
+                nix::sys::signal::Signal::SIGKILL,
+            );
+            break child.wait().ok();
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    let mut output = String::new();
+    for reader in [stdout, stderr].into_iter().flatten() {
+        output.push_str(&reader.join().unwrap_or_default());
+    }
+    if timed_out {
+        let secs = timeout.map(|t| t.as_secs()).unwrap_or(0);
+        output.push_str(&format!("\nTimed out after {secs}s"));
+    }
+
+    CommandResult {
+        success: !timed_out && status.is_some_and(|s| s.success()),
+        output,
+        timed_out,
+    }
+}
+
+fn read_to_string_thread<R: Read + Send + 'static>(
+    mut reader: R,
+) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = reader.read_to_end(&mut buf);
+        String::from_utf8_lossy(&buf).into_owned()
+    })
+}
+
+/// Whether a gate should run given the changed files.
+///
+/// Gates without `only_if_changed` always run, as do all gates when the
+/// changed set is unknown (`None`).
+pub fn should_run(gate: &GateConfig, changed: Option<&[String]>) -> bool {
+    if gate.only_if_changed.is_empty() {
+        return true;
+    }
+    let Some(changed) = changed else {
+        return true;
+    };
+    gate.only_if_changed.iter().any(|pattern| {
+        glob::Pattern::new(pattern)
+            .map(|p| changed.iter().any(|f| p.matches(f)))
+            .unwrap_or(false)
+    })
+}
+
+/// Run one gate: each command in order, stopping at the first failure.
+pub fn run_gate(gate: &GateConfig, repo_root: &Path, changed: Option<&[String]>) -> GateOutcome {
+    let start = Instant::now();
+    let mut outcome = GateOutcome {
+        name: gate.name.clone(),
+        status: GateStatus::Passed,
+        duration_ms: 0,
+        failed_command: None,
+        output: String::new(),
+        timed_out: false,
+        test_report: None,
+    };
+
+    if !should_run(gate, changed) {
+        eprintln!(
+            "  Skipping {} gate: no changes match only_if_changed",
+            gate.name
+        );
+        outcome.status = GateStatus::Skipped;
+        return outcome;
+    }
+
+    let dir = match &gate.working_dir {
+        Some(d) => repo_root.join(d),
+        None => repo_root.to_path_buf(),
+    };
+    let deadline = (gate.timeout_secs > 0).then(|| start + Duration::from_secs(gate.timeout_secs));
+
+    for cmd in &gate.commands {
+        eprintln!("  Running {} gate: {cmd}", gate.name);
+        let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+        let result = run_command(cmd, &dir, &gate.env, remaining);
+        if !result.success {
+            outcome.status = if gate.allow_failure {
+                GateStatus::AllowedFailure
+            } else {
+                GateStatus::Failed
+            };
+            outcome.failed_command = Some(cmd.clone());
+            outcome.output = result.output;
+            outcome.timed_out = result.timed_out;
+            outcome.test_report = parse_test_report(gate, &dir, &outcome.output);
+            break;
+        }
+    }
+
+    outcome.duration_ms = start.elapsed().as_millis() as u64;
+    outcome
+}
+
+/// Parse failing tests from the gate's report file, falling back to its
+/// command output.
+fn parse_test_report(gate: &GateConfig, dir: &Path, output: &str) -> Option<TestReport> {
+    let from_file = gate
+        .report
+        .as_ref()
+        .and_then(|p| std::fs::read_to_string(dir.join(p)).ok())
+        .and_then(|text| test_report::parse(&text));
+    from_file.or_else(|| test_report::parse(output))
+}
+
+/// Split gates into stages: consecutive gates with the same
+/// `parallel_group` share a stage, ungrouped gates get their own.
+pub fn stages(gates: &[GateConfig]) -> Vec<&[GateConfig]> {
+    let mut stages = Vec::new();
+    let mut start = 0;
+    for i in 1..=gates.len() {
+        let boundary = i == gates.len()
+            || gates[i].parallel_group.is_none()
+            || gates[i].parallel_group != gates[start].parallel_group;
+        if boundary {
+            stages.push(&gates[start..i]);
+            start = i;
+        }
+    }
+    stages
+}
+
+/// Run the pipeline, returning outcomes for every gate that was attempted
+/// (including skipped ones). Stops after the first stage with a blocking
+/// failure, so later gates have no outcome.
+pub fn run_pipeline(
+    gates: &[GateConfig],
+    repo_root: &Path,
+    changed: Option<&[String]>,
+) -> Vec<GateOutcome> {
+    let mut outcomes = Vec::new();
+    for stage in stages(gates) {
+        let stage_outcomes: Vec<GateOutcome> = if stage.len() == 1 {
+            vec![run_gate(&stage[0], repo_root, changed)]
+        } else {
+            std::thread::scope(|s| {
+                let handles: Vec<_> = stage
+                    .iter()
+                    .map(|gate| s.spawn(move || run_gate(gate, repo_root, changed)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .zip(stage)
+                    .map(|(h, gate)| {
+                        h.join().unwrap_or_else(|_| GateOutcome {
+                            name: gate.name.clone(),
+                            status: GateStatus::Failed,
+                            duration_ms: 0,
+                            failed_command: None,
+                            output: "gate thread panicked".to_string(),
+                            timed_out: false,
+                            test_report: None,
+                        })
+                    })
+                    .collect()
+            })
+        };
+
+        let blocked = stage_outcomes
+            .iter()
+            .any(|o| o.status == GateStatus::Failed);
+        for o in &stage_outcomes {
+            match o.status {
+                GateStatus::Passed => eprintln!("  {} gate passed", o.name),
+                GateStatus::AllowedFailure => {
+                    eprintln!("  {} gate failed (allowed to fail, continuing)", o.name)
+                }
+                _ => {}
+            }
+        }
+        outcomes.extend(stage_outcomes);
+        if blocked {
+            break;
+        }
+    }
+    outcomes
+}
+
+/// First blocking failure in a pipeline run, if any.
+pub fn first_failure(outcomes: &[GateOutcome]) -> Option<&GateOutcome> {
+    outcomes.iter().find(|o| o.status == GateStatus::Failed)
+}
+
+/// Files changed relative to HEAD (staged, unstaged and untracked).
+///
+/// Returns None when git is unavailable, which makes every
+/// `only_if_changed` gate run.
+pub fn changed_files(repo_root: &Path) -> Option<Vec<String>> {
+    let mut files = Vec::new();
+    for args in [
+        &["diff", "--name-only", "HEAD"][..],
+        &["ls-files", "--others", "--exclude-standard"][..],
+    ] {
+        let out = Command::new("git")
+            .args(args)
+            .current_dir(repo_root)
+            .output()
+            .ok()?;
+        if !out.status.success() {
+            return None;
+        }
+        files.extend(
+            String::from_utf8_lossy(&out.stdout)
+                .lines()
+                .filter(|l| !l.is_empty())
+                .map(String::from),
+        );
+    }
+    Some(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    fn gate(name: &str, commands: &[&str]) -> GateConfig {
+        GateConfig {
+            name: name.to_string(),
+            commands: commands.iter().map(|c| c.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn run_command_captures_output() {
+        let dir = tempdir().unwrap();
+        let result = run_command(
+            "echo out && echo err >&2",
+            dir.path(),
+            &HashMap::new(),
+            None,
+        );
+        assert!(result.success);
+        assert!(result.output.contains("out"));
+        assert!(result.output.contains("err"));
+    }
+
+    #[test]
+    fn run_command_passes_env() {
+        let dir = tempdir().unwrap();
+        let env = HashMap::from([("GATE_VAR".to_string(), "hello".to_string())]);
+        let result = run_command("test \"$GATE_VAR\" = hello", dir.path(), &env, None);
+        assert!(result.success);
+    }
+
+    #[test]
+    fn run_command_times_out() {
+        let dir = tempdir().unwrap();
+        let start = Instant::now();
+        let result = run_command(
+            "sleep 5",
+            dir.path(),
+            &HashMap::new(),
+            Some(Duration::from_millis(200)),
+        );
+        assert!(!result.success);
+        assert!(result.timed_out);
+        assert!(start.elapsed() < Duration::from_secs(4));
+    }
+
+    #[test]
+    fn gate_runs_in_working_dir() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("web")).unwrap();
+        std::fs::write(dir.path().join("web/marker"), "").unwrap();
+        let mut g = gate("sub", &["test -f marker"]);
+        g.working_dir = Some("web".into());
+        assert_eq!(run_gate(&g, dir.path(), None).status, GateStatus::Passed);
+    }
+
+    #[test]
+    fn gate_stops_at_first_failing_command() {
+        let dir = tempdir().unwrap();
+        let g = gate("multi", &["true", "exit 3", "touch never"]);
+        let outcome = run_gate(&g, dir.path(), None);
+        assert_eq!(outcome.status, GateStatus::Failed);
+        assert_eq!(outcome.failed_command.as_deref(), Some("exit 3"));
+        assert!(!dir.path().join("never").exists());
+    }
+
+    #[test]
+    fn only_if_changed_filters() {
+        let mut g = gate("web", &["true"]);
+        g.only_if_changed = vec!["web/**/*.ts".to_string()];
+        let rust_only = vec!["src/main.rs".to_string()];
+        let web = vec!["web/src/app.ts".to_string()];
+        assert!(!should_run(&g, Some(&rust_only)));
+        assert!(should_run(&g, Some(&web)));
+        assert!(should_run(&g, None));
+        assert!(should_run(&gate("always", &["true"]), Some(&rust_only)));
+
+        let dir = tempdir().unwrap();
+        let outcome = run_gate(&g, dir.path(), Some(&rust_only));
+        assert_eq!(outcome.status, GateStatus::Skipped);
+    }
+
+    #[test]
+    fn stages_group_consecutive_parallel_gates() {
+        let mut a = gate("a", &["true"]);
+        let mut b = gate("b", &["true"]);
+        let c = gate("c", &["true"]);
+        let d = gate("d", &["true"]);
+        a.parallel_group = Some("static".into());
+        b.parallel_group = Some("static".into());
+        let gates = vec![a, b, c, d];
+        let stages = stages(&gates);
+        let sizes: Vec<usize> = stages.iter().map(|s| s.len()).collect();
+        assert_eq!(sizes, vec![2, 1, 1]);
+        assert!(super::stages(&[]).is_empty());
+    }
+
+    #[test]
+    fn pipeline_stops_after_blocking_failure() {
+        let dir = tempdir().unwrap();
+        let gates = vec![
+            gate("check", &["true"]),
+            gate("test", &["exit 1"]),
+            gate("lint", &["true"]),
+        ];
+        let outcomes = run_pipeline(&gates, dir.path(), None);
+        assert_eq!(outcomes.len(), 2);
+        let failure = first_failure(&outcomes).unwrap();
+        assert_eq!(failure.name, "test");
+        assert!(failure.failure_message().contains("test gate failed"));
+    }
+
+    #[test]
+    fn pipeline_continues_past_allowed_failure() {
+        let dir = tempdir().unwrap();
+        let mut flaky = gate("flaky", &["exit 1"]);
+        flaky.allow_failure = true;
+        let gates = vec![flaky, gate("test", &["true"])];
+        let outcomes = run_pipeline(&gates, dir.path(), None);
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].status, GateStatus::AllowedFailure);
+        assert_eq!(outcomes[1].status, GateStatus::Passed);
+        assert!(first_failure(&outcomes).is_none());
+    }
+
+    #[test]
+    fn pipeline_runs_parallel_group_concurrently() {
+        let dir = tempdir().unwrap();
+        let mut a = gate("a", &["sleep 0.5"]);
+        let mut b = gate("b", &["sleep 0.5"]);
+        let mut c = gate("c", &["exit 1"]);
+        a.parallel_group = Some("g".into());
+        b.parallel_group = Some("g".into());
+        c.parallel_group = Some("g".into());
+        let start = Instant::now();
+        let outcomes = run_pipeline(&[a, b, c], dir.path(), None);
+        // Whole group completes even though one member failed
+        assert_eq!(outcomes.len(), 3);
+        assert_eq!(outcomes[2].status, GateStatus::Failed);
+        assert!(start.elapsed() < Duration::from_millis(950));
+    }
+
+    #[test]
+    fn failing_gate_parses_test_report() {
+        let dir = tempdir().unwrap();
+        let cmd = "printf 'test a::b ... FAILED\\n\\ntest result: FAILED. 0 passed; 1 failed\\n'; exit 101";
+        let outcome = run_gate(&gate("test", &[cmd]), dir.path(), None);
+        let report = outcome.test_report.as_ref().unwrap();
+        assert_eq!(report.failures[0].name, "a::b");
+        assert!(outcome
+            .failure_message()
+            .contains("1 test(s) failed, 0 passed (libtest):\n- a::b"));
+    }
+
+    #[test]
+    fn failing_gate_prefers_report_file() {
+        let dir = tempdir().unwrap();
+        let mut g = gate("web", &["echo '<testcase name=\"x\"><failure message=\"bad\"/></testcase>' > junit.xml; exit 1"]);
+        g.report = Some("junit.xml".into());
+        let outcome = run_gate(&g, dir.path(), None);
+        let report = outcome.test_report.unwrap();
+        assert_eq!(report.format, test_report::ReportFormat::JUnit);
+        assert_eq!(report.failures[0].message, "bad");
+    }
+
+    #[test]
+    fn gate_status_round_trips() {
+        for status in [
+            GateStatus::Passed,
+            GateStatus::Failed,
+            GateStatus::AllowedFailure,
+            GateStatus::Skipped,
+        ] {
+            assert_eq!(GateStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(GateStatus::parse("bogus"), None);
+    }
+}
@@ -3,7 +3,8 @@
 //! A "god file" is a large file containing multiple unrelated concerns.
 //! Detection works by:
 //! 1. Filtering files above a line-count threshold (default: 200 lines).
-//! 2. Extracting public symbols from each candidate file.
+//! 2. Extracting top-level symbols from each candidate file (Rust items, or the
+//!    definitions reported by the file's `LanguageFrontend` for other languages).
 //! 3. Building a symbol reference graph within the file (which symbols mention each other).
 //! 4. Computing connected components (independent symbol clusters).
 //! 5. Flagging files with 3+ independent clusters as god file candidates.
@@ -11,6 +12,8 @@
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
+use crate::lang;
+
 /// A god file candidate with its cohesion analysis.
 #[derive(Debug, Clone)]
 pub struct GodFileEntry {
@@ -88,7 +91,11 @@ pub fn analyze_file(file: &Path, content: &str) -> GodFileEntry {
         .unwrap_or_default();
 
     // Extract all symbols (pub + private) for cohesion analysis
-    let all_symbols = extract_all_defined_symbols(content, &file_name);
+    let all_symbols = if file.extension().is_some_and(|e| e == "rs") {
+        extract_all_defined_symbols(content, &file_name)
+    } else {
+        frontend_defined_symbols(file, content)
+    };
 
     if all_symbols.is_empty() {
         return GodFileEntry {
@@ -111,14 +118,34 @@ pub fn analyze_file(file: &Path, content: &str) -> GodFileEntry {
 
 /// A symbol definition with its line range for body scanning.
 #[derive(Debug, Clone)]
-struct DefinedSymbol {
-    name: String,
-    start_line: usize,
-    end_line: usize,
+pub(crate) struct DefinedSymbol {
+    pub(crate) name: String,
+    pub(crate) start_line: usize,
+    pub(crate) end_line: usize,
+}
+
+/// Top-level definitions of a non-Rust file, each spanning up to the line
+/// before the next definition (or the end of the file).
+fn frontend_defined_symbols(file: &Path, content: &str) -> Vec<DefinedSymbol> {
+    let Some(frontend) = lang::for_path(file) else {
+        return Vec::new();
+    };
+    let defs = frontend.definitions(content);
+    let last_line = content.lines().count().saturating_sub(1);
+    defs.iter()
+        .enumerate()
+        .map(|(i, def)| DefinedSymbol {
+            name: def.name.clone(),
+            start_line: def.line,
+            end_line: defs
+                .get(i + 1)
+                .map_or(last_line, |next| next.line.saturating_sub(1)),
+        })
+        .collect()
 }
 
 /// Extract all top-level symbol definitions (pub and private) with their line ranges.
-fn extract_all_defined_symbols(source: &str, _file_name: &str) -> Vec<DefinedSymbol> {
+pub(crate) fn extract_all_defined_symbols(source: &str, _file_name: &str) -> Vec<DefinedSymbol> {
     let lines: Vec<&str> = source.lines().collect();
     let mut symbols = Vec::new();
     let mut in_block_comment = false;
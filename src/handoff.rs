@@ -0,0 +1,419 @@
+//! Structured handoffs between sessions working on the same bead.
+//!
+//! A handoff records what a session got done, what is left, what blocked it,
+//! which files it touched and which commands worked. Agents write one with
+//! `blacksmith progress add --bead <id> --done … --remaining …`; when a
+//! coding session ends without one, the coordinator captures a handoff from
+//! the session's tool calls and worktree diff. The latest handoff for a bead
+//! is injected into the prompt of the next worker assigned to it.
+
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use std::path::Path;
+
+/// Who wrote a handoff.
+pub const SOURCE_AGENT: &str = "agent";
+pub const SOURCE_AUTO: &str = "auto";
+
+/// Create the handoffs table if it doesn't exist.
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS handoffs (
+            id        INTEGER PRIMARY KEY AUTOINCREMENT,
+            bead_id   TEXT NOT NULL,
+            session   INTEGER,
+            source    TEXT NOT NULL,
+            created   TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            done      TEXT NOT NULL DEFAULT '[]',
+            remaining TEXT NOT NULL DEFAULT '[]',
+            blockers  TEXT NOT NULL DEFAULT '[]',
+            files     TEXT NOT NULL DEFAULT '[]',
+            commands  TEXT NOT NULL DEFAULT '[]',
+            notes     TEXT
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_handoffs_bead ON handoffs(bead_id);",
+    )
+}
+
+/// A typed handoff record.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Handoff {
+    pub bead_id: String,
+    /// The session that produced it, for automatic captures.
+    pub session: Option<i64>,
+    /// [`SOURCE_AGENT`] or [`SOURCE_AUTO`].
+    pub source: String,
+    /// Set when read back from the database.
+    pub created: String,
+    pub done: Vec<String>,
+    pub remaining: Vec<String>,
+    pub blockers: Vec<String>,
+    pub files: Vec<String>,
+    /// Commands that worked, e.g. the invocation that runs the right tests.
+    pub commands: Vec<String>,
+    pub notes: Option<String>,
+}
+
+impl Handoff {
+    /// Whether there is nothing worth handing off.
+    pub fn is_empty(&self) -> bool {
+        self.done.is_empty()
+            && self.remaining.is_empty()
+            && self.blockers.is_empty()
+            && self.files.is_empty()
+            && self.commands.is_empty()
+            && self.notes.as_deref().is_none_or(|n| n.trim().is_empty())
+    }
+
+    /// Markdown rendering, one `### ` section per non-empty field.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let mut section = |title: &str, items: &[String], code: bool| {
+            if items.is_empty() {
+                return;
+            }
+            out.push_str(&format!("### {title}\n"));
+            for item in items {
+                if code {
+                    out.push_str(&format!("- `{item}`\n"));
+                } else {
+                    out.push_str(&format!("- {item}\n"));
+                }
+            }
+            out.push('\n');
+        };
+        section("Done", &self.done, false);
+        section("Remaining", &self.remaining, false);
+        section("Blockers", &self.blockers, false);
+        section("Files touched", &self.files, true);
+        section("Commands that worked", &self.commands, true);
+        if let Some(notes) = self.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+            out.push_str(&format!("### Notes\n{}\n", notes.trim()));
+        }
+        out.trim_end().to_string()
+    }
+}
+
+fn to_json(items: &[String]) -> String {
+    serde_json::to_string(items).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn from_json(text: String) -> Vec<String> {
+    serde_json::from_str(&text).unwrap_or_default()
+}
+
+/// Store a handoff and return its row id.
+pub fn record(conn: &Connection, handoff: &Handoff) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO handoffs (bead_id, session, source, done, remaining, blockers, files, commands, notes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            handoff.bead_id,
+            handoff.session,
+            handoff.source,
+            to_json(&handoff.done),
+            to_json(&handoff.remaining),
+            to_json(&handoff.blockers),
+            to_json(&handoff.files),
+            to_json(&handoff.commands),
+            handoff.notes,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// The most recent handoff for a bead.
+pub fn latest(conn: &Connection, bead_id: &str) -> Result<Option<Handoff>> {
+    conn.query_row(
+        "SELECT bead_id, session, source, created, done, remaining, blockers, files, commands, notes
+         FROM handoffs WHERE bead_id = ?1 ORDER BY id DESC LIMIT 1",
+        params![bead_id],
+        |row| {
+            Ok(Handoff {
+                bead_id: row.get(0)?,
+                session: row.get(1)?,
+                source: row.get(2)?,
+                created: row.get(3)?,
+                done: from_json(row.get(4)?),
+                remaining: from_json(row.get(5)?),
+                blockers: from_json(row.get(6)?),
+                files: from_json(row.get(7)?),
+                commands: from_json(row.get(8)?),
+                notes: row.get(9)?,
+            })
+        },
+    )
+    .optional()
+}
+
+/// Whether the agent wrote a handoff for the bead at or after `since`.
+pub fn agent_wrote_since(conn: &Connection, bead_id: &str, since: &str) -> Result<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM handoffs WHERE bead_id = ?1 AND source = ?2 AND created >= ?3",
+        params![bead_id, SOURCE_AGENT, since],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+/// Build a handoff for a finished session from what it left behind: shell
+/// commands whose last run succeeded are "commands that worked", ones whose
+/// last run failed are blockers, and `files` is the worktree diff.
+pub fn capture(
+    conn: &Connection,
+    bead_id: &str,
+    session: i64,
+    exit_code: Option<i32>,
+    files: Vec<String>,
+) -> Result<Handoff> {
+    let mut stmt = conn.prepare(
+        "SELECT command, success FROM tool_calls
+         WHERE session = ?1 AND command IS NOT NULL AND command != '' AND success IS NOT NULL
+         ORDER BY id",
+    )?;
+    let rows = stmt.query_map(params![session], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?))
+    })?;
+    // Last outcome per command, in first-seen order
+    let mut last: Vec<(String, bool)> = Vec::new();
+    for row in rows {
+        let (command, ok) = row?;
+        match last.iter_mut().find(|(c, _)| *c == command) {
+            Some(entry) => entry.1 = ok,
+            None => last.push((command, ok)),
+        }
+    }
+
+    let mut handoff = Handoff {
+        bead_id: bead_id.to_string(),
+        session: Some(session),
+        source: SOURCE_AUTO.to_string(),
+        files,
+        ..Default::default()
+    };
+    for (command, ok) in last {
+        if ok {
+            handoff.commands.push(command);
+        } else {
+            handoff.blockers.push(format!(
+                "`{command}` was still failing when the session ended"
+            ));
+        }
+    }
+    match exit_code {
+        Some(0) => handoff.done.push(format!(
+            "Session {session} exited cleanly and was queued for integration"
+        )),
+        code => handoff.remaining.push(format!(
+            "Session {session} ended before finishing (exit code {}); continue the bead",
+            code.map_or("unknown".to_string(), |c| c.to_string())
+        )),
+    }
+    Ok(handoff)
+}
+
+/// Files changed in `worktree` relative to `base`, committed or not,
+/// plus untracked files.
+pub fn changed_files(worktree: &Path, base: &str) -> Vec<String> {
+    let mut files = Vec::new();
+    for args in [
+        &["diff", "--name-only", base][..],
+        &["ls-files", "--others", "--exclude-standard"][..],
+    ] {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(worktree)
+            .output();
+        if let Some(o) = output.ok().filter(|o| o.status.success()) {
+            files.extend(
+                String::from_utf8_lossy(&o.stdout)
+                    .lines()
+                    .filter(|l| !l.is_empty())
+                    .map(String::from),
+            );
+        }
+    }
+    files
+}
+
+/// The prompt section carrying a bead's latest handoff, if it has one.
+pub fn prompt_section(conn: &Connection, bead_id: &str) -> Result<Option<String>> {
+    let Some(handoff) = latest(conn, bead_id)? else {
+        return Ok(None);
+    };
+    if handoff.is_empty() {
+        return Ok(None);
+    }
+    let origin = match handoff.session {
+        Some(session) => format!("session {session}, {}", handoff.created),
+        None => handoff.created.clone(),
+    };
+    Ok(Some(format!(
+        "## Handoff from the previous session\n\n\
+         This bead was worked on before ({origin}). Continue from this handoff \
+         rather than starting over.\n\n{}",
+        handoff.to_markdown()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::ToolCall;
+    use crate::db;
+
+    fn setup() -> (tempfile::TempDir, Connection) {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = db::open_or_create(&dir.path().join("blacksmith.db")).unwrap();
+        (dir, conn)
+    }
+
+    fn shell(command: &str, ok: bool) -> ToolCall {
+        ToolCall {
+            tool: "Bash".to_string(),
+            command: Some(command.to_string()),
+            success: Some(ok),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn record_and_latest_round_trip() {
+        let (_dir, conn) = setup();
+        assert!(latest(&conn, "b1").unwrap().is_none());
+
+        let first = Handoff {
+            bead_id: "b1".to_string(),
+            source: SOURCE_AGENT.to_string(),
+            done: vec!["parser".to_string()],
+            ..Default::default()
+        };
+        record(&conn, &first).unwrap();
+        let second = Handoff {
+            remaining: vec!["wire up CLI".to_string()],
+            commands: vec!["cargo test parser".to_string()],
+            notes: Some("see PROGRESS.txt".to_string()),
+            ..first.clone()
+        };
+        record(&conn, &second).unwrap();
+
+        let got = latest(&conn, "b1").unwrap().unwrap();
+        assert_eq!(got.done, vec!["parser"]);
+        assert_eq!(got.remaining, vec!["wire up CLI"]);
+        assert_eq!(got.commands, vec!["cargo test parser"]);
+        assert_eq!(got.notes.as_deref(), Some("see PROGRESS.txt"));
+        assert!(!got.created.is_empty());
+        assert!(latest(&conn, "b2").unwrap().is_none());
+    }
+
+    #[test]
+    fn markdown_skips_empty_sections() {
+        let handoff = Handoff {
+            done: vec!["parser".to_string()],
+            files: vec!["src/parser.rs".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            handoff.to_markdown(),
+            "### Done\n- parser\n\n### Files touched\n- `src/parser.rs`"
+        );
+        assert!(Handoff::default().is_empty());
+    }
+
+    #[test]
+    fn capture_uses_last_outcome_per_command() {
+        let (_dir, conn) = setup();
+        crate::tool_call::record(
+            &conn,
+            7,
+            &[
+                shell("cargo test", false),
+                shell("cargo build", true),
+                shell("cargo test", true),
+                shell("cargo clippy", false),
+            ],
+        )
+        .unwrap();
+
+        let handoff = capture(&conn, "b1", 7, Some(1), vec!["src/a.rs".to_string()]).unwrap();
+        assert_eq!(handoff.source, SOURCE_AUTO);
+        assert_eq!(handoff.session, Some(7));
+        assert_eq!(handoff.commands, vec!["cargo test", "cargo build"]);
+        assert_eq!(
+            handoff.blockers,
+            vec!["`cargo clippy` was still failing when the session ended"]
+        );
+        assert_eq!(handoff.files, vec!["src/a.rs"]);
+        assert!(handoff.done.is_empty());
+        assert!(handoff.remaining[0].contains("exit code 1"));
+
+        let done = capture(&conn, "b1", 7, Some(0), Vec::new()).unwrap();
+        assert_eq!(done.done.len(), 1);
+        assert!(done.remaining.is_empty());
+    }
+
+    #[test]
+    fn agent_wrote_since_ignores_auto_and_older() {
+        let (_dir, conn) = setup();
+        let auto = Handoff {
+            bead_id: "b1".to_string(),
+            source: SOURCE_AUTO.to_string(),
+            ..Default::default()
+        };
+        record(&conn, &auto).unwrap();
+        assert!(!agent_wrote_since(&conn, "b1", "2000-01-01T00:00:00Z").unwrap());
+
+        let agent = Handoff {
+            source: SOURCE_AGENT.to_string(),
+            ..auto
+        };
+        record(&conn, &agent).unwrap();
+        assert!(agent_wrote_since(&conn, "b1", "2000-01-01T00:00:00Z").unwrap());
+        assert!(!agent_wrote_since(&conn, "b1", "2999-01-01T00:00:00Z").unwrap());
+    }
+
+    #[test]
+    fn changed_files_includes_untracked() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(dir.path())
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {args:?}");
+        };
+        git(&["init", "-q"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        std::fs::write(dir.path().join("a.rs"), "fn a() {}").unwrap();
+        git(&["add", "a.rs"]);
+        git(&["commit", "-qm", "init"]);
+
+        std::fs::write(dir.path().join("a.rs"), "fn a() { todo!() }").unwrap();
+        std::fs::write(dir.path().join("new.rs"), "fn b() {}").unwrap();
+        assert_eq!(changed_files(dir.path(), "HEAD"), vec!["a.rs", "new.rs"]);
+    }
+
+    #[test]
+    fn prompt_section_renders_latest() {
+        let (_dir, conn) = setup();
+        assert!(prompt_section(&conn, "b1").unwrap().is_none());
+        record(
+            &conn,
+            &Handoff {
+                bead_id: "b1".to_string(),
+                session: Some(3),
+                source: SOURCE_AUTO.to_string(),
+                blockers: vec!["flaky test".to_string()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let section = prompt_section(&conn, "b1").unwrap().unwrap();
+        assert!(section.starts_with("## Handoff from the previous session"));
+        assert!(section.contains("(session 3, "));
+        assert!(section.ends_with("### Blockers\n- flaky test"));
+    }
+}
@@ -0,0 +1,551 @@
+//! Before/after impact of promoted improvements.
+//!
+//! An improvement may declare the observation metric it targets (e.g.
+//! `turns.narration_only`) and whether it should push that metric `below` or
+//! `above` its current level. When it is promoted, the mean and spread of
+//! the metric over the preceding sessions are recorded as a baseline. Once
+//! as many sessions again have been observed, the follow-up window is
+//! compared against it: the difference in means, its 95% confidence interval
+//! (Welch) and Cohen's d decide whether the rule helped. Rules with no
+//! measurable effect, or a harmful one, are flagged for removal in
+//! `improve list` and the brief.
+
+use rusqlite::{params, Connection, OptionalExtension, Result};
+
+use crate::db;
+
+/// Create the improvement_impact table if it doesn't exist.
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS improvement_impact (
+            ref              TEXT PRIMARY KEY,
+            metric           TEXT NOT NULL,
+            direction        TEXT NOT NULL,
+            promoted_session INTEGER NOT NULL,
+            baseline_n       INTEGER NOT NULL,
+            baseline_mean    REAL NOT NULL,
+            baseline_sd      REAL NOT NULL,
+            followup_n       INTEGER,
+            followup_mean    REAL,
+            followup_sd      REAL,
+            effect           REAL,
+            ci_low           REAL,
+            ci_high          REAL,
+            cohens_d         REAL,
+            verdict          TEXT NOT NULL DEFAULT 'pending',
+            recorded_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            evaluated_at     TEXT
+        );",
+    )
+}
+
+/// Outcome of comparing the follow-up window against the baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Follow-up window not yet complete.
+    Pending,
+    /// The metric moved in the intended direction; the CI excludes zero.
+    Helped,
+    /// The CI includes zero.
+    NoEffect,
+    /// The metric moved the wrong way; the CI excludes zero.
+    Hurt,
+    /// Too few baseline sessions to measure anything.
+    Insufficient,
+}
+
+impl Verdict {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Verdict::Pending => "pending",
+            Verdict::Helped => "helped",
+            Verdict::NoEffect => "no_effect",
+            Verdict::Hurt => "hurt",
+            Verdict::Insufficient => "insufficient",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        match s {
+            "helped" => Verdict::Helped,
+            "no_effect" => Verdict::NoEffect,
+            "hurt" => Verdict::Hurt,
+            "insufficient" => Verdict::Insufficient,
+            _ => Verdict::Pending,
+        }
+    }
+
+    /// Whether the rule should be considered for removal.
+    pub fn flagged(self) -> bool {
+        matches!(self, Verdict::NoEffect | Verdict::Hurt)
+    }
+}
+
+/// Summary statistics of a metric over a window of sessions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Window {
+    pub n: usize,
+    pub mean: f64,
+    /// Sample standard deviation (0 when `n < 2`).
+    pub sd: f64,
+}
+
+impl Window {
+    pub fn of(values: &[f64]) -> Self {
+        let n = values.len();
+        let mean = if n == 0 {
+            0.0
+        } else {
+            values.iter().sum::<f64>() / n as f64
+        };
+        let sd = if n < 2 {
+            0.0
+        } else {
+            (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt()
+        };
+        Window { n, mean, sd }
+    }
+}
+
+/// Follow-up statistics and the comparison against the baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Comparison {
+    /// The compared sample (the follow-up window, or an experiment variant).
+    pub followup: Window,
+    /// `followup.mean - baseline.mean`.
+    pub effect: f64,
+    pub ci_low: f64,
+    pub ci_high: f64,
+    pub cohens_d: f64,
+}
+
+/// Recorded impact of one promoted improvement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Impact {
+    pub ref_id: String,
+    pub metric: String,
+    /// "below" or "above".
+    pub direction: String,
+    /// First session that ran with the rule in place.
+    pub promoted_session: i64,
+    pub baseline: Window,
+    pub comparison: Option<Comparison>,
+    pub verdict: Verdict,
+}
+
+impl Impact {
+    /// One-line description, e.g.
+    /// `turns.narration_only 4.20 -> 1.10 (-3.10, 95% CI [-4.02, -2.18], d=-1.9): helped`.
+    pub fn describe(&self) -> String {
+        match (&self.comparison, self.verdict) {
+            (Some(c), verdict) => format!(
+                "{} {:.2} -> {:.2} ({:+.2}, 95% CI [{:.2}, {:.2}], d={:.1}): {}",
+                self.metric,
+                self.baseline.mean,
+                c.followup.mean,
+                c.effect,
+                c.ci_low,
+                c.ci_high,
+                c.cohens_d,
+                verdict.as_str().replace('_', " ")
+            ),
+            (None, Verdict::Insufficient) => format!(
+                "{}: only {} baseline session(s), not measurable",
+                self.metric, self.baseline.n
+            ),
+            (None, _) => format!(
+                "{}: baseline {:.2} over {} sessions, follow-up pending",
+                self.metric, self.baseline.mean, self.baseline.n
+            ),
+        }
+    }
+}
+
+/// Value of `metric` in an observation's data, with booleans as 0/1.
+fn metric_value(data: &str, metric: &str) -> Option<f64> {
+    let data: serde_json::Value = serde_json::from_str(data).ok()?;
+    let value = data.get(metric)?;
+    value.as_f64().or_else(|| value.as_bool().map(f64::from))
+}
+
+/// `(session, value)` of observations carrying `metric`, oldest first.
+fn metric_series(conn: &Connection, metric: &str) -> Result<Vec<(i64, f64)>> {
+    Ok(db::all_observations(conn)?
+        .into_iter()
+        .filter_map(|o| metric_value(&o.data, metric).map(|v| (o.session, v)))
+        .collect())
+}
+
+/// Record the baseline for `ref_id` at promotion time: the last `window`
+/// observed sessions carrying its target metric. Returns None (and records
+/// nothing) when the improvement declares no target metric or `window` is 0.
+pub fn record_baseline(conn: &Connection, ref_id: &str, window: u32) -> Result<Option<Impact>> {
+    if window == 0 {
+        return Ok(None);
+    }
+    let Some((metric, direction)) = db::get_improvement_target(conn, ref_id)? else {
+        return Ok(None);
+    };
+
+    let series = metric_series(conn, &metric)?;
+    let last_session: Option<i64> =
+        conn.query_row("SELECT MAX(session) FROM observations", [], |row| {
+            row.get(0)
+        })?;
+    let promoted_session = last_session.map_or(0, |s| s + 1);
+    let values: Vec<f64> = series
+        .iter()
+        .rev()
+        .take(window as usize)
+        .map(|(_, v)| *v)
+        .collect();
+    let baseline = Window::of(&values);
+
+    conn.execute(
+        "INSERT OR REPLACE INTO improvement_impact
+            (ref, metric, direction, promoted_session, baseline_n, baseline_mean, baseline_sd)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            ref_id,
+            metric,
+            direction,
+            promoted_session,
+            baseline.n as i64,
+            baseline.mean,
+            baseline.sd,
+        ],
+    )?;
+    Ok(Some(Impact {
+        ref_id: ref_id.to_string(),
+        metric,
+        direction,
+        promoted_session,
+        baseline,
+        comparison: None,
+        verdict: Verdict::Pending,
+    }))
+}
+
+/// Evaluate every pending impact whose follow-up window of `window`
+/// sessions is complete. Returns the impacts that reached a verdict.
+pub fn evaluate_pending(conn: &Connection, window: u32) -> Result<Vec<Impact>> {
+    if window == 0 {
+        return Ok(Vec::new());
+    }
+    let mut decided = Vec::new();
+    for mut impact in list(conn)?
+        .into_iter()
+        .filter(|i| i.verdict == Verdict::Pending)
+    {
+        let followup: Vec<f64> = metric_series(conn, &impact.metric)?
+            .into_iter()
+            .filter(|(s, _)| *s >= impact.promoted_session)
+            .take(window as usize)
+            .map(|(_, v)| v)
+            .collect();
+        if followup.len() < window as usize {
+            continue;
+        }
+        let (comparison, verdict) = judge(&impact.baseline, &followup, &impact.direction);
+        impact.comparison = comparison;
+        impact.verdict = verdict;
+
+        conn.execute(
+            "UPDATE improvement_impact SET
+                followup_n = ?2, followup_mean = ?3, followup_sd = ?4, effect = ?5,
+                ci_low = ?6, ci_high = ?7, cohens_d = ?8, verdict = ?9,
+                evaluated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE ref = ?1",
+            params![
+                impact.ref_id,
+                comparison.map(|c| c.followup.n as i64),
+                comparison.map(|c| c.followup.mean),
+                comparison.map(|c| c.followup.sd),
+                comparison.map(|c| c.effect),
+                comparison.map(|c| c.ci_low),
+                comparison.map(|c| c.ci_high),
+                comparison.map(|c| c.cohens_d),
+                verdict.as_str(),
+            ],
+        )?;
+        decided.push(impact);
+    }
+    Ok(decided)
+}
+
+/// Compare a follow-up sample against the baseline.
+fn judge(baseline: &Window, followup: &[f64], direction: &str) -> (Option<Comparison>, Verdict) {
+    let Some(comparison) = compare(baseline, &Window::of(followup)) else {
+        return (None, Verdict::Insufficient);
+    };
+    // "below" wants the metric to drop, i.e. a negative effect.
+    let (improved, worsened) = if direction == "above" {
+        (comparison.ci_low > 0.0, comparison.ci_high < 0.0)
+    } else {
+        (comparison.ci_high < 0.0, comparison.ci_low > 0.0)
+    };
+    let verdict = if improved {
+        Verdict::Helped
+    } else if worsened {
+        Verdict::Hurt
+    } else {
+        Verdict::NoEffect
+    };
+    (Some(comparison), verdict)
+}
+
+/// Difference of `other` from `baseline` with its 95% Welch confidence
+/// interval. None when either sample has fewer than two values.
+pub fn compare(baseline: &Window, other: &Window) -> Option<Comparison> {
+    if baseline.n < 2 || other.n < 2 {
+        return None;
+    }
+
+    let effect = other.mean - baseline.mean;
+    let a = baseline.sd.powi(2) / baseline.n as f64;
+    let b = other.sd.powi(2) / other.n as f64;
+    let se = (a + b).sqrt();
+    // Welch–Satterthwaite degrees of freedom.
+    let df = if se == 0.0 {
+        f64::INFINITY
+    } else {
+        (a + b).powi(2) / (a.powi(2) / (baseline.n - 1) as f64 + b.powi(2) / (other.n - 1) as f64)
+    };
+    let margin = t_critical(df) * se;
+    let pooled = ((baseline.sd.powi(2) + other.sd.powi(2)) / 2.0).sqrt();
+    let cohens_d = if pooled == 0.0 { 0.0 } else { effect / pooled };
+
+    Some(Comparison {
+        followup: *other,
+        effect,
+        ci_low: effect - margin,
+        ci_high: effect + margin,
+        cohens_d,
+    })
+}
+
+/// Two-sided 95% critical value of Student's t for `df` degrees of freedom.
+fn t_critical(df: f64) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+    if !df.is_finite() || df > 30.0 {
+        return 1.96;
+    }
+    // Round down: fewer degrees of freedom give a wider, conservative interval.
+    TABLE[(df.floor() as usize).clamp(1, 30) - 1]
+}
+
+fn map_impact(row: &rusqlite::Row) -> Result<Impact> {
+    let followup_n: Option<i64> = row.get(7)?;
+    let comparison = match followup_n {
+        Some(n) => Some(Comparison {
+            followup: Window {
+                n: n as usize,
+                mean: row.get(8)?,
+                sd: row.get(9)?,
+            },
+            effect: row.get(10)?,
+            ci_low: row.get(11)?,
+            ci_high: row.get(12)?,
+            cohens_d: row.get(13)?,
+        }),
+        None => None,
+    };
+    Ok(Impact {
+        ref_id: row.get(0)?,
+        metric: row.get(1)?,
+        direction: row.get(2)?,
+        promoted_session: row.get(3)?,
+        baseline: Window {
+            n: row.get::<_, i64>(4)? as usize,
+            mean: row.get(5)?,
+            sd: row.get(6)?,
+        },
+        comparison,
+        verdict: Verdict::parse(&row.get::<_, String>(14)?),
+    })
+}
+
+const SELECT: &str = "SELECT ref, metric, direction, promoted_session, baseline_n, baseline_mean,
+        baseline_sd, followup_n, followup_mean, followup_sd, effect, ci_low, ci_high, cohens_d,
+        verdict FROM improvement_impact";
+
+/// All recorded impacts, in promotion order.
+pub fn list(conn: &Connection) -> Result<Vec<Impact>> {
+    let mut stmt = conn.prepare(&format!("{SELECT} ORDER BY promoted_session, ref"))?;
+    let rows = stmt.query_map([], map_impact)?;
+    rows.collect()
+}
+
+/// The recorded impact of one improvement.
+pub fn get(conn: &Connection, ref_id: &str) -> Result<Option<Impact>> {
+    conn.query_row(&format!("{SELECT} WHERE ref = ?1"), [ref_id], map_impact)
+        .optional()
+}
+
+/// Still-promoted improvements whose impact is flagged for removal.
+pub fn flagged(conn: &Connection) -> Result<Vec<(db::Improvement, Impact)>> {
+    let mut out = Vec::new();
+    for impact in list(conn)?.into_iter().filter(|i| i.verdict.flagged()) {
+        if let Some(imp) = db::get_improvement(conn, &impact.ref_id)? {
+            if imp.status == "promoted" {
+                out.push((imp, impact));
+            }
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (tempfile::TempDir, Connection) {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = db::open_or_create(&dir.path().join("blacksmith.db")).unwrap();
+        (dir, conn)
+    }
+
+    fn observe(conn: &Connection, session: i64, narration: f64) {
+        let data = serde_json::json!({ "turns.narration_only": narration }).to_string();
+        db::upsert_observation(conn, session, "2026-01-01T00:00:00Z", None, None, &data).unwrap();
+    }
+
+    fn promoted_with_target(conn: &Connection, direction: &str) -> String {
+        let r =
+            db::insert_improvement(conn, "workflow", "Skip narration", None, None, None).unwrap();
+        db::set_improvement_target(conn, &r, "turns.narration_only", direction).unwrap();
+        db::update_improvement(conn, &r, Some("promoted"), None, None, None).unwrap();
+        r
+    }
+
+    #[test]
+    fn baseline_uses_last_window_sessions() {
+        let (_dir, conn) = setup();
+        for (s, v) in [(0, 100.0), (1, 4.0), (2, 6.0), (3, 5.0)] {
+            observe(&conn, s, v);
+        }
+        let r = promoted_with_target(&conn, "below");
+
+        let impact = record_baseline(&conn, &r, 3).unwrap().unwrap();
+        assert_eq!(impact.promoted_session, 4);
+        assert_eq!(impact.baseline.n, 3);
+        assert!((impact.baseline.mean - 5.0).abs() < 1e-9);
+        assert!((impact.baseline.sd - 1.0).abs() < 1e-9);
+        assert_eq!(get(&conn, &r).unwrap(), Some(impact));
+    }
+
+    #[test]
+    fn no_baseline_without_target_metric() {
+        let (_dir, conn) = setup();
+        let r = db::insert_improvement(&conn, "workflow", "Untargeted", None, None, None).unwrap();
+        assert!(record_baseline(&conn, &r, 5).unwrap().is_none());
+        assert!(list(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn improvement_that_lowers_metric_helped() {
+        let (_dir, conn) = setup();
+        for (s, v) in [(0, 4.0), (1, 6.0), (2, 5.0), (3, 5.0)] {
+            observe(&conn, s, v);
+        }
+        let r = promoted_with_target(&conn, "below");
+        record_baseline(&conn, &r, 4).unwrap();
+
+        // Follow-up window not complete yet.
+        observe(&conn, 4, 1.0);
+        assert!(evaluate_pending(&conn, 4).unwrap().is_empty());
+
+        for (s, v) in [(5, 0.0), (6, 1.0), (7, 2.0)] {
+            observe(&conn, s, v);
+        }
+        let decided = evaluate_pending(&conn, 4).unwrap();
+        assert_eq!(decided.len(), 1);
+        assert_eq!(decided[0].verdict, Verdict::Helped);
+        let c = decided[0].comparison.unwrap();
+        assert!((c.effect + 4.0).abs() < 1e-9);
+        assert!(c.ci_high < 0.0);
+        assert!(flagged(&conn).unwrap().is_empty());
+
+        // Decided impacts are not re-evaluated.
+        assert!(evaluate_pending(&conn, 4).unwrap().is_empty());
+        assert_eq!(get(&conn, &r).unwrap().unwrap().verdict, Verdict::Helped);
+    }
+
+    #[test]
+    fn improvement_without_effect_is_flagged() {
+        let (_dir, conn) = setup();
+        for (s, v) in [(0, 3.0), (1, 6.0), (2, 4.0), (3, 5.0)] {
+            observe(&conn, s, v);
+        }
+        let r = promoted_with_target(&conn, "below");
+        record_baseline(&conn, &r, 4).unwrap();
+        for (s, v) in [(4, 5.0), (5, 3.0), (6, 6.0), (7, 4.0)] {
+            observe(&conn, s, v);
+        }
+
+        let decided = evaluate_pending(&conn, 4).unwrap();
+        assert_eq!(decided[0].verdict, Verdict::NoEffect);
+        let flagged = flagged(&conn).unwrap();
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].0.ref_id, r);
+        assert!(flagged[0].1.describe().contains("no effect"));
+    }
+
+    #[test]
+    fn raising_a_lower_is_better_metric_hurt() {
+        let (_dir, conn) = setup();
+        for (s, v) in [(0, 1.0), (1, 2.0), (2, 1.0)] {
+            observe(&conn, s, v);
+        }
+        let r = promoted_with_target(&conn, "below");
+        record_baseline(&conn, &r, 3).unwrap();
+        for (s, v) in [(3, 8.0), (4, 9.0), (5, 8.0)] {
+            observe(&conn, s, v);
+        }
+        assert_eq!(
+            evaluate_pending(&conn, 3).unwrap()[0].verdict,
+            Verdict::Hurt
+        );
+
+        // The same change is a win for a higher-is-better target.
+        let (_dir, conn) = setup();
+        for (s, v) in [(0, 1.0), (1, 2.0), (2, 1.0)] {
+            observe(&conn, s, v);
+        }
+        let r = promoted_with_target(&conn, "above");
+        record_baseline(&conn, &r, 3).unwrap();
+        for (s, v) in [(3, 8.0), (4, 9.0), (5, 8.0)] {
+            observe(&conn, s, v);
+        }
+        assert_eq!(
+            evaluate_pending(&conn, 3).unwrap()[0].verdict,
+            Verdict::Helped
+        );
+    }
+
+    #[test]
+    fn single_baseline_session_is_insufficient() {
+        let (_dir, conn) = setup();
+        observe(&conn, 0, 5.0);
+        let r = promoted_with_target(&conn, "below");
+        record_baseline(&conn, &r, 2).unwrap();
+        observe(&conn, 1, 1.0);
+        observe(&conn, 2, 1.0);
+
+        let decided = evaluate_pending(&conn, 2).unwrap();
+        assert_eq!(decided[0].verdict, Verdict::Insufficient);
+        assert!(!decided[0].verdict.flagged());
+    }
+
+    #[test]
+    fn t_critical_widens_for_small_samples() {
+        assert!((t_critical(1.0) - 12.706).abs() < 1e-9);
+        assert!((t_critical(5.7) - 2.571).abs() < 1e-9);
+        assert!((t_critical(120.0) - 1.96).abs() < 1e-9);
+        assert!((t_critical(f64::INFINITY) - 1.96).abs() < 1e-9);
+    }
+}
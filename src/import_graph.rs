@@ -1,32 +1,44 @@
-//! Codebase import graph parser for Rust projects.
+//! Codebase import graph parser.
 //!
-//! Walks all `.rs` files under a repo root, extracts `use crate::`, `mod`,
-//! and `pub use` statements, and builds a directed graph of file-to-file imports
-//! as an adjacency list.
+//! Walks the source files of every language with a `LanguageFrontend`
+//! (Rust, TypeScript/JavaScript, Python, Go), extracts their imports, and
+//! builds one directed graph of file-to-file imports as an adjacency list.
+//! For Rust this means `use crate::`, `mod`, and `pub use` statements.
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
-/// Build a directed import graph for a Rust codebase.
+use crate::lang::{self, SourceIndex};
+
+/// Build a directed import graph for a codebase.
 ///
-/// Given a `repo_root`, walks all `.rs` files, parses import statements,
-/// and returns an adjacency list where each key is a source file and the
-/// value is the list of files it imports from.
+/// Given a `repo_root`, walks all source files (see
+/// [`lang::collect_source_files`]), parses import statements with the
+/// file's language frontend, and returns an adjacency list where each key is
+/// a source file and the value is the list of files it imports from.
+/// Mixed-language repos produce a single graph.
 ///
-/// Handles:
+/// For Rust, handles:
 /// - `use crate::module::item` → resolves to `src/module.rs` or `src/module/mod.rs`
 /// - `mod name;` declarations (non-inline) → resolves to sibling `.rs` or subdir `mod.rs`
 /// - `pub use crate::module::item` re-exports
 pub fn build_import_graph(repo_root: &Path) -> HashMap<PathBuf, Vec<PathBuf>> {
-    let src_root = repo_root.join("src");
-    let rs_files = collect_rs_files(&src_root);
+    let files = lang::collect_source_files(repo_root);
+    let index = SourceIndex::new(&files);
     let mut graph: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
 
-    for file in &rs_files {
-        let deps = extract_imports(file, &src_root);
+    for file in &files {
+        let Some(frontend) = lang::for_path(file) else {
+            continue;
+        };
+        let source = std::fs::read_to_string(file).unwrap_or_default();
         // Deduplicate and only keep deps that exist in our file set
-        let mut unique_deps: Vec<PathBuf> =
-            deps.into_iter().filter(|d| rs_files.contains(d)).collect();
+        let mut unique_deps: Vec<PathBuf> = frontend
+            .imports(file, &source, &index)
+            .into_iter()
+            .map(|i| i.target)
+            .filter(|d| index.contains(d))
+            .collect();
         unique_deps.sort();
         unique_deps.dedup();
         graph.insert(file.clone(), unique_deps);
@@ -35,38 +47,10 @@ pub fn build_import_graph(repo_root: &Path) -> HashMap<PathBuf, Vec<PathBuf>> {
     graph
 }
 
-/// Recursively collect all `.rs` files under a directory.
-fn collect_rs_files(dir: &Path) -> Vec<PathBuf> {
-    let mut files = Vec::new();
-    collect_rs_files_recursive(dir, &mut files);
-    files.sort();
-    files
-}
-
-fn collect_rs_files_recursive(dir: &Path, files: &mut Vec<PathBuf>) {
-    let entries = match std::fs::read_dir(dir) {
-        Ok(e) => e,
-        Err(_) => return,
-    };
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_dir() {
-            collect_rs_files_recursive(&path, files);
-        } else if path.extension().and_then(|e| e.to_str()) == Some("rs") {
-            files.push(path);
-        }
-    }
-}
-
-/// Extract import targets from a single `.rs` file.
+/// Extract import targets from the source of a single `.rs` file.
 ///
 /// Returns resolved file paths for each import statement found.
-fn extract_imports(file: &Path, src_root: &Path) -> Vec<PathBuf> {
-    let content = match std::fs::read_to_string(file) {
-        Ok(c) => c,
-        Err(_) => return Vec::new(),
-    };
-
+pub(crate) fn rust_imports(file: &Path, content: &str, src_root: &Path) -> Vec<PathBuf> {
     let mut deps = Vec::new();
 
     for line in content.lines() {
@@ -517,4 +501,39 @@ mod tests {
         let main_deps = &graph[&src.join("main.rs")];
         assert!(main_deps.is_empty());
     }
+
+    #[test]
+    fn mixed_language_repo_forms_one_graph() {
+        let tmp = setup_project(&[("main.rs", "mod config;\nfn main() {}"), ("config.rs", "")]);
+        let root = tmp.path();
+        for (path, content) in [
+            (
+                "web/app.ts",
+                "import { login } from './auth';\nimport React from 'react';",
+            ),
+            ("web/auth/index.ts", "export function login() {}"),
+            ("tools/pkg/__init__.py", ""),
+            ("tools/pkg/cli.py", "from pkg.util import slugify\n"),
+            ("tools/pkg/util.py", "def slugify(s):\n    return s\n"),
+        ] {
+            let full = root.join(path);
+            fs::create_dir_all(full.parent().unwrap()).unwrap();
+            fs::write(&full, content).unwrap();
+        }
+
+        let graph = build_import_graph(root);
+        assert_eq!(graph.len(), 7);
+        assert_eq!(
+            graph[&root.join("src/main.rs")],
+            vec![root.join("src/config.rs")]
+        );
+        assert_eq!(
+            graph[&root.join("web/app.ts")],
+            vec![root.join("web/auth/index.ts")]
+        );
+        assert_eq!(
+            graph[&root.join("tools/pkg/cli.py")],
+            vec![root.join("tools/pkg/util.py")]
+        );
+    }
 }
@@ -1,7 +1,37 @@
+use crate::config::ImprovementsConfig;
 use crate::db;
+use crate::dedup;
+use crate::impact;
+use crate::rules;
 use std::path::Path;
 
+fn validate_target(target: Option<(&str, &str)>) -> Result<(), String> {
+    match target {
+        Some((_, direction)) if direction != "below" && direction != "above" => Err(format!(
+            "Invalid direction '{direction}': expected 'below' or 'above'"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Record `(metric, direction)` as the improvement's target.
+/// Returns false if no such ref exists.
+fn set_target(
+    conn: &rusqlite::Connection,
+    ref_id: &str,
+    (metric, direction): (&str, &str),
+) -> Result<bool, String> {
+    db::set_improvement_target(conn, ref_id, metric, direction)
+        .map_err(|e| format!("Failed to set target metric: {e}"))
+}
+
 /// Handle the `improve add` subcommand.
+///
+/// `target` is the `(metric, direction)` the improvement aims to move, e.g.
+/// `("turns.narration_only", "below")`. Near-duplicates of existing
+/// (non-dismissed) improvements are refused unless `force` is set; merely
+/// similar ones are added with a warning.
+#[allow(clippy::too_many_arguments)]
 pub fn handle_add(
     db_path: &Path,
     title: &str,
@@ -9,11 +39,40 @@ pub fn handle_add(
     body: Option<&str>,
     context: Option<&str>,
     tags: Option<&str>,
+    target: Option<(&str, &str)>,
+    force: bool,
 ) -> Result<(), String> {
+    validate_target(target)?;
     let conn = db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
+
+    let existing: Vec<db::Improvement> = db::list_improvements(&conn, None, None)
+        .map_err(|e| format!("Failed to list improvements: {e}"))?
+        .into_iter()
+        .filter(|imp| imp.status != "dismissed")
+        .collect();
+    let similar = dedup::Index::build(&existing).similar(title, body, dedup::WARN_SIMILARITY);
+    if let Some(top) = similar.first() {
+        if top.score >= dedup::REJECT_SIMILARITY && !force {
+            return Err(format!(
+                "'{title}' looks like a duplicate of {} \"{}\" (similarity {:.2}); \
+                 update or merge into {} instead, or pass --force to add it anyway",
+                top.ref_id, top.title, top.score, top.ref_id
+            ));
+        }
+    }
+
     let ref_id = db::insert_improvement(&conn, category, title, body, context, tags)
         .map_err(|e| format!("Failed to insert improvement: {e}"))?;
+    if let Some(target) = target {
+        set_target(&conn, &ref_id, target)?;
+    }
     println!("Created improvement {ref_id}: {title}");
+    for m in &similar {
+        println!(
+            "Warning: similar to {} \"{}\" (similarity {:.2}); consider `blacksmith improve merge {} {ref_id}`",
+            m.ref_id, m.title, m.score, m.ref_id
+        );
+    }
     Ok(())
 }
 
@@ -45,6 +104,16 @@ pub fn handle_show(db_path: &Path, ref_id: &str) -> Result<(), String> {
             if let Some(meta) = meta {
                 println!("Meta:     {meta}");
             }
+            let target = db::get_improvement_target(&conn, ref_id)
+                .map_err(|e| format!("Failed to read target: {e}"))?;
+            if let Some((metric, direction)) = target {
+                println!("Target:   {metric} ({direction})");
+            }
+            let impact =
+                impact::get(&conn, ref_id).map_err(|e| format!("Failed to read impact: {e}"))?;
+            if let Some(impact) = impact {
+                println!("Impact:   {}", impact.describe());
+            }
             Ok(())
         }
         None => Err(format!("No improvement found with ref '{ref_id}'")),
@@ -58,10 +127,15 @@ pub fn handle_update(
     status: Option<&str>,
     body: Option<&str>,
     context: Option<&str>,
+    target: Option<(&str, &str)>,
 ) -> Result<(), String> {
+    validate_target(target)?;
     let conn = db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
-    let updated = db::update_improvement(&conn, ref_id, status, body, context, None)
+    let mut updated = db::update_improvement(&conn, ref_id, status, body, context, None)
         .map_err(|e| format!("Failed to update improvement: {e}"))?;
+    if let Some(target) = target {
+        updated |= set_target(&conn, ref_id, target)?;
+    }
 
     if updated {
         println!("Updated {ref_id}");
@@ -71,20 +145,176 @@ pub fn handle_update(
     }
 }
 
+/// Rewrite the promoted-rules block in the prompt file and report what changed.
+fn sync_rules(conn: &rusqlite::Connection, config: &ImprovementsConfig) -> Result<(), String> {
+    let report = rules::sync(conn, &config.prompt_file, config.rules_budget_chars)?;
+    for (gone, kept) in &report.merged {
+        println!("Merged {gone} into {kept} to stay within the rules budget");
+    }
+    if !report.omitted.is_empty() {
+        println!(
+            "Over the {}-character rules budget; left out of {}: {}",
+            config.rules_budget_chars,
+            config.prompt_file.display(),
+            report.omitted.join(", ")
+        );
+    }
+    Ok(())
+}
+
 /// Handle the `improve promote` subcommand (shorthand for status=promoted).
-pub fn handle_promote(db_path: &Path, ref_id: &str) -> Result<(), String> {
+///
+/// The rule is written into the managed block of `config.prompt_file`.
+/// Improvements with a target metric get a baseline over the last
+/// `impact_window` sessions, against which their effect is measured later.
+pub fn handle_promote(
+    db_path: &Path,
+    ref_id: &str,
+    config: &ImprovementsConfig,
+) -> Result<(), String> {
     let conn = db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
     let updated = db::update_improvement(&conn, ref_id, Some("promoted"), None, None, None)
         .map_err(|e| format!("Failed to promote improvement: {e}"))?;
 
     if updated {
         println!("Promoted {ref_id}");
-        Ok(())
+        if let Some(imp) = db::get_improvement(&conn, ref_id)
+            .map_err(|e| format!("Failed to read improvement: {e}"))?
+        {
+            rules::record_revision(&conn, ref_id, "promoted", Some(rules::rule_text(&imp)))
+                .map_err(|e| format!("Failed to record rule revision: {e}"))?;
+        }
+        let baseline = impact::record_baseline(&conn, ref_id, config.impact_window)
+            .map_err(|e| format!("Failed to record impact baseline: {e}"))?;
+        if let Some(impact) = baseline {
+            println!("Baseline: {}", impact.describe());
+        }
+        sync_rules(&conn, config)
     } else {
         Err(format!("No improvement found with ref '{ref_id}'"))
     }
 }
 
+/// Handle the `improve demote` subcommand: take a promoted rule back out of
+/// the prompt and return it to `revisit`.
+pub fn handle_demote(
+    db_path: &Path,
+    ref_id: &str,
+    reason: Option<&str>,
+    config: &ImprovementsConfig,
+) -> Result<(), String> {
+    let conn = db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
+    let imp = db::get_improvement(&conn, ref_id)
+        .map_err(|e| format!("Failed to read improvement: {e}"))?
+        .ok_or_else(|| format!("No improvement found with ref '{ref_id}'"))?;
+    if imp.status != "promoted" {
+        return Err(format!("{ref_id} is {}, not promoted", imp.status));
+    }
+
+    let meta = reason.map(|r| serde_json::json!({ "demote_reason": r }).to_string());
+    db::update_improvement(&conn, ref_id, Some("revisit"), None, None, meta.as_deref())
+        .map_err(|e| format!("Failed to demote improvement: {e}"))?;
+    rules::record_revision(&conn, ref_id, "demoted", reason)
+        .map_err(|e| format!("Failed to record rule revision: {e}"))?;
+    println!("Demoted {ref_id}");
+    sync_rules(&conn, config)
+}
+
+/// Handle the `improve history` subcommand.
+pub fn handle_history(db_path: &Path, ref_id: Option<&str>) -> Result<(), String> {
+    let conn = db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
+    let revisions =
+        rules::history(&conn, ref_id).map_err(|e| format!("Failed to read rule history: {e}"))?;
+    if revisions.is_empty() {
+        println!("No rule revisions recorded.");
+        return Ok(());
+    }
+    for rev in &revisions {
+        match &rev.detail {
+            Some(detail) => println!("{}  {:<6} {:<9} {detail}", rev.ts, rev.ref_id, rev.action),
+            None => println!("{}  {:<6} {}", rev.ts, rev.ref_id, rev.action),
+        }
+    }
+    Ok(())
+}
+
+/// Handle the `improve merge` subcommand: fold `dup_ref` into `keep_ref`.
+///
+/// The kept improvement takes the duplicate's body (if it has none), target
+/// metric (likewise) and context; the duplicate is dismissed as
+/// `merged_into` the kept one. If the duplicate was promoted, the kept one
+/// is promoted in its place and the rules block is rewritten.
+pub fn handle_merge(
+    db_path: &Path,
+    keep_ref: &str,
+    dup_ref: &str,
+    config: &ImprovementsConfig,
+) -> Result<(), String> {
+    if keep_ref == dup_ref {
+        return Err("Cannot merge an improvement into itself".to_string());
+    }
+    let conn = db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
+    let get = |r: &str| {
+        db::get_improvement(&conn, r)
+            .map_err(|e| format!("Failed to read improvement: {e}"))?
+            .ok_or_else(|| format!("No improvement found with ref '{r}'"))
+    };
+    let keep = get(keep_ref)?;
+    let dup = get(dup_ref)?;
+
+    let mut context = keep.context.clone().unwrap_or_default();
+    if !context.is_empty() {
+        context.push('\n');
+    }
+    context.push_str(&format!("Merged {}: {}", dup.ref_id, dup.title));
+    if let Some(dup_context) = &dup.context {
+        context.push_str(&format!(" ({dup_context})"));
+    }
+    let body = match (&keep.body, &dup.body) {
+        (None, Some(b)) => Some(b.as_str()),
+        _ => None,
+    };
+    let promote = dup.status == "promoted" && keep.status != "promoted";
+    db::update_improvement(
+        &conn,
+        keep_ref,
+        promote.then_some("promoted"),
+        body,
+        Some(&context),
+        None,
+    )
+    .map_err(|e| format!("Failed to update {keep_ref}: {e}"))?;
+    let db_err = |e: rusqlite::Error| format!("Failed to merge improvements: {e}");
+    if db::get_improvement_target(&conn, keep_ref)
+        .map_err(db_err)?
+        .is_none()
+    {
+        if let Some((metric, direction)) =
+            db::get_improvement_target(&conn, dup_ref).map_err(db_err)?
+        {
+            set_target(&conn, keep_ref, (&metric, &direction))?;
+        }
+    }
+
+    let meta = serde_json::json!({ "merged_into": keep_ref }).to_string();
+    db::update_improvement(&conn, dup_ref, Some("dismissed"), None, None, Some(&meta))
+        .map_err(|e| format!("Failed to dismiss {dup_ref}: {e}"))?;
+    println!("Merged {dup_ref} into {keep_ref}");
+
+    if dup.status == "promoted" {
+        rules::record_revision(&conn, dup_ref, "merged", Some(&format!("into {keep_ref}")))
+            .map_err(db_err)?;
+        if promote {
+            let kept = get(keep_ref)?;
+            rules::record_revision(&conn, keep_ref, "promoted", Some(rules::rule_text(&kept)))
+                .map_err(db_err)?;
+            println!("Promoted {keep_ref} in place of {dup_ref}");
+        }
+        sync_rules(&conn, config)?;
+    }
+    Ok(())
+}
+
 /// Handle the `improve dismiss` subcommand (shorthand for status=dismissed with reason in meta).
 pub fn handle_dismiss(db_path: &Path, ref_id: &str, reason: Option<&str>) -> Result<(), String> {
     let conn = db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
@@ -151,6 +381,7 @@ pub fn handle_list(
     let conn = db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
     let improvements = db::list_improvements(&conn, status, category)
         .map_err(|e| format!("Failed to list improvements: {e}"))?;
+    let flagged = impact::flagged(&conn).map_err(|e| format!("Failed to read impact: {e}"))?;
 
     if improvements.is_empty() {
         println!("No improvements found.");
@@ -175,6 +406,9 @@ pub fn handle_list(
             "{:<6} {:<12} {:<14} {:<10} {}",
             imp.ref_id, imp.status, imp.category, date, imp.title
         );
+        if let Some((_, impact)) = flagged.iter().find(|(f, _)| f.ref_id == imp.ref_id) {
+            println!("{:<6} ! consider removing: {}", "", impact.describe());
+        }
     }
 
     println!("\n{} improvement(s)", improvements.len());
@@ -192,10 +426,28 @@ mod tests {
         (dir, path)
     }
 
+    /// Improvements config writing promoted rules next to the test database.
+    fn rules_config(db_path: &Path) -> ImprovementsConfig {
+        ImprovementsConfig {
+            prompt_file: db_path.with_file_name("PROMPT.md"),
+            ..ImprovementsConfig::default()
+        }
+    }
+
     #[test]
     fn add_creates_improvement() {
         let (_dir, path) = test_db_path();
-        handle_add(&path, "Test title", "workflow", None, None, None).unwrap();
+        handle_add(
+            &path,
+            "Test title",
+            "workflow",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
 
         let conn = db::open_or_create(&path).unwrap();
         let items = db::list_improvements(&conn, None, None).unwrap();
@@ -216,6 +468,8 @@ mod tests {
             Some("Detailed body text"),
             Some("sessions 1-5"),
             Some("tag1,tag2"),
+            None,
+            false,
         )
         .unwrap();
 
@@ -230,9 +484,9 @@ mod tests {
     #[test]
     fn add_multiple_increments_ref() {
         let (_dir, path) = test_db_path();
-        handle_add(&path, "First", "workflow", None, None, None).unwrap();
-        handle_add(&path, "Second", "cost", None, None, None).unwrap();
-        handle_add(&path, "Third", "reliability", None, None, None).unwrap();
+        handle_add(&path, "First", "workflow", None, None, None, None, false).unwrap();
+        handle_add(&path, "Second", "cost", None, None, None, None, false).unwrap();
+        handle_add(&path, "Third", "reliability", None, None, None, None, false).unwrap();
 
         let conn = db::open_or_create(&path).unwrap();
         let items = db::list_improvements(&conn, None, None).unwrap();
@@ -319,6 +573,8 @@ mod tests {
             Some("body"),
             Some("ctx"),
             Some("t1"),
+            None,
+            false,
         )
         .unwrap();
         // Should succeed without error
@@ -340,8 +596,18 @@ mod tests {
     #[test]
     fn update_status() {
         let (_dir, path) = test_db_path();
-        handle_add(&path, "To update", "workflow", None, None, None).unwrap();
-        handle_update(&path, "R1", Some("validated"), None, None).unwrap();
+        handle_add(
+            &path,
+            "To update",
+            "workflow",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        handle_update(&path, "R1", Some("validated"), None, None, None).unwrap();
 
         let conn = db::open_or_create(&path).unwrap();
         let imp = db::get_improvement(&conn, "R1").unwrap().unwrap();
@@ -351,8 +617,26 @@ mod tests {
     #[test]
     fn update_body_and_context() {
         let (_dir, path) = test_db_path();
-        handle_add(&path, "To update", "workflow", None, None, None).unwrap();
-        handle_update(&path, "R1", None, Some("new body"), Some("new context")).unwrap();
+        handle_add(
+            &path,
+            "To update",
+            "workflow",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        handle_update(
+            &path,
+            "R1",
+            None,
+            Some("new body"),
+            Some("new context"),
+            None,
+        )
+        .unwrap();
 
         let conn = db::open_or_create(&path).unwrap();
         let imp = db::get_improvement(&conn, "R1").unwrap().unwrap();
@@ -364,7 +648,7 @@ mod tests {
     fn update_nonexistent_returns_error() {
         let (_dir, path) = test_db_path();
         let _conn = db::open_or_create(&path).unwrap();
-        let result = handle_update(&path, "R999", Some("open"), None, None);
+        let result = handle_update(&path, "R999", Some("open"), None, None, None);
         assert!(result.is_err());
     }
 
@@ -373,8 +657,8 @@ mod tests {
     #[test]
     fn promote_sets_status() {
         let (_dir, path) = test_db_path();
-        handle_add(&path, "To promote", "cost", None, None, None).unwrap();
-        handle_promote(&path, "R1").unwrap();
+        handle_add(&path, "To promote", "cost", None, None, None, None, false).unwrap();
+        handle_promote(&path, "R1", &rules_config(&path)).unwrap();
 
         let conn = db::open_or_create(&path).unwrap();
         let imp = db::get_improvement(&conn, "R1").unwrap().unwrap();
@@ -384,8 +668,8 @@ mod tests {
     #[test]
     fn promote_sets_resolved_timestamp() {
         let (_dir, path) = test_db_path();
-        handle_add(&path, "To promote", "cost", None, None, None).unwrap();
-        handle_promote(&path, "R1").unwrap();
+        handle_add(&path, "To promote", "cost", None, None, None, None, false).unwrap();
+        handle_promote(&path, "R1", &rules_config(&path)).unwrap();
 
         let conn = db::open_or_create(&path).unwrap();
         let resolved: Option<String> = conn
@@ -398,11 +682,311 @@ mod tests {
         assert!(resolved.is_some());
     }
 
+    #[test]
+    fn promote_with_target_records_baseline() {
+        let (_dir, path) = test_db_path();
+        handle_add(
+            &path,
+            "Fewer narration turns",
+            "workflow",
+            None,
+            None,
+            None,
+            Some(("turns.narration_only", "below")),
+            false,
+        )
+        .unwrap();
+        let conn = db::open_or_create(&path).unwrap();
+        for (session, narration) in [(0, 2), (1, 4)] {
+            let data = format!(r#"{{"turns.narration_only":{narration}}}"#);
+            db::upsert_observation(&conn, session, "2026-01-01T00:00:00Z", None, None, &data)
+                .unwrap();
+        }
+        handle_promote(&path, "R1", &rules_config(&path)).unwrap();
+
+        let impact = impact::get(&conn, "R1").unwrap().unwrap();
+        assert_eq!(impact.metric, "turns.narration_only");
+        assert_eq!(impact.promoted_session, 2);
+        assert_eq!(impact.baseline.n, 2);
+        assert!((impact.baseline.mean - 3.0).abs() < 1e-9);
+        assert_eq!(impact.verdict, impact::Verdict::Pending);
+    }
+
+    #[test]
+    fn add_rejects_invalid_direction() {
+        let (_dir, path) = test_db_path();
+        let err = handle_add(
+            &path,
+            "Sideways",
+            "workflow",
+            None,
+            None,
+            None,
+            Some(("cost.estimate_usd", "sideways")),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.contains("expected 'below' or 'above'"), "{err}");
+    }
+
+    #[test]
+    fn update_sets_target() {
+        let (_dir, path) = test_db_path();
+        handle_add(&path, "Cheaper", "cost", None, None, None, None, false).unwrap();
+        handle_update(
+            &path,
+            "R1",
+            None,
+            None,
+            None,
+            Some(("cost.estimate_usd", "below")),
+        )
+        .unwrap();
+
+        let conn = db::open_or_create(&path).unwrap();
+        assert_eq!(
+            db::get_improvement_target(&conn, "R1").unwrap(),
+            Some(("cost.estimate_usd".to_string(), "below".to_string()))
+        );
+    }
+
+    #[test]
+    fn promote_writes_rule_block() {
+        let (_dir, path) = test_db_path();
+        handle_add(
+            &path,
+            "Batch reads",
+            "workflow",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        handle_promote(&path, "R1", &rules_config(&path)).unwrap();
+
+        let prompt = std::fs::read_to_string(rules_config(&path).prompt_file).unwrap();
+        assert!(prompt.contains(rules::BEGIN));
+        assert!(prompt.contains("<!-- Promoted from R1 [workflow] -->\n- Batch reads"));
+    }
+
+    #[test]
+    fn demote_removes_rule_and_records_history() {
+        let (_dir, path) = test_db_path();
+        let config = rules_config(&path);
+        handle_add(
+            &path,
+            "Batch reads",
+            "workflow",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        handle_promote(&path, "R1", &config).unwrap();
+        handle_demote(&path, "R1", Some("no measurable effect"), &config).unwrap();
+
+        let prompt = std::fs::read_to_string(&config.prompt_file).unwrap_or_default();
+        assert!(!prompt.contains("Promoted from R1"));
+        let conn = db::open_or_create(&path).unwrap();
+        assert_eq!(
+            db::get_improvement(&conn, "R1").unwrap().unwrap().status,
+            "revisit"
+        );
+        let actions: Vec<String> = rules::history(&conn, Some("R1"))
+            .unwrap()
+            .into_iter()
+            .map(|r| r.action)
+            .collect();
+        assert_eq!(actions, vec!["promoted", "demoted"]);
+    }
+
+    #[test]
+    fn demote_requires_promoted_rule() {
+        let (_dir, path) = test_db_path();
+        handle_add(
+            &path,
+            "Batch reads",
+            "workflow",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        let err = handle_demote(&path, "R1", None, &rules_config(&path)).unwrap_err();
+        assert!(err.contains("not promoted"), "{err}");
+    }
+
+    #[test]
+    fn add_rejects_near_duplicate_unless_forced() {
+        let (_dir, path) = test_db_path();
+        let body = Some("Issue independent Read calls in a single turn");
+        handle_add(
+            &path,
+            "Batch independent file reads",
+            "workflow",
+            body,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+
+        let err = handle_add(
+            &path,
+            "Batch independent file reads",
+            "workflow",
+            body,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap_err();
+        assert!(err.contains("duplicate of R1"), "{err}");
+
+        handle_add(
+            &path,
+            "Batch independent file reads",
+            "workflow",
+            body,
+            None,
+            None,
+            None,
+            true,
+        )
+        .unwrap();
+        let conn = db::open_or_create(&path).unwrap();
+        assert_eq!(db::list_improvements(&conn, None, None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn add_ignores_dismissed_duplicates() {
+        let (_dir, path) = test_db_path();
+        handle_add(
+            &path,
+            "Batch independent file reads",
+            "workflow",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        handle_dismiss(&path, "R1", None).unwrap();
+        handle_add(
+            &path,
+            "Batch independent file reads",
+            "workflow",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn merge_folds_duplicate_into_kept() {
+        let (_dir, path) = test_db_path();
+        handle_add(
+            &path,
+            "Batch reads",
+            "workflow",
+            None,
+            Some("sessions 3-5"),
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        handle_add(
+            &path,
+            "Group file reads",
+            "workflow",
+            Some("Issue independent Read calls together"),
+            None,
+            None,
+            Some(("turns.total", "below")),
+            false,
+        )
+        .unwrap();
+        handle_merge(&path, "R1", "R2", &rules_config(&path)).unwrap();
+
+        let conn = db::open_or_create(&path).unwrap();
+        let kept = db::get_improvement(&conn, "R1").unwrap().unwrap();
+        assert_eq!(kept.status, "open");
+        assert_eq!(
+            kept.body.as_deref(),
+            Some("Issue independent Read calls together")
+        );
+        assert_eq!(
+            kept.context.as_deref(),
+            Some("sessions 3-5\nMerged R2: Group file reads")
+        );
+        assert_eq!(
+            db::get_improvement_target(&conn, "R1").unwrap(),
+            Some(("turns.total".to_string(), "below".to_string()))
+        );
+        assert_eq!(
+            db::get_improvement(&conn, "R2").unwrap().unwrap().status,
+            "dismissed"
+        );
+    }
+
+    #[test]
+    fn merge_promoted_duplicate_promotes_kept() {
+        let (_dir, path) = test_db_path();
+        let config = rules_config(&path);
+        handle_add(
+            &path,
+            "Batch reads",
+            "workflow",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        handle_add(
+            &path,
+            "Group file reads",
+            "workflow",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        handle_promote(&path, "R2", &config).unwrap();
+        handle_merge(&path, "R1", "R2", &config).unwrap();
+
+        let conn = db::open_or_create(&path).unwrap();
+        assert_eq!(
+            db::get_improvement(&conn, "R1").unwrap().unwrap().status,
+            "promoted"
+        );
+        let prompt = std::fs::read_to_string(&config.prompt_file).unwrap();
+        assert!(prompt.contains("Promoted from R1 [workflow]"));
+        assert!(!prompt.contains("Promoted from R2"));
+        assert!(handle_merge(&path, "R1", "R1", &config).is_err());
+    }
+
     #[test]
     fn promote_nonexistent_returns_error() {
         let (_dir, path) = test_db_path();
         let _conn = db::open_or_create(&path).unwrap();
-        let result = handle_promote(&path, "R999");
+        let result = handle_promote(&path, "R999", &rules_config(&path));
         assert!(result.is_err());
     }
 
@@ -411,7 +995,17 @@ mod tests {
     #[test]
     fn dismiss_sets_status() {
         let (_dir, path) = test_db_path();
-        handle_add(&path, "To dismiss", "workflow", None, None, None).unwrap();
+        handle_add(
+            &path,
+            "To dismiss",
+            "workflow",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
         handle_dismiss(&path, "R1", None).unwrap();
 
         let conn = db::open_or_create(&path).unwrap();
@@ -422,7 +1016,17 @@ mod tests {
     #[test]
     fn dismiss_with_reason_stores_meta() {
         let (_dir, path) = test_db_path();
-        handle_add(&path, "To dismiss", "workflow", None, None, None).unwrap();
+        handle_add(
+            &path,
+            "To dismiss",
+            "workflow",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
         handle_dismiss(&path, "R1", Some("not relevant")).unwrap();
 
         let conn = db::open_or_create(&path).unwrap();
@@ -434,7 +1038,17 @@ mod tests {
     #[test]
     fn dismiss_sets_resolved_timestamp() {
         let (_dir, path) = test_db_path();
-        handle_add(&path, "To dismiss", "workflow", None, None, None).unwrap();
+        handle_add(
+            &path,
+            "To dismiss",
+            "workflow",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
         handle_dismiss(&path, "R1", None).unwrap();
 
         let conn = db::open_or_create(&path).unwrap();
@@ -461,8 +1075,28 @@ mod tests {
     #[test]
     fn search_by_title() {
         let (_dir, path) = test_db_path();
-        handle_add(&path, "Reduce token usage", "cost", None, None, None).unwrap();
-        handle_add(&path, "Fix retry logic", "reliability", None, None, None).unwrap();
+        handle_add(
+            &path,
+            "Reduce token usage",
+            "cost",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        handle_add(
+            &path,
+            "Fix retry logic",
+            "reliability",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
         handle_search(&path, "token").unwrap();
 
         // Verify via DB that search would match
@@ -482,9 +1116,21 @@ mod tests {
             Some("Parallel tool calls save turns"),
             None,
             None,
+            None,
+            false,
+        )
+        .unwrap();
+        handle_add(
+            &path,
+            "Other",
+            "cost",
+            Some("Unrelated body"),
+            None,
+            None,
+            None,
+            false,
         )
         .unwrap();
-        handle_add(&path, "Other", "cost", Some("Unrelated body"), None, None).unwrap();
 
         let conn = db::open_or_create(&path).unwrap();
         let results = db::search_improvements(&conn, "parallel").unwrap();
@@ -502,6 +1148,8 @@ mod tests {
             None,
             Some("sessions 340-348"),
             None,
+            None,
+            false,
         )
         .unwrap();
 
@@ -513,7 +1161,17 @@ mod tests {
     #[test]
     fn search_no_results() {
         let (_dir, path) = test_db_path();
-        handle_add(&path, "Something", "workflow", None, None, None).unwrap();
+        handle_add(
+            &path,
+            "Something",
+            "workflow",
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
         // Should not error
         handle_search(&path, "nonexistent_xyz").unwrap();
 
@@ -525,7 +1183,7 @@ mod tests {
     #[test]
     fn search_case_insensitive() {
         let (_dir, path) = test_db_path();
-        handle_add(&path, "Token Usage", "cost", None, None, None).unwrap();
+        handle_add(&path, "Token Usage", "cost", None, None, None, None, false).unwrap();
 
         let conn = db::open_or_create(&path).unwrap();
         let results = db::search_improvements(&conn, "token").unwrap();
@@ -1,9 +1,11 @@
 /// Metric extraction: parse a session output file via an adapter and write
 /// extracted events + observations to the database.
 use crate::adapters::{AgentAdapter, ExtractionSource};
-use crate::config::CompiledRule;
+use crate::config::{CompiledRule, PricingConfig};
 use crate::db;
-use rusqlite::Connection;
+use crate::pricing;
+use crate::tool_call;
+use rusqlite::{Connection, OptionalExtension};
 use serde_json::Value;
 use std::path::Path;
 
@@ -12,6 +14,9 @@ use std::path::Path;
 pub struct IngestResult {
     pub turns_total: u64,
     pub cost_estimate_usd: f64,
+    /// Cost computed from token counts and the `[pricing]` table, when the
+    /// session's model has a pricing entry.
+    pub cost_computed_usd: Option<f64>,
     pub session_duration_ms: u64,
     #[allow(dead_code)]
     pub bead_id: Option<String>,
@@ -19,6 +24,10 @@ pub struct IngestResult {
 
 /// Ingest a session output file: extract metrics via adapter, write events
 /// and observation to the database. Returns a summary of extracted metrics.
+///
+/// No extraction rules and no pricing table; production callers go through
+/// [`ingest_session_with_rules`].
+#[cfg(test)]
 pub fn ingest_session(
     conn: &Connection,
     session: i64,
@@ -26,23 +35,50 @@ pub fn ingest_session(
     exit_code: Option<i32>,
     adapter: &dyn AgentAdapter,
 ) -> Result<IngestResult, IngestError> {
-    ingest_session_with_rules(conn, session, output_path, exit_code, &[], adapter)
+    ingest_session_with_rules(
+        conn,
+        session,
+        output_path,
+        exit_code,
+        &[],
+        &PricingConfig::default(),
+        adapter,
+    )
 }
 
 /// Ingest a session output file with configurable extraction rules applied.
+///
+/// When the pricing table has a rate for the session's model, a
+/// `cost.computed_usd` metric is derived from the token counts and stored
+/// alongside the adapter's own (possibly absent) `cost.estimate_usd`.
 pub fn ingest_session_with_rules(
     conn: &Connection,
     session: i64,
     output_path: &Path,
     exit_code: Option<i32>,
     rules: &[CompiledRule],
+    pricing_config: &PricingConfig,
     adapter: &dyn AgentAdapter,
 ) -> Result<IngestResult, IngestError> {
     // Extract built-in metrics via the adapter
-    let builtin_metrics = adapter
+    let mut builtin_metrics = adapter
         .extract_builtin_metrics(output_path)
         .map_err(|e| IngestError::Io(std::io::Error::other(e.to_string())))?;
 
+    let cost_by_model = pricing::compute_cost_by_model(&builtin_metrics, pricing_config);
+    let cost_computed_usd = pricing::compute_session_cost(&builtin_metrics, pricing_config);
+    if let Some(cost) = cost_computed_usd {
+        if let Some(n) = serde_json::Number::from_f64(cost) {
+            builtin_metrics.push(("cost.computed_usd".to_string(), Value::Number(n)));
+        }
+    }
+    if let Some(by_model) = cost_by_model {
+        builtin_metrics.push((
+            "cost.computed_by_model".to_string(),
+            serde_json::json!(by_model),
+        ));
+    }
+
     let ts = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
 
     // Write individual events for built-in metrics
@@ -86,6 +122,16 @@ pub fn ingest_session_with_rules(
     db::upsert_observation(conn, session, &ts, Some(duration_secs), None, &data)
         .map_err(IngestError::Db)?;
 
+    // Per-invocation tool calls, for `metrics tools` (best-effort, non-fatal)
+    match adapter.extract_tool_calls(output_path) {
+        Ok(tool_calls) => {
+            if let Err(e) = tool_call::record(conn, session, &tool_calls) {
+                tracing::warn!(session, error = %e, "failed to record tool calls");
+            }
+        }
+        Err(e) => tracing::warn!(session, error = %e, "failed to extract tool calls"),
+    }
+
     // Attribute session to a bead (best-effort, non-fatal)
     let bead_id = match attribute_session(conn, session, output_path) {
         Ok(id) => id,
@@ -107,6 +153,7 @@ pub fn ingest_session_with_rules(
             .find(|(k, _)| k == "cost.estimate_usd")
             .and_then(|(_, v)| v.as_f64())
             .unwrap_or(0.0),
+        cost_computed_usd,
         session_duration_ms: duration_ms,
         bead_id,
     };
@@ -332,7 +379,7 @@ fn build_observation_data(
 /// Returns the bead ID if attribution succeeds, or None if no bead can be identified.
 ///
 /// Strategy order:
-/// 1. Multi-agent: look up worker_assignments table for a matching bead_id
+/// 1. Multi-agent: the `session.bead_id` event the coordinator recorded for the worker
 /// 2. Git commit correlation: find commits within the session time window
 /// 3. Fallback: scan session JSONL for bead ID mentions (bd update, bd-finish.sh)
 ///
@@ -343,9 +390,9 @@ pub fn attribute_session(
     session: i64,
     output_path: &Path,
 ) -> Result<Option<String>, IngestError> {
-    // Strategy 1: Multi-agent explicit attribution from worker_assignments
-    if let Some(bead_id) = attribute_from_worker_assignments(conn, session)? {
-        store_attribution(conn, session, &bead_id)?;
+    // Strategy 1: Multi-agent explicit attribution recorded by the coordinator
+    if let Some(bead_id) = recorded_attribution(conn, session)? {
+        update_bead_metrics(conn, session, &bead_id)?;
         return Ok(Some(bead_id));
     }
 
@@ -376,51 +423,18 @@ fn store_attribution(conn: &Connection, session: i64, bead_id: &str) -> Result<(
     Ok(())
 }
 
-/// Multi-agent attribution: look up worker_assignments for a session's bead.
-///
-/// In multi-agent mode, the coordinator stores (worker_id, bead_id) in
-/// worker_assignments. The session number corresponds to a worker assignment
-/// that was recorded with that bead_id.
-fn attribute_from_worker_assignments(
-    conn: &Connection,
-    _session: i64,
-) -> Result<Option<String>, IngestError> {
-    // Check if worker_assignments has any rows with matching session info.
-    // The coordinator stores assignment_id keyed by worker_id. We look for
-    // the most recent completed assignment.
-    // Since sessions in single-agent mode are numbered sequentially and
-    // worker_assignments is only populated in multi-agent mode, we check
-    // if there are any assignments at all first.
-    let count: i64 = conn
-        .query_row("SELECT COUNT(*) FROM worker_assignments", [], |row| {
-            row.get(0)
-        })
-        .map_err(IngestError::Db)?;
-
-    if count == 0 {
-        return Ok(None);
-    }
-
-    // In multi-agent mode, look for a session.bead_id event already stored
-    // (the coordinator would have created the attribution at spawn time).
-    // We don't re-attribute if already attributed.
-    let existing: Option<String> = conn
-        .query_row(
-            "SELECT value FROM events WHERE session = ?1 AND kind = 'session.bead_id' LIMIT 1",
-            rusqlite::params![_session],
-            |row| row.get(0),
-        )
-        .ok();
-
-    if let Some(bead_id) = existing {
-        return Ok(Some(bead_id));
-    }
-
-    // Check if there's a worker assignment whose session correlates
-    // In the current architecture, the coordinator records assignment_id
-    // but not the session number directly. For now, skip multi-agent attribution
-    // (it will be handled by the coordinator storing the event at spawn time).
-    Ok(None)
+/// Multi-agent attribution: the coordinator knows which bead each worker was
+/// assigned and records a `session.bead_id` event before the session is
+/// ingested (see `coordinator::record_session_bead`).
+fn recorded_attribution(conn: &Connection, session: i64) -> Result<Option<String>, IngestError> {
+    conn.query_row(
+        "SELECT value FROM events WHERE session = ?1 AND kind = 'session.bead_id' LIMIT 1",
+        rusqlite::params![session],
+        |row| row.get(0),
+    )
+    .optional()
+    .map(Option::flatten)
+    .map_err(IngestError::Db)
 }
 
 /// Git commit correlation: find commits within the session file's time window
@@ -656,8 +670,8 @@ mod tests {
 
         // Verify events were written
         let events = db::events_by_session(&conn, 42).unwrap();
-        // 11 built-in metrics + 1 exit_code = 12
-        assert_eq!(events.len(), 12);
+        // 11 built-in metrics + cost.model_tokens + 1 exit_code = 13
+        assert_eq!(events.len(), 13);
 
         // Verify specific event values
         let turns_total = events.iter().find(|e| e.kind == "turns.total").unwrap();
@@ -728,6 +742,21 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    #[test]
+    fn ingest_session_survives_tool_call_record_failure() {
+        let (_db_dir, conn) = test_db();
+        let data_dir = TempDir::new().unwrap();
+        let lines = &[
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Read","input":{}}]}}"#,
+        ];
+        let path = write_jsonl(data_dir.path(), lines);
+        conn.execute_batch("DROP TABLE tool_calls").unwrap();
+
+        let adapter = claude_adapter();
+        ingest_session(&conn, 9, &path, None, &adapter).unwrap();
+        assert!(db::get_observation(&conn, 9).unwrap().is_some());
+    }
+
     #[test]
     fn build_observation_data_roundtrip() {
         let builtin = vec![
@@ -997,7 +1026,16 @@ mod tests {
         let c2 = r2.compile().unwrap();
 
         let adapter = claude_adapter();
-        ingest_session_with_rules(&conn, 1, &path, Some(0), &[c1, c2], &adapter).unwrap();
+        ingest_session_with_rules(
+            &conn,
+            1,
+            &path,
+            Some(0),
+            &[c1, c2],
+            &PricingConfig::default(),
+            &adapter,
+        )
+        .unwrap();
 
         // Check events include rule-extracted ones
         let events = db::events_by_session(&conn, 1).unwrap();
@@ -1019,6 +1057,44 @@ mod tests {
         assert_eq!(data["turns.total"], 2);
     }
 
+    #[test]
+    fn ingest_computes_cost_from_pricing_table() {
+        let (_db_dir, conn) = test_db();
+        let data_dir = TempDir::new().unwrap();
+        let lines = &[
+            r#"{"type":"system","subtype":"init","model":"claude-sonnet-4-5"}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Done."}]}}"#,
+            r#"{"type":"result","duration_ms":5000,"total_cost_usd":0.5,"modelUsage":{"claude-sonnet-4-5":{"inputTokens":1000000,"outputTokens":100000,"cacheReadInputTokens":0,"cacheCreationInputTokens":0}}}"#,
+        ];
+        let path = write_jsonl(data_dir.path(), lines);
+        let pricing = PricingConfig {
+            models: vec![crate::config::ModelPricing {
+                model: "claude-sonnet-4-5".to_string(),
+                provider: None,
+                input: 3.0,
+                output: 15.0,
+                cache_read: 0.3,
+                cache_write: 3.75,
+            }],
+        };
+
+        let adapter = claude_adapter();
+        let result =
+            ingest_session_with_rules(&conn, 1, &path, Some(0), &[], &pricing, &adapter).unwrap();
+        let computed = result.cost_computed_usd.unwrap();
+        assert!((computed - 4.5).abs() < 1e-9);
+        assert!((result.cost_estimate_usd - 0.5).abs() < 1e-9);
+
+        let obs = db::get_observation(&conn, 1).unwrap().unwrap();
+        let data: Value = serde_json::from_str(&obs.data).unwrap();
+        assert!((data["cost.computed_usd"].as_f64().unwrap() - 4.5).abs() < 1e-9);
+        assert_eq!(data["session.model"], "claude-sonnet-4-5");
+
+        // Without a pricing entry there is no computed cost
+        let result = ingest_session(&conn, 2, &path, Some(0), &adapter).unwrap();
+        assert!(result.cost_computed_usd.is_none());
+    }
+
     #[test]
     fn ingest_with_raw_adapter() {
         let (_db_dir, conn) = test_db();
@@ -1215,6 +1291,35 @@ mod tests {
         assert_eq!(bm.total_output_tokens, Some(5000)); // 2000 + 3000
     }
 
+    #[test]
+    fn attribute_session_prefers_recorded_bead() {
+        let (_db_dir, conn) = test_db();
+        let data_dir = TempDir::new().unwrap();
+
+        // The JSONL mentions another bead; the coordinator's record wins
+        let lines = &[
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Bash","input":{"command":"bd update other-bead --status in_progress"}}]}}"#,
+            r#"{"type":"result","duration_ms":60000,"total_cost_usd":0.5,"modelUsage":{}}"#,
+        ];
+        let path = write_jsonl(data_dir.path(), lines);
+        db::insert_event(&conn, 1, "session.bead_id", Some("my-proj-abc"), None).unwrap();
+
+        let adapter = claude_adapter();
+        let result = ingest_session(&conn, 1, &path, Some(0), &adapter).unwrap();
+        assert_eq!(result.bead_id.as_deref(), Some("my-proj-abc"));
+
+        let events = db::events_by_session(&conn, 1).unwrap();
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| e.kind == "session.bead_id")
+                .count(),
+            1
+        );
+        let bm = db::get_bead_metrics(&conn, "my-proj-abc").unwrap().unwrap();
+        assert_eq!(bm.sessions, 1);
+    }
+
     #[test]
     fn attribute_session_no_bead_id_found() {
         let (_db_dir, conn) = test_db();
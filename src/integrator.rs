@@ -5,15 +5,22 @@
 /// 2. Applies manifest entries from task_manifest.toml
 /// 3. Runs compiler checks (cargo check / tsc --noEmit)
 /// 4. If errors, spawns integration agent to fix them (up to 3 retries)
-/// 5. On success, fast-forwards main to the branch tip
-/// 6. Records the integration in the database
+/// 5. Checks `[[architecture.fitness]]` rules against the base commit
+/// 6. On success, fast-forwards main to the branch tip
+/// 7. Records the integration in the database
 ///
 /// Only one integration runs at a time to keep main's history linear.
 /// Workers continue coding while one task integrates.
-use crate::config::{ResolvedAgentConfig, SpeckValidateConfig};
+use crate::config::{
+    BoundaryPolicy, FitnessRule, ReconciliationConfig, ResolvedAgentConfig, SpeckValidateConfig,
+};
 use crate::db;
 use crate::expansion_event::{self, ExpansionEvent};
+use crate::fitness;
+use crate::flaky::{self, Verdict};
+use crate::module_detect;
 use crate::task_manifest;
+use crate::test_report::{self, TestReport};
 use crate::worktree;
 use glob::Pattern;
 use rusqlite::Connection;
@@ -55,8 +62,20 @@ impl TrippedFailure {
     }
 
     /// Notes string suitable for `bd update <id> --notes="..."`.
+    ///
+    /// When the error contains test output, the notes list the failing tests
+    /// instead of the raw output.
     pub fn failure_notes(&self) -> String {
-        format!("Integration failed: {}", self.error_summary)
+        match test_report::parse(&self.error_summary) {
+            Some(report) if !report.failures.is_empty() => {
+                let headline = self.error_summary.lines().next().unwrap_or("");
+                format!(
+                    "Integration failed: {headline}\n{}",
+                    report.summary(test_report::SUMMARY_MAX_FAILURES)
+                )
+            }
+            _ => format!("Integration failed: {}", self.error_summary),
+        }
     }
 }
 
@@ -154,6 +173,14 @@ impl CircuitBreaker {
         self.attempts.remove(bead_id);
     }
 
+    /// Trip the breaker immediately for failures that retrying can't fix
+    /// (e.g. a blocked boundary violation). Returns the new state.
+    pub fn trip(&mut self, bead_id: &str) -> CircuitState {
+        let count = self.attempts.entry(bead_id.to_string()).or_insert(0);
+        *count = (*count + 1).max(MAX_INTEGRATION_ATTEMPTS);
+        self.state(bead_id)
+    }
+
     /// Get attempt count for a bead.
     pub fn attempt_count(&self, bead_id: &str) -> u32 {
         self.attempts.get(bead_id).copied().unwrap_or(0)
@@ -261,7 +288,6 @@ impl ValidationCircuitBreaker {
 /// the full test suite is run on main. If failures are detected, the last N
 /// integrated tasks are flagged for human review.
 #[derive(Debug)]
-#[allow(dead_code)]
 pub struct ReconciliationTracker {
     /// How many successful integrations between reconciliation runs.
     every: u32,
@@ -271,7 +297,6 @@ pub struct ReconciliationTracker {
     recent_beads: Vec<String>,
 }
 
-#[allow(dead_code)]
 impl ReconciliationTracker {
     /// Create a new tracker with the given reconciliation interval.
     pub fn new(every: u32) -> Self {
@@ -314,8 +339,14 @@ pub struct ReconciliationResult {
     pub passed: bool,
     /// Test output (stdout + stderr).
     pub output: String,
+    /// Failing tests parsed from `output`, when it is recognizable test output.
+    pub report: Option<TestReport>,
+    /// Failing tests that passed on an isolated re-run (not blamed on beads).
+    pub flaky_tests: Vec<String>,
+    /// Failing tests that failed every re-run.
+    pub broken_tests: Vec<String>,
     /// Bead IDs that were integrated since the last reconciliation
-    /// (flagged for review if tests failed).
+    /// (flagged for review if tests failed for reasons other than flakes).
     pub flagged_beads: Vec<String>,
 }
 
@@ -590,6 +621,14 @@ pub struct IntegrationQueue {
     base_branch: String,
     /// Configuration for the speck validate gate.
     speck_validate: SpeckValidateConfig,
+    /// Flaky-test re-run settings for reconciliation.
+    reconciliation: ReconciliationConfig,
+    /// Root of per-slot `CARGO_TARGET_DIR`s, shared with the worker pool.
+    target_cache_dir: Option<PathBuf>,
+    /// How to handle changes outside the declared affected set.
+    boundary_policy: BoundaryPolicy,
+    /// Architecture fitness rules checked against the base commit.
+    fitness_rules: Vec<FitnessRule>,
 }
 
 impl IntegrationQueue {
@@ -599,15 +638,44 @@ impl IntegrationQueue {
             repo_dir,
             base_branch,
             speck_validate: SpeckValidateConfig::default(),
+            reconciliation: ReconciliationConfig::default(),
+            target_cache_dir: None,
+            boundary_policy: BoundaryPolicy::default(),
+            fitness_rules: Vec::new(),
         }
     }
 
+    /// Configure enforcement of declared affected-set boundaries.
+    pub fn with_boundary_policy(mut self, policy: BoundaryPolicy) -> Self {
+        self.boundary_policy = policy;
+        self
+    }
+
+    /// Configure the architecture fitness rules gate.
+    pub fn with_fitness_rules(mut self, rules: Vec<FitnessRule>) -> Self {
+        self.fitness_rules = rules;
+        self
+    }
+
     /// Configure the speck validate pre-integration gate.
     pub fn with_speck_validate(mut self, config: SpeckValidateConfig) -> Self {
         self.speck_validate = config;
         self
     }
 
+    /// Build in the worker slot's persistent `CARGO_TARGET_DIR` under `dir`,
+    /// matching the directory the worker's agent session used.
+    pub fn with_target_cache(mut self, dir: Option<PathBuf>) -> Self {
+        self.target_cache_dir = dir;
+        self
+    }
+
+    /// Configure flaky-test handling for reconciliation runs.
+    pub fn with_reconciliation(mut self, config: ReconciliationConfig) -> Self {
+        self.reconciliation = config;
+        self
+    }
+
     /// Integrate a single completed worktree into main.
     ///
     /// Steps:
@@ -615,9 +683,11 @@ impl IntegrationQueue {
     /// 2. Apply manifest entries from task_manifest.toml
     /// 3. Run compiler check (cargo check / tsc --noEmit)
     /// 4. If errors, spawn integration agent to fix; retry up to MAX_INTEGRATION_ATTEMPTS
-    /// 5. Fast-forward main to the worktree's HEAD
-    /// 6. Record integration in the database
-    /// 7. Clean up the worktree
+    ///    (then speck validate and architecture fitness rules, with the same fix loop)
+    /// 5. Check the diff against the declared affected set (per `boundary_policy`)
+    /// 6. Fast-forward main to the worktree's HEAD
+    /// 7. Record integration in the database
+    /// 8. Clean up the worktree
     #[allow(clippy::too_many_arguments)]
     pub fn integrate(
         &self,
@@ -650,7 +720,7 @@ impl IntegrationQueue {
                 // Abort the merge if it's in a conflicted state
                 let _ = self.abort_merge(worktree_path);
 
-                self.record_failure(assignment_id, db_conn, &reason);
+                self.record_failure(assignment_id, bead_id, db_conn, &reason);
 
                 return IntegrationResult {
                     worker_id,
@@ -700,7 +770,7 @@ impl IntegrationQueue {
                 // Check if circuit breaker allows retry
                 if !circuit_breaker.state(bead_id).can_retry() {
                     let reason = "circuit breaker tripped during compiler fix loop".to_string();
-                    self.record_failure(assignment_id, db_conn, &reason);
+                    self.record_failure(assignment_id, bead_id, db_conn, &reason);
                     return IntegrationResult {
                         worker_id,
                         assignment_id,
@@ -712,7 +782,7 @@ impl IntegrationQueue {
                 }
 
                 // Run compiler check
-                match self.run_compiler_check(worktree_path) {
+                match self.run_compiler_check(worktree_path, worker_id) {
                     Ok(()) => {
                         tracing::info!(worker_id, bead_id, "compiler check passed");
                         break; // All good, proceed to fast-forward
@@ -733,7 +803,7 @@ impl IntegrationQueue {
                                 state.attempt_count(),
                                 compiler_errors
                             );
-                            self.record_failure(assignment_id, db_conn, &reason);
+                            self.record_failure(assignment_id, bead_id, db_conn, &reason);
                             return IntegrationResult {
                                 worker_id,
                                 assignment_id,
@@ -749,12 +819,13 @@ impl IntegrationQueue {
                             "Fix the following compiler errors in this codebase. \
                              Apply minimal, surgical fixes (add missing imports, resolve name collisions, fix type mismatches). \
                              Do NOT refactor or change logic.\n\nCompiler output:\n{}",
-                            compiler_errors
+                            test_report::failure_context(&compiler_errors, usize::MAX)
                         );
 
                         match self.spawn_integration_agent_sync(
                             agent_config,
                             worktree_path,
+                            worker_id,
                             &fix_prompt,
                         ) {
                             Ok(exit_code) => {
@@ -772,7 +843,7 @@ impl IntegrationQueue {
                             Err(e) => {
                                 let reason = format!("failed to spawn integration agent: {e}");
                                 tracing::error!(worker_id, bead_id, error = %e, "integration agent spawn failed");
-                                self.record_failure(assignment_id, db_conn, &reason);
+                                self.record_failure(assignment_id, bead_id, db_conn, &reason);
                                 return IntegrationResult {
                                     worker_id,
                                     assignment_id,
@@ -823,7 +894,7 @@ impl IntegrationQueue {
                                 attempts = state.attempt_count(),
                                 "speck validate: validation retries exhausted"
                             );
-                            self.record_failure(assignment_id, db_conn, &reason);
+                            self.record_failure(assignment_id, bead_id, db_conn, &reason);
                             return IntegrationResult {
                                 worker_id,
                                 assignment_id,
@@ -843,6 +914,7 @@ impl IntegrationQueue {
                             match self.spawn_integration_agent_sync(
                                 agent_config,
                                 worktree_path,
+                                worker_id,
                                 &fix_prompt,
                             ) {
                                 Ok(exit_code) => {
@@ -865,7 +937,7 @@ impl IntegrationQueue {
                                         error = %e,
                                         "validation fix agent spawn failed"
                                     );
-                                    self.record_failure(assignment_id, db_conn, &reason);
+                                    self.record_failure(assignment_id, bead_id, db_conn, &reason);
                                     return IntegrationResult {
                                         worker_id,
                                         assignment_id,
@@ -881,7 +953,7 @@ impl IntegrationQueue {
                             let reason = format!(
                                 "speck validate failed (no agent available to retry): {notes}"
                             );
-                            self.record_failure(assignment_id, db_conn, &reason);
+                            self.record_failure(assignment_id, bead_id, db_conn, &reason);
                             return IntegrationResult {
                                 worker_id,
                                 assignment_id,
@@ -905,13 +977,119 @@ impl IntegrationQueue {
             }
         }
 
+        // Step 4b: architecture fitness rules against the base commit (if configured)
+        //
+        // Violations are fed back to the integration agent and share the
+        // validation circuit breaker with speck validate.
+        if !self.fitness_rules.is_empty() {
+            loop {
+                let violations = match fitness::check(
+                    &self.fitness_rules,
+                    &self.repo_dir,
+                    &self.base_branch,
+                    worktree_path,
+                ) {
+                    Ok(violations) => violations,
+                    Err(e) => {
+                        tracing::warn!(worker_id, bead_id, error = %e, "fitness check skipped");
+                        break;
+                    }
+                };
+                if violations.is_empty() {
+                    tracing::info!(worker_id, bead_id, "fitness rules passed");
+                    break;
+                }
+
+                let details = fitness::describe(&violations);
+                tracing::warn!(
+                    worker_id,
+                    bead_id,
+                    violations = violations.len(),
+                    state = %validation_circuit_breaker.state(bead_id),
+                    "fitness rules violated"
+                );
+
+                let state = validation_circuit_breaker.record_attempt(bead_id);
+                if state.is_tripped() {
+                    let reason = format!(
+                        "fitness rules violated after {} attempts:\n{details}",
+                        state.attempt_count()
+                    );
+                    self.record_failure(assignment_id, bead_id, db_conn, &reason);
+                    return IntegrationResult {
+                        worker_id,
+                        assignment_id,
+                        bead_id: bead_id.to_string(),
+                        success: false,
+                        merge_commit: None,
+                        failure_reason: Some(reason),
+                    };
+                }
+
+                let Some(agent_config) = integration_agent else {
+                    let reason =
+                        format!("fitness rules violated (no agent available to retry):\n{details}");
+                    self.record_failure(assignment_id, bead_id, db_conn, &reason);
+                    return IntegrationResult {
+                        worker_id,
+                        assignment_id,
+                        bead_id: bead_id.to_string(),
+                        success: false,
+                        merge_commit: None,
+                        failure_reason: Some(reason),
+                    };
+                };
+
+                let fix_prompt = format!(
+                    "The merged change violates the project's architecture fitness rules:\n{details}\n\n\
+                     Restructure the change so every rule holds. Do NOT edit the rules in the \
+                     blacksmith config."
+                );
+                match self.spawn_integration_agent_sync(
+                    agent_config,
+                    worktree_path,
+                    worker_id,
+                    &fix_prompt,
+                ) {
+                    Ok(exit_code) => {
+                        if exit_code != Some(0) {
+                            tracing::warn!(
+                                worker_id,
+                                bead_id,
+                                exit_code = ?exit_code,
+                                "fitness fix agent exited with non-zero status"
+                            );
+                        }
+                        let _ = self.git_add_and_commit(
+                            worktree_path,
+                            &format!("integration: fix fitness violations for {bead_id}"),
+                        );
+                        // Loop back to re-check the rules
+                    }
+                    Err(e) => {
+                        let reason = format!("failed to spawn fitness fix agent: {e}");
+                        tracing::error!(worker_id, bead_id, error = %e, "fitness fix agent spawn failed");
+                        self.record_failure(assignment_id, bead_id, db_conn, &reason);
+                        return IntegrationResult {
+                            worker_id,
+                            assignment_id,
+                            bead_id: bead_id.to_string(),
+                            success: false,
+                            merge_commit: None,
+                            failure_reason: Some(reason),
+                        };
+                    }
+                }
+            }
+        }
+
         // Step 5: Get the HEAD commit of the worktree (the merge/fix result)
         let worktree_head = match self.get_head_commit(worktree_path) {
             Ok(head) => head,
             Err(e) => {
                 let reason = format!("failed to get worktree HEAD: {e}");
                 tracing::warn!(worker_id, bead_id, error = %e, "failed to get worktree HEAD");
-                self.record_failure(assignment_id, db_conn, &reason);
+                self.record_failure(assignment_id, bead_id, db_conn, &reason);
                 return IntegrationResult {
                     worker_id,
                     assignment_id,
@@ -923,19 +1101,80 @@ impl IntegrationQueue {
             }
         };
 
-        let pending_expansion_event =
-            match self.build_expansion_event(assignment_id, bead_id, worktree_path, db_conn) {
-                Ok(event) => event,
-                Err(e) => {
+        // Step 5a: Check the final diff against the declared affected set
+        let violation = match self.check_boundary(assignment_id, bead_id, worktree_path, db_conn) {
+            Ok(event) => event,
+            Err(e) if self.boundary_policy == BoundaryPolicy::Block => {
+                // An unverified boundary must not slip through under Block
+                let reason = format!("boundary check failed: {e}");
+                tracing::warn!(worker_id, bead_id, error = %e, "integration blocked");
+                self.record_failure(assignment_id, bead_id, db_conn, &reason);
+                return IntegrationResult {
+                    worker_id,
+                    assignment_id,
+                    bead_id: bead_id.to_string(),
+                    success: false,
+                    merge_commit: None,
+                    failure_reason: Some(reason),
+                };
+            }
+            Err(e) => {
+                tracing::warn!(
+                    worker_id,
+                    bead_id,
+                    error = %e,
+                    "failed to compute expansion event (non-fatal)"
+                );
+                None
+            }
+        };
+        if let Some(violation) = violation.as_ref() {
+            let event = &violation.event;
+            if let Err(e) = expansion_event::record(db_conn, event) {
+                tracing::warn!(error = %e, bead_id, "failed to record expansion event");
+            }
+            match self.boundary_policy {
+                BoundaryPolicy::Warn => {
                     tracing::warn!(
                         worker_id,
                         bead_id,
-                        error = %e,
-                        "failed to compute expansion event (non-fatal)"
+                        reason = %event.expansion_reason,
+                        "changes outside declared affected set"
                     );
-                    None
                 }
-            };
+                BoundaryPolicy::Expand => {
+                    let widened = widen_affected_globs(violation);
+                    tracing::info!(
+                        worker_id,
+                        bead_id,
+                        affected = %widened,
+                        "expanding affected set to cover changed files"
+                    );
+                    if let Err(e) = db::update_worker_assignment_affected_globs(
+                        db_conn,
+                        assignment_id,
+                        &widened,
+                    ) {
+                        tracing::warn!(error = %e, bead_id, "failed to widen affected set");
+                    }
+                }
+                BoundaryPolicy::Block => {
+                    let reason = format!("boundary violation: {}", event.expansion_reason);
+                    tracing::warn!(worker_id, bead_id, reason = %reason, "integration blocked");
+                    // Retrying produces the same diff, so escalate straight to review
+                    circuit_breaker.trip(bead_id);
+                    self.record_failure(assignment_id, bead_id, db_conn, &reason);
+                    return IntegrationResult {
+                        worker_id,
+                        assignment_id,
+                        bead_id: bead_id.to_string(),
+                        success: false,
+                        merge_commit: None,
+                        failure_reason: Some(reason),
+                    };
+                }
+            }
+        }
 
         // Step 6: Fast-forward main to the worktree's HEAD
         match self.fast_forward_main(&worktree_head) {
@@ -952,7 +1191,7 @@ impl IntegrationQueue {
             Err(e) => {
                 let reason = format!("fast-forward failed: {e}");
                 tracing::warn!(worker_id, bead_id, error = %e, "fast-forward main failed");
-                self.record_failure(assignment_id, db_conn, &reason);
+                self.record_failure(assignment_id, bead_id, db_conn, &reason);
                 return IntegrationResult {
                     worker_id,
                     assignment_id,
@@ -1022,12 +1261,6 @@ impl IntegrationQueue {
             tracing::warn!(error = %e, "failed to update assignment status to integrated");
         }
 
-        if let Some(event) = pending_expansion_event.as_ref() {
-            if let Err(e) = expansion_event::record(db_conn, event) {
-                tracing::warn!(error = %e, bead_id, "failed to record expansion event");
-            }
-        }
-
         // Reset circuit breakers on success
         circuit_breaker.reset(bead_id);
         validation_circuit_breaker.reset(bead_id);
@@ -1133,15 +1366,16 @@ impl IntegrationQueue {
         Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
     }
 
-    /// Build an expansion event if the assignment declared affected globs and
-    /// the integrated changes touched files outside that declared set.
-    fn build_expansion_event(
+    /// Compare `git diff --name-only` against the assignment's declared
+    /// affected globs. Returns a violation (with an expansion event mapping
+    /// both sides to modules) if any changed file falls outside the set.
+    fn check_boundary(
         &self,
         assignment_id: i64,
         bead_id: &str,
         worktree_path: &Path,
         db_conn: &Connection,
-    ) -> Result<Option<ExpansionEvent>, IntegrationError> {
+    ) -> Result<Option<BoundaryViolation>, IntegrationError> {
         let Some(assignment) = db::get_worker_assignment(db_conn, assignment_id)? else {
             return Ok(None);
         };
@@ -1155,25 +1389,40 @@ impl IntegrationQueue {
             return Ok(None);
         }
 
-        let expansions: Vec<String> = modified_files
+        let outside: Vec<String> = modified_files
             .iter()
             .filter(|path| !path_matches_any_glob(path, &declared_globs))
             .cloned()
             .collect();
 
-        if expansions.is_empty() {
+        if outside.is_empty() {
             return Ok(None);
         }
 
-        Ok(Some(ExpansionEvent {
+        let modules = module_detect::detect_modules_from_repo(worktree_path);
+        let event = ExpansionEvent {
             task_id: bead_id.to_string(),
-            predicted_modules: declared_globs,
-            actual_modules: modified_files,
+            predicted_modules: module_detect::modules_matching(
+                &modules,
+                worktree_path,
+                &declared_globs,
+            ),
+            actual_modules: module_detect::modules_matching(
+                &modules,
+                worktree_path,
+                &modified_files,
+            ),
             expansion_reason: format!(
                 "Files outside declared affected globs: {}",
-                expansions.join(", ")
+                outside.join(", ")
             ),
             timestamp: chrono_now_utc(),
+        };
+
+        Ok(Some(BoundaryViolation {
+            declared: declared_globs,
+            outside,
+            event,
         }))
     }
 
@@ -1447,12 +1696,27 @@ impl IntegrationQueue {
         }
     }
 
-    fn run_compiler_check(&self, worktree_path: &Path) -> Result<(), String> {
+    /// Per-slot `CARGO_TARGET_DIR`, if shared target dirs are enabled.
+    fn slot_env(&self, worker_id: u32) -> Vec<(&'static str, PathBuf)> {
+        self.target_cache_dir
+            .as_ref()
+            .map(|dir| {
+                (
+                    "CARGO_TARGET_DIR",
+                    worktree::slot_target_dir(dir, worker_id),
+                )
+            })
+            .into_iter()
+            .collect()
+    }
+
+    fn run_compiler_check(&self, worktree_path: &Path, worker_id: u32) -> Result<(), String> {
         // Try cargo check first (Rust projects)
         let cargo_toml = worktree_path.join("Cargo.toml");
         if cargo_toml.exists() {
             let output = Command::new("cargo")
                 .args(["check", "--release", "--message-format=short"])
+                .envs(self.slot_env(worker_id))
                 .current_dir(worktree_path)
                 .output()
                 .map_err(|e| format!("failed to run cargo check: {e}"))?;
@@ -1498,6 +1762,7 @@ impl IntegrationQueue {
         &self,
         agent_config: &ResolvedAgentConfig,
         worktree_path: &Path,
+        worker_id: u32,
         prompt: &str,
     ) -> Result<Option<i32>, IntegrationError> {
         let args: Vec<String> = agent_config
@@ -1515,6 +1780,7 @@ impl IntegrationQueue {
         let output = Command::new(&agent_config.command)
             .args(&args)
             .envs(&agent_config.env)
+            .envs(self.slot_env(worker_id))
             .current_dir(worktree_path)
             .output()
             .map_err(|e| {
@@ -1567,26 +1833,87 @@ impl IntegrationQueue {
     /// Run the full test suite on main as a reconciliation check.
     ///
     /// This catches cross-task semantic bugs that pass individual integration
-    /// but fail when combined. If the test suite fails, the recently-integrated
-    /// bead IDs are returned as flagged for human review.
-    #[allow(dead_code)]
-    pub fn run_reconciliation(&self, tracker: &mut ReconciliationTracker) -> ReconciliationResult {
+    /// but fail when combined. Each failing test is re-run in isolation (see
+    /// `flaky::classify_failures`); if every failure is flaky, no beads are
+    /// blamed. Otherwise the recently-integrated bead IDs are returned as
+    /// flagged for human review.
+    pub fn run_reconciliation(
+        &self,
+        tracker: &mut ReconciliationTracker,
+        db_conn: &Connection,
+    ) -> ReconciliationResult {
         tracing::info!(
             count = tracker.count(),
             beads = ?tracker.recent_beads(),
             "running periodic reconciliation on main"
         );
 
-        let flagged_beads = tracker.recent_beads().to_vec();
+        let recent_beads = tracker.recent_beads().to_vec();
 
         // Run the test suite in the main repo
         let (passed, output) = self.run_test_suite(&self.repo_dir);
 
+        let report = if passed {
+            None
+        } else {
+            test_report::parse(&output)
+        };
+
+        let mut flaky_tests = Vec::new();
+        let mut broken_tests = Vec::new();
+        if let Some(report) = report.as_ref().filter(|r| !r.failures.is_empty()) {
+            for (name, verdict) in
+                flaky::classify_failures(db_conn, &self.reconciliation, report, &self.repo_dir)
+            {
+                match verdict {
+                    Verdict::Flaky => flaky_tests.push(name),
+                    Verdict::Broken => broken_tests.push(name),
+                }
+            }
+        }
+
+        // Blame recent beads unless every failure was identified as flaky
+        let only_flaky = !flaky_tests.is_empty() && broken_tests.is_empty();
+        let flagged_beads = if passed || only_flaky {
+            Vec::new()
+        } else {
+            recent_beads
+        };
+
+        // Record the non-flaky failures against each flagged bead
+        if let Some(report) = &report {
+            let failures: Vec<_> = report
+                .failures
+                .iter()
+                .filter(|f| !flaky_tests.contains(&f.name))
+                .cloned()
+                .collect();
+            for bead_id in &flagged_beads {
+                if let Err(e) = test_report::record(db_conn, bead_id, "reconciliation", &failures) {
+                    tracing::warn!(
+                        error = %e,
+                        bead_id,
+                        "failed to record reconciliation test failures"
+                    );
+                }
+            }
+        }
+
         if passed {
             tracing::info!("reconciliation passed — full test suite OK");
+        } else if only_flaky {
+            tracing::warn!(
+                flaky = ?flaky_tests,
+                "reconciliation failed only on flaky tests — not flagging beads"
+            );
         } else {
             tracing::warn!(
                 flagged = ?flagged_beads,
+                flaky = ?flaky_tests,
+                failures = %report
+                    .as_ref()
+                    .map(|r| r.summary(test_report::SUMMARY_MAX_FAILURES))
+                    .unwrap_or_default(),
                 "reconciliation FAILED — flagging {} beads for review",
                 flagged_beads.len()
             );
@@ -1598,12 +1925,14 @@ impl IntegrationQueue {
         ReconciliationResult {
             passed,
             output,
-            flagged_beads: if passed { Vec::new() } else { flagged_beads },
+            report,
+            flaky_tests,
+            broken_tests,
+            flagged_beads,
         }
     }
 
     /// Run the project's test suite. Returns (passed, output).
-    #[allow(dead_code)]
     fn run_test_suite(&self, dir: &Path) -> (bool, String) {
         // Try cargo test first (Rust projects)
         let cargo_toml = dir.join("Cargo.toml");
@@ -1749,8 +2078,15 @@ impl IntegrationQueue {
         })
     }
 
-    /// Record a failed integration in the database.
-    fn record_failure(&self, assignment_id: i64, db_conn: &Connection, reason: &str) {
+    /// Record a failed integration in the database, along with any failing
+    /// tests parsed from the failure reason.
+    fn record_failure(
+        &self,
+        assignment_id: i64,
+        bead_id: &str,
+        db_conn: &Connection,
+        reason: &str,
+    ) {
         if let Err(e) = db::update_worker_assignment_status(
             db_conn,
             assignment_id,
@@ -1759,6 +2095,11 @@ impl IntegrationQueue {
         ) {
             tracing::warn!(error = %e, "failed to update assignment status to integration_failed");
         }
+        if let Some(report) = test_report::parse(reason) {
+            if let Err(e) = test_report::record(db_conn, bead_id, "integration", &report.failures) {
+                tracing::warn!(error = %e, "failed to record integration test failures");
+            }
+        }
     }
 }
 
@@ -1828,6 +2169,25 @@ fn parse_comma_separated_globs(s: &str) -> Vec<String> {
         .collect()
 }
 
+/// Changes outside a bead's declared affected set, found at integration time.
+#[derive(Debug)]
+struct BoundaryViolation {
+    /// The assignment's declared affected globs.
+    declared: Vec<String>,
+    /// Changed files not covered by any declared glob.
+    outside: Vec<String>,
+    /// Predicted vs actual modules, recorded for `blacksmith arch`.
+    event: ExpansionEvent,
+}
+
+/// The declared globs plus every changed file they didn't cover, as a
+/// comma-separated affected set.
+fn widen_affected_globs(violation: &BoundaryViolation) -> String {
+    let mut globs = violation.declared.clone();
+    globs.extend(violation.outside.iter().cloned());
+    globs.join(",")
+}
+
 fn path_matches_any_glob(path: &str, declared_globs: &[String]) -> bool {
     declared_globs.iter().any(|glob| match Pattern::new(glob) {
         Ok(pattern) => pattern.matches(path),
@@ -2074,6 +2434,13 @@ mod tests {
         let wt_dir = repo_dir.join("worktrees");
         std::fs::create_dir_all(&wt_dir).unwrap();
 
+        // Two Rust modules on main: the bead declares only `auth`
+        for module in ["auth", "db"] {
+            std::fs::create_dir_all(repo_dir.join("src").join(module)).unwrap();
+            std::fs::write(repo_dir.join("src").join(module).join("mod.rs"), "").unwrap();
+        }
+        git_commit_all(repo_dir, "add modules");
+
         let db_path = repo_dir.join("test.db");
         let conn = db::open_or_create(&db_path).unwrap();
         let assignment_id = db::insert_worker_assignment(
@@ -2082,11 +2449,13 @@ mod tests {
             "beads-expand",
             "/tmp/wt-0",
             "completed",
-            Some("src/**"),
+            Some("src/auth/**"),
         )
         .unwrap();
 
         let wt_path = create_worktree_with_commit(repo_dir, &wt_dir, 0, "beads-expand");
+        std::fs::write(wt_path.join("src/db/mod.rs"), "pub fn query() {}\n").unwrap();
+        git_commit_all(&wt_path, "touch db");
         let queue = IntegrationQueue::new(repo_dir.to_path_buf(), "main".to_string());
         let mut cb = CircuitBreaker::new();
         let mut vcb = ValidationCircuitBreaker::new(2);
@@ -2108,9 +2477,263 @@ mod tests {
             .iter()
             .find(|e| e.task_id == "beads-expand")
             .expect("expected expansion event");
-        assert_eq!(event.predicted_modules, vec!["src/**".to_string()]);
-        assert!(event.actual_modules.iter().any(|p| p == "feature.txt"));
+        assert_eq!(event.predicted_modules, vec!["auth".to_string()]);
+        assert_eq!(event.actual_modules, vec!["db".to_string()]);
         assert!(event.expansion_reason.contains("feature.txt"));
+        assert!(event.expansion_reason.contains("src/db/mod.rs"));
+    }
+
+    fn git_commit_all(dir: &Path, message: &str) {
+        for args in [vec!["add", "-A"], vec!["commit", "-m", message]] {
+            StdCommand::new("git")
+                .args(&args)
+                .current_dir(dir)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_boundary_policy_block_refuses_integration() {
+        let dir = init_test_repo();
+        let repo_dir = dir.path();
+        let wt_dir = repo_dir.join("worktrees");
+        std::fs::create_dir_all(&wt_dir).unwrap();
+
+        let db_path = repo_dir.join("test.db");
+        let conn = db::open_or_create(&db_path).unwrap();
+        let assignment_id = db::insert_worker_assignment(
+            &conn,
+            0,
+            "beads-block",
+            "/tmp/wt-0",
+            "completed",
+            Some("src/**"),
+        )
+        .unwrap();
+
+        let main_before = StdCommand::new("git")
+            .args(["rev-parse", "main"])
+            .current_dir(repo_dir)
+            .output()
+            .unwrap()
+            .stdout;
+        let wt_path = create_worktree_with_commit(repo_dir, &wt_dir, 0, "beads-block");
+        let queue = IntegrationQueue::new(repo_dir.to_path_buf(), "main".to_string())
+            .with_boundary_policy(BoundaryPolicy::Block);
+        let mut cb = CircuitBreaker::new();
+        let mut vcb = ValidationCircuitBreaker::new(2);
+
+        let result = queue.integrate(
+            0,
+            assignment_id,
+            "beads-block",
+            &wt_path,
+            &conn,
+            None,
+            &mut cb,
+            &mut vcb,
+        );
+
+        assert!(!result.success);
+        let reason = result.failure_reason.unwrap();
+        assert!(reason.contains("boundary violation"), "{reason}");
+        assert!(reason.contains("feature.txt"), "{reason}");
+        assert!(cb.check_tripped("beads-block", &reason, &wt_path).is_some());
+
+        let main_after = StdCommand::new("git")
+            .args(["rev-parse", "main"])
+            .current_dir(repo_dir)
+            .output()
+            .unwrap()
+            .stdout;
+        assert_eq!(main_before, main_after, "main must not advance");
+
+        let wa = db::get_worker_assignment(&conn, assignment_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(wa.status, "integration_failed");
+        let events = expansion_event::get_recent(&conn, 10).unwrap();
+        assert!(events.iter().any(|e| e.task_id == "beads-block"));
+    }
+
+    #[test]
+    fn test_boundary_check_error_fails_only_under_block() {
+        for (policy, expect_success) in
+            [(BoundaryPolicy::Block, false), (BoundaryPolicy::Warn, true)]
+        {
+            let dir = init_test_repo();
+            let repo_dir = dir.path();
+            let wt_dir = repo_dir.join("worktrees");
+            std::fs::create_dir_all(&wt_dir).unwrap();
+
+            let conn = db::open_or_create(&repo_dir.join("test.db")).unwrap();
+            let assignment_id = db::insert_worker_assignment(
+                &conn,
+                0,
+                "beads-unchecked",
+                "/tmp/wt-0",
+                "completed",
+                Some("src/**"),
+            )
+            .unwrap();
+            // A non-text affected set makes the boundary check itself fail
+            conn.execute(
+                "UPDATE worker_assignments SET affected_globs = X'00' WHERE id = ?1",
+                [assignment_id],
+            )
+            .unwrap();
+
+            let wt_path = create_worktree_with_commit(repo_dir, &wt_dir, 0, "beads-unchecked");
+            let queue = IntegrationQueue::new(repo_dir.to_path_buf(), "main".to_string())
+                .with_boundary_policy(policy);
+            let mut cb = CircuitBreaker::new();
+            let mut vcb = ValidationCircuitBreaker::new(2);
+
+            let result = queue.integrate(
+                0,
+                assignment_id,
+                "beads-unchecked",
+                &wt_path,
+                &conn,
+                None,
+                &mut cb,
+                &mut vcb,
+            );
+
+            assert_eq!(result.success, expect_success, "{policy:?}");
+            if !expect_success {
+                let reason = result.failure_reason.unwrap();
+                assert!(reason.contains("boundary check failed"), "{reason}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_fitness_rule_violation_fails_integration() {
+        let dir = init_test_repo();
+        let repo_dir = dir.path();
+        let wt_dir = repo_dir.join("worktrees");
+        std::fs::create_dir_all(repo_dir.join("src/adapters")).unwrap();
+        std::fs::create_dir_all(repo_dir.join("src/coordinator")).unwrap();
+        std::fs::write(
+            repo_dir.join("src/main.rs"),
+            "mod adapters;\nmod coordinator;\nfn main() {}",
+        )
+        .unwrap();
+        std::fs::write(repo_dir.join("src/adapters/mod.rs"), "pub fn run() {}").unwrap();
+        std::fs::write(
+            repo_dir.join("src/coordinator/mod.rs"),
+            "pub struct Coordinator;",
+        )
+        .unwrap();
+        git_commit_all(repo_dir, "add modules");
+        std::fs::create_dir_all(&wt_dir).unwrap();
+
+        let db_path = repo_dir.join("test.db");
+        let conn = db::open_or_create(&db_path).unwrap();
+        let assignment_id =
+            db::insert_worker_assignment(&conn, 0, "beads-fit", "/tmp/wt-0", "completed", None)
+                .unwrap();
+
+        let wt_path = worktree::create(repo_dir, &wt_dir, 0, "beads-fit", "main").unwrap();
+        std::fs::write(
+            wt_path.join("src/adapters/mod.rs"),
+            "use crate::coordinator::Coordinator;\npub fn run() {}",
+        )
+        .unwrap();
+        git_commit_all(&wt_path, "adapters calls coordinator");
+
+        let queue = IntegrationQueue::new(repo_dir.to_path_buf(), "main".to_string())
+            .with_fitness_rules(vec![FitnessRule {
+                rule: "forbid_import".to_string(),
+                from: Some("adapters".to_string()),
+                to: Some("coordinator".to_string()),
+                module: None,
+                max: None,
+            }]);
+        let mut cb = CircuitBreaker::new();
+        let mut vcb = ValidationCircuitBreaker::new(2);
+
+        let result = queue.integrate(
+            0,
+            assignment_id,
+            "beads-fit",
+            &wt_path,
+            &conn,
+            None,
+            &mut cb,
+            &mut vcb,
+        );
+
+        assert!(!result.success);
+        let reason = result.failure_reason.unwrap();
+        assert!(reason.contains("fitness rules violated"), "{reason}");
+        assert!(
+            reason.contains("[forbid_import] src/adapters/mod.rs imports src/coordinator/mod.rs"),
+            "{reason}"
+        );
+
+        // The temporary base checkout is cleaned up.
+        let worktrees = StdCommand::new("git")
+            .args(["worktree", "list"])
+            .current_dir(repo_dir)
+            .output()
+            .unwrap()
+            .stdout;
+        assert!(!String::from_utf8_lossy(&worktrees).contains("blacksmith-fitness-"));
+    }
+
+    #[test]
+    fn test_boundary_policy_expand_widens_affected_set() {
+        let dir = init_test_repo();
+        let repo_dir = dir.path();
+        let wt_dir = repo_dir.join("worktrees");
+        std::fs::create_dir_all(&wt_dir).unwrap();
+
+        let db_path = repo_dir.join("test.db");
+        let conn = db::open_or_create(&db_path).unwrap();
+        let assignment_id = db::insert_worker_assignment(
+            &conn,
+            0,
+            "beads-widen",
+            "/tmp/wt-0",
+            "completed",
+            Some("src/**"),
+        )
+        .unwrap();
+
+        let wt_path = create_worktree_with_commit(repo_dir, &wt_dir, 0, "beads-widen");
+        let queue = IntegrationQueue::new(repo_dir.to_path_buf(), "main".to_string())
+            .with_boundary_policy(BoundaryPolicy::Expand);
+        let mut cb = CircuitBreaker::new();
+        let mut vcb = ValidationCircuitBreaker::new(2);
+
+        let result = queue.integrate(
+            0,
+            assignment_id,
+            "beads-widen",
+            &wt_path,
+            &conn,
+            None,
+            &mut cb,
+            &mut vcb,
+        );
+
+        assert!(result.success, "{result:?}");
+        let wa = db::get_worker_assignment(&conn, assignment_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(wa.affected_globs.as_deref(), Some("src/**,feature.txt"));
+    }
+
+    #[test]
+    fn test_circuit_breaker_trip_escalates_immediately() {
+        let mut cb = CircuitBreaker::new();
+        assert!(cb.trip("beads-x").is_tripped());
+        assert_eq!(cb.attempt_count("beads-x"), MAX_INTEGRATION_ATTEMPTS);
     }
 
     #[test]
@@ -2559,12 +3182,36 @@ mod tests {
         assert_eq!(notes, "Integration failed: merge conflict in main.rs");
     }
 
+    #[test]
+    fn test_tripped_failure_notes_summarize_test_failures() {
+        let tripped = TrippedFailure {
+            bead_id: "beads-abc".to_string(),
+            error_summary: "compiler fix failed after 3 attempts: \n\
+                running 2 tests\n\
+                test a::ok ... ok\n\
+                test a::bad ... FAILED\n\n\
+                ---- a::bad stdout ----\n\
+                thread 'a::bad' panicked at src/a.rs:9:5:\n\
+                boom\n\n\
+                test result: FAILED. 1 passed; 1 failed"
+                .to_string(),
+            worktree_path: PathBuf::from("/tmp/wt"),
+            attempts: 3,
+        };
+        assert_eq!(
+            tripped.failure_notes(),
+            "Integration failed: compiler fix failed after 3 attempts: \n\
+             1 test(s) failed, 1 passed (libtest):\n\
+             - a::bad (src/a.rs:9): boom"
+        );
+    }
+
     #[test]
     fn test_compiler_check_no_build_system_passes() {
         // When no Cargo.toml or tsconfig.json exists, compiler check should pass
         let dir = TempDir::new().unwrap();
         let queue = IntegrationQueue::new(dir.path().to_path_buf(), "main".to_string());
-        let result = queue.run_compiler_check(dir.path());
+        let result = queue.run_compiler_check(dir.path(), 0);
         assert!(result.is_ok(), "should pass when no build system detected");
     }
 
@@ -2591,7 +3238,7 @@ edition = "2021"
         .unwrap();
 
         let queue = IntegrationQueue::new(root.to_path_buf(), "main".to_string());
-        let result = queue.run_compiler_check(root);
+        let result = queue.run_compiler_check(root, 0);
         assert!(
             result.is_ok(),
             "valid Rust project should pass: {:?}",
@@ -2618,7 +3265,7 @@ edition = "2021"
         std::fs::write(src.join("lib.rs"), "fn hello() -> i32 { \"not an int\" }\n").unwrap();
 
         let queue = IntegrationQueue::new(root.to_path_buf(), "main".to_string());
-        let result = queue.run_compiler_check(root);
+        let result = queue.run_compiler_check(root, 0);
         assert!(result.is_err(), "should fail with compile error");
         let errors = result.unwrap_err();
         assert!(
@@ -2669,11 +3316,35 @@ edition = "2021"
             env: HashMap::new(),
         };
 
-        let result = queue.spawn_integration_agent_sync(&agent, repo_dir, "test errors");
+        let result = queue.spawn_integration_agent_sync(&agent, repo_dir, 0, "test errors");
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), Some(0));
     }
 
+    #[test]
+    fn test_spawn_integration_agent_uses_slot_target_dir() {
+        let dir = init_test_repo();
+        let repo_dir = dir.path();
+        let cache = repo_dir.join("target-cache");
+
+        let queue = IntegrationQueue::new(repo_dir.to_path_buf(), "main".to_string())
+            .with_target_cache(Some(cache.clone()));
+        let expected = worktree::slot_target_dir(&cache, 2);
+        let agent = crate::config::ResolvedAgentConfig {
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                format!("test \"$CARGO_TARGET_DIR\" = '{}'", expected.display()),
+            ],
+            adapter: None,
+            prompt_via: crate::config::PromptVia::Arg,
+            env: HashMap::new(),
+        };
+
+        let result = queue.spawn_integration_agent_sync(&agent, repo_dir, 2, "fix");
+        assert_eq!(result.unwrap(), Some(0));
+    }
+
     #[test]
     fn test_spawn_integration_agent_nonexistent_command() {
         let dir = init_test_repo();
@@ -2688,7 +3359,7 @@ edition = "2021"
             env: HashMap::new(),
         };
 
-        let result = queue.spawn_integration_agent_sync(&agent, repo_dir, "test");
+        let result = queue.spawn_integration_agent_sync(&agent, repo_dir, 0, "test");
         assert!(result.is_err(), "should fail with nonexistent command");
     }
 
@@ -2808,9 +3479,10 @@ edition = "2021"
             .unwrap();
 
         let queue = IntegrationQueue::new(dir.path().to_path_buf(), "main".to_string());
+        let conn = Connection::open_in_memory().unwrap();
         let mut tracker = ReconciliationTracker::new(1);
         tracker.record_success("beads-a");
-        let result = queue.run_reconciliation(&mut tracker);
+        let result = queue.run_reconciliation(&mut tracker, &conn);
         assert!(result.passed);
         assert!(result.flagged_beads.is_empty());
         assert_eq!(tracker.count(), 0); // should be reset
@@ -2852,7 +3524,8 @@ mod tests {
         let mut tracker = ReconciliationTracker::new(2);
         tracker.record_success("beads-x");
         tracker.record_success("beads-y");
-        let result = queue.run_reconciliation(&mut tracker);
+        let conn = Connection::open_in_memory().unwrap();
+        let result = queue.run_reconciliation(&mut tracker, &conn);
         assert!(result.passed);
         assert!(result.flagged_beads.is_empty());
     }
@@ -2891,13 +3564,74 @@ mod tests {
         tracker.record_success("beads-a");
         tracker.record_success("beads-b");
         tracker.record_success("beads-c");
-        let result = queue.run_reconciliation(&mut tracker);
+        let conn = db::open_or_create(&dir.path().join("test.db")).unwrap();
+        let result = queue.run_reconciliation(&mut tracker, &conn);
         assert!(!result.passed);
         assert_eq!(result.flagged_beads, vec!["beads-a", "beads-b", "beads-c"]);
+        assert_eq!(result.broken_tests, vec!["tests::test_fail"]);
+        assert!(result.flaky_tests.is_empty());
+        let recorded: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM test_failures
+                 WHERE source = 'reconciliation' AND test_name = 'tests::test_fail'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(recorded, 3);
         // Tracker should still be reset after failure
         assert_eq!(tracker.count(), 0);
     }
 
+    #[test]
+    fn test_reconciliation_flaky_failure_not_blamed() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+
+        // A test that fails on its first run and passes afterwards
+        std::fs::write(
+            root.join("Cargo.toml"),
+            r#"[package]
+name = "reconcile-flaky-test"
+version = "0.1.0"
+edition = "2021"
+"#,
+        )
+        .unwrap();
+        let src = root.join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(
+            src.join("lib.rs"),
+            r#"
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_flaky() {
+        let marker = concat!(env!("CARGO_MANIFEST_DIR"), "/ran-once");
+        if !std::path::Path::new(marker).exists() {
+            std::fs::write(marker, "").unwrap();
+            panic!("first run fails");
+        }
+    }
+}
+"#,
+        )
+        .unwrap();
+
+        let queue = IntegrationQueue::new(root.to_path_buf(), "main".to_string());
+        let mut tracker = ReconciliationTracker::new(2);
+        tracker.record_success("beads-a");
+        tracker.record_success("beads-b");
+        let db_path = dir.path().join("test.db");
+        let conn = db::open_or_create(&db_path).unwrap();
+        let result = queue.run_reconciliation(&mut tracker, &conn);
+        assert!(!result.passed);
+        assert_eq!(result.flaky_tests, vec!["tests::test_flaky"]);
+        assert!(result.broken_tests.is_empty());
+        assert!(result.flagged_beads.is_empty());
+        assert_eq!(flaky::flaky_tests(&conn, 20).unwrap().len(), 1);
+    }
+
     #[test]
     fn test_integration_with_compiler_check_no_build_system() {
         // When there's no build system, compiler check is skipped and integration succeeds
@@ -0,0 +1,348 @@
+//! Go frontend: `import` blocks and capitalized (exported) identifiers.
+//!
+//! Import paths under the module declared in the nearest `go.mod` resolve to
+//! every non-test `.go` file of the imported package directory. Go imports
+//! whole packages, so imports carry no symbol names.
+
+use std::path::{Path, PathBuf};
+
+use super::{code_lines, identifier, Definition, Import, LanguageFrontend, SourceIndex};
+use crate::public_api::Symbol;
+
+/// Top-level declaration keywords and the symbol kind they produce.
+const DECLARATIONS: &[(&str, &str)] = &[
+    ("func ", "fn"),
+    ("type ", "type"),
+    ("var ", "var"),
+    ("const ", "const"),
+];
+
+pub struct GoFrontend;
+
+impl LanguageFrontend for GoFrontend {
+    fn name(&self) -> &'static str {
+        "go"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["go"]
+    }
+
+    fn entry_points(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn imports(&self, file: &Path, source: &str, index: &SourceIndex) -> Vec<Import> {
+        let dir = file.parent().unwrap_or(Path::new(""));
+        let module = find_go_module(dir);
+        let mut imports = Vec::new();
+
+        for spec in import_paths(source) {
+            let package_dir = if spec.starts_with('.') {
+                Some(dir.join(&spec))
+            } else {
+                module.as_ref().and_then(|(root, path)| {
+                    if spec == *path {
+                        Some(root.clone())
+                    } else {
+                        spec.strip_prefix(&format!("{path}/"))
+                            .map(|rest| root.join(rest))
+                    }
+                })
+            };
+            let Some(package_dir) = package_dir else {
+                continue;
+            };
+            for target in index.files_in(&package_dir) {
+                if target.to_string_lossy().ends_with("_test.go") {
+                    continue;
+                }
+                imports.push(Import {
+                    target,
+                    symbols: Vec::new(),
+                    line: format!("import \"{spec}\""),
+                });
+            }
+        }
+        imports
+    }
+
+    fn exports(&self, source: &str, file_name: &str) -> Vec<Symbol> {
+        declarations(source)
+            .into_iter()
+            .filter(|d| d.name.starts_with(|c: char| c.is_uppercase()))
+            .map(|d| Symbol {
+                kind: d.kind.to_string(),
+                name: d.name,
+                signature: d.signature,
+                file: file_name.to_string(),
+            })
+            .collect()
+    }
+
+    fn definitions(&self, source: &str) -> Vec<Definition> {
+        declarations(source)
+            .into_iter()
+            .map(|d| Definition {
+                name: match d.receiver {
+                    Some(recv) => format!("{recv}.{}", d.name),
+                    None => d.name,
+                },
+                line: d.line,
+            })
+            .collect()
+    }
+}
+
+/// A top-level Go declaration.
+struct Declaration {
+    kind: &'static str,
+    name: String,
+    /// Receiver type for methods.
+    receiver: Option<String>,
+    signature: String,
+    line: usize,
+}
+
+/// Top-level declarations, including the entries of grouped
+/// `var (...)`, `const (...)` and `type (...)` blocks.
+fn declarations(source: &str) -> Vec<Declaration> {
+    let mut out = Vec::new();
+    let mut group: Option<&'static str> = None;
+    // Brace depth inside a group, so struct fields aren't read as entries.
+    let mut depth: i32 = 0;
+
+    for (i, line) in code_lines(source).iter().enumerate() {
+        let trimmed = line.trim();
+        if let Some(kind) = group {
+            if depth == 0 && trimmed.starts_with(')') {
+                group = None;
+            } else if depth == 0 {
+                if let Some(name) = identifier(trimmed) {
+                    out.push(Declaration {
+                        kind: type_kind(kind, trimmed),
+                        name,
+                        receiver: None,
+                        signature: trimmed.trim_end_matches('{').trim_end().to_string(),
+                        line: i,
+                    });
+                }
+            }
+            depth += trimmed.matches('{').count() as i32 - trimmed.matches('}').count() as i32;
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        for (keyword, kind) in DECLARATIONS {
+            let Some(rest) = trimmed.strip_prefix(keyword) else {
+                continue;
+            };
+            let rest = rest.trim_start();
+            if rest.starts_with('(') && *kind != "fn" {
+                group = Some(kind);
+                break;
+            }
+            let (receiver, rest) = if *kind == "fn" && rest.starts_with('(') {
+                // `func (s *Server) Start()`
+                let close = rest.find(')').unwrap_or(rest.len() - 1);
+                let recv = rest[1..close]
+                    .split_whitespace()
+                    .last()
+                    .map(|t| t.trim_start_matches('*').to_string());
+                (recv, rest[close + 1..].trim_start())
+            } else {
+                (None, rest)
+            };
+            if let Some(name) = identifier(rest) {
+                out.push(Declaration {
+                    kind: type_kind(kind, rest),
+                    name,
+                    receiver,
+                    signature: trimmed.trim_end_matches('{').trim_end().to_string(),
+                    line: i,
+                });
+            }
+            break;
+        }
+    }
+    out
+}
+
+/// Refine `type` declarations into `struct` / `interface`.
+fn type_kind(kind: &'static str, rest: &str) -> &'static str {
+    if kind != "type" {
+        return kind;
+    }
+    let mut words = rest.split_whitespace().skip(1);
+    match words.next() {
+        Some(w) if w.starts_with("struct") => "struct",
+        Some(w) if w.starts_with("interface") => "interface",
+        _ => "type",
+    }
+}
+
+/// Quoted import paths from `import "x"` lines and `import ( ... )` blocks.
+fn import_paths(source: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut in_block = false;
+    for line in code_lines(source) {
+        let trimmed = line.trim();
+        if in_block {
+            if trimmed.starts_with(')') {
+                in_block = false;
+            } else if let Some(path) = quoted(trimmed) {
+                paths.push(path);
+            }
+        } else if let Some(rest) = trimmed.strip_prefix("import") {
+            let rest = rest.trim_start();
+            if rest.starts_with('(') {
+                in_block = !rest.contains(')');
+                if let Some(path) = quoted(rest) {
+                    paths.push(path);
+                }
+            } else if let Some(path) = quoted(rest) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+fn quoted(s: &str) -> Option<String> {
+    let start = s.find(['"', '`'])?;
+    let quote = s[start..].chars().next()?;
+    let rest = &s[start + 1..];
+    let end = rest.find(quote)?;
+    Some(rest[..end].to_string())
+}
+
+/// The directory holding the nearest `go.mod` above `dir`, and its module path.
+fn find_go_module(dir: &Path) -> Option<(PathBuf, String)> {
+    dir.ancestors().find_map(|d| {
+        let content = std::fs::read_to_string(d.join("go.mod")).ok()?;
+        content.lines().find_map(|l| {
+            l.trim()
+                .strip_prefix("module ")
+                .map(|m| (d.to_path_buf(), m.trim().trim_matches('"').to_string()))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn resolves_module_imports_to_package_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        fs::write(root.join("go.mod"), "module example.com/app\n\ngo 1.22\n").unwrap();
+        let files: Vec<PathBuf> = [
+            "cmd/main.go",
+            "internal/db/db.go",
+            "internal/db/conn.go",
+            "internal/db/db_test.go",
+            "internal/auth/auth.go",
+        ]
+        .iter()
+        .map(|f| root.join(f))
+        .collect();
+        let idx = SourceIndex::new(&files);
+
+        let source = "\
+package main
+
+import (
+\t\"fmt\"
+\tdb \"example.com/app/internal/db\"
+\t// \"example.com/app/internal/gone\"
+)
+import \"example.com/app/internal/auth\"
+";
+        let imports = GoFrontend.imports(&root.join("cmd/main.go"), source, &idx);
+        let targets: Vec<PathBuf> = imports
+            .iter()
+            .map(|i| i.target.strip_prefix(root).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(
+            targets,
+            vec![
+                PathBuf::from("internal/db/conn.go"),
+                PathBuf::from("internal/db/db.go"),
+                PathBuf::from("internal/auth/auth.go"),
+            ]
+        );
+        assert!(imports.iter().all(|i| i.symbols.is_empty()));
+    }
+
+    #[test]
+    fn exports_are_capitalized_identifiers() {
+        let source = "\
+package db
+
+type Conn struct {
+\taddr string
+}
+
+type store interface{}
+
+func Open(addr string) (*Conn, error) {
+\treturn nil, nil
+}
+
+func (c *Conn) Close() error { return nil }
+
+func helper() {}
+
+const (
+\tMaxConns = 10
+\tminConns = 1
+)
+
+type (
+\tOptions struct {
+\t\tRetries int
+\t}
+)
+
+var ErrClosed = errors.New(\"closed\")
+";
+        let names: Vec<(String, String)> = GoFrontend
+            .exports(source, "db.go")
+            .into_iter()
+            .map(|s| (s.kind, s.name))
+            .collect();
+        let expected: Vec<(String, String)> = [
+            ("struct", "Conn"),
+            ("fn", "Open"),
+            ("fn", "Close"),
+            ("const", "MaxConns"),
+            ("struct", "Options"),
+            ("var", "ErrClosed"),
+        ]
+        .iter()
+        .map(|(k, n)| (k.to_string(), n.to_string()))
+        .collect();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn definitions_qualify_methods_with_receiver() {
+        let source = "type T struct{}\n\nfunc (t T) run() {}\n\nfunc main() {}\n";
+        let defs: Vec<(String, usize)> = GoFrontend
+            .definitions(source)
+            .into_iter()
+            .map(|d| (d.name, d.line))
+            .collect();
+        assert_eq!(
+            defs,
+            vec![
+                ("T".to_string(), 0),
+                ("T.run".to_string(), 2),
+                ("main".to_string(), 4),
+            ]
+        );
+    }
+}
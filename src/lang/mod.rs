@@ -0,0 +1,339 @@
+//! Language frontends for architecture analysis.
+//!
+//! Each frontend knows how to find the source files of one language, extract
+//! the files they import, the symbols they export, and their top-level
+//! definitions. `import_graph`, `module_detect`, `public_api`,
+//! `boundary_violation` and `god_file` dispatch on file extension, so a
+//! mixed-language repo is analyzed as one merged graph.
+
+pub mod go;
+pub mod python;
+pub mod rust;
+pub mod typescript;
+
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+use crate::public_api::Symbol;
+
+/// Directories never scanned for source files.
+const IGNORED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "vendor",
+    "dist",
+    "build",
+    "out",
+    "coverage",
+    "__pycache__",
+    "venv",
+];
+
+/// An import resolved to a file in the repo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    /// The imported file.
+    pub target: PathBuf,
+    /// Named symbols imported from it (empty for whole-module imports).
+    pub symbols: Vec<String>,
+    /// The import statement, for reporting.
+    pub line: String,
+}
+
+/// A top-level definition and the (0-based) line it starts on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    pub name: String,
+    pub line: usize,
+}
+
+/// Extracts imports, exports and definitions for one language.
+pub trait LanguageFrontend: Send + Sync {
+    /// Human-readable language name (e.g., "rust", "python").
+    fn name(&self) -> &'static str;
+
+    /// File extensions handled by this frontend, without the dot.
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// File names that act as a directory's entry point, in priority order.
+    fn entry_points(&self) -> &'static [&'static str];
+
+    /// Imports of `file` (with contents `source`) that resolve to files in `index`.
+    fn imports(&self, file: &Path, source: &str, index: &SourceIndex) -> Vec<Import>;
+
+    /// Symbols visible outside the file's module.
+    fn exports(&self, source: &str, file_name: &str) -> Vec<Symbol>;
+
+    /// All top-level definitions, public or not, in source order.
+    fn definitions(&self, source: &str) -> Vec<Definition>;
+}
+
+static FRONTENDS: [&dyn LanguageFrontend; 4] = [
+    &rust::RustFrontend,
+    &typescript::TypeScriptFrontend,
+    &python::PythonFrontend,
+    &go::GoFrontend,
+];
+
+/// The frontend handling `path`, by extension.
+pub fn for_path(path: &Path) -> Option<&'static dyn LanguageFrontend> {
+    let ext = path.extension()?.to_str()?;
+    FRONTENDS
+        .iter()
+        .copied()
+        .find(|f| f.extensions().contains(&ext))
+}
+
+/// Entry point file names across all languages, in priority order.
+pub fn entry_point_names() -> impl Iterator<Item = &'static str> {
+    FRONTENDS
+        .iter()
+        .flat_map(|f| f.entry_points().iter().copied())
+}
+
+/// Collect every source file a frontend can analyze, sorted.
+///
+/// Rust files are only taken from `src/` (so build scripts, tests and benches
+/// stay out of the graph); other languages are collected from the whole repo,
+/// skipping hidden and dependency/build directories.
+pub fn collect_source_files(repo_root: &Path) -> Vec<PathBuf> {
+    let src_root = repo_root.join("src");
+    let mut files = Vec::new();
+    collect_recursive(repo_root, &mut files);
+    files.retain(|f| match for_path(f) {
+        Some(frontend) if frontend.name() == "rust" => f.starts_with(&src_root),
+        Some(_) => true,
+        None => false,
+    });
+    files.sort();
+    files
+}
+
+fn collect_recursive(dir: &Path, files: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || IGNORED_DIRS.contains(&name.as_str()) {
+                continue;
+            }
+            collect_recursive(&path, files);
+        } else if for_path(&path).is_some() {
+            files.push(path);
+        }
+    }
+}
+
+/// The set of files being analyzed, used to resolve import specifiers.
+#[derive(Debug, Default)]
+pub struct SourceIndex {
+    files: HashSet<PathBuf>,
+}
+
+impl SourceIndex {
+    pub fn new(files: &[PathBuf]) -> Self {
+        Self {
+            files: files.iter().cloned().collect(),
+        }
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.files.contains(path)
+    }
+
+    /// The first candidate (after normalizing `.` and `..`) that is an indexed file.
+    pub fn first_existing(&self, candidates: impl IntoIterator<Item = PathBuf>) -> Option<PathBuf> {
+        candidates
+            .into_iter()
+            .map(|c| normalize(&c))
+            .find(|c| self.files.contains(c))
+    }
+
+    /// Indexed files directly inside `dir`.
+    pub fn files_in(&self, dir: &Path) -> Vec<PathBuf> {
+        let dir = normalize(dir);
+        let mut files: Vec<PathBuf> = self
+            .files
+            .iter()
+            .filter(|f| f.parent() == Some(dir.as_path()))
+            .cloned()
+            .collect();
+        files.sort();
+        files
+    }
+}
+
+/// Lexically resolve `.` and `..` components.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Extract the first identifier (alphanumeric + underscore, plus `$` for JS).
+pub fn identifier(s: &str) -> Option<String> {
+    let s = s.trim_start();
+    let end = s
+        .find(|c: char| !c.is_alphanumeric() && c != '_' && c != '$')
+        .unwrap_or(s.len());
+    if end == 0 {
+        return None;
+    }
+    Some(s[..end].to_string())
+}
+
+/// Split a comma-separated name list, dropping empties and surrounding
+/// brackets, parentheses and quotes.
+pub fn split_names(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|s| {
+            s.trim()
+                .trim_matches(|c| matches!(c, '(' | ')' | '[' | ']' | '{' | '}'))
+                .trim()
+                .trim_matches(|c| c == '"' || c == '\'')
+                .to_string()
+        })
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Remove a trailing line comment starting with `marker` (ignores markers inside quotes).
+pub fn strip_line_comment<'a>(line: &'a str, marker: &str) -> &'a str {
+    let mut quote: Option<char> = None;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' || c == '`' => quote = Some(c),
+            None if line[i..].starts_with(marker) => return &line[..i],
+            None => {}
+        }
+    }
+    line
+}
+
+/// Source lines of a C-like language (TypeScript, Go) with `//` and `/* */`
+/// comments blanked out. Line numbering is preserved.
+pub fn code_lines(source: &str) -> Vec<String> {
+    let mut in_block = false;
+    source
+        .lines()
+        .map(|line| {
+            let mut out = String::new();
+            let mut rest = line;
+            loop {
+                if in_block {
+                    match rest.find("*/") {
+                        Some(end) => {
+                            in_block = false;
+                            rest = &rest[end + 2..];
+                        }
+                        None => break,
+                    }
+                } else {
+                    let line_end = strip_line_comment(rest, "//").len();
+                    let block_start = strip_line_comment(rest, "/*").len();
+                    if block_start < line_end {
+                        out.push_str(&rest[..block_start]);
+                        in_block = true;
+                        rest = &rest[block_start + 2..];
+                    } else {
+                        out.push_str(&rest[..line_end]);
+                        break;
+                    }
+                }
+            }
+            out
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn for_path_dispatches_on_extension() {
+        assert_eq!(for_path(Path::new("src/a.rs")).unwrap().name(), "rust");
+        assert_eq!(
+            for_path(Path::new("web/a.tsx")).unwrap().name(),
+            "typescript"
+        );
+        assert_eq!(
+            for_path(Path::new("web/a.mjs")).unwrap().name(),
+            "typescript"
+        );
+        assert_eq!(for_path(Path::new("pkg/a.py")).unwrap().name(), "python");
+        assert_eq!(for_path(Path::new("cmd/a.go")).unwrap().name(), "go");
+        assert!(for_path(Path::new("README.md")).is_none());
+    }
+
+    #[test]
+    fn collect_source_files_skips_ignored_dirs_and_rust_outside_src() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        for path in [
+            "src/main.rs",
+            "build.rs",
+            "web/app.ts",
+            "web/node_modules/dep/index.js",
+            ".venv/lib/x.py",
+            "pkg/__init__.py",
+            "cmd/main.go",
+            "notes.md",
+        ] {
+            let full = root.join(path);
+            fs::create_dir_all(full.parent().unwrap()).unwrap();
+            fs::write(&full, "").unwrap();
+        }
+        let files: Vec<String> = collect_source_files(root)
+            .iter()
+            .map(|f| f.strip_prefix(root).unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                "cmd/main.go",
+                "pkg/__init__.py",
+                "src/main.rs",
+                "web/app.ts"
+            ]
+        );
+    }
+
+    #[test]
+    fn normalize_resolves_parent_components() {
+        assert_eq!(
+            normalize(Path::new("/r/web/auth/../db/./index.ts")),
+            PathBuf::from("/r/web/db/index.ts")
+        );
+    }
+
+    #[test]
+    fn code_lines_blanks_comments_and_keeps_line_numbers() {
+        let lines = code_lines("a /* x\ny */ b // c\n/* d */ e");
+        assert_eq!(lines, vec!["a ", " b ", " e"]);
+    }
+
+    #[test]
+    fn strip_line_comment_ignores_markers_in_strings() {
+        assert_eq!(
+            strip_line_comment("import x from 'a//b'; // c", "//"),
+            "import x from 'a//b'; "
+        );
+        assert_eq!(strip_line_comment("x = 1  # note", "#"), "x = 1  ");
+    }
+}
@@ -0,0 +1,444 @@
+//! Python frontend: `import`, `from ... import`, `__all__`.
+//!
+//! Absolute imports are resolved against the directory containing the
+//! importing file's top-level package (and the file's own directory, for
+//! script-style sibling imports); relative imports against the package itself.
+//! Without `__all__`, every top-level name not starting with `_` is exported.
+
+use std::path::{Path, PathBuf};
+
+use super::{
+    identifier, split_names, strip_line_comment, Definition, Import, LanguageFrontend, SourceIndex,
+};
+use crate::public_api::Symbol;
+
+pub struct PythonFrontend;
+
+impl LanguageFrontend for PythonFrontend {
+    fn name(&self) -> &'static str {
+        "python"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["py"]
+    }
+
+    fn entry_points(&self) -> &'static [&'static str] {
+        &["__init__.py"]
+    }
+
+    fn imports(&self, file: &Path, source: &str, index: &SourceIndex) -> Vec<Import> {
+        let dir = file.parent().unwrap_or(Path::new(""));
+        let roots = [package_root(dir, index), dir.to_path_buf()];
+        let mut imports = Vec::new();
+
+        for statement in logical_lines(source) {
+            let statement = statement.trim();
+            if let Some(rest) = statement.strip_prefix("import ") {
+                // `import a.b as c, d`
+                for item in rest.split(',') {
+                    let module = item.split(" as ").next().unwrap_or("").trim();
+                    let parts: Vec<&str> = module.split('.').collect();
+                    if let Some(target) = resolve(&roots, &parts, index) {
+                        imports.push(Import {
+                            target,
+                            symbols: Vec::new(),
+                            line: statement.to_string(),
+                        });
+                    }
+                }
+            } else if let Some(rest) = statement.strip_prefix("from ") {
+                // `from [.]*a.b import x, y as z`
+                let Some((module, names)) = rest.split_once(" import ") else {
+                    continue;
+                };
+                let module = module.trim();
+                let dots = module.chars().take_while(|c| *c == '.').count();
+                let parts: Vec<&str> = module[dots..]
+                    .split('.')
+                    .filter(|p| !p.is_empty())
+                    .collect();
+                let bases: Vec<PathBuf> = if dots == 0 {
+                    roots.to_vec()
+                } else {
+                    let mut base = dir.to_path_buf();
+                    for _ in 1..dots {
+                        base.pop();
+                    }
+                    vec![base]
+                };
+
+                let package_dir = bases
+                    .iter()
+                    .map(|b| parts.iter().fold(b.clone(), |acc, p| acc.join(p)))
+                    .find(|d| index.contains(&d.join("__init__.py")) || parts.is_empty());
+
+                let mut symbols = Vec::new();
+                for name in split_names(names) {
+                    let name = name.split(" as ").next().unwrap_or("").trim().to_string();
+                    if name.is_empty() || name == "*" {
+                        continue;
+                    }
+                    // `from pkg import submodule` imports a module, not a symbol.
+                    let submodule = package_dir
+                        .as_ref()
+                        .and_then(|d| resolve(std::slice::from_ref(d), &[name.as_str()], index));
+                    match submodule {
+                        Some(target) => imports.push(Import {
+                            target,
+                            symbols: Vec::new(),
+                            line: statement.to_string(),
+                        }),
+                        None => symbols.push(name),
+                    }
+                }
+
+                if let Some(target) = resolve(&bases, &parts, index) {
+                    imports.push(Import {
+                        target,
+                        symbols,
+                        line: statement.to_string(),
+                    });
+                }
+            }
+        }
+        imports
+    }
+
+    fn exports(&self, source: &str, file_name: &str) -> Vec<Symbol> {
+        let lines = logical_lines(source);
+        let defs: Vec<(String, &'static str, String)> =
+            lines.iter().filter_map(|l| top_level_def(l)).collect();
+
+        let all = lines
+            .iter()
+            .filter_map(|l| {
+                let rest = l.strip_prefix("__all__")?.trim_start();
+                let rest = rest.strip_prefix("+=").or_else(|| rest.strip_prefix('='))?;
+                Some(split_names(rest))
+            })
+            .reduce(|mut acc, more| {
+                acc.extend(more);
+                acc
+            });
+
+        let symbol = |name: String, kind: &str, signature: String| Symbol {
+            kind: kind.to_string(),
+            name,
+            signature,
+            file: file_name.to_string(),
+        };
+
+        match all {
+            Some(names) => names
+                .into_iter()
+                .map(|name| match defs.iter().find(|(n, _, _)| *n == name) {
+                    Some((_, kind, sig)) => symbol(name, kind, sig.clone()),
+                    None => symbol(name.clone(), "name", name),
+                })
+                .collect(),
+            None => defs
+                .into_iter()
+                .filter(|(name, _, _)| !name.starts_with('_'))
+                .map(|(name, kind, sig)| symbol(name, kind, sig))
+                .collect(),
+        }
+    }
+
+    fn definitions(&self, source: &str) -> Vec<Definition> {
+        // Logical lines join continuations, so map back via physical lines.
+        code_lines(source)
+            .iter()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                top_level_def(line).map(|(name, _, _)| Definition { name, line: i })
+            })
+            .collect()
+    }
+}
+
+/// `(name, kind, signature)` for a column-0 `def`, `class` or assignment.
+fn top_level_def(line: &str) -> Option<(String, &'static str, String)> {
+    if line.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let signature = line.trim_end().trim_end_matches(':').to_string();
+    for (prefix, kind) in [("async def ", "fn"), ("def ", "fn"), ("class ", "class")] {
+        if let Some(rest) = line.strip_prefix(prefix) {
+            return identifier(rest).map(|name| (name, kind, signature));
+        }
+    }
+    // `NAME = ...` or `NAME: Type = ...`
+    let name = identifier(line)?;
+    let after = line[name.len()..].trim_start();
+    let is_assignment = (after.starts_with('=') && !after.starts_with("=="))
+        || (after.starts_with(':') && after.contains('='));
+    if is_assignment && !name.starts_with("__") {
+        return Some((name, "const", signature));
+    }
+    None
+}
+
+/// The directory that contains the top-level package enclosing `dir`: walk up
+/// while the parent is still a package (has an indexed `__init__.py`).
+fn package_root(dir: &Path, index: &SourceIndex) -> PathBuf {
+    let mut root = dir.to_path_buf();
+    while index.contains(&root.join("__init__.py")) {
+        match root.parent() {
+            Some(parent) => root = parent.to_path_buf(),
+            None => break,
+        }
+    }
+    root
+}
+
+/// Resolve dotted module `parts` under the first matching base to
+/// `a/b.py` or `a/b/__init__.py`.
+fn resolve(bases: &[PathBuf], parts: &[&str], index: &SourceIndex) -> Option<PathBuf> {
+    if parts.iter().any(|p| p.is_empty()) {
+        return None;
+    }
+    let candidates = bases.iter().flat_map(|base| {
+        let path = parts.iter().fold(base.clone(), |acc, p| acc.join(p));
+        let module = parts
+            .last()
+            .map(|last| path.with_file_name(format!("{last}.py")));
+        module.into_iter().chain([path.join("__init__.py")])
+    });
+    index.first_existing(candidates)
+}
+
+/// Source lines with `#` comments and triple-quoted strings blanked out.
+/// Line numbering is preserved.
+fn code_lines(source: &str) -> Vec<String> {
+    let mut in_string: Option<&str> = None;
+    source
+        .lines()
+        .map(|line| {
+            let mut out = String::new();
+            let mut rest = line;
+            loop {
+                if let Some(delim) = in_string {
+                    match rest.find(delim) {
+                        Some(end) => {
+                            in_string = None;
+                            rest = &rest[end + 3..];
+                        }
+                        None => break,
+                    }
+                } else {
+                    let code = strip_line_comment(rest, "#");
+                    let start = ["\"\"\"", "'''"]
+                        .iter()
+                        .filter_map(|d| code.find(d).map(|i| (i, *d)))
+                        .min();
+                    match start {
+                        Some((i, delim)) => {
+                            out.push_str(&code[..i]);
+                            in_string = Some(delim);
+                            rest = &rest[i + 3..];
+                        }
+                        None => {
+                            out.push_str(code);
+                            break;
+                        }
+                    }
+                }
+            }
+            out
+        })
+        .collect()
+}
+
+/// Statements with bracketed and backslash continuations joined onto one line.
+fn logical_lines(source: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let mut depth: i32 = 0;
+    for line in code_lines(source) {
+        let (line, continued) = match line.trim_end().strip_suffix('\\') {
+            Some(l) => (l.to_string(), true),
+            None => (line, false),
+        };
+        if current.is_empty() {
+            current = line.clone();
+        } else {
+            current.push(' ');
+            current.push_str(line.trim());
+        }
+        depth += bracket_delta(&line);
+        if depth <= 0 && !continued {
+            depth = 0;
+            out.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        out.push(current);
+    }
+    out
+}
+
+/// Net open brackets on a line, ignoring brackets inside string literals.
+fn bracket_delta(line: &str) -> i32 {
+    let mut quote: Option<char> = None;
+    let mut delta = 0;
+    for c in line.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '(' | '[' | '{' => delta += 1,
+                ')' | ']' | '}' => delta -= 1,
+                _ => {}
+            },
+        }
+    }
+    delta
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(root: &Path, files: &[&str]) -> SourceIndex {
+        let files: Vec<PathBuf> = files.iter().map(|f| root.join(f)).collect();
+        SourceIndex::new(&files)
+    }
+
+    #[test]
+    fn resolves_absolute_relative_and_submodule_imports() {
+        let root = Path::new("/r");
+        let idx = index(
+            root,
+            &[
+                "app/__init__.py",
+                "app/main.py",
+                "app/auth/__init__.py",
+                "app/auth/tokens.py",
+                "app/db.py",
+                "helpers.py",
+            ],
+        );
+        let source = "\
+import os
+import app.db as database
+from app.auth import (
+    verify,  # trailing comment
+    tokens,
+)
+from .db import connect
+from . import db
+";
+        let imports = PythonFrontend.imports(&root.join("app/main.py"), source, &idx);
+        let found: Vec<(String, Vec<String>)> = imports
+            .iter()
+            .map(|i| {
+                (
+                    i.target
+                        .strip_prefix(root)
+                        .unwrap()
+                        .to_string_lossy()
+                        .to_string(),
+                    i.symbols.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("app/db.py".to_string(), vec![]),
+                ("app/auth/tokens.py".to_string(), vec![]),
+                (
+                    "app/auth/__init__.py".to_string(),
+                    vec!["verify".to_string()]
+                ),
+                ("app/db.py".to_string(), vec!["connect".to_string()]),
+                ("app/db.py".to_string(), vec![]),
+                ("app/__init__.py".to_string(), vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn exports_follow_dunder_all() {
+        let source = "\
+__all__ = [
+    \"connect\",
+    \"Pool\",
+]
+
+class Pool:
+    pass
+
+def connect(url):
+    return Pool()
+
+def disconnect():
+    pass
+";
+        let names: Vec<(String, String)> = PythonFrontend
+            .exports(source, "db.py")
+            .into_iter()
+            .map(|s| (s.kind, s.name))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("fn".to_string(), "connect".to_string()),
+                ("class".to_string(), "Pool".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn exports_without_all_skip_private_names() {
+        let source = "\
+import os
+
+MAX_RETRIES = 3
+_cache = {}
+
+def _helper():
+    pass
+
+async def fetch(url):
+    x = 1
+    return x
+";
+        let names: Vec<String> = PythonFrontend
+            .exports(source, "net.py")
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, vec!["MAX_RETRIES", "fetch"]);
+    }
+
+    #[test]
+    fn definitions_ignore_docstrings_and_comments() {
+        let source = "\
+\"\"\"Module docs.
+
+def not_a_function():
+\"\"\"
+
+# def commented():
+
+def real():
+    pass
+
+class Thing:
+    def method(self):
+        pass
+";
+        let defs: Vec<(String, usize)> = PythonFrontend
+            .definitions(source)
+            .into_iter()
+            .map(|d| (d.name, d.line))
+            .collect();
+        assert_eq!(
+            defs,
+            vec![("real".to_string(), 7), ("Thing".to_string(), 10)]
+        );
+    }
+}
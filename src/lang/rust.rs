@@ -0,0 +1,76 @@
+//! Rust frontend: `use crate::`, `mod` and `pub` items.
+//!
+//! Delegates to the original Rust parsers in `import_graph`, `public_api` and
+//! `god_file`.
+
+use std::path::{Path, PathBuf};
+
+use super::{Definition, Import, LanguageFrontend, SourceIndex};
+use crate::public_api::Symbol;
+
+pub struct RustFrontend;
+
+impl LanguageFrontend for RustFrontend {
+    fn name(&self) -> &'static str {
+        "rust"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["rs"]
+    }
+
+    fn entry_points(&self) -> &'static [&'static str] {
+        &["mod.rs", "lib.rs", "main.rs"]
+    }
+
+    fn imports(&self, file: &Path, source: &str, _index: &SourceIndex) -> Vec<Import> {
+        let src_root = src_root(file);
+        crate::import_graph::rust_imports(file, source, &src_root)
+            .into_iter()
+            .map(|target| Import {
+                target,
+                symbols: Vec::new(),
+                line: String::new(),
+            })
+            .collect()
+    }
+
+    fn exports(&self, source: &str, file_name: &str) -> Vec<Symbol> {
+        crate::public_api::extract_symbols_from_source(source, file_name)
+    }
+
+    fn definitions(&self, source: &str) -> Vec<Definition> {
+        crate::god_file::extract_all_defined_symbols(source, "")
+            .into_iter()
+            .map(|s| Definition {
+                name: s.name,
+                line: s.start_line,
+            })
+            .collect()
+    }
+}
+
+/// The crate's `src/` directory: the nearest ancestor named `src`, falling
+/// back to the file's own directory.
+fn src_root(file: &Path) -> PathBuf {
+    file.ancestors()
+        .skip(1)
+        .find(|dir| dir.file_name().is_some_and(|n| n == "src"))
+        .or_else(|| file.parent())
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn src_root_is_nearest_src_ancestor() {
+        assert_eq!(
+            src_root(Path::new("/r/src/adapters/claude.rs")),
+            PathBuf::from("/r/src")
+        );
+        assert_eq!(src_root(Path::new("/r/lib/a.rs")), PathBuf::from("/r/lib"));
+    }
+}
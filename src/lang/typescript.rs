@@ -0,0 +1,377 @@
+//! TypeScript / JavaScript frontend: `import`, `export`, `require()`.
+//!
+//! Only relative specifiers (`./x`, `../x`) are resolved; package imports
+//! point outside the repo and are ignored.
+
+use std::path::{Path, PathBuf};
+
+use super::{code_lines, identifier, Definition, Import, LanguageFrontend, SourceIndex};
+use crate::public_api::Symbol;
+
+/// Extensions tried when resolving an extensionless specifier.
+const RESOLVE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+/// Declaration keywords and the symbol kind they produce.
+const DECLARATIONS: &[(&str, &str)] = &[
+    ("function* ", "fn"),
+    ("function ", "fn"),
+    ("class ", "class"),
+    ("interface ", "interface"),
+    ("type ", "type"),
+    ("const enum ", "enum"),
+    ("enum ", "enum"),
+    ("const ", "const"),
+    ("let ", "var"),
+    ("var ", "var"),
+    ("namespace ", "namespace"),
+];
+
+/// Modifiers that may precede a declaration keyword.
+const MODIFIERS: &[&str] = &["export ", "default ", "declare ", "abstract ", "async "];
+
+pub struct TypeScriptFrontend;
+
+impl LanguageFrontend for TypeScriptFrontend {
+    fn name(&self) -> &'static str {
+        "typescript"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ts", "tsx", "js", "jsx", "mjs", "cjs"]
+    }
+
+    fn entry_points(&self) -> &'static [&'static str] {
+        &["index.ts", "index.tsx", "index.js", "index.jsx"]
+    }
+
+    fn imports(&self, file: &Path, source: &str, index: &SourceIndex) -> Vec<Import> {
+        let dir = file.parent().unwrap_or(Path::new(""));
+        let mut imports = Vec::new();
+        for statement in statements(source) {
+            for spec in specifiers(&statement) {
+                if !spec.starts_with('.') {
+                    continue;
+                }
+                if let Some(target) = index.first_existing(candidates(&dir.join(&spec))) {
+                    imports.push(Import {
+                        target,
+                        symbols: imported_names(&statement),
+                        line: statement.clone(),
+                    });
+                }
+            }
+        }
+        imports
+    }
+
+    fn exports(&self, source: &str, file_name: &str) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+        for statement in statements(source) {
+            let Some(rest) = statement.strip_prefix("export ") else {
+                continue;
+            };
+            let rest = rest.trim_start();
+            let symbol = |kind: &str, name: String| Symbol {
+                kind: kind.to_string(),
+                name,
+                signature: statement.trim_end_matches('{').trim_end().to_string(),
+                file: file_name.to_string(),
+            };
+
+            let list = rest.strip_prefix("type ").unwrap_or(rest).trim_start();
+
+            if rest.starts_with("default") {
+                symbols.push(symbol("default", "default".to_string()));
+            } else if list.starts_with('{') {
+                // `export { a, b as c }` and `export { x } from './y'`
+                for name in brace_names(list) {
+                    symbols.push(symbol("export", name.exported));
+                }
+            } else if let Some(after) = rest.strip_prefix("* as ") {
+                if let Some(name) = identifier(after) {
+                    symbols.push(symbol("namespace", name));
+                }
+            } else if let Some((kind, name)) = declaration(rest) {
+                symbols.push(symbol(kind, name));
+            }
+        }
+        symbols
+    }
+
+    fn definitions(&self, source: &str) -> Vec<Definition> {
+        code_lines(source)
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| !line.starts_with(char::is_whitespace))
+            .filter_map(|(i, line)| declaration(line).map(|(_, name)| Definition { name, line: i }))
+            .collect()
+    }
+}
+
+/// Parse a declaration after stripping modifiers: `(kind, name)`.
+fn declaration(line: &str) -> Option<(&'static str, String)> {
+    let mut rest = line.trim();
+    while let Some(m) = MODIFIERS.iter().find(|m| rest.starts_with(**m)) {
+        rest = rest[m.len()..].trim_start();
+    }
+    for (keyword, kind) in DECLARATIONS {
+        if let Some(after) = rest.strip_prefix(keyword) {
+            return identifier(after).map(|name| (*kind, name));
+        }
+    }
+    None
+}
+
+/// Import/export statements, with multi-line `{ ... }` clauses joined onto one line.
+fn statements(source: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut pending: Option<String> = None;
+    for line in code_lines(source) {
+        let trimmed = line.trim();
+        if let Some(mut stmt) = pending.take() {
+            stmt.push(' ');
+            stmt.push_str(trimmed);
+            if trimmed.contains('}') {
+                out.push(stmt);
+            } else {
+                pending = Some(stmt);
+            }
+            continue;
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+        let is_module_stmt = trimmed.starts_with("import") || trimmed.starts_with("export ");
+        if is_module_stmt
+            && trimmed.contains('{')
+            && !trimmed.contains('}')
+            && !trimmed.contains('(')
+        {
+            pending = Some(trimmed.to_string());
+        } else {
+            out.push(trimmed.to_string());
+        }
+    }
+    out.extend(pending);
+    out
+}
+
+/// Module specifiers referenced by a statement: `from '...'`, side-effect
+/// `import '...'`, `require('...')` and dynamic `import('...')`.
+fn specifiers(statement: &str) -> Vec<String> {
+    let mut specs = Vec::new();
+    if statement.starts_with("import") || statement.starts_with("export ") {
+        if let Some(pos) = statement.rfind("from") {
+            if let Some(spec) = quoted(&statement[pos + 4..]) {
+                specs.push(spec);
+            }
+        } else if let Some(after) = statement.strip_prefix("import") {
+            if let Some(spec) = quoted(after).filter(|_| {
+                let after = after.trim_start();
+                after.starts_with('\'') || after.starts_with('"')
+            }) {
+                specs.push(spec);
+            }
+        }
+    }
+    for call in ["require(", "import("] {
+        let mut rest = statement;
+        while let Some(pos) = rest.find(call) {
+            rest = &rest[pos + call.len()..];
+            if let Some(spec) = quoted(rest).filter(|_| {
+                let r = rest.trim_start();
+                r.starts_with('\'') || r.starts_with('"') || r.starts_with('`')
+            }) {
+                specs.push(spec);
+            }
+        }
+    }
+    specs
+}
+
+/// The first quoted string in `s`.
+fn quoted(s: &str) -> Option<String> {
+    let start = s.find(['\'', '"', '`'])?;
+    let quote = s[start..].chars().next()?;
+    let rest = &s[start + 1..];
+    let end = rest.find(quote)?;
+    Some(rest[..end].to_string())
+}
+
+/// Files a relative specifier may refer to, in resolution order.
+fn candidates(base: &Path) -> Vec<PathBuf> {
+    let base_str = base.to_string_lossy();
+    let mut out = vec![base.to_path_buf()];
+    // ESM TypeScript imports `./x.js` for `./x.ts`.
+    for js in [".js", ".jsx", ".mjs"] {
+        if let Some(stem) = base_str.strip_suffix(js) {
+            out.push(PathBuf::from(format!("{stem}.ts")));
+            out.push(PathBuf::from(format!("{stem}.tsx")));
+        }
+    }
+    for ext in RESOLVE_EXTENSIONS {
+        out.push(PathBuf::from(format!("{base_str}.{ext}")));
+    }
+    for entry in TypeScriptFrontend.entry_points() {
+        out.push(base.join(entry));
+    }
+    out
+}
+
+/// A name in an `{ a, b as c }` clause.
+struct BraceName {
+    /// Name in the module being imported from / declared in.
+    local: String,
+    /// Name it is exported as.
+    exported: String,
+}
+
+fn brace_names(s: &str) -> Vec<BraceName> {
+    let Some(start) = s.find('{') else {
+        return Vec::new();
+    };
+    let end = s[start..].find('}').map(|e| start + e).unwrap_or(s.len());
+    s[start + 1..end]
+        .split(',')
+        .filter_map(|item| {
+            let item = item.trim();
+            let item = item.strip_prefix("type ").unwrap_or(item);
+            let mut parts = item.split(" as ");
+            let local = identifier(parts.next()?)?;
+            let exported = parts
+                .next()
+                .and_then(identifier)
+                .unwrap_or_else(|| local.clone());
+            Some(BraceName { local, exported })
+        })
+        .collect()
+}
+
+/// Names an `import ... from` or `export { ... } from` statement takes from
+/// the target module. Default imports are reported as `default`; namespace
+/// imports (`* as ns`) take the whole module and report nothing.
+fn imported_names(statement: &str) -> Vec<String> {
+    let Some(from) = statement.rfind(" from") else {
+        return Vec::new();
+    };
+    let clause = if let Some(rest) = statement.strip_prefix("import") {
+        rest[..from - "import".len()].trim()
+    } else if let Some(rest) = statement.strip_prefix("export ") {
+        rest[..from - "export ".len()].trim()
+    } else {
+        return Vec::new();
+    };
+    let clause = clause.strip_prefix("type ").unwrap_or(clause);
+
+    let mut names = Vec::new();
+    let head = clause.split('{').next().unwrap_or("");
+    for part in head.split(',') {
+        let part = part.trim();
+        if !part.is_empty() && !part.starts_with('*') {
+            names.push("default".to_string());
+        }
+    }
+    names.extend(brace_names(clause).into_iter().map(|n| n.local));
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(files: &[&str]) -> SourceIndex {
+        let files: Vec<PathBuf> = files.iter().map(PathBuf::from).collect();
+        SourceIndex::new(&files)
+    }
+
+    #[test]
+    fn resolves_relative_imports_with_extensions_and_index_files() {
+        let idx = index(&[
+            "/r/web/app.ts",
+            "/r/web/auth/index.ts",
+            "/r/web/db.tsx",
+            "/r/web/util.js",
+            "/r/web/legacy.ts",
+        ]);
+        let source = "\
+import { login } from './auth';
+import Db, { connect as open } from \"./db\";
+import * as util from './util.js';
+import './legacy.js';
+import React from 'react';
+// import { gone } from './gone';
+const x = require('./util');
+";
+        let imports = TypeScriptFrontend.imports(Path::new("/r/web/app.ts"), source, &idx);
+        let targets: Vec<&str> = imports.iter().map(|i| i.target.to_str().unwrap()).collect();
+        assert_eq!(
+            targets,
+            vec![
+                "/r/web/auth/index.ts",
+                "/r/web/db.tsx",
+                "/r/web/util.js",
+                "/r/web/legacy.ts",
+                "/r/web/util.js",
+            ]
+        );
+        assert_eq!(imports[0].symbols, vec!["login"]);
+        assert_eq!(imports[1].symbols, vec!["default", "connect"]);
+        assert!(imports[2].symbols.is_empty());
+    }
+
+    #[test]
+    fn joins_multi_line_import_clauses() {
+        let idx = index(&["/r/src/a.ts", "/r/src/b.ts"]);
+        let source = "import {\n  one,\n  type Two,\n} from '../src/b';\n";
+        let imports = TypeScriptFrontend.imports(Path::new("/r/src/a.ts"), source, &idx);
+        assert_eq!(imports.len(), 1);
+        assert_eq!(imports[0].symbols, vec!["one", "Two"]);
+    }
+
+    #[test]
+    fn extracts_exports() {
+        let source = "\
+export function login() {}
+export async function logout() {}
+export default class Session {}
+export const TIMEOUT = 5;
+export interface User {}
+export type Id = string;
+export enum Role { Admin }
+export { helper, inner as renamed } from './helpers';
+export * as models from './models';
+export * from './all';
+function hidden() {}
+";
+        let names: Vec<(String, String)> = TypeScriptFrontend
+            .exports(source, "auth.ts")
+            .into_iter()
+            .map(|s| (s.kind, s.name))
+            .collect();
+        let expected = [
+            ("fn", "login"),
+            ("fn", "logout"),
+            ("default", "default"),
+            ("const", "TIMEOUT"),
+            ("interface", "User"),
+            ("type", "Id"),
+            ("enum", "Role"),
+            ("export", "helper"),
+            ("export", "renamed"),
+            ("namespace", "models"),
+        ];
+        let expected: Vec<(String, String)> = expected
+            .iter()
+            .map(|(k, n)| (k.to_string(), n.to_string()))
+            .collect();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn definitions_are_top_level_only() {
+        let source = "export class A {\n  method() {}\n}\nfunction b() {\n  const inner = 1;\n}\nconst c = 2;\n";
+        let defs = TypeScriptFrontend.definitions(source);
+        let names: Vec<(&str, usize)> = defs.iter().map(|d| (d.name.as_str(), d.line)).collect();
+        assert_eq!(names, vec![("A", 0), ("b", 3), ("c", 6)]);
+    }
+}
@@ -1,6 +1,10 @@
 mod adapters;
+mod arch_history;
+mod arch_review;
+mod archive;
 mod boundary_violation;
 mod brief;
+mod budget;
 mod circular_dep;
 mod commit;
 mod compress;
@@ -9,32 +13,48 @@ mod coordinator;
 mod cycle_detect;
 mod data_dir;
 mod db;
+mod dedup;
 mod defaults;
+mod epoch;
 mod estimation;
 mod expansion_event;
+mod experiment;
 mod fan_in;
 mod finish;
+mod fitness;
+mod flaky;
+mod gate_result;
+mod gates;
 mod gc;
 mod god_file;
+mod handoff;
 mod hooks;
+mod impact;
 mod import_graph;
 mod improve;
 mod ingest;
 mod init;
 mod integrator;
+mod lang;
 mod metrics;
 mod metrics_cmd;
 mod migrate;
+mod mining;
 mod module_detect;
+mod planning;
 mod pool;
 mod preflight;
+mod pricing;
 mod progress;
 mod prompt;
 mod public_api;
 mod ratelimit;
 mod retention;
 mod retry;
+mod rules;
+mod salvage;
 mod scheduler;
+mod schema;
 #[cfg(feature = "serve")]
 mod serve;
 mod session;
@@ -45,6 +65,8 @@ mod skills;
 mod status;
 mod structural_metrics;
 mod task_manifest;
+mod test_report;
+mod tool_call;
 mod watchdog;
 mod worktree;
 
@@ -176,9 +198,48 @@ enum Commands {
         /// Output as JSON instead of human-readable text
         #[arg(long)]
         json: bool,
+        #[command(subcommand)]
+        action: Option<ArchAction>,
     },
     /// Run preflight environment checks
     Preflight,
+    /// Print current loop state and exit (same as --status)
+    Status,
+    /// Export or import run history as a portable bundle
+    Archive {
+        #[command(subcommand)]
+        action: ArchiveAction,
+    },
+    /// Compare prompt variants of the configured A/B experiment
+    Experiment {
+        #[command(subcommand)]
+        action: ExperimentAction,
+    },
+    /// Inspect or migrate the metrics database schema
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+    /// Inspect test health recorded by reconciliation
+    Tests {
+        #[command(subcommand)]
+        action: TestsAction,
+    },
+    /// Inspect and recover work archived from failed worktrees
+    Salvage {
+        #[command(subcommand)]
+        action: SalvageAction,
+    },
+    /// Mark sprint boundaries and report what landed in between
+    Epoch {
+        #[command(subcommand)]
+        action: EpochAction,
+    },
+    /// Review child-bead plans proposed for oversized beads
+    Plan {
+        #[command(subcommand)]
+        action: PlanAction,
+    },
     /// Close a bead with quality gates (replaces bd-finish.sh)
     Finish {
         /// Bead ID to close (e.g. simple-agent-harness-abc)
@@ -253,6 +314,163 @@ enum MetricsAction {
     },
     /// Show per-bead timing report
     Beads,
+    /// Show quality gate failure rates and durations from `finish`
+    Gates {
+        /// Only consider the N most recent gate results (default: all)
+        #[arg(long, default_value = "0")]
+        last: u32,
+    },
+    /// Show the most used, slowest and most error-prone tools and commands
+    Tools {
+        /// Only consider the N most recent sessions (default: all)
+        #[arg(long, default_value = "0")]
+        last: u32,
+        /// Output as JSON instead of tables
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ArchiveAction {
+    /// Write the database, session files and config to a single zstd bundle
+    Export {
+        /// Bundle file to write
+        file: PathBuf,
+    },
+    /// Merge a bundle into this data directory, renumbering sessions
+    Import {
+        /// Bundle file to read
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ExperimentAction {
+    /// Compare success rate, turns, cost and integration iterations per variant
+    Report {
+        /// Experiment to report on (default: [experiment] name)
+        #[arg(long)]
+        name: Option<String>,
+        /// Output as JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum DbAction {
+    /// Show the schema version and pending migrations
+    Status,
+    /// Back up the database and apply pending migrations
+    Migrate,
+}
+
+#[derive(Subcommand, Debug)]
+enum TestsAction {
+    /// List tests that passed on isolated re-run after failing reconciliation
+    Flaky {
+        /// Number of recent runs per test to consider (default: from config)
+        #[arg(long)]
+        window: Option<u32>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ArchAction {
+    /// Run an architecture review now and file or queue refactor proposals
+    Review,
+    /// List refactor proposals awaiting approval
+    Proposals {
+        /// Include filed and rejected proposals
+        #[arg(long)]
+        all: bool,
+    },
+    /// Approve a proposal and file it as a refactor bead
+    Approve {
+        /// Proposal ID
+        id: i64,
+    },
+    /// Reject a proposal so its module is not proposed again until the next integration
+    Reject {
+        /// Proposal ID
+        id: i64,
+    },
+    /// Show how architecture metrics moved over recent integrations
+    Trend {
+        /// Number of most recent integrations to show
+        #[arg(long, default_value = "10")]
+        last: u32,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum EpochAction {
+    /// Open a new epoch at the current HEAD
+    Start {
+        /// Epoch name (default: epoch-N)
+        name: Option<String>,
+    },
+    /// Close the open epoch at the current HEAD
+    Close,
+    /// QA digest of what was integrated during an epoch
+    Report {
+        /// Epoch name (default: the most recent epoch)
+        name: Option<String>,
+        /// Output as JSON instead of Markdown
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum PlanAction {
+    /// List plans awaiting approval
+    List {
+        /// Include filed, rejected, declined and failed plans
+        #[arg(long)]
+        all: bool,
+    },
+    /// Show a plan's proposed child beads
+    Show {
+        /// Plan ID
+        id: i64,
+    },
+    /// Approve a plan and file its child beads
+    Approve {
+        /// Plan ID
+        id: i64,
+    },
+    /// Reject a plan so the bead is coded as-is
+    Reject {
+        /// Plan ID
+        id: i64,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SalvageAction {
+    /// List salvaged worktrees
+    List {
+        /// Include discarded entries
+        #[arg(long)]
+        all: bool,
+    },
+    /// Show the commits and diffstat salvaged for a bead
+    Show {
+        /// Bead ID
+        bead_id: String,
+    },
+    /// Apply a bead's salvaged commits onto the current branch
+    Apply {
+        /// Bead ID
+        bead_id: String,
+    },
+    /// Delete a bead's salvage branch and patch
+    Discard {
+        /// Bead ID
+        bead_id: String,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -324,10 +542,22 @@ enum ImproveAction {
         /// Comma-separated tags
         #[arg(long)]
         tags: Option<String>,
+
+        /// Observation metric this improvement should move (e.g. turns.narration_only)
+        #[arg(long)]
+        metric: Option<String>,
+
+        /// Which way the metric should move: below (lower is better) or above
+        #[arg(long, default_value = "below", requires = "metric")]
+        direction: String,
+
+        /// Add even if it looks like a duplicate of an existing improvement
+        #[arg(long)]
+        force: bool,
     },
     /// List improvements with optional filters
     List {
-        /// Filter by status (open, promoted, dismissed, revisit, validated)
+        /// Filter by status (draft, open, promoted, dismissed, revisit, validated)
         #[arg(long)]
         status: Option<String>,
 
@@ -358,6 +588,14 @@ enum ImproveAction {
         /// New context text
         #[arg(long)]
         context: Option<String>,
+
+        /// New target metric (e.g. turns.narration_only)
+        #[arg(long)]
+        metric: Option<String>,
+
+        /// Which way the target metric should move: below or above
+        #[arg(long, default_value = "below", requires = "metric")]
+        direction: String,
     },
     /// Promote an improvement (shorthand for --status=promoted)
     Promote {
@@ -365,6 +603,46 @@ enum ImproveAction {
         #[arg(name = "REF")]
         ref_id: String,
     },
+    /// Take a promoted rule back out of the prompt (status becomes revisit)
+    Demote {
+        /// Improvement ref (e.g. R1, R42)
+        #[arg(name = "REF")]
+        ref_id: String,
+
+        /// Why the rule is being demoted (stored in meta JSON and rule history)
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Fold a duplicate improvement into another (the duplicate is dismissed)
+    Merge {
+        /// Improvement to keep
+        #[arg(name = "KEEP")]
+        keep: String,
+
+        /// Duplicate to fold into KEEP
+        #[arg(name = "DUPLICATE")]
+        duplicate: String,
+    },
+    /// Mine recent session traces for recurring failures
+    Mine {
+        /// Number of recent sessions to scan
+        #[arg(long, default_value_t = 50)]
+        last: u32,
+
+        /// Number of top clusters to show or file
+        #[arg(long, default_value_t = 5)]
+        top: usize,
+
+        /// File the top clusters as draft improvements
+        #[arg(long)]
+        file: bool,
+    },
+    /// Show the revision history of promoted rules
+    History {
+        /// Limit to one improvement ref
+        #[arg(name = "REF")]
+        ref_id: Option<String>,
+    },
     /// Dismiss an improvement (shorthand for --status=dismissed)
     Dismiss {
         /// Improvement ref (e.g. R1, R42)
@@ -387,7 +665,7 @@ enum ProgressAction {
     /// Add a progress entry (supports multiline markdown)
     Add {
         /// Bead ID for this entry (optional, but recommended)
-        #[arg(long)]
+        #[arg(long, alias = "bead")]
         bead_id: Option<String>,
 
         /// Progress markdown as a single argument
@@ -401,6 +679,27 @@ enum ProgressAction {
         /// Read progress markdown from a file path
         #[arg(long)]
         file: Option<PathBuf>,
+
+        /// Something this session finished (repeatable; makes the entry a
+        /// structured handoff for the bead's next session)
+        #[arg(long)]
+        done: Vec<String>,
+
+        /// Something still left to do (repeatable)
+        #[arg(long)]
+        remaining: Vec<String>,
+
+        /// Something blocking progress (repeatable)
+        #[arg(long = "blocker")]
+        blockers: Vec<String>,
+
+        /// A file this session touched (repeatable)
+        #[arg(long = "touched")]
+        touched: Vec<String>,
+
+        /// A command that worked, e.g. the right test invocation (repeatable)
+        #[arg(long = "worked")]
+        worked: Vec<String>,
     },
     /// List recent progress entries
     List {
@@ -1394,7 +1693,7 @@ async fn main() {
         return;
     }
 
-    if let Some(Commands::Arch { json }) = &cli.command {
+    if let Some(Commands::Arch { json, action }) = &cli.command {
         let config = HarnessConfig::load(&cli.config).unwrap_or_default();
         let dd = runtime_data_dir(&config.storage.data_dir, &cli.config);
         let db_path = dd.db();
@@ -1403,6 +1702,23 @@ async fn main() {
             std::process::exit(1);
         });
 
+        if let Some(action) = action {
+            let result = match action {
+                ArchAction::Review => {
+                    arch_review::handle_review(&db_path, &repo_root, &config.architecture)
+                }
+                ArchAction::Proposals { all } => arch_review::handle_list(&db_path, *all),
+                ArchAction::Approve { id } => arch_review::handle_approve(&db_path, *id),
+                ArchAction::Reject { id } => arch_review::handle_reject(&db_path, *id),
+                ArchAction::Trend { last } => arch_history::handle_trend(&db_path, *last, *json),
+            };
+            if let Err(e) = result {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+            return;
+        }
+
         let report = structural_metrics::analyze(&repo_root);
 
         // Try signal correlation if DB exists
@@ -1443,7 +1759,14 @@ async fn main() {
     }) = &cli.command
     {
         let config = HarnessConfig::load(&cli.config).unwrap_or_default();
-        let result = finish::handle_finish(bead_id, message, files, &config.quality_gates);
+        let db_path = runtime_data_dir(&config.storage.data_dir, &cli.config).db();
+        let result = finish::handle_finish(
+            bead_id,
+            message,
+            files,
+            &config.quality_gates,
+            Some(&db_path),
+        );
         if !result.success {
             eprintln!("Error: {}", result.message);
             std::process::exit(1);
@@ -1469,6 +1792,120 @@ async fn main() {
         return;
     }
 
+    if let Some(Commands::Archive { action }) = &cli.command {
+        let config = HarnessConfig::load(&cli.config).unwrap_or_default();
+        let data_dir = runtime_data_dir(&config.storage.data_dir, &cli.config);
+        let result = match action {
+            ArchiveAction::Export { file } => archive::handle_export(&data_dir, file),
+            ArchiveAction::Import { file } => archive::handle_import(&data_dir, file),
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(Commands::Experiment { action }) = &cli.command {
+        let config = HarnessConfig::load(&cli.config).unwrap_or_default();
+        let db_path = runtime_data_dir(&config.storage.data_dir, &cli.config).db();
+        let result = match action {
+            ExperimentAction::Report { name, json } => {
+                experiment::handle_report(&db_path, &config.experiment, name.as_deref(), *json)
+            }
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(Commands::Db { action }) = &cli.command {
+        let config = HarnessConfig::load(&cli.config).unwrap_or_default();
+        let db_path = runtime_data_dir(&config.storage.data_dir, &cli.config).db();
+        let result = match action {
+            DbAction::Status => schema::handle_status(&db_path),
+            DbAction::Migrate => schema::handle_migrate(&db_path),
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(Commands::Tests { action }) = &cli.command {
+        let config = HarnessConfig::load(&cli.config).unwrap_or_default();
+        let db_path = runtime_data_dir(&config.storage.data_dir, &cli.config).db();
+        let result = match action {
+            TestsAction::Flaky { window } => flaky::handle_flaky(
+                &db_path,
+                window.unwrap_or(config.reconciliation.flaky_window),
+            ),
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(Commands::Salvage { action }) = &cli.command {
+        let config = HarnessConfig::load(&cli.config).unwrap_or_default();
+        let db_path = runtime_data_dir(&config.storage.data_dir, &cli.config).db();
+        let repo_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let result = match action {
+            SalvageAction::List { all } => salvage::handle_list(&db_path, *all),
+            SalvageAction::Show { bead_id } => salvage::handle_show(&db_path, &repo_dir, bead_id),
+            SalvageAction::Apply { bead_id } => salvage::handle_apply(&db_path, &repo_dir, bead_id),
+            SalvageAction::Discard { bead_id } => {
+                salvage::handle_discard(&db_path, &repo_dir, bead_id)
+            }
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(Commands::Epoch { action }) = &cli.command {
+        let config = HarnessConfig::load(&cli.config).unwrap_or_default();
+        let db_path = runtime_data_dir(&config.storage.data_dir, &cli.config).db();
+        let repo_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let result = match action {
+            EpochAction::Start { name } => {
+                epoch::handle_start(&db_path, &repo_dir, name.as_deref())
+            }
+            EpochAction::Close => epoch::handle_close(&db_path, &repo_dir),
+            EpochAction::Report { name, json } => {
+                epoch::handle_report(&db_path, &repo_dir, name.as_deref(), *json)
+            }
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(Commands::Plan { action }) = &cli.command {
+        let config = HarnessConfig::load(&cli.config).unwrap_or_default();
+        let db_path = runtime_data_dir(&config.storage.data_dir, &cli.config).db();
+        let result = match action {
+            PlanAction::List { all } => planning::handle_list(&db_path, *all),
+            PlanAction::Show { id } => planning::handle_show(&db_path, *id),
+            PlanAction::Approve { id } => planning::handle_approve(&db_path, *id),
+            PlanAction::Reject { id } => planning::handle_reject(&db_path, *id),
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     if let Some(Commands::Integration { action }) = &cli.command {
         let config_for_integration = HarnessConfig::load(&cli.config).unwrap_or_default();
         let dd = runtime_data_dir(&config_for_integration.storage.data_dir, &cli.config);
@@ -1503,6 +1940,9 @@ async fn main() {
                 body,
                 context,
                 tags,
+                metric,
+                direction,
+                force,
             } => improve::handle_add(
                 &db_path,
                 title,
@@ -1510,6 +1950,8 @@ async fn main() {
                 body.as_deref(),
                 context.as_deref(),
                 tags.as_deref(),
+                metric.as_deref().map(|m| (m, direction.as_str())),
+                *force,
             ),
             ImproveAction::List { status, category } => {
                 improve::handle_list(&db_path, status.as_deref(), category.as_deref())
@@ -1520,14 +1962,34 @@ async fn main() {
                 status,
                 body,
                 context,
+                metric,
+                direction,
             } => improve::handle_update(
                 &db_path,
                 ref_id,
                 status.as_deref(),
                 body.as_deref(),
                 context.as_deref(),
+                metric.as_deref().map(|m| (m, direction.as_str())),
             ),
-            ImproveAction::Promote { ref_id } => improve::handle_promote(&db_path, ref_id),
+            ImproveAction::Promote { ref_id } => {
+                improve::handle_promote(&db_path, ref_id, &config_for_improve.improvements)
+            }
+            ImproveAction::Demote { ref_id, reason } => improve::handle_demote(
+                &db_path,
+                ref_id,
+                reason.as_deref(),
+                &config_for_improve.improvements,
+            ),
+            ImproveAction::Merge { keep, duplicate } => {
+                improve::handle_merge(&db_path, keep, duplicate, &config_for_improve.improvements)
+            }
+            ImproveAction::Mine { last, top, file } => {
+                mining::handle_mine(&db_path, *last, *top, *file)
+            }
+            ImproveAction::History { ref_id } => {
+                improve::handle_history(&db_path, ref_id.as_deref())
+            }
             ImproveAction::Dismiss { ref_id, reason } => {
                 improve::handle_dismiss(&db_path, ref_id, reason.as_deref())
             }
@@ -1552,6 +2014,40 @@ async fn main() {
                 text,
                 stdin,
                 file,
+                done,
+                remaining,
+                blockers,
+                touched,
+                worked,
+            } if !(done.is_empty()
+                && remaining.is_empty()
+                && blockers.is_empty()
+                && touched.is_empty()
+                && worked.is_empty()) =>
+            {
+                let fields = handoff::Handoff {
+                    bead_id: bead_id.clone().unwrap_or_default(),
+                    done: done.clone(),
+                    remaining: remaining.clone(),
+                    blockers: blockers.clone(),
+                    files: touched.clone(),
+                    commands: worked.clone(),
+                    ..Default::default()
+                };
+                progress::handle_add_handoff(
+                    &db_path,
+                    fields,
+                    text.as_deref(),
+                    *stdin,
+                    file.as_deref(),
+                )
+            }
+            ProgressAction::Add {
+                bead_id,
+                text,
+                stdin,
+                file,
+                ..
             } => progress::handle_add(
                 &db_path,
                 bead_id.as_deref(),
@@ -1577,7 +2073,9 @@ async fn main() {
         let dd = runtime_data_dir(&config_for_metrics.storage.data_dir, &cli.config);
         let db_path = dd.db();
         let result = match action {
-            MetricsAction::Log { file } => metrics_cmd::handle_log(&db_path, file),
+            MetricsAction::Log { file } => {
+                metrics_cmd::handle_log(&db_path, file, &config_for_metrics.pricing)
+            }
             MetricsAction::Status { last } => metrics_cmd::handle_status(&db_path, *last),
             MetricsAction::Targets { last } => {
                 let adapter_name = adapters::resolve_adapter_name(
@@ -1621,10 +2119,15 @@ async fn main() {
                     *last,
                     *all,
                     &rules,
+                    &config_for_metrics.pricing,
                     adapter.as_ref(),
                 )
             }
             MetricsAction::Beads => metrics_cmd::handle_beads(&db_path),
+            MetricsAction::Gates { last } => metrics_cmd::handle_gates(&db_path, *last),
+            MetricsAction::Tools { last, json } => {
+                metrics_cmd::handle_tools(&db_path, *last, *json)
+            }
         };
 
         if let Err(e) = result {
@@ -1785,6 +2288,24 @@ async fn main() {
             "  architecture.refactor_auto_approve = {}",
             config.architecture.refactor_auto_approve
         );
+        println!(
+            "  architecture.review_every = {}",
+            config.architecture.review_every
+        );
+        if config.architecture.fitness.is_empty() {
+            println!("  architecture.fitness = (none)");
+        } else {
+            println!(
+                "  architecture.fitness = ({} rules)",
+                config.architecture.fitness.len()
+            );
+            for (i, rule) in config.architecture.fitness.iter().enumerate() {
+                println!(
+                    "    [{}] rule={:?} from={:?} to={:?} module={:?} max={:?}",
+                    i, rule.rule, rule.from, rule.to, rule.module, rule.max
+                );
+            }
+        }
         if config.metrics.extract.rules.is_empty() {
             println!("  metrics.extract.rules = (none)");
         } else {
@@ -1822,10 +2343,15 @@ async fn main() {
         return;
     }
 
-    if cli.status {
+    if cli.status || matches!(cli.command, Some(Commands::Status)) {
         let status_path = data_dir.status();
         let db_path = data_dir.db();
-        match status::display_status(&status_path, Some(&db_path), config.workers.max) {
+        match status::display_status(
+            &status_path,
+            Some(&db_path),
+            config.workers.max,
+            &config.budget,
+        ) {
             Ok(true) => {}
             Ok(false) => {
                 println!("No running blacksmith detected.");
@@ -1835,6 +2361,7 @@ async fn main() {
                 std::process::exit(1);
             }
         }
+        status::display_unscheduled(&coordinator::unscheduled_beads(&config.workers, &db_path));
         return;
     }
 
@@ -1968,6 +2495,8 @@ mod tests {
             None,
             None,
             None,
+            None,
+            false,
         )
         .unwrap();
         improve::handle_add(
@@ -1977,6 +2506,8 @@ mod tests {
             None,
             None,
             None,
+            None,
+            false,
         )
         .unwrap();
 
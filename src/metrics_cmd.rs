@@ -1,7 +1,8 @@
 use crate::adapters;
-use crate::config::MetricsTargetsConfig;
+use crate::config::{MetricsTargetsConfig, PricingConfig};
 use crate::db;
 use crate::ingest;
+use crate::pricing;
 use rusqlite::Connection;
 use std::path::Path;
 
@@ -9,7 +10,7 @@ use std::path::Path;
 ///
 /// Ingests a JSONL session file into the database. The session number is
 /// auto-assigned as max(session)+1 from the observations table.
-pub fn handle_log(db_path: &Path, file: &Path) -> Result<(), String> {
+pub fn handle_log(db_path: &Path, file: &Path, pricing: &PricingConfig) -> Result<(), String> {
     let conn = db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
 
     // Auto-assign session number: max existing + 1
@@ -23,23 +24,35 @@ pub fn handle_log(db_path: &Path, file: &Path) -> Result<(), String> {
 
     // Use Claude adapter by default for `metrics log` (most common format)
     let adapter = adapters::claude::ClaudeAdapter::new();
-    let result = ingest::ingest_session(&conn, session, file, None, &adapter)
-        .map_err(|e| format!("Ingestion failed: {e}"))?;
+    let result =
+        ingest::ingest_session_with_rules(&conn, session, file, None, &[], pricing, &adapter)
+            .map_err(|e| format!("Ingestion failed: {e}"))?;
 
     println!("Ingested session {session} from {}", file.display());
     println!(
-        "  turns: {}  cost: ${:.2}  duration: {}s",
+        "  turns: {}  cost: ${:.2}{}  duration: {}s",
         result.turns_total,
         result.cost_estimate_usd,
+        format_computed_suffix(result.cost_computed_usd),
         result.session_duration_ms / 1000
     );
 
     Ok(())
 }
 
+/// Format a computed cost as ` (computed $X.XX)`, or nothing when unpriced.
+fn format_computed_suffix(computed: Option<f64>) -> String {
+    match computed {
+        Some(c) => format!(" (computed ${c:.2})"),
+        None => String::new(),
+    }
+}
+
 /// Handle the `metrics status [--last N]` subcommand.
 ///
 /// Displays a dashboard of recent session observations with key metrics.
+/// COST is the agent-reported cost; CALC is the cost computed from token
+/// counts and the `[pricing]` table ("-" when the model is unpriced).
 pub fn handle_status(db_path: &Path, last: i64) -> Result<(), String> {
     if !db_path.exists() {
         println!("No metrics database found. Run some sessions first.");
@@ -58,14 +71,15 @@ pub fn handle_status(db_path: &Path, last: i64) -> Result<(), String> {
 
     // Print header
     println!(
-        "{:<8} {:<12} {:>6} {:>8} {:>10} {:>6} {:>8}",
-        "SESSION", "DATE", "TURNS", "COST", "DURATION", "NARR%", "PARALL%"
+        "{:<8} {:<12} {:>6} {:>8} {:>8} {:>10} {:>6} {:>8}",
+        "SESSION", "DATE", "TURNS", "COST", "CALC", "DURATION", "NARR%", "PARALL%"
     );
-    println!("{}", "-".repeat(64));
+    println!("{}", "-".repeat(73));
 
     // Collect for summary stats
     let mut total_turns: u64 = 0;
     let mut total_cost: f64 = 0.0;
+    let mut total_computed: f64 = 0.0;
     let mut total_duration: u64 = 0;
     let mut total_narration: u64 = 0;
     let mut total_parallel: u64 = 0;
@@ -79,11 +93,13 @@ pub fn handle_status(db_path: &Path, last: i64) -> Result<(), String> {
         let narration = data["turns.narration_only"].as_u64().unwrap_or(0);
         let parallel = data["turns.parallel"].as_u64().unwrap_or(0);
         let cost = data["cost.estimate_usd"].as_f64().unwrap_or(0.0);
+        let computed = data["cost.computed_usd"].as_f64();
         let duration_ms = data["session.duration_ms"].as_u64().unwrap_or(0);
         let duration_s = duration_ms / 1000;
 
         total_turns += turns;
         total_cost += cost;
+        total_computed += computed.unwrap_or(0.0);
         total_duration += duration_s;
         total_narration += narration;
         total_parallel += parallel;
@@ -109,11 +125,12 @@ pub fn handle_status(db_path: &Path, last: i64) -> Result<(), String> {
         let duration_str = format_duration(duration_s);
 
         println!(
-            "{:<8} {:<12} {:>6} {:>8} {:>10} {:>5}% {:>7}%",
+            "{:<8} {:<12} {:>6} {:>8} {:>8} {:>10} {:>5}% {:>7}%",
             obs.session,
             date,
             turns,
             format!("${:.2}", cost),
+            computed.map_or_else(|| "-".to_string(), |c| format!("${c:.2}")),
             duration_str,
             narr_pct,
             par_pct
@@ -121,9 +138,10 @@ pub fn handle_status(db_path: &Path, last: i64) -> Result<(), String> {
     }
 
     // Summary
-    println!("{}", "-".repeat(64));
+    println!("{}", "-".repeat(73));
     let avg_turns = total_turns as f64 / count as f64;
     let avg_cost = total_cost / count as f64;
+    let avg_computed = total_computed / count as f64;
     let avg_duration = total_duration / count as u64;
     let avg_narr_pct = if total_turns > 0 {
         (total_narration as f64 / total_turns as f64 * 100.0) as u64
@@ -137,28 +155,58 @@ pub fn handle_status(db_path: &Path, last: i64) -> Result<(), String> {
     };
 
     println!(
-        "{:<8} {:<12} {:>6} {:>8} {:>10} {:>5}% {:>7}%",
+        "{:<8} {:<12} {:>6} {:>8} {:>8} {:>10} {:>5}% {:>7}%",
         "AVG",
         "",
         format!("{:.0}", avg_turns),
         format!("${:.2}", avg_cost),
+        format!("${:.2}", avg_computed),
         format_duration(avg_duration),
         avg_narr_pct,
         avg_par_pct
     );
     println!(
-        "{:<8} {:<12} {:>6} {:>8}",
+        "{:<8} {:<12} {:>6} {:>8} {:>8}",
         "TOTAL",
         "",
         total_turns,
-        format!("${:.2}", total_cost)
+        format!("${:.2}", total_cost),
+        format!("${:.2}", total_computed)
     );
 
+    let datas: Vec<serde_json::Value> = observations
+        .iter()
+        .map(|o| serde_json::from_str(&o.data).unwrap_or_default())
+        .collect();
+    print_cost_by_model(&pricing::cost_by_model(&datas));
+
     println!("\n{count} session(s) shown");
 
     Ok(())
 }
 
+/// Print the per-model cost breakdown below the status table.
+///
+/// Skipped when no session reported a model, since a single "(unknown)" row
+/// adds nothing over the TOTAL line.
+fn print_cost_by_model(rows: &[pricing::ModelCost]) {
+    if rows.iter().all(|r| r.model == pricing::UNKNOWN_MODEL) {
+        return;
+    }
+    println!("\nCost by model:");
+    for row in rows {
+        let computed = if row.computed_sessions > 0 {
+            format!("${:.2}", row.computed_usd)
+        } else {
+            "-".to_string()
+        };
+        println!(
+            "  {:<32} {:>4} session(s)  reported ${:<8.2} computed {}",
+            row.model, row.sessions, row.reported_usd, computed
+        );
+    }
+}
+
 /// Handle the `metrics targets` subcommand.
 ///
 /// Evaluates configured target rules against recent observations and
@@ -280,6 +328,7 @@ pub fn handle_reingest(
     last: Option<u64>,
     all: bool,
     rules: &[crate::config::CompiledRule],
+    pricing: &PricingConfig,
     adapter: &dyn crate::adapters::AgentAdapter,
 ) -> Result<(), String> {
     if !all && last.is_none() {
@@ -344,14 +393,16 @@ pub fn handle_reingest(
             &jsonl_path,
             None,
             rules,
+            pricing,
             adapter,
         ) {
             Ok(result) => {
                 success_count += 1;
                 println!(
-                    "  session {session}: turns={} cost=${:.2} duration={}s",
+                    "  session {session}: turns={} cost=${:.2}{} duration={}s",
                     result.turns_total,
                     result.cost_estimate_usd,
+                    format_computed_suffix(result.cost_computed_usd),
                     result.session_duration_ms / 1000
                 );
             }
@@ -1185,6 +1236,151 @@ pub fn handle_beads(db_path: &Path) -> Result<(), String> {
     Ok(())
 }
 
+/// Handle `blacksmith metrics gates` — per-gate failure rates and durations
+/// from `blacksmith finish` runs.
+pub fn handle_gates(db_path: &Path, last: u32) -> Result<(), String> {
+    if !db_path.exists() {
+        println!("No metrics database found. Run some sessions first.");
+        return Ok(());
+    }
+
+    let conn = db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
+    let stats = crate::gate_result::gate_stats(&conn, last)
+        .map_err(|e| format!("Failed to query gate results: {e}"))?;
+
+    if stats.is_empty() {
+        println!("No gate results recorded yet.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<20} {:>6} {:>6} {:>8} {:>8} {:>9} {:>10}",
+        "GATE", "RUNS", "FAILED", "ALLOWED", "SKIPPED", "FAIL RATE", "AVG TIME"
+    );
+    println!("{}", "-".repeat(73));
+    for s in &stats {
+        let failed = if s.timeouts > 0 {
+            format!("{} ({}t)", s.failures, s.timeouts)
+        } else {
+            s.failures.to_string()
+        };
+        println!(
+            "{:<20} {:>6} {:>6} {:>8} {:>8} {:>8.0}% {:>10}",
+            truncate_bead_id(&s.gate, 19),
+            s.runs,
+            failed,
+            s.allowed_failures,
+            s.skipped,
+            s.failure_rate() * 100.0,
+            format_duration((s.avg_duration_ms / 1000.0) as u64)
+        );
+    }
+
+    Ok(())
+}
+
+/// Rows shown per section of `metrics tools`.
+const TOOLS_TOP: usize = 10;
+
+/// Handle `blacksmith metrics tools` — the most used, slowest and most
+/// error-prone tools and shell commands across recent sessions.
+pub fn handle_tools(db_path: &Path, last: u32, json: bool) -> Result<(), String> {
+    if !db_path.exists() {
+        println!("No metrics database found. Run some sessions first.");
+        return Ok(());
+    }
+
+    let conn = db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
+    let report = crate::tool_call::report(&conn, last)
+        .map_err(|e| format!("Failed to query tool calls: {e}"))?;
+
+    if json {
+        let out = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+        println!("{out}");
+        return Ok(());
+    }
+
+    if report.tools.is_empty() {
+        println!("No tool calls recorded yet.");
+        return Ok(());
+    }
+
+    // Commands are shown with a `$ ` prefix where they share a table with tools
+    let all: Vec<(String, &crate::tool_call::ToolStats)> = report
+        .tools
+        .iter()
+        .map(|s| (s.name.clone(), s))
+        .chain(report.commands.iter().map(|s| (format!("$ {}", s.name), s)))
+        .collect();
+
+    print_tool_table(
+        "Most used tools",
+        report.tools.iter().map(|s| (s.name.clone(), s)).collect(),
+    );
+    print_tool_table(
+        "Most used commands",
+        report
+            .commands
+            .iter()
+            .map(|s| (format!("$ {}", s.name), s))
+            .collect(),
+    );
+
+    let mut slowest: Vec<(String, &crate::tool_call::ToolStats)> = all
+        .iter()
+        .filter(|(_, s)| s.avg_duration_ms.is_some())
+        .cloned()
+        .collect();
+    let avg = |s: &crate::tool_call::ToolStats| s.avg_duration_ms.unwrap_or(0.0);
+    slowest.sort_by(|a, b| avg(b.1).total_cmp(&avg(a.1)));
+    print_tool_table("Slowest", slowest);
+
+    let mut failing: Vec<(String, &crate::tool_call::ToolStats)> =
+        all.iter().filter(|(_, s)| s.errors > 0).cloned().collect();
+    failing.sort_by(|a, b| {
+        b.1.error_rate
+            .total_cmp(&a.1.error_rate)
+            .then(b.1.errors.cmp(&a.1.errors))
+    });
+    print_tool_table("Most error-prone", failing);
+
+    Ok(())
+}
+
+fn print_tool_table(title: &str, rows: Vec<(String, &crate::tool_call::ToolStats)>) {
+    if rows.is_empty() {
+        return;
+    }
+    println!("{title}");
+    println!(
+        "  {:<30} {:>6} {:>8} {:>9} {:>7} {:>10}",
+        "NAME", "CALLS", "PER BEAD", "AVG TIME", "ERRORS", "ERROR RATE"
+    );
+    for (name, s) in rows.into_iter().take(TOOLS_TOP) {
+        println!(
+            "  {:<30} {:>6} {:>8} {:>9} {:>7} {:>9.0}%",
+            truncate_bead_id(&name, 30),
+            s.calls,
+            s.per_bead.map_or("-".to_string(), |n| format!("{n:.1}")),
+            s.avg_duration_ms.map_or("-".to_string(), format_millis),
+            s.errors,
+            s.error_rate * 100.0
+        );
+    }
+    println!();
+}
+
+/// Format a millisecond duration, keeping sub-second precision.
+fn format_millis(ms: f64) -> String {
+    if ms < 1000.0 {
+        format!("{ms:.0}ms")
+    } else if ms < 60_000.0 {
+        format!("{:.1}s", ms / 1000.0)
+    } else {
+        format_duration((ms / 1000.0) as u64)
+    }
+}
+
 /// Truncate a bead ID for display, adding "..." if it exceeds max_len.
 fn truncate_bead_id(id: &str, max_len: usize) -> String {
     if id.len() <= max_len {
@@ -1421,7 +1617,7 @@ mod tests {
         )
         .unwrap();
 
-        handle_log(&path, &jsonl_path).unwrap();
+        handle_log(&path, &jsonl_path, &PricingConfig::default()).unwrap();
 
         // Verify it was ingested
         let conn = db::open_or_create(&path).unwrap();
@@ -1443,8 +1639,8 @@ mod tests {
         )
         .unwrap();
 
-        handle_log(&path, &jsonl_path).unwrap();
-        handle_log(&path, &jsonl_path).unwrap();
+        handle_log(&path, &jsonl_path, &PricingConfig::default()).unwrap();
+        handle_log(&path, &jsonl_path, &PricingConfig::default()).unwrap();
 
         let conn = db::open_or_create(&path).unwrap();
         let obs = db::recent_observations(&conn, 10).unwrap();
@@ -1458,7 +1654,11 @@ mod tests {
     #[test]
     fn log_file_not_found() {
         let (_dir, path) = test_db_path();
-        let result = handle_log(&path, Path::new("/nonexistent/file.jsonl"));
+        let result = handle_log(
+            &path,
+            Path::new("/nonexistent/file.jsonl"),
+            &PricingConfig::default(),
+        );
         assert!(result.is_err());
     }
 
@@ -2684,7 +2884,15 @@ label = "Avg turns per session"
         let (_dir, path) = test_db_path();
         let sessions_dir = TempDir::new().unwrap();
         let adapter = crate::adapters::claude::ClaudeAdapter::new();
-        let result = handle_reingest(&path, sessions_dir.path(), None, false, &[], &adapter);
+        let result = handle_reingest(
+            &path,
+            sessions_dir.path(),
+            None,
+            false,
+            &[],
+            &PricingConfig::default(),
+            &adapter,
+        );
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("--last N or --all"));
     }
@@ -2699,6 +2907,7 @@ label = "Avg turns per session"
             None,
             true,
             &[],
+            &PricingConfig::default(),
             &adapter,
         );
         assert!(result.is_err());
@@ -2710,7 +2919,16 @@ label = "Avg turns per session"
         let (_dir, path) = test_db_path();
         let sessions_dir = TempDir::new().unwrap();
         let adapter = crate::adapters::claude::ClaudeAdapter::new();
-        handle_reingest(&path, sessions_dir.path(), None, true, &[], &adapter).unwrap();
+        handle_reingest(
+            &path,
+            sessions_dir.path(),
+            None,
+            true,
+            &[],
+            &PricingConfig::default(),
+            &adapter,
+        )
+        .unwrap();
     }
 
     #[test]
@@ -2734,7 +2952,16 @@ label = "Avg turns per session"
         }
 
         let adapter = crate::adapters::claude::ClaudeAdapter::new();
-        handle_reingest(&path, sessions_dir.path(), None, true, &[], &adapter).unwrap();
+        handle_reingest(
+            &path,
+            sessions_dir.path(),
+            None,
+            true,
+            &[],
+            &PricingConfig::default(),
+            &adapter,
+        )
+        .unwrap();
 
         // Verify observations were created for both sessions
         let conn = db::open_or_create(&path).unwrap();
@@ -2758,7 +2985,16 @@ label = "Avg turns per session"
         }
 
         let adapter = crate::adapters::claude::ClaudeAdapter::new();
-        handle_reingest(&path, sessions_dir.path(), Some(2), false, &[], &adapter).unwrap();
+        handle_reingest(
+            &path,
+            sessions_dir.path(),
+            Some(2),
+            false,
+            &[],
+            &PricingConfig::default(),
+            &adapter,
+        )
+        .unwrap();
 
         // Only last 2 sessions (3, 4) should be ingested
         let conn = db::open_or_create(&path).unwrap();
@@ -2797,7 +3033,16 @@ label = "Avg turns per session"
         drop(conn);
 
         let adapter = crate::adapters::claude::ClaudeAdapter::new();
-        handle_reingest(&path, sessions_dir.path(), None, true, &[], &adapter).unwrap();
+        handle_reingest(
+            &path,
+            sessions_dir.path(),
+            None,
+            true,
+            &[],
+            &PricingConfig::default(),
+            &adapter,
+        )
+        .unwrap();
 
         // Verify old fake event was removed
         let conn = db::open_or_create(&path).unwrap();
@@ -2819,7 +3064,16 @@ label = "Avg turns per session"
         std::fs::write(sessions_dir.path().join("0.jsonl.zst"), compressed).unwrap();
 
         let adapter = crate::adapters::claude::ClaudeAdapter::new();
-        handle_reingest(&path, sessions_dir.path(), None, true, &[], &adapter).unwrap();
+        handle_reingest(
+            &path,
+            sessions_dir.path(),
+            None,
+            true,
+            &[],
+            &PricingConfig::default(),
+            &adapter,
+        )
+        .unwrap();
 
         // Verify session was ingested from compressed data
         let conn = db::open_or_create(&path).unwrap();
@@ -2853,6 +3107,66 @@ label = "Avg turns per session"
         assert_eq!(parse_session_iteration(path), None);
     }
 
+    // ── Gates report tests ──────────────────────────────────────────────
+
+    #[test]
+    fn gates_no_database() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nonexistent.db");
+        handle_gates(&path, 0).unwrap();
+    }
+
+    #[test]
+    fn gates_with_results() {
+        let (_dir, path) = test_db_path();
+        let conn = db::open_or_create(&path).unwrap();
+        let outcome = crate::gates::GateOutcome {
+            name: "test".to_string(),
+            status: crate::gates::GateStatus::Failed,
+            duration_ms: 1500,
+            failed_command: Some("cargo test".to_string()),
+            output: String::new(),
+            timed_out: true,
+            test_report: None,
+        };
+        crate::gate_result::record(&conn, "bead-1", &outcome).unwrap();
+        handle_gates(&path, 0).unwrap();
+        handle_gates(&path, 10).unwrap();
+    }
+
+    // ── Tools report tests ──────────────────────────────────────────────
+
+    #[test]
+    fn tools_no_database() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nonexistent.db");
+        handle_tools(&path, 0, false).unwrap();
+    }
+
+    #[test]
+    fn tools_with_calls() {
+        let (_dir, path) = test_db_path();
+        let conn = db::open_or_create(&path).unwrap();
+        let calls = [adapters::ToolCall {
+            turn: 0,
+            tool: "Bash".to_string(),
+            command: Some("cargo test".to_string()),
+            duration_ms: Some(1500),
+            success: Some(false),
+            output_bytes: Some(12),
+        }];
+        crate::tool_call::record(&conn, 1, &calls).unwrap();
+        handle_tools(&path, 0, false).unwrap();
+        handle_tools(&path, 5, true).unwrap();
+    }
+
+    #[test]
+    fn format_millis_units() {
+        assert_eq!(format_millis(250.0), "250ms");
+        assert_eq!(format_millis(2500.0), "2.5s");
+        assert_eq!(format_millis(125_000.0), "2m05s");
+    }
+
     // ── Beads report tests ──────────────────────────────────────────────
 
     #[test]
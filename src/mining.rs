@@ -0,0 +1,504 @@
+//! Failure-trace mining: improvement candidates from what sessions did.
+//!
+//! A deterministic pass over the tool calls recorded at ingestion (whatever
+//! the agent's output format) and the database that clusters recurring
+//! trouble:
+//!
+//! - failing tool calls, keyed by tool and normalized command;
+//! - commands re-run several times within one session;
+//! - streaks of assistant turns without tool calls;
+//! - finish-gate failures, keyed by gate name.
+//!
+//! The top clusters are handed to the analysis agent as evidence, and
+//! `improve mine --file` files them as `draft` improvements whose context
+//! links example sessions.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use rusqlite::{Connection, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::adapters::ToolCall;
+use crate::db;
+use crate::dedup;
+use crate::tool_call;
+
+/// A command run at least this many times in one session counts as retried.
+const RETRY_THRESHOLD: usize = 3;
+/// Consecutive turns without tool calls that make a streak.
+const NARRATION_STREAK: usize = 3;
+/// A cluster needs at least this many occurrences to be a pattern.
+const MIN_OCCURRENCES: usize = 2;
+/// Example sessions listed per cluster.
+const EXAMPLES: usize = 5;
+
+/// What a cluster groups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClusterKind {
+    ToolFailure,
+    RetriedCommand,
+    NarrationStreak,
+    GateFailure,
+}
+
+impl ClusterKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ClusterKind::ToolFailure => "tool-failure",
+            ClusterKind::RetriedCommand => "retried-command",
+            ClusterKind::NarrationStreak => "narration-streak",
+            ClusterKind::GateFailure => "gate-failure",
+        }
+    }
+
+    /// Improvement category for drafts of this kind.
+    fn category(self) -> &'static str {
+        match self {
+            ClusterKind::ToolFailure => "reliability",
+            ClusterKind::RetriedCommand => "cost",
+            ClusterKind::NarrationStreak => "workflow",
+            ClusterKind::GateFailure => "quality",
+        }
+    }
+}
+
+/// Occurrences of one kind of trouble sharing a signature.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cluster {
+    pub kind: ClusterKind,
+    pub signature: String,
+    pub occurrences: usize,
+    pub sessions: BTreeSet<i64>,
+}
+
+impl Cluster {
+    pub fn title(&self) -> String {
+        match self.kind {
+            ClusterKind::ToolFailure => format!("Recurring tool failure: {}", self.signature),
+            ClusterKind::RetriedCommand => {
+                format!("Repeatedly retried command: {}", self.signature)
+            }
+            ClusterKind::NarrationStreak => {
+                format!("Narration-only streaks of {NARRATION_STREAK}+ turns without tool calls")
+            }
+            ClusterKind::GateFailure => format!("Finish gate '{}' keeps failing", self.signature),
+        }
+    }
+
+    fn body(&self) -> &'static str {
+        match self.kind {
+            ClusterKind::ToolFailure => {
+                "Sessions keep hitting the same tool error. Add a PROMPT.md rule that avoids \
+                 the call or says how to do it correctly."
+            }
+            ClusterKind::RetriedCommand => {
+                "Sessions re-run the same command several times. Add a PROMPT.md rule on \
+                 reading its output properly or fixing the cause before re-running."
+            }
+            ClusterKind::NarrationStreak => {
+                "Sessions spend several turns narrating without acting. Add a PROMPT.md rule \
+                 to pair every reply with a tool call."
+            }
+            ClusterKind::GateFailure => {
+                "Beads keep failing this finish gate. Add a PROMPT.md rule to run the gate's \
+                 check locally before finishing."
+            }
+        }
+    }
+
+    /// "N occurrence(s) in M session(s); e.g. sessions 4, 9"
+    pub fn evidence(&self) -> String {
+        let examples: Vec<String> = self
+            .sessions
+            .iter()
+            .rev()
+            .take(EXAMPLES)
+            .map(|s| s.to_string())
+            .collect();
+        format!(
+            "{} occurrence(s) in {} session(s); e.g. sessions {}",
+            self.occurrences,
+            self.sessions.len(),
+            examples.join(", ")
+        )
+    }
+}
+
+type Key = (ClusterKind, String);
+
+#[derive(Default)]
+struct Collector {
+    clusters: BTreeMap<Key, (usize, BTreeSet<i64>)>,
+}
+
+impl Collector {
+    fn add(&mut self, kind: ClusterKind, signature: String, session: i64, count: usize) {
+        let entry = self.clusters.entry((kind, signature)).or_default();
+        entry.0 += count;
+        entry.1.insert(session);
+    }
+
+    /// Clusters with enough occurrences, most widespread first.
+    fn finish(self) -> Vec<Cluster> {
+        let mut clusters: Vec<Cluster> = self
+            .clusters
+            .into_iter()
+            .filter(|(_, (n, _))| *n >= MIN_OCCURRENCES)
+            .map(|((kind, signature), (occurrences, sessions))| Cluster {
+                kind,
+                signature,
+                occurrences,
+                sessions,
+            })
+            .collect();
+        clusters.sort_by(|a, b| {
+            b.sessions
+                .len()
+                .cmp(&a.sessions.len())
+                .then(b.occurrences.cmp(&a.occurrences))
+        });
+        clusters
+    }
+}
+
+/// Scan one session's tool calls for failures, retried commands and
+/// narration streaks. A streak is a gap of turns between calls; `turns` is
+/// the session's total turn count, when known, so a trailing one counts too.
+fn scan_session(calls: &[ToolCall], turns: Option<u32>, session: i64, out: &mut Collector) {
+    let mut commands: HashMap<&str, usize> = HashMap::new();
+    let streak = |gap: u32, out: &mut Collector| {
+        if gap as usize >= NARRATION_STREAK {
+            out.add(ClusterKind::NarrationStreak, String::new(), session, 1);
+        }
+    };
+
+    let mut next_turn = 0;
+    for call in calls {
+        streak(call.turn.saturating_sub(next_turn), out);
+        next_turn = next_turn.max(call.turn + 1);
+
+        let command = call.command.as_deref().filter(|c| !c.is_empty());
+        if let Some(command) = command {
+            *commands.entry(command).or_default() += 1;
+        }
+        if call.success == Some(false) {
+            let signature = match command {
+                Some(command) => format!("{} `{command}`", call.tool),
+                None => call.tool.clone(),
+            };
+            out.add(ClusterKind::ToolFailure, signature, session, 1);
+        }
+    }
+    if let Some(turns) = turns {
+        streak(turns.saturating_sub(next_turn), out);
+    }
+
+    for (command, runs) in commands {
+        if runs >= RETRY_THRESHOLD {
+            let signature = match command.char_indices().nth(80) {
+                Some((i, _)) => format!("`{}…`", &command[..i]),
+                None => format!("`{command}`"),
+            };
+            out.add(ClusterKind::RetriedCommand, signature, session, runs - 1);
+        }
+    }
+}
+
+/// Failed finish gates for the beads worked on in `sessions`.
+fn scan_gates(conn: &Connection, sessions: &[i64], out: &mut Collector) -> Result<()> {
+    let mut stmt = conn.prepare(
+        "SELECT value FROM events WHERE kind = 'session.bead_id' AND session = ?1 LIMIT 1",
+    )?;
+    let mut bead_sessions: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+    for &session in sessions {
+        let bead: Option<String> = stmt
+            .query_map([session], |row| row.get(0))?
+            .next()
+            .transpose()?;
+        if let Some(bead) = bead {
+            bead_sessions.entry(bead).or_default().push(session);
+        }
+    }
+
+    let mut failures = conn.prepare(
+        "SELECT gate, COUNT(*) FROM gate_results
+         WHERE bead_id = ?1 AND status = 'failed' GROUP BY gate",
+    )?;
+    for (bead, sessions) in &bead_sessions {
+        let rows: Vec<(String, i64)> = failures
+            .query_map([bead], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_>>()?;
+        for (gate, count) in rows {
+            for (i, &session) in sessions.iter().enumerate() {
+                // Count the bead's failures once, link all its sessions.
+                let n = if i == 0 { count as usize } else { 0 };
+                out.add(ClusterKind::GateFailure, gate.clone(), session, n);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Mine the last `last` sessions (by observation) for failure clusters.
+pub fn mine(conn: &Connection, last: u32) -> Result<Vec<Cluster>> {
+    let observations = db::recent_observations(conn, last as i64)?;
+    let mut collector = Collector::default();
+    for obs in &observations {
+        let data: Value = serde_json::from_str(&obs.data).unwrap_or_default();
+        let turns = data["turns.total"].as_u64().map(|t| t as u32);
+        let calls = tool_call::for_session(conn, obs.session)?;
+        scan_session(&calls, turns, obs.session, &mut collector);
+    }
+    let sessions: Vec<i64> = observations.iter().map(|o| o.session).collect();
+    scan_gates(conn, &sessions, &mut collector)?;
+    Ok(collector.finish())
+}
+
+/// File the top `top` clusters as draft improvements.
+///
+/// A cluster whose draft already exists (same title, not dismissed) gets its
+/// evidence refreshed instead; one that near-duplicates another improvement
+/// is linked to it rather than filed again. Returns each cluster's ref.
+pub fn file_drafts(
+    conn: &Connection,
+    clusters: &[Cluster],
+    top: usize,
+) -> Result<Vec<(Cluster, String)>> {
+    let existing: Vec<db::Improvement> = db::list_improvements(conn, None, None)?
+        .into_iter()
+        .filter(|imp| imp.status != "dismissed")
+        .collect();
+    let index = dedup::Index::build(&existing);
+
+    let mut filed = Vec::new();
+    for cluster in clusters.iter().take(top) {
+        let title = cluster.title();
+        let context = format!("Mined from session traces: {}", cluster.evidence());
+        let ref_id = if let Some(imp) = existing.iter().find(|imp| imp.title == title) {
+            if imp.status == "draft" {
+                db::update_improvement(conn, &imp.ref_id, None, None, Some(&context), None)?;
+            }
+            imp.ref_id.clone()
+        } else if let Some(m) = index
+            .similar(&title, Some(cluster.body()), dedup::REJECT_SIMILARITY)
+            .first()
+        {
+            m.ref_id.clone()
+        } else {
+            let ref_id = db::insert_improvement(
+                conn,
+                cluster.kind.category(),
+                &title,
+                Some(cluster.body()),
+                Some(&context),
+                Some(&format!("mined,{}", cluster.kind.as_str())),
+            )?;
+            db::update_improvement(conn, &ref_id, Some("draft"), None, None, None)?;
+            ref_id
+        };
+        filed.push((cluster.clone(), ref_id));
+    }
+    Ok(filed)
+}
+
+/// Mine and render the top `top` clusters as evidence for the analysis
+/// prompt. Nothing is filed; that is left to the agent or `improve mine --file`.
+pub fn analysis_evidence(conn: &Connection, last: u32, top: usize) -> Result<String> {
+    if top == 0 {
+        return Ok("(failure mining disabled)".to_string());
+    }
+    let clusters = mine(conn, last)?;
+    if clusters.is_empty() {
+        return Ok("(no recurring failures found)".to_string());
+    }
+    Ok(clusters
+        .iter()
+        .take(top)
+        .map(|cluster| {
+            format!(
+                "- {}: {} — {}",
+                cluster.kind.as_str(),
+                cluster.title(),
+                cluster.evidence()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Handle `blacksmith improve mine`.
+pub fn handle_mine(
+    db_path: &Path,
+    last: u32,
+    top: usize,
+    file: bool,
+) -> std::result::Result<(), String> {
+    let conn = db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
+    let clusters = mine(&conn, last).map_err(|e| format!("Failed to mine sessions: {e}"))?;
+    if clusters.is_empty() {
+        println!("No recurring failures in the last {last} sessions.");
+        return Ok(());
+    }
+
+    if file {
+        let filed = file_drafts(&conn, &clusters, top)
+            .map_err(|e| format!("Failed to file drafts: {e}"))?;
+        for (cluster, ref_id) in &filed {
+            println!("{ref_id:<6} {}", cluster.title());
+            println!("{:<6} {}", "", cluster.evidence());
+        }
+        println!(
+            "\n{} draft(s); accept one with `blacksmith improve update <REF> --status open --body \"...\"`",
+            filed.len()
+        );
+    } else {
+        for cluster in clusters.iter().take(top) {
+            println!("{:<17} {}", cluster.kind.as_str(), cluster.title());
+            println!("{:<17} {}", "", cluster.evidence());
+        }
+        println!(
+            "\n{} cluster(s) in the last {last} sessions; pass --file to record the top {top} as drafts",
+            clusters.len()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (tempfile::TempDir, Connection) {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = db::open_or_create(&dir.path().join("blacksmith.db")).unwrap();
+        (dir, conn)
+    }
+
+    fn call(turn: u32, command: &str, success: bool) -> ToolCall {
+        ToolCall {
+            turn,
+            tool: "Bash".to_string(),
+            command: Some(command.to_string()),
+            success: Some(success),
+            ..Default::default()
+        }
+    }
+
+    fn write_session(conn: &Connection, session: i64, turns: u32, calls: &[ToolCall]) {
+        let data = serde_json::json!({"turns.total": turns}).to_string();
+        db::upsert_observation(conn, session, "2026-01-01T00:00:00Z", None, None, &data).unwrap();
+        tool_call::record(conn, session, calls).unwrap();
+    }
+
+    #[test]
+    fn clusters_failures_across_sessions() {
+        let (_dir, conn) = setup();
+        for session in [1, 2] {
+            write_session(
+                &conn,
+                session,
+                2,
+                &[call(0, "cargo test", false), call(1, "cargo test", true)],
+            );
+        }
+        // A one-off failure is not a pattern.
+        write_session(&conn, 3, 1, &[call(0, "npm run", false)]);
+
+        let clusters = mine(&conn, 10).unwrap();
+        assert_eq!(clusters.len(), 1);
+        let c = &clusters[0];
+        assert_eq!(c.kind, ClusterKind::ToolFailure);
+        assert_eq!(c.signature, "Bash `cargo test`");
+        assert_eq!(c.occurrences, 2);
+        assert_eq!(c.sessions, BTreeSet::from([1, 2]));
+        assert_eq!(
+            c.evidence(),
+            "2 occurrence(s) in 2 session(s); e.g. sessions 2, 1"
+        );
+    }
+
+    #[test]
+    fn detects_retries_and_narration_streaks() {
+        let (_dir, conn) = setup();
+        // Turns 0-3 build, 4-6 narrate, 7 lists, 8-10 narrate.
+        let mut calls: Vec<ToolCall> = (0..4).map(|t| call(t, "cargo build", true)).collect();
+        calls.push(call(7, "ls", true));
+        write_session(&conn, 1, 11, &calls);
+
+        let clusters = mine(&conn, 10).unwrap();
+        let retried = clusters
+            .iter()
+            .find(|c| c.kind == ClusterKind::RetriedCommand)
+            .unwrap();
+        assert_eq!(retried.signature, "`cargo build`");
+        assert_eq!(retried.occurrences, 3);
+        let streaks = clusters
+            .iter()
+            .find(|c| c.kind == ClusterKind::NarrationStreak)
+            .unwrap();
+        assert_eq!(streaks.occurrences, 2);
+    }
+
+    #[test]
+    fn analysis_evidence_does_not_file_drafts() {
+        let (_dir, conn) = setup();
+        for session in [1, 2] {
+            write_session(&conn, session, 1, &[call(0, "cargo test", false)]);
+        }
+
+        let evidence = analysis_evidence(&conn, 10, 5).unwrap();
+        assert!(evidence.contains("Recurring tool failure: Bash `cargo test`"));
+        assert!(db::list_improvements(&conn, None, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn clusters_gate_failures_by_gate() {
+        let (_dir, conn) = setup();
+        for (session, bead) in [(1, "bd-1"), (2, "bd-2")] {
+            write_session(&conn, session, 1, &[]);
+            db::insert_event(&conn, session, "session.bead_id", Some(bead), None).unwrap();
+            conn.execute(
+                "INSERT INTO gate_results (bead_id, gate, status, duration_ms) VALUES (?1, 'clippy', 'failed', 10)",
+                [bead],
+            )
+            .unwrap();
+        }
+
+        let clusters = mine(&conn, 10).unwrap();
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].kind, ClusterKind::GateFailure);
+        assert_eq!(clusters[0].title(), "Finish gate 'clippy' keeps failing");
+        assert_eq!(clusters[0].sessions, BTreeSet::from([1, 2]));
+    }
+
+    #[test]
+    fn files_drafts_once_and_refreshes_evidence() {
+        let (_dir, conn) = setup();
+        let mut cluster = Cluster {
+            kind: ClusterKind::GateFailure,
+            signature: "clippy".to_string(),
+            occurrences: 2,
+            sessions: BTreeSet::from([1, 2]),
+        };
+        let filed = file_drafts(&conn, std::slice::from_ref(&cluster), 5).unwrap();
+        let ref_id = filed[0].1.clone();
+        let draft = db::get_improvement(&conn, &ref_id).unwrap().unwrap();
+        assert_eq!(draft.status, "draft");
+        assert_eq!(draft.category, "quality");
+        assert!(draft.context.unwrap().contains("e.g. sessions 2, 1"));
+
+        cluster.occurrences = 3;
+        cluster.sessions.insert(7);
+        let again = file_drafts(&conn, &[cluster], 5).unwrap();
+        assert_eq!(again[0].1, ref_id);
+        assert_eq!(db::list_improvements(&conn, None, None).unwrap().len(), 1);
+        let draft = db::get_improvement(&conn, &ref_id).unwrap().unwrap();
+        assert!(draft
+            .context
+            .unwrap()
+            .contains("3 occurrence(s) in 3 session(s)"));
+    }
+}
@@ -1,13 +1,16 @@
-//! Module boundary detection for Rust codebases.
+//! Module boundary detection.
 //!
-//! Given a file tree (list of `.rs` files), identifies logical module boundaries —
-//! groups of files that form a cohesive unit (e.g., `src/adapters/` is the "adapters" module).
+//! Given a file tree (list of source files in any language with a
+//! `LanguageFrontend`), identifies logical module boundaries — groups of files
+//! that form a cohesive unit (e.g., `src/adapters/` is the "adapters" module).
 //! Each detected module includes its name, root path, contained files, and whether it
-//! has a canonical entry point (`mod.rs` or `lib.rs`).
+//! has a canonical entry point (`mod.rs`, `lib.rs`, `index.ts`, `__init__.py`, ...).
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use crate::lang;
+
 /// A detected module boundary in the codebase.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Module {
@@ -25,14 +28,15 @@ pub struct Module {
     pub submodules: Vec<String>,
 }
 
-/// Detect module boundaries from a list of `.rs` files under a source root.
+/// Detect module boundaries from a list of source files under a source root.
 ///
 /// Groups files by their parent directory, treating each directory as a module.
-/// The `src/` directory itself is the crate root module.
+/// The `src/` directory itself is the crate root module. Directories outside
+/// `src_root` are named relative to its parent (the repo root).
 ///
 /// # Arguments
-/// * `src_root` - The `src/` directory of the Rust project
-/// * `rs_files` - All `.rs` files found under `src_root`
+/// * `src_root` - The `src/` directory of the project
+/// * `rs_files` - All source files found in the project
 ///
 /// # Returns
 /// A map from module name to `Module` struct. The crate root is keyed as `"crate"`.
@@ -84,14 +88,21 @@ pub fn detect_modules(src_root: &Path, rs_files: &[PathBuf]) -> HashMap<String,
 
 /// Derive a module name from a directory path relative to src_root.
 ///
-/// e.g., `src/adapters/claude` → `"adapters::claude"`
+/// e.g., `src/adapters/claude` → `"adapters::claude"`, and for a directory
+/// outside `src/`, `web/components` → `"web::components"`. Files directly in
+/// the repo root (when it is not the source root) form the `"root"` module.
 fn module_name_from_path(src_root: &Path, dir: &Path) -> String {
-    match dir.strip_prefix(src_root) {
-        Ok(rel) => rel
-            .components()
+    let join = |rel: &Path| {
+        rel.components()
             .map(|c| c.as_os_str().to_string_lossy().to_string())
             .collect::<Vec<_>>()
-            .join("::"),
+            .join("::")
+    };
+    let repo_root = src_root.parent().unwrap_or(src_root);
+    match dir.strip_prefix(src_root) {
+        Ok(rel) => join(rel),
+        Err(_) if dir == repo_root => "root".to_string(),
+        Err(_) if dir.starts_with(repo_root) => join(dir.strip_prefix(repo_root).unwrap()),
         Err(_) => dir
             .file_name()
             .map(|n| n.to_string_lossy().to_string())
@@ -101,10 +112,10 @@ fn module_name_from_path(src_root: &Path, dir: &Path) -> String {
 
 /// Find the canonical entry point for a module directory.
 ///
-/// Looks for `mod.rs`, `lib.rs`, or `main.rs` (in that priority order).
+/// Looks for `mod.rs`, `lib.rs`, or `main.rs` (in that priority order), then
+/// the entry points of the other language frontends (`index.ts`, `__init__.py`, ...).
 fn find_entry_point(dir: &Path, files: &[PathBuf]) -> Option<PathBuf> {
-    let candidates = ["mod.rs", "lib.rs", "main.rs"];
-    for candidate in &candidates {
+    for candidate in lang::entry_point_names() {
         let path = dir.join(candidate);
         if files.contains(&path) {
             return Some(path);
@@ -143,34 +154,48 @@ fn detect_submodules(dir: &Path, rs_files: &[PathBuf]) -> Vec<String> {
 
 /// Build a complete module tree from a repo root.
 ///
-/// Convenience function that collects `.rs` files and detects modules in one call.
+/// Convenience function that collects source files (see
+/// [`lang::collect_source_files`]) and detects modules in one call. The source
+/// root is `src/` when it exists, otherwise the repo root itself (the usual
+/// layout for Python packages and Go modules).
 pub fn detect_modules_from_repo(repo_root: &Path) -> HashMap<String, Module> {
     let src_root = repo_root.join("src");
-    let rs_files = collect_rs_files_for_modules(&src_root);
-    detect_modules(&src_root, &rs_files)
-}
-
-/// Recursively collect all `.rs` files under a directory.
-fn collect_rs_files_for_modules(dir: &Path) -> Vec<PathBuf> {
-    let mut files = Vec::new();
-    collect_recursive(dir, &mut files);
-    files.sort();
-    files
+    let src_root = if src_root.is_dir() {
+        src_root
+    } else {
+        repo_root.to_path_buf()
+    };
+    let files = lang::collect_source_files(repo_root);
+    detect_modules(&src_root, &files)
 }
 
-fn collect_recursive(dir: &Path, files: &mut Vec<PathBuf>) {
-    let entries = match std::fs::read_dir(dir) {
-        Ok(e) => e,
-        Err(_) => return,
-    };
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_dir() {
-            collect_recursive(&path, files);
-        } else if path.extension().and_then(|e| e.to_str()) == Some("rs") {
-            files.push(path);
-        }
-    }
+/// Names of the modules containing at least one file matched by `patterns`.
+///
+/// Patterns are repo-relative globs or plain paths, as used in bead
+/// `affected:` sets and `git diff --name-only` output. Returns sorted,
+/// de-duplicated module names; files outside every module are ignored.
+pub fn modules_matching(
+    modules: &HashMap<String, Module>,
+    repo_root: &Path,
+    patterns: &[String],
+) -> Vec<String> {
+    let patterns: Vec<glob::Pattern> = patterns
+        .iter()
+        .filter_map(|p| glob::Pattern::new(p).ok())
+        .collect();
+
+    let mut names: Vec<String> = modules
+        .values()
+        .filter(|m| {
+            m.files.iter().any(|f| {
+                let rel = f.strip_prefix(repo_root).unwrap_or(f);
+                patterns.iter().any(|p| p.matches_path(rel))
+            })
+        })
+        .map(|m| m.name.clone())
+        .collect();
+    names.sort();
+    names
 }
 
 #[cfg(test)]
@@ -354,11 +379,38 @@ mod tests {
             ("adapters/claude.rs", "pub struct Claude;"),
         ]);
         let src_root = tmp.path().join("src");
-        let rs_files = collect_rs_files_for_modules(&src_root);
+        let rs_files = lang::collect_source_files(tmp.path());
         let modules = detect_modules(&src_root, &rs_files);
 
         assert_eq!(modules.len(), 2); // crate + adapters
         assert!(modules.contains_key("crate"));
         assert!(modules.contains_key("adapters"));
     }
+
+    #[test]
+    fn modules_matching_maps_globs_and_paths() {
+        let tmp = setup_project(&[
+            ("main.rs", "fn main() {}"),
+            ("adapters/mod.rs", ""),
+            ("adapters/claude.rs", ""),
+            ("db/mod.rs", ""),
+        ]);
+        let modules = detect_modules_from_repo(tmp.path());
+
+        let by_glob = modules_matching(&modules, tmp.path(), &["src/adapters/**".to_string()]);
+        assert_eq!(by_glob, vec!["adapters"]);
+
+        let by_path = modules_matching(
+            &modules,
+            tmp.path(),
+            &[
+                "src/db/mod.rs".to_string(),
+                "src/main.rs".to_string(),
+                "README.md".to_string(),
+            ],
+        );
+        assert_eq!(by_path, vec!["crate", "db"]);
+
+        assert!(modules_matching(&modules, tmp.path(), &[]).is_empty());
+    }
 }
@@ -0,0 +1,872 @@
+//! Planning phase: decompose oversized beads before a worker takes them.
+//!
+//! A big leaf bead handed straight to a coding worker can burn a whole session
+//! and fail. With `[planning] enabled`, a ready bead over a size threshold —
+//! description length, number of `affected:` globs, or past failed attempts —
+//! first goes to the `[agent.planning]` agent, which writes child beads with
+//! `affected:` sets and dependencies to `.blacksmith-plan.json`. The plan is
+//! stored in `plan_proposals`; with `auto_approve` the children are filed
+//! straight away, otherwise they wait for `blacksmith plan approve` or
+//! `plan reject`. Filed children become parent-child dependencies of the
+//! original bead, which is retyped as an epic and auto-closes with them.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::config::PlanningConfig;
+use crate::scheduler::ReadyBead;
+
+/// File the planning agent writes its proposal to, in its working directory.
+pub const PLAN_FILE_NAME: &str = ".blacksmith-plan.json";
+
+/// Prefix of the pseudo bead ids planning workers run under.
+const BEAD_PREFIX: &str = "plan-";
+
+/// Worker bead id for planning `bead_id`.
+pub fn planning_bead_id(bead_id: &str) -> String {
+    format!("{BEAD_PREFIX}{bead_id}")
+}
+
+/// The bead a planning worker id is planning, if it is one.
+pub fn planned_bead(id: &str) -> Option<&str> {
+    id.strip_prefix(BEAD_PREFIX)
+}
+
+/// Why a bead is too big to hand to a coding worker as-is, one threshold
+/// per line, or `None` if it fits.
+pub fn oversize_reason(config: &PlanningConfig, bead: &ReadyBead, failures: u32) -> Option<String> {
+    let mut reasons = Vec::new();
+    if config.max_description_chars > 0 && bead.description_len > config.max_description_chars {
+        reasons.push(format!(
+            "- description is {} chars (max_description_chars {})",
+            bead.description_len, config.max_description_chars
+        ));
+    }
+    let globs = bead.affected_globs.as_ref().map_or(0, Vec::len);
+    if config.max_affected_globs > 0 && globs > config.max_affected_globs {
+        reasons.push(format!(
+            "- declares {globs} affected globs (max_affected_globs {})",
+            config.max_affected_globs
+        ));
+    }
+    if config.max_failures > 0 && failures >= config.max_failures {
+        reasons.push(format!(
+            "- {failures} earlier attempts failed (max_failures {})",
+            config.max_failures
+        ));
+    }
+    (!reasons.is_empty()).then(|| reasons.join("\n"))
+}
+
+/// Number of failed coding attempts recorded for a bead.
+pub fn failed_attempts(conn: &Connection, bead_id: &str) -> rusqlite::Result<u32> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM worker_assignments
+         WHERE bead_id = ?1 AND status IN ('failed', 'integration_failed')",
+        params![bead_id],
+        |row| row.get(0),
+    )
+}
+
+/// Assemble the planning prompt: `PLANNING_PROMPT.md` in the data directory
+/// if present, otherwise the embedded default.
+pub fn prompt(data_root: &Path, bead_id: &str, reason: &str) -> String {
+    let custom_path = data_root.join("PLANNING_PROMPT.md");
+    let template = std::fs::read_to_string(&custom_path)
+        .unwrap_or_else(|_| crate::defaults::PLANNING_PROMPT.to_string());
+    template
+        .replace("{{bead_id}}", bead_id)
+        .replace("{{reason}}", reason)
+        .replace("{{plan_file}}", PLAN_FILE_NAME)
+}
+
+/// A child bead proposed by the planning agent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChildBead {
+    /// Plan-local key other children reference in `depends_on`.
+    #[serde(default)]
+    pub key: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub affected: Vec<String>,
+    /// Keys of children that must land first.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct PlanFile {
+    #[serde(default)]
+    children: Vec<ChildBead>,
+}
+
+/// Parse and check a plan. Children without a key are keyed by position
+/// (`1`, `2`, ...); every `depends_on` must name another child.
+pub fn parse_plan(text: &str) -> Result<Vec<ChildBead>, String> {
+    let plan: PlanFile =
+        serde_json::from_str(text).map_err(|e| format!("invalid plan JSON: {e}"))?;
+    let mut children = plan.children;
+    let mut keys = HashSet::new();
+    for (i, child) in children.iter_mut().enumerate() {
+        if child.title.trim().is_empty() {
+            return Err(format!("child {} has no title", i + 1));
+        }
+        if child.key.is_empty() {
+            child.key = (i + 1).to_string();
+        }
+        if !keys.insert(child.key.clone()) {
+            return Err(format!("duplicate child key '{}'", child.key));
+        }
+    }
+    for child in &children {
+        for dep in &child.depends_on {
+            if dep == &child.key || !keys.contains(dep) {
+                return Err(format!(
+                    "child '{}' depends on unknown child '{dep}'",
+                    child.key
+                ));
+            }
+        }
+    }
+    Ok(children)
+}
+
+/// Read, remove and parse the plan file a planning agent left in `dir`.
+pub fn take_plan_file(dir: &Path) -> Result<Vec<ChildBead>, String> {
+    let path = dir.join(PLAN_FILE_NAME);
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("planning agent wrote no {PLAN_FILE_NAME}: {e}"))?;
+    let _ = std::fs::remove_file(&path);
+    parse_plan(&text)
+}
+
+/// Lifecycle of a plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanStatus {
+    /// Waiting for human approval; the bead is not scheduled meanwhile.
+    Pending,
+    /// Children filed as beads.
+    Filed,
+    /// Rejected via `blacksmith plan reject`; the bead is coded as-is.
+    Rejected,
+    /// The planning agent judged the bead small enough to code as-is.
+    Declined,
+    /// The planning agent failed or wrote no usable plan; the bead is coded as-is.
+    Failed,
+}
+
+impl PlanStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlanStatus::Pending => "pending",
+            PlanStatus::Filed => "filed",
+            PlanStatus::Rejected => "rejected",
+            PlanStatus::Declined => "declined",
+            PlanStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(PlanStatus::Pending),
+            "filed" => Some(PlanStatus::Filed),
+            "rejected" => Some(PlanStatus::Rejected),
+            "declined" => Some(PlanStatus::Declined),
+            "failed" => Some(PlanStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// A stored plan for one bead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    pub id: i64,
+    pub bead_id: String,
+    pub children: Vec<ChildBead>,
+    pub status: PlanStatus,
+    /// Bead ids of the filed children, in plan order.
+    pub filed: Vec<String>,
+    /// Why planning failed, or why auto-filing fell back to approval.
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+/// Create the plan_proposals table if it doesn't exist.
+pub fn create_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS plan_proposals (
+            id         INTEGER PRIMARY KEY,
+            bead_id    TEXT NOT NULL,
+            children   TEXT NOT NULL,
+            status     TEXT NOT NULL DEFAULT 'pending',
+            filed      TEXT NOT NULL DEFAULT '[]',
+            note       TEXT,
+            created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_plan_proposals_bead ON plan_proposals(bead_id);",
+    )
+}
+
+fn insert(
+    conn: &Connection,
+    bead_id: &str,
+    children: &[ChildBead],
+    status: PlanStatus,
+    filed: &[String],
+    note: Option<&str>,
+) -> rusqlite::Result<i64> {
+    conn.execute(
+        "INSERT INTO plan_proposals (bead_id, children, status, filed, note)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            bead_id,
+            serde_json::to_string(children).unwrap_or_else(|_| "[]".to_string()),
+            status.as_str(),
+            serde_json::to_string(filed).unwrap_or_else(|_| "[]".to_string()),
+            note,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+fn map_plan(row: &rusqlite::Row) -> rusqlite::Result<Plan> {
+    let children: String = row.get(2)?;
+    let status: String = row.get(3)?;
+    let filed: String = row.get(4)?;
+    Ok(Plan {
+        id: row.get(0)?,
+        bead_id: row.get(1)?,
+        children: serde_json::from_str(&children).unwrap_or_default(),
+        status: PlanStatus::parse(&status).unwrap_or(PlanStatus::Pending),
+        filed: serde_json::from_str(&filed).unwrap_or_default(),
+        note: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+const PLAN_COLUMNS: &str = "id, bead_id, children, status, filed, note, created_at";
+
+/// Look up a plan by id.
+pub fn get(conn: &Connection, id: i64) -> rusqlite::Result<Option<Plan>> {
+    conn.query_row(
+        &format!("SELECT {PLAN_COLUMNS} FROM plan_proposals WHERE id = ?1"),
+        params![id],
+        map_plan,
+    )
+    .optional()
+}
+
+/// The most recent plan for a bead.
+pub fn latest_for(conn: &Connection, bead_id: &str) -> rusqlite::Result<Option<Plan>> {
+    conn.query_row(
+        &format!(
+            "SELECT {PLAN_COLUMNS} FROM plan_proposals WHERE bead_id = ?1 ORDER BY id DESC LIMIT 1"
+        ),
+        params![bead_id],
+        map_plan,
+    )
+    .optional()
+}
+
+/// Plans in id order. Only pending ones unless `all` is set.
+pub fn list(conn: &Connection, all: bool) -> rusqlite::Result<Vec<Plan>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {PLAN_COLUMNS} FROM plan_proposals WHERE ?1 OR status = 'pending' ORDER BY id ASC"
+    ))?;
+    let rows = stmt
+        .query_map(params![all], map_plan)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rows)
+}
+
+fn set_status(
+    conn: &Connection,
+    id: i64,
+    status: PlanStatus,
+    filed: &[String],
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE plan_proposals SET status = ?1, filed = ?2 WHERE id = ?3",
+        params![
+            status.as_str(),
+            serde_json::to_string(filed).unwrap_or_else(|_| "[]".to_string()),
+            id
+        ],
+    )?;
+    Ok(())
+}
+
+/// The bead operations filing a plan needs.
+pub trait Tracker {
+    /// File a task bead, returning its id.
+    fn create(&self, child: &ChildBead) -> Result<String, String>;
+    /// Make `from` depend on `to` with the given dependency type.
+    fn depend(&self, from: &str, to: &str, kind: &str) -> Result<(), String>;
+    /// Turn a bead into an epic.
+    fn make_epic(&self, bead_id: &str) -> Result<(), String>;
+}
+
+/// [`Tracker`] backed by the `bd` CLI.
+pub struct Bd;
+
+impl Bd {
+    fn run(args: &[&str]) -> Result<String, String> {
+        let output = Command::new("bd")
+            .args(args)
+            .output()
+            .map_err(|e| format!("failed to run bd {}: {e}", args[0]))?;
+        if !output.status.success() {
+            return Err(format!(
+                "bd {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+impl Tracker for Bd {
+    fn create(&self, child: &ChildBead) -> Result<String, String> {
+        let description = format!("--description={}", child.description);
+        let design = format!("--design=affected: {}", child.affected.join(", "));
+        let mut args = vec![
+            "create",
+            child.title.as_str(),
+            "--type=task",
+            "--priority=2",
+            description.as_str(),
+        ];
+        if !child.affected.is_empty() {
+            args.push(design.as_str());
+        }
+        args.push("--json");
+        let stdout = Self::run(&args)?;
+        crate::arch_review::parse_created_id(&stdout)
+            .ok_or_else(|| "bd create did not report a bead id".to_string())
+    }
+
+    fn depend(&self, from: &str, to: &str, kind: &str) -> Result<(), String> {
+        Self::run(&["dep", "add", from, to, &format!("--type={kind}")]).map(|_| ())
+    }
+
+    fn make_epic(&self, bead_id: &str) -> Result<(), String> {
+        Self::run(&["update", bead_id, "--type=epic"]).map(|_| ())
+    }
+}
+
+/// A `file_children` run that stopped part-way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilingError {
+    /// Ids of the children filed before the error, in plan order.
+    pub filed: Vec<String>,
+    pub error: String,
+}
+
+/// File a plan's children as beads, wire their dependencies, and hang them
+/// off `parent`, which becomes an epic. Returns the child ids in plan order.
+///
+/// `already` holds the ids of children an earlier, interrupted run filed
+/// (a prefix of the plan); those are reused rather than created again.
+pub fn file_children(
+    tracker: &dyn Tracker,
+    parent: &str,
+    children: &[ChildBead],
+    already: &[String],
+) -> Result<Vec<String>, FilingError> {
+    let mut ids: HashMap<&str, String> = HashMap::new();
+    let mut filed = Vec::new();
+    for (i, child) in children.iter().enumerate() {
+        let id = match already.get(i) {
+            Some(id) => id.clone(),
+            None => tracker.create(child).map_err(|error| FilingError {
+                filed: filed.clone(),
+                error,
+            })?,
+        };
+        ids.insert(child.key.as_str(), id.clone());
+        filed.push(id);
+    }
+
+    match wire_children(tracker, parent, children, &ids, &filed) {
+        Ok(()) => Ok(filed),
+        Err(error) => Err(FilingError { filed, error }),
+    }
+}
+
+fn wire_children(
+    tracker: &dyn Tracker,
+    parent: &str,
+    children: &[ChildBead],
+    ids: &HashMap<&str, String>,
+    filed: &[String],
+) -> Result<(), String> {
+    for child in children {
+        for dep in &child.depends_on {
+            if let (Some(from), Some(to)) = (ids.get(child.key.as_str()), ids.get(dep.as_str())) {
+                tracker.depend(from, to, "blocks")?;
+            }
+        }
+    }
+    for id in filed {
+        tracker.depend(parent, id, "parent-child")?;
+    }
+    tracker.make_epic(parent)
+}
+
+/// Store what a planning run produced for `bead_id`, filing the children
+/// when `auto_approve` is set. If filing fails, the plan is queued as
+/// pending instead so it is not lost.
+pub fn record(
+    conn: &Connection,
+    tracker: &dyn Tracker,
+    bead_id: &str,
+    plan: Result<Vec<ChildBead>, String>,
+    auto_approve: bool,
+) -> rusqlite::Result<Plan> {
+    let id = match plan {
+        Err(e) => insert(conn, bead_id, &[], PlanStatus::Failed, &[], Some(&e))?,
+        Ok(children) if children.is_empty() => {
+            insert(conn, bead_id, &[], PlanStatus::Declined, &[], None)?
+        }
+        Ok(children) if auto_approve => match file_children(tracker, bead_id, &children, &[]) {
+            Ok(filed) => insert(conn, bead_id, &children, PlanStatus::Filed, &filed, None)?,
+            Err(e) => {
+                tracing::warn!(
+                    bead_id,
+                    error = %e.error,
+                    filed = e.filed.len(),
+                    "failed to file planned beads, queuing plan for approval instead"
+                );
+                // Keep what was filed so approval resumes instead of duplicating
+                insert(
+                    conn,
+                    bead_id,
+                    &children,
+                    PlanStatus::Pending,
+                    &e.filed,
+                    Some(&e.error),
+                )?
+            }
+        },
+        Ok(children) => insert(conn, bead_id, &children, PlanStatus::Pending, &[], None)?,
+    };
+    get(conn, id).map(|plan| plan.expect("plan just inserted"))
+}
+
+fn open_db(db_path: &Path) -> Result<Option<Connection>, String> {
+    if !db_path.exists() {
+        println!("No metrics database found. Run some sessions first.");
+        return Ok(None);
+    }
+    crate::db::open_or_create(db_path)
+        .map(Some)
+        .map_err(|e| format!("Failed to open database: {e}"))
+}
+
+fn require(conn: &Connection, id: i64) -> Result<Plan, String> {
+    get(conn, id)
+        .map_err(|e| format!("Failed to query plans: {e}"))?
+        .ok_or_else(|| format!("No plan with id {id}"))
+}
+
+fn require_pending(conn: &Connection, id: i64) -> Result<Plan, String> {
+    let plan = require(conn, id)?;
+    if plan.status != PlanStatus::Pending {
+        return Err(format!("Plan {id} is already {}", plan.status.as_str()));
+    }
+    Ok(plan)
+}
+
+/// `blacksmith plan list [--all]`
+pub fn handle_list(db_path: &Path, all: bool) -> Result<(), String> {
+    let Some(conn) = open_db(db_path)? else {
+        return Ok(());
+    };
+    let plans = list(&conn, all).map_err(|e| format!("Failed to query plans: {e}"))?;
+    if plans.is_empty() {
+        println!("No plans awaiting approval.");
+        return Ok(());
+    }
+
+    println!(
+        "{:>4} {:<24} {:<9} {:>8}  FILED",
+        "ID", "BEAD", "STATUS", "CHILDREN"
+    );
+    println!("{}", "-".repeat(80));
+    for p in &plans {
+        println!(
+            "{:>4} {:<24} {:<9} {:>8}  {}",
+            p.id,
+            p.bead_id,
+            p.status.as_str(),
+            p.children.len(),
+            if p.filed.is_empty() {
+                "-".to_string()
+            } else {
+                p.filed.join(", ")
+            }
+        );
+    }
+    Ok(())
+}
+
+/// `blacksmith plan show <id>`
+pub fn handle_show(db_path: &Path, id: i64) -> Result<(), String> {
+    let Some(conn) = open_db(db_path)? else {
+        return Ok(());
+    };
+    let plan = require(&conn, id)?;
+
+    println!("Plan:     {}", plan.id);
+    println!("Bead:     {}", plan.bead_id);
+    println!("Status:   {}", plan.status.as_str());
+    println!("Created:  {}", plan.created_at);
+    if let Some(note) = &plan.note {
+        println!("Note:     {note}");
+    }
+    for (i, child) in plan.children.iter().enumerate() {
+        println!();
+        let filed = plan
+            .filed
+            .get(i)
+            .map(|id| format!(" → {id}"))
+            .unwrap_or_default();
+        println!("[{}] {}{filed}", child.key, child.title);
+        if !child.affected.is_empty() {
+            println!("    affected:   {}", child.affected.join(", "));
+        }
+        if !child.depends_on.is_empty() {
+            println!("    depends on: {}", child.depends_on.join(", "));
+        }
+        for line in child.description.lines() {
+            println!("    {line}");
+        }
+    }
+    Ok(())
+}
+
+/// File a pending plan's children, skipping any an earlier attempt filed.
+/// On failure the children filed so far are saved and the plan stays pending.
+fn approve(conn: &Connection, tracker: &dyn Tracker, plan: &Plan) -> Result<Vec<String>, String> {
+    match file_children(tracker, &plan.bead_id, &plan.children, &plan.filed) {
+        Ok(filed) => {
+            set_status(conn, plan.id, PlanStatus::Filed, &filed).map_err(|e| e.to_string())?;
+            Ok(filed)
+        }
+        Err(e) => {
+            set_status(conn, plan.id, PlanStatus::Pending, &e.filed).map_err(|e| e.to_string())?;
+            Err(e.error)
+        }
+    }
+}
+
+/// `blacksmith plan approve <id>`: file the plan's children as beads.
+pub fn handle_approve(db_path: &Path, id: i64) -> Result<(), String> {
+    let Some(conn) = open_db(db_path)? else {
+        return Ok(());
+    };
+    let plan = require_pending(&conn, id)?;
+    let filed = approve(&conn, &Bd, &plan)?;
+    println!(
+        "Filed {} child bead(s) under {}: {}",
+        filed.len(),
+        plan.bead_id,
+        filed.join(", ")
+    );
+    Ok(())
+}
+
+/// `blacksmith plan reject <id>`: code the bead as-is.
+pub fn handle_reject(db_path: &Path, id: i64) -> Result<(), String> {
+    let Some(conn) = open_db(db_path)? else {
+        return Ok(());
+    };
+    let plan = require_pending(&conn, id)?;
+    set_status(&conn, id, PlanStatus::Rejected, &[]).map_err(|e| e.to_string())?;
+    println!(
+        "Rejected plan {id}; {} will be handed to a coding worker as-is.",
+        plan.bead_id
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+
+    fn test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        create_table(&conn).unwrap();
+        conn
+    }
+
+    fn bead(description_len: usize, globs: usize) -> ReadyBead {
+        ReadyBead {
+            id: "bd-1".to_string(),
+            priority: 2,
+            issue_type: "task".to_string(),
+            parent_child_ids: Vec::new(),
+            affected_globs: (globs > 0)
+                .then(|| (0..globs).map(|i| format!("src/m{i}/**")).collect()),
+            labels: Vec::new(),
+            updated_at: None,
+            description_len,
+        }
+    }
+
+    fn child(key: &str, depends_on: &[&str]) -> ChildBead {
+        ChildBead {
+            key: key.to_string(),
+            title: format!("Do {key}"),
+            description: String::new(),
+            affected: vec![format!("src/{key}/**")],
+            depends_on: depends_on.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// Records every call and hands out sequential ids.
+    #[derive(Default)]
+    struct FakeTracker {
+        calls: RefCell<Vec<String>>,
+        fail_create: bool,
+        /// Fail this create (1-based) once.
+        fail_nth_create: Cell<Option<usize>>,
+    }
+
+    impl Tracker for FakeTracker {
+        fn create(&self, child: &ChildBead) -> Result<String, String> {
+            if self.fail_create {
+                return Err("bd create failed".to_string());
+            }
+            let mut calls = self.calls.borrow_mut();
+            let creates = calls.iter().filter(|c| c.starts_with("create")).count();
+            if self.fail_nth_create.get() == Some(creates + 1) {
+                self.fail_nth_create.set(None);
+                return Err("bd create failed".to_string());
+            }
+            calls.push(format!("create {}", child.title));
+            Ok(format!("bd-c{}", calls.len()))
+        }
+
+        fn depend(&self, from: &str, to: &str, kind: &str) -> Result<(), String> {
+            self.calls
+                .borrow_mut()
+                .push(format!("dep {from} {to} {kind}"));
+            Ok(())
+        }
+
+        fn make_epic(&self, bead_id: &str) -> Result<(), String> {
+            self.calls.borrow_mut().push(format!("epic {bead_id}"));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn planning_bead_ids_round_trip() {
+        assert_eq!(planning_bead_id("bd-7"), "plan-bd-7");
+        assert_eq!(planned_bead("plan-bd-7"), Some("bd-7"));
+        assert_eq!(planned_bead("bd-7"), None);
+    }
+
+    #[test]
+    fn oversize_reason_checks_each_threshold() {
+        let config = PlanningConfig {
+            enabled: true,
+            max_description_chars: 100,
+            max_affected_globs: 2,
+            max_failures: 2,
+            auto_approve: false,
+        };
+        assert_eq!(oversize_reason(&config, &bead(100, 2), 1), None);
+
+        let reason = oversize_reason(&config, &bead(101, 3), 2).unwrap();
+        assert_eq!(reason.lines().count(), 3);
+        assert!(reason.contains("description is 101 chars"));
+        assert!(reason.contains("declares 3 affected globs"));
+        assert!(reason.contains("2 earlier attempts failed"));
+
+        let disabled = PlanningConfig {
+            max_description_chars: 0,
+            max_affected_globs: 0,
+            max_failures: 0,
+            ..config
+        };
+        assert_eq!(oversize_reason(&disabled, &bead(10_000, 50), 9), None);
+    }
+
+    #[test]
+    fn parse_plan_keys_children_and_checks_dependencies() {
+        let children = parse_plan(
+            r#"{"children": [
+                {"title": "First", "affected": ["src/a/**"]},
+                {"key": "b", "title": "Second", "depends_on": ["1"]}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(children[0].key, "1");
+        assert_eq!(children[1].depends_on, vec!["1"]);
+
+        assert!(parse_plan(r#"{"children": []}"#).unwrap().is_empty());
+        assert!(parse_plan("not json").is_err());
+        assert!(parse_plan(r#"{"children": [{"title": ""}]}"#).is_err());
+        assert!(
+            parse_plan(r#"{"children": [{"title": "A", "depends_on": ["x"]}]}"#)
+                .unwrap_err()
+                .contains("unknown child 'x'")
+        );
+        assert!(parse_plan(
+            r#"{"children": [{"key": "a", "title": "A"}, {"key": "a", "title": "B"}]}"#
+        )
+        .unwrap_err()
+        .contains("duplicate"));
+    }
+
+    #[test]
+    fn take_plan_file_reads_and_removes_it() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(take_plan_file(dir.path()).is_err());
+
+        std::fs::write(
+            dir.path().join(PLAN_FILE_NAME),
+            r#"{"children": [{"title": "A"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(take_plan_file(dir.path()).unwrap().len(), 1);
+        assert!(!dir.path().join(PLAN_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn file_children_wires_dependencies_and_parent() {
+        let tracker = FakeTracker::default();
+        let children = [child("a", &[]), child("b", &["a"])];
+        let filed = file_children(&tracker, "bd-1", &children, &[]).unwrap();
+        assert_eq!(filed, vec!["bd-c1", "bd-c2"]);
+        assert_eq!(
+            *tracker.calls.borrow(),
+            vec![
+                "create Do a",
+                "create Do b",
+                "dep bd-c2 bd-c1 blocks",
+                "dep bd-1 bd-c1 parent-child",
+                "dep bd-1 bd-c2 parent-child",
+                "epic bd-1",
+            ]
+        );
+    }
+
+    #[test]
+    fn approve_resumes_after_a_partial_filing() {
+        let conn = test_db();
+        let tracker = FakeTracker {
+            fail_nth_create: Cell::new(Some(2)),
+            ..Default::default()
+        };
+        let children = vec![child("a", &[]), child("b", &["a"])];
+
+        // Auto-approve stops at the second create but keeps the first child
+        let plan = record(&conn, &tracker, "bd-1", Ok(children), true).unwrap();
+        assert_eq!(plan.status, PlanStatus::Pending);
+        assert_eq!(plan.filed, vec!["bd-c1"]);
+        assert_eq!(plan.note.as_deref(), Some("bd create failed"));
+
+        // Approval files only the missing child and wires both
+        let filed = approve(&conn, &tracker, &plan).unwrap();
+        assert_eq!(filed, vec!["bd-c1", "bd-c2"]);
+        assert_eq!(
+            *tracker.calls.borrow(),
+            vec![
+                "create Do a",
+                "create Do b",
+                "dep bd-c2 bd-c1 blocks",
+                "dep bd-1 bd-c1 parent-child",
+                "dep bd-1 bd-c2 parent-child",
+                "epic bd-1",
+            ]
+        );
+        let plan = get(&conn, plan.id).unwrap().unwrap();
+        assert_eq!(plan.status, PlanStatus::Filed);
+        assert_eq!(plan.filed, vec!["bd-c1", "bd-c2"]);
+    }
+
+    #[test]
+    fn record_queues_or_files_by_config() {
+        let conn = test_db();
+        let tracker = FakeTracker::default();
+
+        let queued = record(&conn, &tracker, "bd-1", Ok(vec![child("a", &[])]), false).unwrap();
+        assert_eq!(queued.status, PlanStatus::Pending);
+        assert!(tracker.calls.borrow().is_empty());
+
+        let filed = record(&conn, &tracker, "bd-2", Ok(vec![child("a", &[])]), true).unwrap();
+        assert_eq!(filed.status, PlanStatus::Filed);
+        assert_eq!(filed.filed, vec!["bd-c1"]);
+
+        let declined = record(&conn, &tracker, "bd-3", Ok(Vec::new()), true).unwrap();
+        assert_eq!(declined.status, PlanStatus::Declined);
+
+        let failed = record(&conn, &tracker, "bd-4", Err("no plan".to_string()), true).unwrap();
+        assert_eq!(failed.status, PlanStatus::Failed);
+        assert_eq!(failed.note.as_deref(), Some("no plan"));
+
+        // Filing errors fall back to approval
+        let broken = FakeTracker {
+            fail_create: true,
+            ..Default::default()
+        };
+        let fallback = record(&conn, &broken, "bd-5", Ok(vec![child("a", &[])]), true).unwrap();
+        assert_eq!(fallback.status, PlanStatus::Pending);
+        assert_eq!(fallback.note.as_deref(), Some("bd create failed"));
+
+        let pending: Vec<String> = list(&conn, false)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.bead_id)
+            .collect();
+        assert_eq!(pending, vec!["bd-1", "bd-5"]);
+        assert_eq!(list(&conn, true).unwrap().len(), 5);
+        assert_eq!(
+            latest_for(&conn, "bd-2").unwrap().unwrap().children,
+            vec![child("a", &[])]
+        );
+        assert!(latest_for(&conn, "bd-9").unwrap().is_none());
+    }
+
+    #[test]
+    fn set_status_records_filed_children() {
+        let conn = test_db();
+        let plan = record(
+            &conn,
+            &FakeTracker::default(),
+            "bd-1",
+            Ok(vec![child("a", &[])]),
+            false,
+        )
+        .unwrap();
+        set_status(&conn, plan.id, PlanStatus::Filed, &["bd-9".to_string()]).unwrap();
+        let plan = get(&conn, plan.id).unwrap().unwrap();
+        assert_eq!(plan.status, PlanStatus::Filed);
+        assert_eq!(plan.filed, vec!["bd-9"]);
+        assert!(require_pending(&conn, plan.id).is_err());
+    }
+
+    #[test]
+    fn failed_attempts_counts_failed_assignments() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = crate::db::open_or_create(&dir.path().join("blacksmith.db")).unwrap();
+        for status in ["failed", "integration_failed", "completed"] {
+            crate::db::insert_worker_assignment(&conn, 0, "bd-1", "/tmp/wt", status, None).unwrap();
+        }
+        assert_eq!(failed_attempts(&conn, "bd-1").unwrap(), 2);
+        assert_eq!(failed_attempts(&conn, "bd-2").unwrap(), 0);
+    }
+}
@@ -4,6 +4,7 @@
 /// idle -> coding -> completed/failed. Completed workers are queued for integration.
 use crate::config::{ResolvedAgentConfig, WorkersConfig};
 use crate::db;
+use crate::salvage;
 use crate::worktree;
 use rusqlite::Connection;
 use std::path::{Path, PathBuf};
@@ -95,6 +96,18 @@ pub struct WorkerPool {
     repo_dir: PathBuf,
     worktrees_dir: PathBuf,
     base_branch: String,
+    /// Keep one worktree per slot and reset it between beads.
+    reuse_worktrees: bool,
+    /// Directories copied from `seed_from` into fresh worktrees.
+    seed_dirs: Vec<String>,
+    /// Template checkout for seeding (defaults to `repo_dir`).
+    seed_from: Option<PathBuf>,
+    /// Commands run in the worktree before the agent starts.
+    setup: Vec<String>,
+    /// Replay pending salvaged work for a retried bead into its worktree.
+    resume_salvage: bool,
+    /// Root of per-slot `CARGO_TARGET_DIR`s, when shared target dirs are enabled.
+    target_cache_dir: Option<PathBuf>,
     /// Next numeric session ID to use for output file naming.
     /// Ensures worker output files follow the `{N}.jsonl` convention
     /// expected by compress, retention/gc, and metrics subsystems.
@@ -114,6 +127,8 @@ pub enum PoolError {
     Db(rusqlite::Error),
     /// Session spawn error.
     Spawn(std::io::Error),
+    /// Worktree provisioning (seeding or setup commands) failed.
+    Setup(String),
 }
 
 impl std::fmt::Display for PoolError {
@@ -124,6 +139,7 @@ impl std::fmt::Display for PoolError {
             PoolError::Worktree(e) => write!(f, "worktree error: {e}"),
             PoolError::Db(e) => write!(f, "database error: {e}"),
             PoolError::Spawn(e) => write!(f, "spawn error: {e}"),
+            PoolError::Setup(msg) => write!(f, "setup error: {msg}"),
         }
     }
 }
@@ -172,10 +188,25 @@ impl WorkerPool {
             repo_dir,
             worktrees_dir: worktrees_base,
             base_branch: config.base_branch.clone(),
+            reuse_worktrees: config.reuse_worktrees,
+            seed_dirs: config.seed_dirs.clone(),
+            seed_from: config.seed_from.clone(),
+            setup: config.setup.clone(),
+            resume_salvage: config.resume_salvage,
+            target_cache_dir: None,
             next_session_id: initial_session_id,
         }
     }
 
+    /// Give each worker slot a persistent `CARGO_TARGET_DIR` under `dir`.
+    ///
+    /// Ignored in single-agent mode, where the agent builds in the repo's own
+    /// target directory.
+    pub fn with_target_cache(mut self, dir: Option<PathBuf>) -> Self {
+        self.target_cache_dir = dir;
+        self
+    }
+
     /// Number of total worker slots.
     #[allow(dead_code)]
     pub fn capacity(&self) -> u32 {
@@ -253,6 +284,15 @@ impl WorkerPool {
         // In single-agent mode, skip worktree creation and use repo_dir directly
         let wt_path = if self.is_single_agent() {
             self.repo_dir.clone()
+        } else if self.reuse_worktrees {
+            let (path, reused) = worktree::acquire_slot(
+                &self.repo_dir,
+                &self.worktrees_dir,
+                worker_id,
+                &self.base_branch,
+            )?;
+            tracing::debug!(worker_id, reused, path = %path.display(), "worker slot ready");
+            path
         } else {
             worktree::create(
                 &self.repo_dir,
@@ -263,6 +303,35 @@ impl WorkerPool {
             )?
         };
 
+        let mut agent_config = agent_config.clone();
+        if let Some(target_dir) = self.slot_target_dir(worker_id) {
+            agent_config.env.insert(
+                "CARGO_TARGET_DIR".to_string(),
+                target_dir.to_string_lossy().into_owned(),
+            );
+        }
+
+        if !self.is_single_agent() {
+            if let Err(e) = self.provision(&wt_path, &agent_config.env) {
+                if !self.reuse_worktrees {
+                    let _ = worktree::remove(&self.repo_dir, &wt_path);
+                }
+                return Err(e);
+            }
+
+            if self.resume_salvage {
+                match salvage::resume(db_conn, &wt_path, bead_id) {
+                    Ok(Some(commits)) => {
+                        tracing::info!(worker_id, bead_id, commits, "resumed from salvaged work");
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        tracing::warn!(worker_id, bead_id, error = %e, "failed to resume salvaged work, starting fresh");
+                    }
+                }
+            }
+        }
+
         // Insert assignment into DB with the bead's declared affected set
         let assignment_id = db::insert_worker_assignment(
             db_conn,
@@ -284,7 +353,7 @@ impl WorkerPool {
         // Spawn the agent process in the working directory
         let handle = spawn_agent_in_worktree(
             worker_id,
-            agent_config,
+            &agent_config,
             &wt_path,
             &output_file,
             prompt,
@@ -306,6 +375,45 @@ impl WorkerPool {
         Ok((worker_id, assignment_id))
     }
 
+    /// Per-slot `CARGO_TARGET_DIR`, if shared target dirs are enabled.
+    fn slot_target_dir(&self, worker_id: u32) -> Option<PathBuf> {
+        if self.is_single_agent() {
+            return None;
+        }
+        self.target_cache_dir
+            .as_ref()
+            .map(|dir| worktree::slot_target_dir(dir, worker_id))
+    }
+
+    /// Seed build caches and run `[workers] setup` commands in a worktree.
+    fn provision(
+        &self,
+        wt_path: &Path,
+        env: &std::collections::HashMap<String, String>,
+    ) -> Result<(), PoolError> {
+        if !self.seed_dirs.is_empty() {
+            let template = self.seed_from.as_deref().unwrap_or(&self.repo_dir);
+            let seeded = worktree::seed(template, wt_path, &self.seed_dirs)?;
+            if !seeded.is_empty() {
+                tracing::info!(path = %wt_path.display(), dirs = ?seeded, "seeded worktree");
+            }
+        }
+
+        for cmd in &self.setup {
+            let result = crate::gates::run_command(cmd, wt_path, env, None);
+            if !result.success {
+                let lines: Vec<&str> = result.output.lines().collect();
+                let tail = &lines[lines.len().saturating_sub(20)..];
+                return Err(PoolError::Setup(format!(
+                    "`{cmd}` failed:\n{}",
+                    tail.join("\n")
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Poll all coding workers for completion. Returns a list of completed/failed outcomes.
     ///
     /// Only collects workers whose JoinHandle has already resolved (is_finished).
@@ -433,8 +541,11 @@ impl WorkerPool {
     }
 
     /// Reset a worker back to idle, cleaning up its worktree.
+    ///
+    /// With `reuse_worktrees` the slot's worktree is kept; it is reset to the
+    /// base branch when the next bead is assigned.
     pub fn reset_worker(&mut self, worker_id: u32) -> Result<(), PoolError> {
-        let single_agent = self.is_single_agent();
+        let keep_worktree = self.is_single_agent() || self.reuse_worktrees;
         let worker = self
             .workers
             .get_mut(worker_id as usize)
@@ -447,7 +558,7 @@ impl WorkerPool {
         }
 
         // Clean up worktree (skip in single-agent mode — worker used repo dir directly)
-        if !single_agent {
+        if !keep_worktree {
             if let Some(ref wt_path) = worker.worktree_path {
                 if let Err(e) = worktree::remove(&self.repo_dir, wt_path) {
                     tracing::warn!(
@@ -680,6 +791,7 @@ mod tests {
             base_branch: "main".to_string(),
             worktrees_dir: "worktrees".to_string(),
             persistent: false,
+            ..Default::default()
         }
     }
 
@@ -872,6 +984,97 @@ mod tests {
         assert!(pool.completed_workers().is_empty());
     }
 
+    #[tokio::test]
+    async fn test_reused_slot_is_provisioned_and_kept() {
+        let dir = init_test_repo();
+        let wt_dir = dir.path().join("worktrees");
+        std::fs::create_dir_all(&wt_dir).unwrap();
+        let output_dir = dir.path().join("output");
+        std::fs::create_dir_all(&output_dir).unwrap();
+        let cache = dir.path().join("target-cache");
+
+        let workers_config = WorkersConfig {
+            reuse_worktrees: true,
+            setup: vec!["touch setup-ran".to_string()],
+            ..test_workers_config(2)
+        };
+        let mut pool =
+            WorkerPool::new(&workers_config, dir.path().to_path_buf(), wt_dir.clone(), 0)
+                .with_target_cache(Some(cache.clone()));
+
+        let db_path = dir.path().join("test.db");
+        let conn = db::open_or_create(&db_path).unwrap();
+        let agent = ResolvedAgentConfig {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), "echo $CARGO_TARGET_DIR".to_string()],
+            adapter: None,
+            prompt_via: crate::config::PromptVia::Arg,
+            env: HashMap::new(),
+        };
+
+        let (worker_id, _) = pool
+            .spawn_worker("beads-warm", None, &agent, "test", &output_dir, &conn)
+            .await
+            .unwrap();
+        let slot = worktree::slot_path(&wt_dir, worker_id);
+        assert_eq!(pool.worker_worktree_path(worker_id), Some(slot.clone()));
+        assert!(slot.join("setup-ran").exists());
+
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        let outcomes = pool.poll_completed().await;
+        let output = std::fs::read_to_string(&outcomes[0].output_file).unwrap();
+        assert_eq!(
+            output.trim(),
+            worktree::slot_target_dir(&cache, worker_id).to_string_lossy()
+        );
+
+        // The slot survives reset and is reused for the next bead
+        pool.reset_worker(worker_id).unwrap();
+        assert!(slot.exists());
+        let (next_id, _) = pool
+            .spawn_worker("beads-next", None, &agent, "test", &output_dir, &conn)
+            .await
+            .unwrap();
+        assert_eq!(next_id, worker_id);
+        assert_eq!(pool.worker_worktree_path(next_id), Some(slot));
+    }
+
+    #[tokio::test]
+    async fn test_failed_setup_removes_fresh_worktree() {
+        let dir = init_test_repo();
+        let wt_dir = dir.path().join("worktrees");
+        std::fs::create_dir_all(&wt_dir).unwrap();
+        let output_dir = dir.path().join("output");
+        std::fs::create_dir_all(&output_dir).unwrap();
+
+        let workers_config = WorkersConfig {
+            setup: vec!["echo lockfile out of date; exit 1".to_string()],
+            ..test_workers_config(2)
+        };
+        let mut pool =
+            WorkerPool::new(&workers_config, dir.path().to_path_buf(), wt_dir.clone(), 0);
+
+        let db_path = dir.path().join("test.db");
+        let conn = db::open_or_create(&db_path).unwrap();
+        let result = pool
+            .spawn_worker(
+                "beads-setup",
+                None,
+                &test_agent_config(),
+                "test",
+                &output_dir,
+                &conn,
+            )
+            .await;
+
+        match result {
+            Err(PoolError::Setup(msg)) => assert!(msg.contains("lockfile out of date")),
+            other => panic!("expected setup error, got {other:?}"),
+        }
+        assert!(!worktree::worktree_path(&wt_dir, 0, "beads-setup").exists());
+        assert_eq!(pool.idle_count(), 2);
+    }
+
     #[tokio::test]
     async fn test_no_idle_worker_error() {
         let dir = init_test_repo();
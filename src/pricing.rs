@@ -0,0 +1,294 @@
+//! Cost accounting from token counts.
+//!
+//! Agents report cost inconsistently: Claude prints `total_cost_usd`, Aider
+//! prints a running session total, and Codex/OpenCode usually print nothing.
+//! This module derives `cost.computed_usd` from the adapter's token events and
+//! the `[pricing]` table, and aggregates reported vs computed cost per model
+//! for `metrics status`, `brief` and `/api/metrics/summary`.
+
+use crate::config::{ModelPricing, PricingConfig};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Model label used when a session did not report its model.
+pub const UNKNOWN_MODEL: &str = "(unknown)";
+
+/// Token counts for one session, split by billing category.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TokenUsage {
+    /// Uncached input tokens.
+    pub input: u64,
+    pub output: u64,
+    pub cache_read: u64,
+    pub cache_write: u64,
+}
+
+impl TokenUsage {
+    /// Collect token counts from adapter metrics (`cost.*_tokens` kinds).
+    ///
+    /// Returns None when the adapter emitted no token events at all, so
+    /// callers can tell "zero tokens" apart from "tokens unknown".
+    pub fn from_metrics(metrics: &[(String, Value)]) -> Option<Self> {
+        let mut usage = TokenUsage::default();
+        let mut seen = false;
+        for (kind, value) in metrics {
+            let slot = match kind.as_str() {
+                "cost.input_tokens" => &mut usage.input,
+                "cost.output_tokens" => &mut usage.output,
+                "cost.cache_read_tokens" => &mut usage.cache_read,
+                "cost.cache_creation_tokens" => &mut usage.cache_write,
+                _ => continue,
+            };
+            if let Some(n) = value.as_u64() {
+                *slot += n;
+                seen = true;
+            }
+        }
+        seen.then_some(usage)
+    }
+
+    /// Cost in USD for this usage at the given rates (USD per million tokens).
+    pub fn cost_usd(&self, rate: &ModelPricing) -> f64 {
+        (self.input as f64 * rate.input
+            + self.output as f64 * rate.output
+            + self.cache_read as f64 * rate.cache_read
+            + self.cache_write as f64 * rate.cache_write)
+            / 1_000_000.0
+    }
+}
+
+/// Look up a string-valued metric (e.g. `session.model`) in adapter output.
+pub fn metric_str<'a>(metrics: &'a [(String, Value)], kind: &str) -> Option<&'a str> {
+    metrics
+        .iter()
+        .find(|(k, _)| k == kind)
+        .and_then(|(_, v)| v.as_str())
+        .filter(|s| !s.is_empty())
+}
+
+/// Compute a session's cost from its adapter metrics and the pricing table.
+///
+/// Returns None when the session has no model, no token events, or any of its
+/// models has no pricing entry — a partial sum would understate the cost.
+pub fn compute_session_cost(metrics: &[(String, Value)], pricing: &PricingConfig) -> Option<f64> {
+    compute_cost_by_model(metrics, pricing).map(|by_model| by_model.values().sum())
+}
+
+/// Compute a session's cost per model from its adapter metrics and the
+/// pricing table.
+///
+/// When the adapter reports a per-model breakdown (`cost.model_tokens`, e.g.
+/// Claude sessions that delegate to a cheaper subagent model), each model is
+/// priced separately. Otherwise the session totals are priced at the rate for
+/// `session.model`. None under the same conditions as [`compute_session_cost`].
+pub fn compute_cost_by_model(
+    metrics: &[(String, Value)],
+    pricing: &PricingConfig,
+) -> Option<BTreeMap<String, f64>> {
+    let provider = metric_str(metrics, "session.provider");
+
+    let breakdown = metrics
+        .iter()
+        .find(|(k, _)| k == "cost.model_tokens")
+        .and_then(|(_, v)| v.as_object());
+    if let Some(by_model) = breakdown {
+        let mut costs = BTreeMap::new();
+        for (model, tokens) in by_model {
+            let rate = pricing.rate_for(model, provider)?;
+            let get = |key: &str| tokens.get(key).and_then(|t| t.as_u64()).unwrap_or(0);
+            let usage = TokenUsage {
+                input: get("input_tokens"),
+                output: get("output_tokens"),
+                cache_read: get("cache_read_tokens"),
+                cache_write: get("cache_creation_tokens"),
+            };
+            costs.insert(model.clone(), usage.cost_usd(rate));
+        }
+        return Some(costs);
+    }
+
+    let model = metric_str(metrics, "session.model")?;
+    let rate = pricing.rate_for(model, provider)?;
+    let usage = TokenUsage::from_metrics(metrics)?;
+    Some(BTreeMap::from([(model.to_string(), usage.cost_usd(rate))]))
+}
+
+/// Reported and computed cost totals for one model.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize)]
+pub struct ModelCost {
+    pub model: String,
+    pub sessions: u64,
+    pub reported_usd: f64,
+    pub computed_usd: f64,
+    /// Sessions that had a computed cost (i.e. a matching pricing entry).
+    pub computed_sessions: u64,
+}
+
+/// Aggregate observation data into per-model cost totals, sorted by the
+/// larger of reported/computed cost (descending).
+pub fn cost_by_model<'a>(data: impl IntoIterator<Item = &'a Value>) -> Vec<ModelCost> {
+    let mut by_model: BTreeMap<String, ModelCost> = BTreeMap::new();
+    for d in data {
+        let model = d["session.model"]
+            .as_str()
+            .filter(|s| !s.is_empty())
+            .unwrap_or(UNKNOWN_MODEL);
+        let entry = by_model
+            .entry(model.to_string())
+            .or_insert_with(|| ModelCost {
+                model: model.to_string(),
+                ..Default::default()
+            });
+        entry.sessions += 1;
+        entry.reported_usd += d["cost.estimate_usd"].as_f64().unwrap_or(0.0);
+        if let Some(c) = d["cost.computed_usd"].as_f64() {
+            entry.computed_usd += c;
+            entry.computed_sessions += 1;
+        }
+    }
+    let mut rows: Vec<ModelCost> = by_model.into_values().collect();
+    rows.sort_by(|a, b| {
+        let ka = a.reported_usd.max(a.computed_usd);
+        let kb = b.reported_usd.max(b.computed_usd);
+        kb.partial_cmp(&ka).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(input: f64, output: f64, cache_read: f64, cache_write: f64) -> ModelPricing {
+        ModelPricing {
+            model: "claude-sonnet-4-5".to_string(),
+            provider: None,
+            input,
+            output,
+            cache_read,
+            cache_write,
+        }
+    }
+
+    fn metrics(pairs: &[(&str, Value)]) -> Vec<(String, Value)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn usage_from_metrics_sums_token_kinds() {
+        let m = metrics(&[
+            ("turns.total", Value::from(3)),
+            ("cost.input_tokens", Value::from(1000)),
+            ("cost.output_tokens", Value::from(500)),
+            ("cost.cache_read_tokens", Value::from(20000)),
+            ("cost.cache_creation_tokens", Value::from(4000)),
+        ]);
+        let usage = TokenUsage::from_metrics(&m).unwrap();
+        assert_eq!(
+            usage,
+            TokenUsage {
+                input: 1000,
+                output: 500,
+                cache_read: 20000,
+                cache_write: 4000,
+            }
+        );
+    }
+
+    #[test]
+    fn usage_from_metrics_none_without_token_events() {
+        let m = metrics(&[("turns.total", Value::from(3))]);
+        assert!(TokenUsage::from_metrics(&m).is_none());
+    }
+
+    #[test]
+    fn cost_usd_applies_per_million_rates() {
+        let usage = TokenUsage {
+            input: 1_000_000,
+            output: 100_000,
+            cache_read: 2_000_000,
+            cache_write: 0,
+        };
+        let cost = usage.cost_usd(&rate(3.0, 15.0, 0.3, 3.75));
+        assert!((cost - (3.0 + 1.5 + 0.6)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn compute_session_cost_uses_model_rate() {
+        let pricing = PricingConfig {
+            models: vec![rate(3.0, 15.0, 0.3, 3.75)],
+        };
+        let m = metrics(&[
+            ("session.model", Value::from("claude-sonnet-4-5-20250929")),
+            ("cost.input_tokens", Value::from(1_000_000)),
+            ("cost.output_tokens", Value::from(0)),
+        ]);
+        let cost = compute_session_cost(&m, &pricing).unwrap();
+        assert!((cost - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn compute_session_cost_none_without_model_or_rate() {
+        let pricing = PricingConfig {
+            models: vec![rate(3.0, 15.0, 0.0, 0.0)],
+        };
+        let no_model = metrics(&[("cost.input_tokens", Value::from(10))]);
+        assert!(compute_session_cost(&no_model, &pricing).is_none());
+
+        let unpriced = metrics(&[
+            ("session.model", Value::from("gpt-5")),
+            ("cost.input_tokens", Value::from(10)),
+        ]);
+        assert!(compute_session_cost(&unpriced, &pricing).is_none());
+    }
+
+    #[test]
+    fn compute_session_cost_prices_each_model_in_breakdown() {
+        let mut haiku = rate(1.0, 5.0, 0.0, 0.0);
+        haiku.model = "claude-haiku-4-5".to_string();
+        let pricing = PricingConfig {
+            models: vec![rate(3.0, 15.0, 0.3, 3.75), haiku],
+        };
+        let m = metrics(&[
+            ("session.model", Value::from("claude-sonnet-4-5")),
+            ("cost.input_tokens", Value::from(2_000_000)),
+            (
+                "cost.model_tokens",
+                serde_json::json!({
+                    "claude-sonnet-4-5": {"input_tokens": 1_000_000, "output_tokens": 0},
+                    "claude-haiku-4-5-20251001": {"input_tokens": 1_000_000, "output_tokens": 0},
+                }),
+            ),
+        ]);
+        let cost = compute_session_cost(&m, &pricing).unwrap();
+        assert!((cost - 4.0).abs() < 1e-9);
+
+        // One unpriced model in the breakdown means no computed cost at all
+        let sonnet_only = PricingConfig {
+            models: vec![rate(3.0, 15.0, 0.3, 3.75)],
+        };
+        assert!(compute_session_cost(&m, &sonnet_only).is_none());
+    }
+
+    #[test]
+    fn cost_by_model_groups_and_sorts() {
+        let data: Vec<Value> = vec![
+            serde_json::json!({"session.model": "gpt-5", "cost.computed_usd": 0.4}),
+            serde_json::json!({"session.model": "claude-sonnet-4-5", "cost.estimate_usd": 1.0, "cost.computed_usd": 0.9}),
+            serde_json::json!({"session.model": "claude-sonnet-4-5", "cost.estimate_usd": 2.0}),
+            serde_json::json!({"cost.estimate_usd": 0.1}),
+        ];
+        let rows = cost_by_model(&data);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].model, "claude-sonnet-4-5");
+        assert_eq!(rows[0].sessions, 2);
+        assert!((rows[0].reported_usd - 3.0).abs() < 1e-9);
+        assert!((rows[0].computed_usd - 0.9).abs() < 1e-9);
+        assert_eq!(rows[0].computed_sessions, 1);
+        assert_eq!(rows[1].model, "gpt-5");
+        assert_eq!(rows[2].model, UNKNOWN_MODEL);
+    }
+}
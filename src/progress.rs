@@ -1,4 +1,5 @@
 use crate::db;
+use crate::handoff::{self, Handoff};
 use std::io::Read;
 use std::path::Path;
 
@@ -65,6 +66,36 @@ pub fn handle_add(
     Ok(())
 }
 
+/// Handle `blacksmith progress add` with structured handoff fields.
+///
+/// The handoff is stored for injection into the bead's next session, and its
+/// markdown is also saved as a progress entry so `progress list`/`show` see
+/// it. A body given via --text, --stdin or --file becomes the handoff's notes.
+pub fn handle_add_handoff(
+    db_path: &Path,
+    mut handoff: Handoff,
+    text: Option<&str>,
+    use_stdin: bool,
+    file: Option<&Path>,
+) -> Result<(), String> {
+    if handoff.bead_id.is_empty() {
+        return Err("A structured handoff needs a bead: pass --bead <id>".to_string());
+    }
+    if text.is_some() || use_stdin || file.is_some() {
+        handoff.notes = Some(resolve_body(text, use_stdin, file)?);
+    }
+    handoff.source = handoff::SOURCE_AGENT.to_string();
+
+    let conn = db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
+    let handoff_id =
+        handoff::record(&conn, &handoff).map_err(|e| format!("Failed to save handoff: {e}"))?;
+    let body = format!("## Handoff\n\n{}\n", handoff.to_markdown());
+    db::insert_progress_entry(&conn, Some(&handoff.bead_id), &body)
+        .map_err(|e| format!("Failed to insert progress entry: {e}"))?;
+    println!("Saved handoff #{handoff_id} for bead {}", handoff.bead_id);
+    Ok(())
+}
+
 /// Handle `blacksmith progress list`.
 pub fn handle_list(db_path: &Path, bead_id: Option<&str>, last: i64) -> Result<(), String> {
     let conn = db::open_or_create(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
@@ -202,4 +233,41 @@ mod tests {
         assert_eq!(latest.bead_id.as_deref(), Some("simple-agent-harness-c34r"));
         assert!(latest.body.contains("## Handoff"));
     }
+
+    #[test]
+    fn add_handoff_records_typed_fields_and_entry() {
+        let (_dir, db_path) = test_db_path();
+        let fields = Handoff {
+            bead_id: "simple-agent-harness-c34r".to_string(),
+            done: vec!["Parser".to_string()],
+            remaining: vec!["CLI wiring".to_string()],
+            commands: vec!["cargo test parser".to_string()],
+            ..Default::default()
+        };
+        handle_add_handoff(&db_path, fields, Some("Watch the lifetimes"), false, None).unwrap();
+
+        let conn = db::open_or_create(&db_path).unwrap();
+        let latest = handoff::latest(&conn, "simple-agent-harness-c34r")
+            .unwrap()
+            .expect("handoff should be saved");
+        assert_eq!(latest.source, handoff::SOURCE_AGENT);
+        assert_eq!(latest.remaining, vec!["CLI wiring"]);
+        assert_eq!(latest.notes.as_deref(), Some("Watch the lifetimes"));
+
+        let entry = db::latest_progress_entry(&conn, Some("simple-agent-harness-c34r"))
+            .unwrap()
+            .expect("progress entry should be saved");
+        assert!(entry.body.contains("### Remaining\n- CLI wiring"));
+    }
+
+    #[test]
+    fn add_handoff_requires_bead() {
+        let (_dir, db_path) = test_db_path();
+        let fields = Handoff {
+            done: vec!["Parser".to_string()],
+            ..Default::default()
+        };
+        let err = handle_add_handoff(&db_path, fields, None, false, None).unwrap_err();
+        assert!(err.contains("--bead"));
+    }
 }
@@ -1,8 +1,9 @@
-//! Public API surface extractor for Rust modules.
+//! Public API surface extractor for modules.
 //!
-//! Given detected modules (from `module_detect`), parses each `.rs` file to extract
-//! public symbols: `pub fn`, `pub struct`, `pub enum`, `pub trait`, `pub const`,
-//! `pub static`, `pub type`. Returns a structured representation per module.
+//! Given detected modules (from `module_detect`), parses each source file to extract
+//! public symbols. For Rust these are `pub fn`, `pub struct`, `pub enum`, `pub trait`,
+//! `pub const`, `pub static`, `pub type`; other languages use their frontend's
+//! notion of exports (see `lang`). Returns a structured representation per module.
 //!
 //! Used to compute API surface width (a structural smell — modules exporting many
 //! symbols create large blast radii) and to detect boundary violations.
@@ -11,9 +12,10 @@ use std::collections::HashMap;
 #[cfg(test)]
 use std::path::Path;
 
+use crate::lang;
 use crate::module_detect::Module;
 
-/// A public symbol extracted from a Rust source file.
+/// A public symbol extracted from a source file.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Symbol {
     /// The kind of symbol: "fn", "struct", "enum", "trait", "const", "static", "type".
@@ -37,7 +39,7 @@ pub struct ModuleApi {
 
 /// Extract public API surfaces for all detected modules.
 ///
-/// For each module, reads all its `.rs` files and extracts `pub` declarations.
+/// For each module, reads all its source files and extracts their exported symbols.
 /// Returns a map from module name to `ModuleApi`.
 pub fn extract_public_apis(modules: &HashMap<String, Module>) -> HashMap<String, ModuleApi> {
     let mut result = HashMap::new();
@@ -56,7 +58,7 @@ pub fn extract_public_apis(modules: &HashMap<String, Module>) -> HashMap<String,
                 .map(|f| f.to_string_lossy().to_string())
                 .unwrap_or_default();
 
-            let mut file_symbols = extract_symbols_from_source(&content, &file_name);
+            let mut file_symbols = file_exports(file_path, &content, &file_name);
             symbols.append(&mut file_symbols);
         }
 
@@ -90,7 +92,7 @@ pub fn extract_module_api(module: &Module) -> ModuleApi {
             .map(|f| f.to_string_lossy().to_string())
             .unwrap_or_default();
 
-        let mut file_symbols = extract_symbols_from_source(&content, &file_name);
+        let mut file_symbols = file_exports(file_path, &content, &file_name);
         symbols.append(&mut file_symbols);
     }
 
@@ -129,20 +131,28 @@ pub fn extract_from_files(files: &[&Path]) -> Vec<Symbol> {
             .file_name()
             .map(|f| f.to_string_lossy().to_string())
             .unwrap_or_default();
-        let mut file_symbols = extract_symbols_from_source(&content, &file_name);
+        let mut file_symbols = file_exports(file_path, &content, &file_name);
         symbols.append(&mut file_symbols);
     }
     symbols.sort_by(|a, b| a.kind.cmp(&b.kind).then(a.name.cmp(&b.name)));
     symbols
 }
 
+/// Exported symbols of one file, using the frontend for its language.
+fn file_exports(path: &std::path::Path, content: &str, file_name: &str) -> Vec<Symbol> {
+    match lang::for_path(path) {
+        Some(frontend) => frontend.exports(content, file_name),
+        None => Vec::new(),
+    }
+}
+
 /// Parse Rust source text and extract public symbol declarations.
 ///
 /// Recognizes: `pub fn`, `pub struct`, `pub enum`, `pub trait`,
 /// `pub const`, `pub static`, `pub type`, and `pub async fn`.
 /// Also handles `pub(crate)` visibility — these are *not* included since
 /// they are not part of the external API surface.
-fn extract_symbols_from_source(source: &str, file_name: &str) -> Vec<Symbol> {
+pub(crate) fn extract_symbols_from_source(source: &str, file_name: &str) -> Vec<Symbol> {
     let mut symbols = Vec::new();
     let mut in_block_comment = false;
 
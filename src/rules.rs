@@ -0,0 +1,441 @@
+//! The machine-managed promoted-rules block in the prompt file.
+//!
+//! Promoted improvements live between [`BEGIN`] and [`END`] markers in the
+//! configured prompt file, regenerated from the database on every promotion
+//! or demotion rather than appended forever. The block has a character
+//! budget: when the promoted rules overflow it, near-duplicate rules within a
+//! category are merged (the newer one is dismissed as `merged_into` the
+//! older), and if that isn't enough the rules least worth keeping — those
+//! measured to have no benefit first, then the newest — are left out of the
+//! prompt with a note. Every promotion, demotion and merge is recorded in
+//! `rule_revisions`.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use rusqlite::{params, Connection, Result};
+
+use crate::db::{self, Improvement};
+use crate::dedup;
+use crate::impact;
+
+/// Opening marker of the managed block.
+pub const BEGIN: &str =
+    "<!-- BEGIN PROMOTED RULES (managed by blacksmith; use `blacksmith improve promote/demote`) -->";
+/// Closing marker of the managed block.
+pub const END: &str = "<!-- END PROMOTED RULES -->";
+
+/// Rules in the same category at least this similar (TF-IDF cosine, as in
+/// `improve add`) are merged when the block is over budget.
+const MERGE_SIMILARITY: f64 = dedup::REJECT_SIMILARITY;
+
+/// Create the rule_revisions table if it doesn't exist.
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS rule_revisions (
+            id     INTEGER PRIMARY KEY AUTOINCREMENT,
+            ts     TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            ref    TEXT NOT NULL,
+            action TEXT NOT NULL,
+            detail TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_rule_revisions_ref ON rule_revisions(ref);",
+    )
+}
+
+/// One change to the promoted rule set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Revision {
+    pub ts: String,
+    pub ref_id: String,
+    /// "promoted", "demoted" or "merged".
+    pub action: String,
+    pub detail: Option<String>,
+}
+
+/// Record a change to the promoted rule set.
+pub fn record_revision(
+    conn: &Connection,
+    ref_id: &str,
+    action: &str,
+    detail: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO rule_revisions (ref, action, detail) VALUES (?1, ?2, ?3)",
+        params![ref_id, action, detail],
+    )?;
+    Ok(())
+}
+
+/// Revision history, oldest first, optionally for one rule.
+pub fn history(conn: &Connection, ref_id: Option<&str>) -> Result<Vec<Revision>> {
+    let mut stmt = conn.prepare(
+        "SELECT ts, ref, action, detail FROM rule_revisions
+         WHERE ?1 IS NULL OR ref = ?1 ORDER BY id ASC",
+    )?;
+    let rows = stmt
+        .query_map([ref_id], |row| {
+            Ok(Revision {
+                ts: row.get(0)?,
+                ref_id: row.get(1)?,
+                action: row.get(2)?,
+                detail: row.get(3)?,
+            })
+        })?
+        .collect();
+    rows
+}
+
+/// The rule text an improvement contributes to the prompt.
+pub fn rule_text(imp: &Improvement) -> &str {
+    imp.body.as_deref().unwrap_or(&imp.title)
+}
+
+/// A promoted rule as it appears in the prompt.
+pub fn render_rule(imp: &Improvement) -> String {
+    format!(
+        "<!-- Promoted from {} [{}] -->\n- {}",
+        imp.ref_id,
+        imp.category,
+        rule_text(imp)
+    )
+}
+
+/// Drop the rules for `refs` from `prompt`: each marker comment and the
+/// lines after it up to the next blank line or marker.
+pub fn remove_rules(prompt: &str, refs: &[String]) -> String {
+    let mut kept: Vec<&str> = Vec::new();
+    let mut skipping = false;
+    for line in prompt.lines() {
+        if let Some(rest) = line.trim().strip_prefix("<!-- Promoted from ") {
+            let ref_id = rest.split_whitespace().next().unwrap_or("");
+            skipping = refs.iter().any(|r| r == ref_id);
+            if skipping {
+                // Also drop the blank line that separated the rule.
+                if kept.last().is_some_and(|l| l.trim().is_empty()) {
+                    kept.pop();
+                }
+                continue;
+            }
+        } else if skipping && line.trim().is_empty() {
+            skipping = false;
+        }
+        if !skipping {
+            kept.push(line);
+        }
+    }
+    let mut out = kept.join("\n");
+    if prompt.ends_with('\n') && !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// What a sync did to the managed block.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncReport {
+    /// Rules written into the block.
+    pub rules: usize,
+    /// Size of the block in characters.
+    pub chars: usize,
+    /// `(merged, kept)` refs consolidated this sync.
+    pub merged: Vec<(String, String)>,
+    /// Promoted rules left out for lack of budget.
+    pub omitted: Vec<String>,
+}
+
+fn render_block(rules: &[Improvement], omitted: &[String], budget: usize) -> String {
+    let mut block = BEGIN.to_string();
+    for imp in rules {
+        block.push_str("\n\n");
+        block.push_str(&render_rule(imp));
+    }
+    if !omitted.is_empty() {
+        block.push_str(&format!(
+            "\n\n<!-- {} promoted rule(s) omitted to stay within the {budget}-character budget: {} -->",
+            omitted.len(),
+            omitted.join(", ")
+        ));
+    }
+    block.push_str("\n\n");
+    block.push_str(END);
+    block
+}
+
+/// Merge near-duplicate rules within each category into the oldest of them,
+/// dismissing the newer ones. Returns `(merged, kept)` pairs.
+fn consolidate(conn: &Connection, rules: &mut Vec<Improvement>) -> Result<Vec<(String, String)>> {
+    let mut categories: Vec<&str> = Vec::new();
+    for rule in rules.iter() {
+        if !categories.contains(&rule.category.as_str()) {
+            categories.push(&rule.category);
+        }
+    }
+
+    let mut merged = Vec::new();
+    for category in categories {
+        let group: Vec<Improvement> = rules
+            .iter()
+            .filter(|r| r.category == category)
+            .cloned()
+            .collect();
+        // Clusters are in list order, so the first of each is the oldest
+        for cluster in dedup::Index::build(&group).clusters(MERGE_SIMILARITY) {
+            let kept = &group[cluster[0]].ref_id;
+            for &i in &cluster[1..] {
+                let gone = &group[i].ref_id;
+                db::update_improvement(
+                    conn,
+                    gone,
+                    Some("dismissed"),
+                    None,
+                    None,
+                    Some(&format!(r#"{{"merged_into": "{kept}"}}"#)),
+                )?;
+                record_revision(conn, gone, "merged", Some(&format!("into {kept}")))?;
+                merged.push((gone.clone(), kept.clone()));
+            }
+        }
+    }
+    rules.retain(|r| !merged.iter().any(|(gone, _)| *gone == r.ref_id));
+    Ok(merged)
+}
+
+/// Rewrite the managed block in `prompt_path` from the promoted improvements
+/// in the database, consolidating when it exceeds `budget_chars` (0 = no
+/// budget). Promoted rules still sitting outside the block, from before it
+/// was managed, are moved into it.
+pub fn sync(
+    conn: &Connection,
+    prompt_path: &Path,
+    budget_chars: usize,
+) -> std::result::Result<SyncReport, String> {
+    let db_err = |e: rusqlite::Error| format!("Failed to read promoted rules: {e}");
+    let mut rules = db::list_improvements(conn, Some("promoted"), None).map_err(db_err)?;
+    let over = |rules: &[Improvement], omitted: &[String]| {
+        budget_chars > 0 && render_block(rules, omitted, budget_chars).len() > budget_chars
+    };
+
+    let mut report = SyncReport::default();
+    if over(&rules, &[]) {
+        report.merged = consolidate(conn, &mut rules).map_err(db_err)?;
+    }
+    if over(&rules, &[]) {
+        // Leave out rules measured to have no benefit first, then the newest.
+        let flagged: HashSet<String> = impact::flagged(conn)
+            .map_err(db_err)?
+            .into_iter()
+            .map(|(imp, _)| imp.ref_id)
+            .collect();
+        while !rules.is_empty() && over(&rules, &report.omitted) {
+            let idx = rules
+                .iter()
+                .rposition(|r| flagged.contains(&r.ref_id))
+                .unwrap_or(rules.len() - 1);
+            report.omitted.push(rules.remove(idx).ref_id);
+        }
+        report
+            .omitted
+            .sort_by_key(|r| r.trim_start_matches('R').parse::<i64>().unwrap_or(0));
+    }
+
+    let existing = match std::fs::read_to_string(prompt_path) {
+        Ok(s) => s,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("failed to read {}: {e}", prompt_path.display())),
+    };
+    let (before, after) = match (existing.find(BEGIN), existing.find(END)) {
+        (Some(start), Some(end)) if end > start => {
+            (&existing[..start], &existing[end + END.len()..])
+        }
+        _ => (existing.as_str(), ""),
+    };
+    let all_refs: Vec<String> = db::list_improvements(conn, None, None)
+        .map_err(db_err)?
+        .into_iter()
+        .map(|imp| imp.ref_id)
+        .collect();
+    let before = remove_rules(before, &all_refs);
+    let after = remove_rules(after, &all_refs);
+
+    let mut content = before.trim_end().to_string();
+    if !rules.is_empty() || !report.omitted.is_empty() {
+        let block = render_block(&rules, &report.omitted, budget_chars);
+        report.rules = rules.len();
+        report.chars = block.len();
+        if !content.is_empty() {
+            content.push_str("\n\n");
+        }
+        content.push_str(&block);
+    }
+    let after = after.trim();
+    if !after.is_empty() {
+        content.push_str("\n\n");
+        content.push_str(after);
+    }
+    if !content.is_empty() {
+        content.push('\n');
+    }
+
+    if content != existing && !(content.is_empty() && existing.is_empty()) {
+        std::fs::write(prompt_path, &content)
+            .map_err(|e| format!("failed to write {}: {e}", prompt_path.display()))?;
+    }
+    for (gone, kept) in &report.merged {
+        tracing::info!(merged = %gone, into = %kept, "consolidated promoted rule");
+    }
+    if !report.omitted.is_empty() {
+        tracing::warn!(
+            omitted = ?report.omitted,
+            budget_chars,
+            "promoted rules exceed the prompt budget; some were left out"
+        );
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (tempfile::TempDir, Connection) {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = db::open_or_create(&dir.path().join("blacksmith.db")).unwrap();
+        (dir, conn)
+    }
+
+    fn promote(conn: &Connection, category: &str, body: &str) -> String {
+        let r = db::insert_improvement(conn, category, body, Some(body), None, None).unwrap();
+        db::update_improvement(conn, &r, Some("promoted"), None, None, None).unwrap();
+        r
+    }
+
+    #[test]
+    fn sync_writes_block_and_absorbs_legacy_rules() {
+        let (dir, conn) = setup();
+        let r1 = promote(&conn, "workflow", "Batch independent file reads");
+        let path = dir.path().join("PROMPT.md");
+        std::fs::write(
+            &path,
+            format!("# Prompt\n\nDo the work.\n\n<!-- Promoted from {r1} [workflow] -->\n- Batch independent file reads"),
+        )
+        .unwrap();
+
+        let report = sync(&conn, &path, 0).unwrap();
+        assert_eq!(report.rules, 1);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# Prompt\n\nDo the work.\n\n<!-- BEGIN PROMOTED RULES"));
+        assert_eq!(content.matches("Promoted from R1").count(), 1);
+        assert!(content.trim_end().ends_with(END));
+
+        // Idempotent, and text after the block is preserved.
+        std::fs::write(&path, format!("{content}\n## Footer\n")).unwrap();
+        sync(&conn, &path, 0).unwrap();
+        let again = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(again.matches(BEGIN).count(), 1);
+        assert!(again.ends_with("## Footer\n"));
+    }
+
+    #[test]
+    fn sync_removes_demoted_rules_and_empty_block() {
+        let (dir, conn) = setup();
+        let r1 = promote(&conn, "workflow", "Batch independent file reads");
+        let path = dir.path().join("PROMPT.md");
+        std::fs::write(&path, "# Prompt\n").unwrap();
+        sync(&conn, &path, 0).unwrap();
+
+        db::update_improvement(&conn, &r1, Some("revisit"), None, None, None).unwrap();
+        let report = sync(&conn, &path, 0).unwrap();
+        assert_eq!(report.rules, 0);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "# Prompt\n");
+    }
+
+    #[test]
+    fn sync_does_not_create_missing_file_without_rules() {
+        let (dir, conn) = setup();
+        let path = dir.path().join("PROMPT.md");
+        sync(&conn, &path, 100).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn over_budget_merges_similar_rules_in_a_category() {
+        let (dir, conn) = setup();
+        let r1 = promote(
+            &conn,
+            "cost",
+            "Run only the affected test files, not the full suite",
+        );
+        let r2 = promote(
+            &conn,
+            "cost",
+            "Run only affected test files instead of the full suite",
+        );
+        let r3 = promote(
+            &conn,
+            "workflow",
+            "Run only the affected test files, not the full suite",
+        );
+        let path = dir.path().join("PROMPT.md");
+
+        // Generous: nothing merged.
+        assert!(sync(&conn, &path, 2000).unwrap().merged.is_empty());
+
+        // Room for two of the three rules.
+        let kept: Vec<Improvement> = [&r1, &r3]
+            .iter()
+            .map(|r| db::get_improvement(&conn, r).unwrap().unwrap())
+            .collect();
+        let budget = render_block(&kept, &[], 0).len();
+        let report = sync(&conn, &path, budget).unwrap();
+        assert_eq!(report.merged, vec![(r2.clone(), r1.clone())]);
+        assert!(report.omitted.is_empty());
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains(&format!("Promoted from {r2} ")));
+        assert!(content.contains(&format!("Promoted from {r3} ")));
+        assert_eq!(
+            db::get_improvement(&conn, &r2).unwrap().unwrap().status,
+            "dismissed"
+        );
+
+        let revisions = history(&conn, Some(&r2)).unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].action, "merged");
+        assert_eq!(revisions[0].detail.as_deref(), Some("into R1"));
+    }
+
+    #[test]
+    fn over_budget_omits_newest_rules() {
+        let (dir, conn) = setup();
+        let r1 = promote(&conn, "workflow", "Batch independent file reads together");
+        let r2 = promote(
+            &conn,
+            "cost",
+            &"Skip CSS tests when only Rust changed. ".repeat(4),
+        );
+        let path = dir.path().join("PROMPT.md");
+
+        // Room for the first rule plus the omission note.
+        let first = db::get_improvement(&conn, &r1).unwrap().unwrap();
+        let budget = render_block(&[first], std::slice::from_ref(&r2), 100).len();
+        assert!((100..1000).contains(&budget));
+        let report = sync(&conn, &path, budget).unwrap();
+        assert_eq!(report.omitted, vec![r2.clone()]);
+        assert_eq!(report.chars, budget);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains(&format!("Promoted from {r1} ")));
+        assert!(content.contains(&format!(
+            "omitted to stay within the {budget}-character budget: {r2}"
+        )));
+    }
+
+    #[test]
+    fn remove_rules_keeps_other_rules() {
+        let prompt = "Intro\n\n<!-- Promoted from R3 [cost] -->\n- Skip CSS\n\n<!-- Promoted from R4 [workflow] -->\n- Be brief\n";
+        let out = remove_rules(prompt, &["R3".to_string()]);
+        assert_eq!(
+            out,
+            "Intro\n\n<!-- Promoted from R4 [workflow] -->\n- Be brief\n"
+        );
+    }
+}
@@ -0,0 +1,570 @@
+//! Salvage work from failed or interrupted worktrees.
+//!
+//! Before a worktree is removed, any commits it has on top of the base branch
+//! (plus uncommitted changes, committed as a WIP commit) are archived as a
+//! `salvage/<bead>` branch and a patch under `.blacksmith/salvage/<bead>/`.
+//! The archive is recorded in the `salvage` table so `blacksmith salvage`
+//! can list, show, apply or discard it, and a retried bead can resume from it.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// File name of the patch bundle inside `salvage/<bead>/`.
+const PATCH_FILE: &str = "changes.patch";
+
+/// Identity used for salvage WIP commits and `git am`, so capture works in
+/// repos without a configured user.
+const GIT_IDENTITY: [&str; 4] = [
+    "-c",
+    "user.name=blacksmith",
+    "-c",
+    "user.email=blacksmith@localhost",
+];
+
+/// Lifecycle of a salvage record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SalvageStatus {
+    /// Archived and not yet acted on.
+    Pending,
+    /// Replayed into a worktree for a retried bead.
+    Resumed,
+    /// Applied to the main checkout via `blacksmith salvage apply`.
+    Applied,
+    /// Branch and patch deleted via `blacksmith salvage discard`.
+    Discarded,
+}
+
+impl SalvageStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SalvageStatus::Pending => "pending",
+            SalvageStatus::Resumed => "resumed",
+            SalvageStatus::Applied => "applied",
+            SalvageStatus::Discarded => "discarded",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(SalvageStatus::Pending),
+            "resumed" => Some(SalvageStatus::Resumed),
+            "applied" => Some(SalvageStatus::Applied),
+            "discarded" => Some(SalvageStatus::Discarded),
+            _ => None,
+        }
+    }
+}
+
+/// A salvaged worktree, one per bead (the latest capture wins).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SalvageRecord {
+    pub bead_id: String,
+    pub branch: String,
+    pub patch_path: PathBuf,
+    pub base_commit: String,
+    pub head_commit: String,
+    pub commits: u32,
+    pub reason: String,
+    pub status: SalvageStatus,
+    pub created_at: String,
+}
+
+/// Create the salvage table if it doesn't exist.
+pub fn create_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS salvage (
+            bead_id     TEXT PRIMARY KEY,
+            branch      TEXT NOT NULL,
+            patch_path  TEXT NOT NULL,
+            base_commit TEXT NOT NULL,
+            head_commit TEXT NOT NULL,
+            commits     INTEGER NOT NULL,
+            reason      TEXT NOT NULL,
+            status      TEXT NOT NULL DEFAULT 'pending',
+            created_at  TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );",
+    )
+}
+
+fn upsert(conn: &Connection, record: &SalvageRecord) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO salvage
+            (bead_id, branch, patch_path, base_commit, head_commit, commits, reason, status)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            record.bead_id,
+            record.branch,
+            record.patch_path.to_string_lossy(),
+            record.base_commit,
+            record.head_commit,
+            record.commits,
+            record.reason,
+            record.status.as_str(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Look up the salvage record for a bead.
+pub fn get(conn: &Connection, bead_id: &str) -> rusqlite::Result<Option<SalvageRecord>> {
+    conn.query_row(
+        "SELECT bead_id, branch, patch_path, base_commit, head_commit, commits, reason,
+                status, created_at
+         FROM salvage WHERE bead_id = ?1",
+        params![bead_id],
+        map_record,
+    )
+    .optional()
+}
+
+/// All salvage records, newest first. Discarded records are excluded unless
+/// `include_discarded` is set.
+pub fn list(conn: &Connection, include_discarded: bool) -> rusqlite::Result<Vec<SalvageRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT bead_id, branch, patch_path, base_commit, head_commit, commits, reason,
+                status, created_at
+         FROM salvage WHERE ?1 OR status != 'discarded'
+         ORDER BY created_at DESC, bead_id",
+    )?;
+    let rows = stmt
+        .query_map(params![include_discarded], map_record)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rows)
+}
+
+fn set_status(conn: &Connection, bead_id: &str, status: SalvageStatus) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE salvage SET status = ?1 WHERE bead_id = ?2",
+        params![status.as_str(), bead_id],
+    )?;
+    Ok(())
+}
+
+fn map_record(row: &rusqlite::Row) -> rusqlite::Result<SalvageRecord> {
+    let status: String = row.get(7)?;
+    Ok(SalvageRecord {
+        bead_id: row.get(0)?,
+        branch: row.get(1)?,
+        patch_path: PathBuf::from(row.get::<_, String>(2)?),
+        base_commit: row.get(3)?,
+        head_commit: row.get(4)?,
+        commits: row.get(5)?,
+        reason: row.get(6)?,
+        status: SalvageStatus::parse(&status).unwrap_or(SalvageStatus::Pending),
+        created_at: row.get(8)?,
+    })
+}
+
+/// Branch name used for a bead's salvaged work.
+pub fn branch_name(bead_id: &str) -> String {
+    format!("salvage/{bead_id}")
+}
+
+/// Run git in `dir` and return trimmed stdout, or stderr as the error.
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("failed to run git {}: {e}", args.join(" ")))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Archive a worktree's work for `bead_id` before it is removed.
+///
+/// Uncommitted changes are committed as a WIP commit, then everything on top
+/// of `base_branch` is saved as the `salvage/<bead>` branch and a
+/// `git format-patch` bundle under `salvage_dir/<bead>/`.
+///
+/// Returns `None` when the worktree has nothing beyond the base branch. A
+/// capture whose head matches the existing record is a no-op, so re-salvaging
+/// a preserved worktree on restart doesn't reset its status.
+pub fn capture(
+    conn: &Connection,
+    salvage_dir: &Path,
+    wt_path: &Path,
+    bead_id: &str,
+    base_branch: &str,
+    reason: &str,
+) -> Result<Option<SalvageRecord>, String> {
+    if !git(wt_path, &["status", "--porcelain"])?.is_empty() {
+        git(wt_path, &["add", "-A"])?;
+        let message = format!("salvage: uncommitted work for {bead_id}");
+        let mut args: Vec<&str> = GIT_IDENTITY.to_vec();
+        args.extend(["commit", "--no-verify", "-m", &message]);
+        git(wt_path, &args)?;
+    }
+
+    let head = git(wt_path, &["rev-parse", "HEAD"])?;
+    let base = git(wt_path, &["merge-base", "HEAD", base_branch])?;
+    if head == base {
+        return Ok(None);
+    }
+
+    if let Some(existing) = get(conn, bead_id).map_err(|e| e.to_string())? {
+        if existing.head_commit == head {
+            return Ok(Some(existing));
+        }
+    }
+
+    let range = format!("{base}..{head}");
+    let commits: u32 = git(wt_path, &["rev-list", "--count", &range])?
+        .parse()
+        .unwrap_or(0);
+    let patch = git(wt_path, &["format-patch", "--stdout", &range])?;
+
+    let bead_dir = salvage_dir.join(bead_id);
+    std::fs::create_dir_all(&bead_dir)
+        .map_err(|e| format!("failed to create {}: {e}", bead_dir.display()))?;
+    let patch_path = bead_dir.join(PATCH_FILE);
+    std::fs::write(&patch_path, format!("{patch}\n"))
+        .map_err(|e| format!("failed to write {}: {e}", patch_path.display()))?;
+
+    let branch = branch_name(bead_id);
+    git(wt_path, &["branch", "-f", &branch, &head])?;
+
+    let record = SalvageRecord {
+        bead_id: bead_id.to_string(),
+        branch,
+        patch_path,
+        base_commit: base,
+        head_commit: head,
+        commits,
+        reason: reason.to_string(),
+        status: SalvageStatus::Pending,
+        created_at: String::new(),
+    };
+    upsert(conn, &record).map_err(|e| format!("failed to record salvage: {e}"))?;
+    get(conn, bead_id).map_err(|e| e.to_string())
+}
+
+/// Replay a salvage patch into `dir` with `git am --3way`.
+///
+/// On conflict the `git am` is aborted and `dir` is left unchanged.
+fn replay(dir: &Path, record: &SalvageRecord) -> Result<(), String> {
+    if !record.patch_path.exists() {
+        return Err(format!(
+            "salvage patch missing: {}",
+            record.patch_path.display()
+        ));
+    }
+
+    let patch = record.patch_path.to_string_lossy().into_owned();
+    let mut args: Vec<&str> = GIT_IDENTITY.to_vec();
+    args.extend(["am", "--3way", &patch]);
+    if let Err(e) = git(dir, &args) {
+        let _ = git(dir, &["am", "--abort"]);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Resume a retried bead from its pending salvage, if any.
+///
+/// Returns the number of commits replayed, or `None` when nothing is pending.
+/// Marks the record as resumed on success. A failed replay leaves the
+/// worktree at the base branch so the agent starts fresh.
+pub fn resume(conn: &Connection, wt_path: &Path, bead_id: &str) -> Result<Option<u32>, String> {
+    let record = match get(conn, bead_id).map_err(|e| e.to_string())? {
+        Some(r) if r.status == SalvageStatus::Pending => r,
+        _ => return Ok(None),
+    };
+    replay(wt_path, &record)?;
+    set_status(conn, bead_id, SalvageStatus::Resumed).map_err(|e| e.to_string())?;
+    Ok(Some(record.commits))
+}
+
+/// Delete a bead's salvage branch and patch directory and mark it discarded.
+fn discard(conn: &Connection, repo_dir: &Path, record: &SalvageRecord) -> Result<(), String> {
+    // The branch may already be gone (deleted by hand); that's fine
+    let _ = git(repo_dir, &["branch", "-D", &record.branch]);
+    if let Some(dir) = record.patch_path.parent() {
+        if dir.exists() {
+            std::fs::remove_dir_all(dir)
+                .map_err(|e| format!("failed to remove {}: {e}", dir.display()))?;
+        }
+    }
+    set_status(conn, &record.bead_id, SalvageStatus::Discarded).map_err(|e| e.to_string())
+}
+
+fn open_db(db_path: &Path) -> Result<Option<Connection>, String> {
+    if !db_path.exists() {
+        println!("No metrics database found. Run some sessions first.");
+        return Ok(None);
+    }
+    crate::db::open_or_create(db_path)
+        .map(Some)
+        .map_err(|e| format!("Failed to open database: {e}"))
+}
+
+fn require(conn: &Connection, bead_id: &str) -> Result<SalvageRecord, String> {
+    match get(conn, bead_id).map_err(|e| format!("Failed to query salvage: {e}"))? {
+        Some(r) if r.status != SalvageStatus::Discarded => Ok(r),
+        _ => Err(format!("No salvaged work for {bead_id}")),
+    }
+}
+
+/// `blacksmith salvage list`
+pub fn handle_list(db_path: &Path, all: bool) -> Result<(), String> {
+    let Some(conn) = open_db(db_path)? else {
+        return Ok(());
+    };
+    let records = list(&conn, all).map_err(|e| format!("Failed to query salvage: {e}"))?;
+    if records.is_empty() {
+        println!("No salvaged worktrees.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<30} {:<10} {:>7} {:<20} REASON",
+        "BEAD", "STATUS", "COMMITS", "SALVAGED"
+    );
+    println!("{}", "-".repeat(100));
+    for r in &records {
+        println!(
+            "{:<30} {:<10} {:>7} {:<20} {}",
+            r.bead_id,
+            r.status.as_str(),
+            r.commits,
+            r.created_at,
+            r.reason.lines().next().unwrap_or("")
+        );
+    }
+    Ok(())
+}
+
+/// `blacksmith salvage show <bead>`
+pub fn handle_show(db_path: &Path, repo_dir: &Path, bead_id: &str) -> Result<(), String> {
+    let Some(conn) = open_db(db_path)? else {
+        return Ok(());
+    };
+    let r = require(&conn, bead_id)?;
+
+    println!("Bead:     {}", r.bead_id);
+    println!("Status:   {}", r.status.as_str());
+    println!("Salvaged: {}", r.created_at);
+    println!("Reason:   {}", r.reason);
+    println!("Branch:   {}", r.branch);
+    println!("Patch:    {}", r.patch_path.display());
+    println!();
+
+    let range = format!("{}..{}", r.base_commit, r.head_commit);
+    match git(repo_dir, &["log", "--oneline", &range]) {
+        Ok(log) => println!("{log}"),
+        Err(e) => println!("(commit log unavailable: {e})"),
+    }
+    if let Ok(stat) = git(repo_dir, &["diff", "--stat", &range]) {
+        println!();
+        println!("{stat}");
+    }
+    Ok(())
+}
+
+/// `blacksmith salvage apply <bead>`: replay the patch onto the current checkout.
+pub fn handle_apply(db_path: &Path, repo_dir: &Path, bead_id: &str) -> Result<(), String> {
+    let Some(conn) = open_db(db_path)? else {
+        return Ok(());
+    };
+    let r = require(&conn, bead_id)?;
+    replay(repo_dir, &r).map_err(|e| {
+        format!(
+            "Failed to apply salvage for {bead_id}: {e}\n\
+             The work is still available on branch {}.",
+            r.branch
+        )
+    })?;
+    set_status(&conn, bead_id, SalvageStatus::Applied).map_err(|e| e.to_string())?;
+    println!(
+        "Applied {} salvaged commit(s) for {bead_id} onto the current branch.",
+        r.commits
+    );
+    Ok(())
+}
+
+/// `blacksmith salvage discard <bead>`
+pub fn handle_discard(db_path: &Path, repo_dir: &Path, bead_id: &str) -> Result<(), String> {
+    let Some(conn) = open_db(db_path)? else {
+        return Ok(());
+    };
+    let r = require(&conn, bead_id)?;
+    discard(&conn, repo_dir, &r)?;
+    println!("Discarded salvaged work for {bead_id}.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worktree;
+    use tempfile::TempDir;
+
+    fn init_test_repo() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let repo = dir.path();
+        for args in [
+            vec!["init"],
+            vec!["config", "user.email", "test@test.com"],
+            vec!["config", "user.name", "Test"],
+        ] {
+            git(repo, &args).unwrap();
+        }
+        std::fs::write(repo.join("README.md"), "test\n").unwrap();
+        git(repo, &["add", "."]).unwrap();
+        git(repo, &["commit", "-m", "init"]).unwrap();
+        git(repo, &["branch", "-M", "main"]).unwrap();
+        dir
+    }
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        create_table(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn status_roundtrip() {
+        for status in [
+            SalvageStatus::Pending,
+            SalvageStatus::Resumed,
+            SalvageStatus::Applied,
+            SalvageStatus::Discarded,
+        ] {
+            assert_eq!(SalvageStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(SalvageStatus::parse("bogus"), None);
+    }
+
+    #[test]
+    fn capture_clean_worktree_is_none() {
+        let dir = init_test_repo();
+        let conn = setup_db();
+        let wt = worktree::create(dir.path(), &dir.path().join("wt"), 0, "b1", "main").unwrap();
+
+        let salvage_dir = dir.path().join("salvage");
+        let result = capture(&conn, &salvage_dir, &wt, "b1", "main", "failed").unwrap();
+        assert!(result.is_none());
+        assert!(list(&conn, true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn capture_archives_commits_and_uncommitted_work() {
+        let dir = init_test_repo();
+        let conn = setup_db();
+        let wt = worktree::create(dir.path(), &dir.path().join("wt"), 0, "b1", "main").unwrap();
+        std::fs::write(wt.join("a.txt"), "committed\n").unwrap();
+        git(&wt, &["add", "a.txt"]).unwrap();
+        git(&wt, &["commit", "-m", "add a"]).unwrap();
+        std::fs::write(wt.join("b.txt"), "wip\n").unwrap();
+
+        let salvage_dir = dir.path().join("salvage");
+        let record = capture(&conn, &salvage_dir, &wt, "b1", "main", "session failed")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(record.branch, "salvage/b1");
+        assert_eq!(record.commits, 2);
+        assert_eq!(record.status, SalvageStatus::Pending);
+        assert_eq!(record.patch_path, salvage_dir.join("b1").join(PATCH_FILE));
+        let patch = std::fs::read_to_string(&record.patch_path).unwrap();
+        assert!(patch.contains("a.txt") && patch.contains("b.txt"));
+        assert_eq!(
+            git(dir.path(), &["rev-parse", "salvage/b1"]).unwrap(),
+            record.head_commit
+        );
+    }
+
+    #[test]
+    fn capture_same_head_keeps_status() {
+        let dir = init_test_repo();
+        let conn = setup_db();
+        let wt = worktree::create(dir.path(), &dir.path().join("wt"), 0, "b1", "main").unwrap();
+        std::fs::write(wt.join("a.txt"), "wip\n").unwrap();
+
+        let salvage_dir = dir.path().join("salvage");
+        capture(&conn, &salvage_dir, &wt, "b1", "main", "tripped").unwrap();
+        set_status(&conn, "b1", SalvageStatus::Applied).unwrap();
+
+        let again = capture(&conn, &salvage_dir, &wt, "b1", "main", "restart")
+            .unwrap()
+            .unwrap();
+        assert_eq!(again.status, SalvageStatus::Applied);
+        assert_eq!(again.reason, "tripped");
+    }
+
+    #[test]
+    fn resume_replays_into_fresh_worktree() {
+        let dir = init_test_repo();
+        let conn = setup_db();
+        let wt_dir = dir.path().join("wt");
+        let wt = worktree::create(dir.path(), &wt_dir, 0, "b1", "main").unwrap();
+        std::fs::write(wt.join("a.txt"), "wip\n").unwrap();
+        let salvage_dir = dir.path().join("salvage");
+        capture(&conn, &salvage_dir, &wt, "b1", "main", "failed").unwrap();
+        worktree::remove(dir.path(), &wt).unwrap();
+
+        let retry = worktree::create(dir.path(), &wt_dir, 1, "b1", "main").unwrap();
+        assert_eq!(resume(&conn, &retry, "b1").unwrap(), Some(1));
+        assert_eq!(
+            std::fs::read_to_string(retry.join("a.txt")).unwrap(),
+            "wip\n"
+        );
+        assert_eq!(
+            get(&conn, "b1").unwrap().unwrap().status,
+            SalvageStatus::Resumed
+        );
+
+        // Nothing pending any more
+        assert_eq!(resume(&conn, &retry, "b1").unwrap(), None);
+    }
+
+    #[test]
+    fn resume_conflict_leaves_worktree_clean() {
+        let dir = init_test_repo();
+        let conn = setup_db();
+        let wt_dir = dir.path().join("wt");
+        let wt = worktree::create(dir.path(), &wt_dir, 0, "b1", "main").unwrap();
+        std::fs::write(wt.join("README.md"), "salvaged\n").unwrap();
+        let salvage_dir = dir.path().join("salvage");
+        capture(&conn, &salvage_dir, &wt, "b1", "main", "failed").unwrap();
+        worktree::remove(dir.path(), &wt).unwrap();
+
+        // main moves on with a conflicting edit
+        std::fs::write(dir.path().join("README.md"), "upstream\n").unwrap();
+        git(dir.path(), &["commit", "-am", "upstream edit"]).unwrap();
+
+        let retry = worktree::create(dir.path(), &wt_dir, 1, "b1", "main").unwrap();
+        assert!(resume(&conn, &retry, "b1").is_err());
+        assert!(git(&retry, &["status", "--porcelain"]).unwrap().is_empty());
+        assert_eq!(
+            get(&conn, "b1").unwrap().unwrap().status,
+            SalvageStatus::Pending
+        );
+    }
+
+    #[test]
+    fn discard_removes_branch_and_patch() {
+        let dir = init_test_repo();
+        let conn = setup_db();
+        let wt = worktree::create(dir.path(), &dir.path().join("wt"), 0, "b1", "main").unwrap();
+        std::fs::write(wt.join("a.txt"), "wip\n").unwrap();
+        let salvage_dir = dir.path().join("salvage");
+        let record = capture(&conn, &salvage_dir, &wt, "b1", "main", "failed")
+            .unwrap()
+            .unwrap();
+        worktree::remove(dir.path(), &wt).unwrap();
+
+        discard(&conn, dir.path(), &record).unwrap();
+        assert!(!salvage_dir.join("b1").exists());
+        assert!(git(dir.path(), &["rev-parse", "--verify", "salvage/b1"]).is_err());
+        assert!(list(&conn, false).unwrap().is_empty());
+        assert_eq!(list(&conn, true).unwrap().len(), 1);
+    }
+}
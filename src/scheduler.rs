@@ -128,6 +128,12 @@ pub struct ReadyBead {
     /// Parsed affected globs from the bead's design field.
     /// `None` means the bead didn't declare an affected set (treats as "everything").
     pub affected_globs: Option<Vec<String>>,
+    /// Labels from bd, checked by the readiness gate.
+    pub labels: Vec<String>,
+    /// When the bead was last updated, if bd reported it.
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Length of the bead's description, for the planning size heuristic.
+    pub description_len: usize,
 }
 
 /// An in-progress assignment with its locked affected set.
@@ -448,6 +454,9 @@ mod tests {
             issue_type: "task".to_string(),
             parent_child_ids: Vec::new(),
             affected_globs: globs.map(|g| g.into_iter().map(|s| s.to_string()).collect()),
+            labels: Vec::new(),
+            updated_at: None,
+            description_len: 0,
         }
     }
 
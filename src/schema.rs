@@ -0,0 +1,428 @@
+//! Versioned schema migrations for the metrics database.
+//!
+//! The schema version is stored in SQLite's `PRAGMA user_version`. Each
+//! `Migration` moves the database from `version - 1` to `version` inside a
+//! transaction; `migrate` applies every pending step in order and backs up an
+//! existing database (via `VACUUM INTO`) before touching it. Databases
+//! created before versioning report version 0 and are brought forward by the
+//! idempotent baseline step.
+//!
+//! Per-feature tables owned by other modules (`flaky`, `salvage`, ...) are
+//! created with `CREATE TABLE IF NOT EXISTS` after migrating; changes to
+//! existing tables, such as new columns, must be added here as a new step.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, Result};
+
+/// One schema change.
+pub struct Migration {
+    /// The `user_version` after this step.
+    pub version: u32,
+    pub description: &'static str,
+    apply: fn(&Connection) -> Result<()>,
+}
+
+/// All migrations, in order. Never edit or reorder a released step; add a new one.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "baseline schema",
+        apply: baseline,
+    },
+    Migration {
+        version: 2,
+        description: "add columns missing from pre-versioning worker_assignments and bead_metrics",
+        apply: backfill_columns,
+    },
+    Migration {
+        version: 3,
+        description: "add target metric and direction to improvements",
+        apply: improvement_targets,
+    },
+];
+
+/// The version a fully migrated database has.
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// The database's current schema version.
+pub fn current_version(conn: &Connection) -> Result<u32> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+/// Migrations not yet applied to `conn`, in order.
+pub fn pending(conn: &Connection) -> Result<Vec<&'static Migration>> {
+    let version = current_version(conn)?;
+    Ok(MIGRATIONS.iter().filter(|m| m.version > version).collect())
+}
+
+/// Apply all pending migrations to the database at `path`.
+///
+/// A database that already holds tables is backed up next to `path` first.
+/// Returns the versions applied. A database newer than this binary is left
+/// untouched.
+pub fn migrate(path: &Path, conn: &Connection) -> Result<Vec<u32>> {
+    let version = current_version(conn)?;
+    if version > latest_version() {
+        tracing::warn!(
+            version,
+            latest = latest_version(),
+            "database schema is newer than this blacksmith; skipping migrations"
+        );
+        return Ok(Vec::new());
+    }
+    let steps = pending(conn)?;
+    if steps.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    if has_tables(conn)? {
+        let backup = backup(path, conn, version)?;
+        tracing::info!(
+            from = version,
+            to = latest_version(),
+            backup = %backup.display(),
+            "migrating database schema"
+        );
+    }
+
+    let mut applied = Vec::new();
+    for step in steps {
+        let tx = conn.unchecked_transaction()?;
+        (step.apply)(&tx)?;
+        tx.pragma_update(None, "user_version", step.version)?;
+        tx.commit()?;
+        applied.push(step.version);
+    }
+    Ok(applied)
+}
+
+fn has_tables(conn: &Connection) -> Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table')",
+        [],
+        |row| row.get(0),
+    )
+}
+
+/// Copy the database to `<path>.v<version>-<timestamp>.bak`.
+fn backup(path: &Path, conn: &Connection, version: u32) -> Result<PathBuf> {
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{version}-{stamp}.bak"));
+    let dest = path.with_file_name(name);
+    conn.execute("VACUUM INTO ?1", [dest.to_string_lossy()])?;
+    Ok(dest)
+}
+
+/// Version 1: every table as it existed before schema versioning.
+fn baseline(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS improvements (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            ref        TEXT UNIQUE,
+            created    TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            resolved   TEXT,
+            category   TEXT NOT NULL,
+            status     TEXT NOT NULL DEFAULT 'open',
+            title      TEXT NOT NULL,
+            body       TEXT,
+            context    TEXT,
+            tags       TEXT,
+            meta       TEXT
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_improvements_status ON improvements(status);
+        CREATE INDEX IF NOT EXISTS idx_improvements_category ON improvements(category);
+
+        CREATE TABLE IF NOT EXISTS progress_entries (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            created    TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            bead_id    TEXT,
+            body       TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_progress_entries_bead_id ON progress_entries(bead_id);
+        CREATE INDEX IF NOT EXISTS idx_progress_entries_created ON progress_entries(created);
+
+        CREATE TABLE IF NOT EXISTS events (
+            id        INTEGER PRIMARY KEY AUTOINCREMENT,
+            ts        TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            session   INTEGER NOT NULL,
+            kind      TEXT NOT NULL,
+            value     TEXT,
+            tags      TEXT
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_events_session ON events(session);
+        CREATE INDEX IF NOT EXISTS idx_events_kind ON events(kind);
+        CREATE INDEX IF NOT EXISTS idx_events_ts ON events(ts);
+
+        CREATE TABLE IF NOT EXISTS observations (
+            session   INTEGER PRIMARY KEY,
+            ts        TEXT NOT NULL,
+            duration  INTEGER,
+            outcome   TEXT,
+            data      TEXT NOT NULL
+        );
+
+        -- Coordinator tables for multi-agent state
+
+        CREATE TABLE IF NOT EXISTS worker_assignments (
+            id             INTEGER PRIMARY KEY,
+            worker_id      INTEGER NOT NULL,
+            bead_id        TEXT NOT NULL,
+            worktree_path  TEXT NOT NULL,
+            status         TEXT NOT NULL,
+            affected_globs TEXT,
+            started_at     TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            completed_at   TEXT,
+            failure_notes  TEXT
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_worker_assignments_status ON worker_assignments(status);
+        CREATE INDEX IF NOT EXISTS idx_worker_assignments_bead_id ON worker_assignments(bead_id);
+
+        CREATE TABLE IF NOT EXISTS task_file_changes (
+            assignment_id  INTEGER NOT NULL REFERENCES worker_assignments(id),
+            file_path      TEXT NOT NULL,
+            change_type    TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_task_file_changes_assignment ON task_file_changes(assignment_id);
+
+        CREATE TABLE IF NOT EXISTS integration_log (
+            id                        INTEGER PRIMARY KEY,
+            assignment_id             INTEGER NOT NULL REFERENCES worker_assignments(id),
+            merged_at                 TEXT NOT NULL,
+            merge_commit              TEXT NOT NULL,
+            manifest_entries_applied  TEXT,
+            cross_task_imports        TEXT,
+            reconciliation_run        BOOLEAN DEFAULT 0
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_integration_log_assignment ON integration_log(assignment_id);
+
+        CREATE TABLE IF NOT EXISTS integration_iterations (
+            id              INTEGER PRIMARY KEY,
+            assignment_id   INTEGER NOT NULL REFERENCES worker_assignments(id),
+            bead_id         TEXT NOT NULL,
+            iteration_count INTEGER NOT NULL,
+            modules         TEXT,
+            recorded_at     TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_integration_iterations_bead ON integration_iterations(bead_id);
+
+        CREATE TABLE IF NOT EXISTS bead_metrics (
+            bead_id               TEXT PRIMARY KEY,
+            sessions              INTEGER NOT NULL DEFAULT 0,
+            wall_time_secs        REAL NOT NULL DEFAULT 0,
+            total_turns           INTEGER NOT NULL DEFAULT 0,
+            total_output_tokens   INTEGER DEFAULT 0,
+            integration_time_secs REAL DEFAULT 0,
+            completed_at          TEXT
+        );",
+    )
+}
+
+/// Version 2: `CREATE TABLE IF NOT EXISTS` never altered tables created by
+/// older releases, so add any column they are missing.
+fn backfill_columns(conn: &Connection) -> Result<()> {
+    for (table, column, definition) in [
+        ("worker_assignments", "affected_globs", "TEXT"),
+        ("worker_assignments", "completed_at", "TEXT"),
+        ("worker_assignments", "failure_notes", "TEXT"),
+        ("bead_metrics", "total_output_tokens", "INTEGER DEFAULT 0"),
+        ("bead_metrics", "integration_time_secs", "REAL DEFAULT 0"),
+        ("bead_metrics", "completed_at", "TEXT"),
+    ] {
+        add_column_if_missing(conn, table, column, definition)?;
+    }
+    Ok(())
+}
+
+fn improvement_targets(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "improvements", "target_metric", "TEXT")?;
+    add_column_if_missing(conn, "improvements", "target_direction", "TEXT")
+}
+
+/// `ALTER TABLE ... ADD COLUMN` unless the column already exists.
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let exists: bool = conn.query_row(
+        &format!("SELECT EXISTS (SELECT 1 FROM pragma_table_info('{table}') WHERE name = ?1)"),
+        [column],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        conn.execute_batch(&format!(
+            "ALTER TABLE {table} ADD COLUMN {column} {definition};"
+        ))?;
+    }
+    Ok(())
+}
+
+/// `blacksmith db status`
+pub fn handle_status(db_path: &Path) -> std::result::Result<(), String> {
+    if !db_path.exists() {
+        println!("No metrics database found. Run some sessions first.");
+        return Ok(());
+    }
+    let conn = Connection::open(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
+    let version = current_version(&conn).map_err(|e| e.to_string())?;
+    let steps = pending(&conn).map_err(|e| e.to_string())?;
+
+    println!("Database:        {}", db_path.display());
+    println!("Schema version:  {version}");
+    println!("Latest version:  {}", latest_version());
+    if version > latest_version() {
+        println!("The database was written by a newer blacksmith.");
+    } else if steps.is_empty() {
+        println!("Up to date.");
+    } else {
+        println!("Pending migrations:");
+        for step in steps {
+            println!("  v{}  {}", step.version, step.description);
+        }
+        println!("Run `blacksmith db migrate` to apply them.");
+    }
+    Ok(())
+}
+
+/// `blacksmith db migrate`
+pub fn handle_migrate(db_path: &Path) -> std::result::Result<(), String> {
+    if !db_path.exists() {
+        println!("No metrics database found. Run some sessions first.");
+        return Ok(());
+    }
+    let conn = Connection::open(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
+    let from = current_version(&conn).map_err(|e| e.to_string())?;
+    let applied = migrate(db_path, &conn).map_err(|e| format!("Migration failed: {e}"))?;
+    if applied.is_empty() {
+        println!("Schema is up to date (version {from}).");
+        return Ok(());
+    }
+    for version in &applied {
+        if let Some(step) = MIGRATIONS.iter().find(|m| m.version == *version) {
+            println!("  applied v{}  {}", step.version, step.description);
+        }
+    }
+    println!(
+        "Migrated schema from version {from} to {}.",
+        applied.last().copied().unwrap_or(from)
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backups(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|n| n.ends_with(".bak"))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn fresh_database_is_migrated_without_backup() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("blacksmith.db");
+        let conn = Connection::open(&path).unwrap();
+
+        let applied = migrate(&path, &conn).unwrap();
+        assert_eq!(applied, vec![1, 2, 3]);
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+        assert!(pending(&conn).unwrap().is_empty());
+        assert!(backups(tmp.path()).is_empty());
+
+        // Re-running is a no-op.
+        assert!(migrate(&path, &conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn legacy_database_gets_missing_columns_and_a_backup() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("blacksmith.db");
+        let conn = Connection::open(&path).unwrap();
+        // A pre-versioning database with an older worker_assignments shape.
+        conn.execute_batch(
+            "CREATE TABLE worker_assignments (
+                id            INTEGER PRIMARY KEY,
+                worker_id     INTEGER NOT NULL,
+                bead_id       TEXT NOT NULL,
+                worktree_path TEXT NOT NULL,
+                status        TEXT NOT NULL,
+                started_at    TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+            );
+            INSERT INTO worker_assignments (worker_id, bead_id, worktree_path, status)
+                VALUES (0, 'b1', '/tmp/wt', 'completed');",
+        )
+        .unwrap();
+        assert_eq!(current_version(&conn).unwrap(), 0);
+
+        migrate(&path, &conn).unwrap();
+
+        let globs: Option<String> = conn
+            .query_row(
+                "SELECT affected_globs FROM worker_assignments WHERE bead_id = 'b1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(globs, None);
+        let backup = backups(tmp.path());
+        assert_eq!(backup.len(), 1);
+        assert!(backup[0].starts_with("blacksmith.db.v0-"), "{backup:?}");
+
+        // The backup is the untouched legacy database.
+        let old = Connection::open(tmp.path().join(&backup[0])).unwrap();
+        assert_eq!(current_version(&old).unwrap(), 0);
+        let has_globs: bool = old
+            .query_row(
+                "SELECT EXISTS (SELECT 1 FROM pragma_table_info('worker_assignments') WHERE name = 'affected_globs')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(!has_globs);
+    }
+
+    #[test]
+    fn newer_database_is_left_alone() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("blacksmith.db");
+        let conn = Connection::open(&path).unwrap();
+        conn.pragma_update(None, "user_version", latest_version() + 1)
+            .unwrap();
+        assert!(migrate(&path, &conn).unwrap().is_empty());
+        assert_eq!(current_version(&conn).unwrap(), latest_version() + 1);
+    }
+
+    #[test]
+    fn migrations_are_ordered_and_contiguous() {
+        for (i, m) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(m.version, i as u32 + 1, "{}", m.description);
+        }
+    }
+
+    #[test]
+    fn open_or_create_migrates() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("blacksmith.db");
+        let conn = crate::db::open_or_create(&path).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+    }
+}
@@ -132,6 +132,9 @@ async fn api_metrics_summary(
 
     let mut cost_today = 0.0_f64;
     let mut cost_this_week = 0.0_f64;
+    let mut computed_cost_today = 0.0_f64;
+    let mut computed_cost_this_week = 0.0_f64;
+    let mut week_data: Vec<serde_json::Value> = Vec::new();
     let mut beads_closed_today = 0_u64;
     let mut outcomes_success = 0_u64;
     let mut outcomes_failed = 0_u64;
@@ -141,12 +144,11 @@ async fn api_metrics_summary(
         let obs_date = &obs.ts[..10]; // "YYYY-MM-DD"
         let data: serde_json::Value = serde_json::from_str(&obs.data).unwrap_or_default();
         let cost = data["cost.estimate_usd"].as_f64().unwrap_or(0.0);
+        let computed = data["cost.computed_usd"].as_f64().unwrap_or(0.0);
 
-        if obs_date >= week_start.as_str() {
-            cost_this_week += cost;
-        }
         if obs_date >= today_start.as_str() {
             cost_today += cost;
+            computed_cost_today += computed;
 
             // Count outcomes for today
             match obs.outcome.as_deref() {
@@ -156,7 +158,13 @@ async fn api_metrics_summary(
                 _ => {}
             }
         }
+        if obs_date >= week_start.as_str() {
+            cost_this_week += cost;
+            computed_cost_this_week += computed;
+            week_data.push(data);
+        }
     }
+    let cost_by_model = crate::pricing::cost_by_model(&week_data);
 
     // Count beads closed today from bead_metrics
     let all_bead_metrics = crate::db::all_bead_metrics(&conn)
@@ -190,6 +198,9 @@ async fn api_metrics_summary(
     Ok(axum::Json(serde_json::json!({
         "cost_today": cost_today,
         "cost_this_week": cost_this_week,
+        "computed_cost_today": computed_cost_today,
+        "computed_cost_this_week": computed_cost_this_week,
+        "cost_by_model": cost_by_model,
         "workers_active": workers_active,
         "workers_max": workers_max,
         "beads_closed_today": beads_closed_today,
@@ -782,6 +793,9 @@ mod tests {
             last_completed_iteration: Some(102),
             last_committed: true,
             consecutive_rate_limits: 0,
+            run_cost_usd: 0.0,
+            over_budget_beads: Vec::new(),
+            reconciliation_flagged: Vec::new(),
         };
         sf.write(&data).unwrap();
 
@@ -845,6 +859,9 @@ mod tests {
         // Should have all expected fields
         assert!(json["cost_today"].is_number());
         assert!(json["cost_this_week"].is_number());
+        assert!(json["computed_cost_today"].is_number());
+        assert!(json["computed_cost_this_week"].is_number());
+        assert!(json["cost_by_model"].as_array().unwrap().is_empty());
         assert!(json["workers_active"].is_number());
         assert!(json["workers_max"].is_number());
         assert!(json["beads_closed_today"].is_number());
@@ -853,6 +870,55 @@ mod tests {
         assert!(json["session_outcomes"]["timed_out"].is_number());
     }
 
+    #[tokio::test]
+    async fn test_api_metrics_summary_cost_by_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(dir.path());
+
+        let conn = crate::db::open_or_create(&state.db_path).unwrap();
+        let ts = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        crate::db::upsert_observation(
+            &conn,
+            1,
+            &ts,
+            Some(60),
+            Some("success"),
+            r#"{"session.model":"gpt-5-codex","cost.computed_usd":1.25}"#,
+        )
+        .unwrap();
+        crate::db::upsert_observation(
+            &conn,
+            2,
+            &ts,
+            Some(60),
+            Some("success"),
+            r#"{"session.model":"claude-sonnet-4-5","cost.estimate_usd":2.0,"cost.computed_usd":1.75}"#,
+        )
+        .unwrap();
+        drop(conn);
+
+        let app = test_app(state);
+        let resp = app
+            .oneshot(
+                Request::get("/api/metrics/summary")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), 1024 * 1024)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["cost_today"], 2.0);
+        assert_eq!(json["computed_cost_today"], 3.0);
+        let by_model = json["cost_by_model"].as_array().unwrap();
+        assert_eq!(by_model.len(), 2);
+        assert_eq!(by_model[0]["model"], "claude-sonnet-4-5");
+        assert_eq!(by_model[1]["model"], "gpt-5-codex");
+        assert_eq!(by_model[1]["computed_usd"], 1.25);
+    }
+
     #[tokio::test]
     async fn test_api_sessions_empty() {
         let dir = tempfile::tempdir().unwrap();
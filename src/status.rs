@@ -1,6 +1,7 @@
 /// Status file: writes `blacksmith.status` as JSON on every state transition.
 ///
 /// Uses atomic write pattern: write to temp file then rename.
+use crate::config::BudgetConfig;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
@@ -35,6 +36,15 @@ pub struct StatusData {
     pub last_completed_iteration: Option<u64>,
     pub last_committed: bool,
     pub consecutive_rate_limits: u32,
+    /// Spend of the current coordinator run, for budget reporting.
+    #[serde(default)]
+    pub run_cost_usd: f64,
+    /// Beads skipped for reaching a per-bead budget limit.
+    #[serde(default)]
+    pub over_budget_beads: Vec<String>,
+    /// Beads flagged for review by the last failed reconciliation.
+    #[serde(default)]
+    pub reconciliation_flagged: Vec<String>,
 }
 
 /// Manages the status file lifecycle.
@@ -115,6 +125,9 @@ pub struct StatusTracker {
     last_completed_iteration: Option<u64>,
     last_committed: bool,
     consecutive_rate_limits: u32,
+    run_cost_usd: f64,
+    over_budget_beads: Vec<String>,
+    reconciliation_flagged: Vec<String>,
 }
 
 impl StatusTracker {
@@ -132,6 +145,9 @@ impl StatusTracker {
             last_completed_iteration: None,
             last_committed: false,
             consecutive_rate_limits: 0,
+            run_cost_usd: 0.0,
+            over_budget_beads: Vec::new(),
+            reconciliation_flagged: Vec::new(),
         }
     }
 
@@ -150,6 +166,9 @@ impl StatusTracker {
             last_completed_iteration: self.last_completed_iteration,
             last_committed: self.last_committed,
             consecutive_rate_limits: self.consecutive_rate_limits,
+            run_cost_usd: self.run_cost_usd,
+            over_budget_beads: self.over_budget_beads.clone(),
+            reconciliation_flagged: self.reconciliation_flagged.clone(),
         };
 
         if let Err(e) = self.file.write(&data) {
@@ -197,6 +216,21 @@ impl StatusTracker {
         self.consecutive_rate_limits = count;
     }
 
+    /// Set the current run's spend.
+    pub fn set_run_cost(&mut self, cost_usd: f64) {
+        self.run_cost_usd = cost_usd;
+    }
+
+    /// Set the beads skipped for reaching a per-bead budget limit.
+    pub fn set_over_budget(&mut self, bead_ids: Vec<String>) {
+        self.over_budget_beads = bead_ids;
+    }
+
+    /// Set the beads flagged by the latest reconciliation run.
+    pub fn set_reconciliation_flagged(&mut self, bead_ids: Vec<String>) {
+        self.reconciliation_flagged = bead_ids;
+    }
+
     /// Remove the status file.
     pub fn remove(&self) {
         self.file.remove();
@@ -250,6 +284,7 @@ pub fn display_status(
     status_path: &Path,
     db_path: Option<&Path>,
     workers: u32,
+    budget: &BudgetConfig,
 ) -> Result<bool, StatusError> {
     let file = StatusFile::new(status_path.to_path_buf());
     let data = match file.read()? {
@@ -295,6 +330,19 @@ pub fn display_status(
     // Bead progress and ETA
     if let Some(db_path) = db_path {
         display_bead_progress(db_path, workers);
+        display_budget(db_path, budget, data.run_cost_usd);
+    }
+    if !data.over_budget_beads.is_empty() {
+        println!(
+            "Skipped (per-bead budget reached): {}",
+            data.over_budget_beads.join(", ")
+        );
+    }
+    if !data.reconciliation_flagged.is_empty() {
+        println!(
+            "Flagged for review by reconciliation: {}",
+            data.reconciliation_flagged.join(", ")
+        );
     }
 
     // Last completed iteration
@@ -328,6 +376,40 @@ pub fn display_status(
     Ok(true)
 }
 
+/// Print the open beads the coordinator isn't scheduling, and why.
+pub fn display_unscheduled(unscheduled: &[(String, crate::coordinator::Unscheduled)]) {
+    if unscheduled.is_empty() {
+        return;
+    }
+    println!(
+        "Not scheduled: {} open bead{}",
+        unscheduled.len(),
+        if unscheduled.len() == 1 { "" } else { "s" }
+    );
+    let width = unscheduled
+        .iter()
+        .map(|(id, _)| id.len())
+        .max()
+        .unwrap_or(0);
+    for (id, reason) in unscheduled {
+        println!("  {id:<width$}  {reason}");
+    }
+}
+
+/// Print remaining spend budget, if any limits are configured.
+fn display_budget(db_path: &Path, budget: &BudgetConfig, run_cost: f64) {
+    if !budget.is_enabled() {
+        return;
+    }
+    let conn = match crate::db::open_or_create(db_path) {
+        Ok(c) => c,
+        Err(_) => return,
+    };
+    if let Some(line) = crate::budget::format_remaining(budget, &conn, run_cost) {
+        println!("{line}");
+    }
+}
+
 /// Display bead progress, ETA, and failed beads count.
 fn display_bead_progress(db_path: &Path, workers: u32) {
     use crate::db;
@@ -605,6 +687,9 @@ mod tests {
             last_completed_iteration: Some(102),
             last_committed: true,
             consecutive_rate_limits: 0,
+            run_cost_usd: 0.0,
+            over_budget_beads: Vec::new(),
+            reconciliation_flagged: Vec::new(),
         };
 
         sf.write(&data).unwrap();
@@ -651,6 +736,9 @@ mod tests {
             last_completed_iteration: None,
             last_committed: false,
             consecutive_rate_limits: 0,
+            run_cost_usd: 0.0,
+            over_budget_beads: Vec::new(),
+            reconciliation_flagged: Vec::new(),
         };
 
         sf.write(&data).unwrap();
@@ -685,6 +773,9 @@ mod tests {
             last_completed_iteration: None,
             last_committed: false,
             consecutive_rate_limits: 0,
+            run_cost_usd: 0.0,
+            over_budget_beads: Vec::new(),
+            reconciliation_flagged: Vec::new(),
         };
 
         sf.write(&data).unwrap();
@@ -777,6 +868,9 @@ mod tests {
             last_completed_iteration: None,
             last_committed: false,
             consecutive_rate_limits: 0,
+            run_cost_usd: 0.0,
+            over_budget_beads: Vec::new(),
+            reconciliation_flagged: Vec::new(),
         };
 
         let result = sf.write(&data);
@@ -814,6 +908,9 @@ mod tests {
             last_completed_iteration: Some(104),
             last_committed: true,
             consecutive_rate_limits: 0,
+            run_cost_usd: 0.0,
+            over_budget_beads: Vec::new(),
+            reconciliation_flagged: Vec::new(),
         };
 
         sf.write(&data).unwrap();
@@ -893,7 +990,7 @@ mod tests {
     fn test_display_status_no_file() {
         let dir = tempdir().unwrap();
         let path = dir.path().join("blacksmith.status");
-        let result = display_status(&path, None, 1).unwrap();
+        let result = display_status(&path, None, 1, &BudgetConfig::default()).unwrap();
         assert!(!result);
     }
 
@@ -917,10 +1014,13 @@ mod tests {
             last_completed_iteration: Some(361),
             last_committed: true,
             consecutive_rate_limits: 0,
+            run_cost_usd: 0.0,
+            over_budget_beads: Vec::new(),
+            reconciliation_flagged: Vec::new(),
         };
 
         sf.write(&data).unwrap();
-        let result = display_status(&path, None, 1).unwrap();
+        let result = display_status(&path, None, 1, &BudgetConfig::default()).unwrap();
         assert!(result);
     }
 
@@ -1003,13 +1103,32 @@ mod tests {
             last_completed_iteration: Some(102),
             last_committed: true,
             consecutive_rate_limits: 0,
+            run_cost_usd: 0.0,
+            over_budget_beads: Vec::new(),
+            reconciliation_flagged: Vec::new(),
         };
 
         sf.write(&data).unwrap();
-        let result = display_status(&path, Some(&db_path), 2).unwrap();
+        let result = display_status(&path, Some(&db_path), 2, &BudgetConfig::default()).unwrap();
         assert!(result);
     }
 
+    #[test]
+    fn test_status_file_without_run_cost_reads_as_zero() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("blacksmith.status");
+        std::fs::write(
+            &path,
+            r#"{"pid":1,"state":"idle","iteration":0,"max_iterations":0,"global_iteration":0,
+                "output_file":"","output_bytes":0,"session_start":null,
+                "last_update":"2026-01-01T00:00:00Z","last_completed_iteration":null,
+                "last_committed":false,"consecutive_rate_limits":0}"#,
+        )
+        .unwrap();
+        let data = StatusFile::new(path).read().unwrap().unwrap();
+        assert_eq!(data.run_cost_usd, 0.0);
+    }
+
     #[test]
     fn test_display_status_multi_worker_label() {
         let dir = tempdir().unwrap();
@@ -1029,11 +1148,14 @@ mod tests {
             last_completed_iteration: None,
             last_committed: false,
             consecutive_rate_limits: 0,
+            run_cost_usd: 0.0,
+            over_budget_beads: Vec::new(),
+            reconciliation_flagged: Vec::new(),
         };
 
         sf.write(&data).unwrap();
         // With workers > 1, label should indicate worker count
-        let result = display_status(&path, None, 3).unwrap();
+        let result = display_status(&path, None, 3, &BudgetConfig::default()).unwrap();
         assert!(result);
     }
 }
@@ -74,7 +74,7 @@ pub struct FileMetrics {
     pub cluster_count: usize,
 }
 
-/// Compute all structural metrics for a Rust codebase in one pass.
+/// Compute all structural metrics for a codebase in one pass.
 ///
 /// Runs fan-in scoring, god file detection, circular dependency detection,
 /// boundary violation detection, and API surface extraction, then aggregates
@@ -0,0 +1,791 @@
+//! Structured test results from gate and reconciliation output.
+//!
+//! Gate and reconciliation failures arrive as raw stdout/stderr. This module
+//! recognizes the common test report formats — libtest JSON and human output
+//! from `cargo test`, JUnit XML, TAP and pytest — and extracts the failing
+//! tests with their file:line and message. The parsed failures are stored in
+//! `test_failures` against the bead and condensed into a short summary for
+//! integration-agent prompts and bead failure notes.
+
+use regex::Regex;
+use rusqlite::{params, Connection, Result};
+use std::sync::OnceLock;
+
+/// Maximum failures listed in a summary before eliding the rest.
+pub const SUMMARY_MAX_FAILURES: usize = 10;
+
+/// Maximum characters of a failure message kept in a summary line.
+const SUMMARY_MESSAGE_CHARS: usize = 200;
+
+/// Test output formats we know how to parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// `--format json` output from libtest or cargo-nextest.
+    LibtestJson,
+    /// Human-readable `cargo test` output.
+    Libtest,
+    JUnit,
+    Tap,
+    Pytest,
+}
+
+impl ReportFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReportFormat::LibtestJson => "libtest-json",
+            ReportFormat::Libtest => "libtest",
+            ReportFormat::JUnit => "junit",
+            ReportFormat::Tap => "tap",
+            ReportFormat::Pytest => "pytest",
+        }
+    }
+}
+
+/// One failing test.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TestFailure {
+    /// Fully qualified test name (e.g. `auth::tests::login`, `tests/test_x.py::test_y`).
+    pub name: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    /// Assertion or panic message; may span several lines.
+    pub message: String,
+}
+
+impl TestFailure {
+    /// `file:line` when known.
+    pub fn location(&self) -> Option<String> {
+        match (&self.file, self.line) {
+            (Some(f), Some(l)) => Some(format!("{f}:{l}")),
+            (Some(f), None) => Some(f.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Parsed test run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestReport {
+    pub format: ReportFormat,
+    pub passed: u64,
+    pub failed: u64,
+    pub failures: Vec<TestFailure>,
+}
+
+impl TestReport {
+    fn new(format: ReportFormat) -> Self {
+        TestReport {
+            format,
+            passed: 0,
+            failed: 0,
+            failures: Vec::new(),
+        }
+    }
+
+    /// Compact, prompt-friendly summary: one line per failing test.
+    pub fn summary(&self, max_failures: usize) -> String {
+        let mut out = format!(
+            "{} test(s) failed, {} passed ({}):",
+            self.failed.max(self.failures.len() as u64),
+            self.passed,
+            self.format.as_str()
+        );
+        for f in self.failures.iter().take(max_failures) {
+            out.push_str("\n- ");
+            out.push_str(&f.name);
+            if let Some(loc) = f.location() {
+                out.push_str(&format!(" ({loc})"));
+            }
+            let message = first_meaningful_line(&f.message);
+            if !message.is_empty() {
+                out.push_str(": ");
+                out.push_str(&truncate_chars(message, SUMMARY_MESSAGE_CHARS));
+            }
+        }
+        if self.failures.len() > max_failures {
+            out.push_str(&format!(
+                "\n... and {} more",
+                self.failures.len() - max_failures
+            ));
+        }
+        out
+    }
+}
+
+fn first_meaningful_line(message: &str) -> &str {
+    message
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("")
+}
+
+fn truncate_chars(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let cut: String = s.chars().take(max.saturating_sub(3)).collect();
+        format!("{cut}...")
+    }
+}
+
+/// Detect the report format and parse it.
+///
+/// Returns None when the output isn't recognizable test output (e.g. a
+/// compiler error), so callers can fall back to the raw text.
+pub fn parse(output: &str) -> Option<TestReport> {
+    let format = detect(output)?;
+    Some(parse_as(format, output))
+}
+
+/// Parse output known to be in `format`.
+pub fn parse_as(format: ReportFormat, output: &str) -> TestReport {
+    match format {
+        ReportFormat::LibtestJson => parse_libtest_json(output),
+        ReportFormat::Libtest => parse_libtest(output),
+        ReportFormat::JUnit => parse_junit(output),
+        ReportFormat::Tap => parse_tap(output),
+        ReportFormat::Pytest => parse_pytest(output),
+    }
+}
+
+fn detect(output: &str) -> Option<ReportFormat> {
+    let has_json_test_event = output.lines().any(|l| {
+        let l = l.trim();
+        l.starts_with('{')
+            && serde_json::from_str::<serde_json::Value>(l)
+                .map(|v| v["type"] == "test" || v["type"] == "suite")
+                .unwrap_or(false)
+    });
+    if has_json_test_event {
+        return Some(ReportFormat::LibtestJson);
+    }
+    if output.contains("<testsuite") || output.contains("<testcase") {
+        return Some(ReportFormat::JUnit);
+    }
+    if output.contains("short test summary info") || pytest_totals_re().is_match(output) {
+        return Some(ReportFormat::Pytest);
+    }
+    if output.contains("test result: ") || libtest_line_re().is_match(output) {
+        return Some(ReportFormat::Libtest);
+    }
+    let tap_lines = output.lines().filter(|l| tap_line_re().is_match(l)).count();
+    if tap_lines > 0 && (output.contains("TAP version") || tap_plan_re().is_match(output)) {
+        return Some(ReportFormat::Tap);
+    }
+    None
+}
+
+// ── libtest ─────────────────────────────────────────────────────────────
+
+fn libtest_line_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?m)^test (\S+) \.\.\. (ok|FAILED|ignored)").unwrap())
+}
+
+fn panic_location_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // `panicked at src/lib.rs:10:5:` (1.73+) or `panicked at 'msg', src/lib.rs:10:5`
+    RE.get_or_init(|| {
+        Regex::new(r"panicked at (?:'(?P<old_msg>.*)', )?(?P<file>[^\s:']+):(?P<line>\d+):\d+:?")
+            .unwrap()
+    })
+}
+
+/// Fill file/line/message of a failure from a libtest captured-output block.
+fn apply_panic_details(failure: &mut TestFailure, captured: &str) {
+    let mut lines = captured.lines();
+    while let Some(line) = lines.next() {
+        if let Some(caps) = panic_location_re().captures(line) {
+            failure.file = Some(caps["file"].to_string());
+            failure.line = caps["line"].parse().ok();
+            failure.message = match caps.name("old_msg") {
+                Some(m) => m.as_str().to_string(),
+                None => lines
+                    .by_ref()
+                    .take_while(|l| !l.starts_with("note: ") && !l.starts_with("stack backtrace"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+                    .trim()
+                    .to_string(),
+            };
+            return;
+        }
+    }
+    if failure.message.is_empty() {
+        failure.message = captured.trim().to_string();
+    }
+}
+
+fn parse_libtest(output: &str) -> TestReport {
+    let mut report = TestReport::new(ReportFormat::Libtest);
+    for caps in libtest_line_re().captures_iter(output) {
+        match &caps[2] {
+            "ok" => report.passed += 1,
+            "FAILED" => {
+                report.failed += 1;
+                report.failures.push(TestFailure {
+                    name: caps[1].to_string(),
+                    ..Default::default()
+                });
+            }
+            _ => {}
+        }
+    }
+
+    // Captured output sections: `---- name stdout ----` up to the next section
+    static SECTION: OnceLock<Regex> = OnceLock::new();
+    let section =
+        SECTION.get_or_init(|| Regex::new(r"(?m)^---- (\S+) std(?:out|err) ----$").unwrap());
+    let headers: Vec<_> = section.captures_iter(output).collect();
+    for (i, caps) in headers.iter().enumerate() {
+        let start = caps.get(0).unwrap().end();
+        let end = headers
+            .get(i + 1)
+            .map(|c| c.get(0).unwrap().start())
+            .or_else(|| output[start..].find("\nfailures:").map(|p| start + p))
+            .unwrap_or(output.len());
+        let name = &caps[1];
+        if let Some(failure) = report.failures.iter_mut().find(|f| f.name == name) {
+            apply_panic_details(failure, &output[start..end]);
+        }
+    }
+    report
+}
+
+fn parse_libtest_json(output: &str) -> TestReport {
+    let mut report = TestReport::new(ReportFormat::LibtestJson);
+    for line in output.lines() {
+        let Ok(event) = serde_json::from_str::<serde_json::Value>(line.trim()) else {
+            continue;
+        };
+        if event["type"] != "test" {
+            continue;
+        }
+        match event["event"].as_str() {
+            Some("ok") => report.passed += 1,
+            Some("failed") | Some("timeout") => {
+                report.failed += 1;
+                let mut failure = TestFailure {
+                    name: event["name"].as_str().unwrap_or("").to_string(),
+                    ..Default::default()
+                };
+                let captured = event["stdout"]
+                    .as_str()
+                    .or_else(|| event["message"].as_str())
+                    .unwrap_or("");
+                apply_panic_details(&mut failure, captured);
+                if event["event"] == "timeout" && failure.message.is_empty() {
+                    failure.message = "timed out".to_string();
+                }
+                report.failures.push(failure);
+            }
+            _ => {}
+        }
+    }
+    report
+}
+
+// ── JUnit XML ───────────────────────────────────────────────────────────
+
+fn xml_attrs(tag_body: &str) -> Vec<(String, String)> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(r#"([\w:-]+)\s*=\s*"([^"]*)""#).unwrap());
+    re.captures_iter(tag_body)
+        .map(|c| (c[1].to_string(), xml_unescape(&c[2])))
+        .collect()
+}
+
+fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.as_str())
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#10;", "\n")
+        .replace("&amp;", "&")
+}
+
+fn parse_junit(output: &str) -> TestReport {
+    static TESTCASE: OnceLock<Regex> = OnceLock::new();
+    static FAILURE: OnceLock<Regex> = OnceLock::new();
+    static FILE_LINE: OnceLock<Regex> = OnceLock::new();
+    let testcase = TESTCASE
+        .get_or_init(|| Regex::new(r"(?s)<testcase\b([^>]*?)(?:/>|>(.*?)</testcase>)").unwrap());
+    let failure_re = FAILURE.get_or_init(|| {
+        Regex::new(r"(?s)<(?:failure|error)\b([^>]*?)(?:/>|>(.*?)</(?:failure|error)>)").unwrap()
+    });
+    let file_line = FILE_LINE.get_or_init(|| Regex::new(r"(?m)([\w./-]+\.\w+):(\d+)").unwrap());
+
+    let mut report = TestReport::new(ReportFormat::JUnit);
+    for caps in testcase.captures_iter(output) {
+        let attrs = xml_attrs(&caps[1]);
+        let body = caps.get(2).map(|m| m.as_str()).unwrap_or("");
+        if body.contains("<skipped") {
+            continue;
+        }
+        let Some(fcaps) = failure_re.captures(body) else {
+            report.passed += 1;
+            continue;
+        };
+        report.failed += 1;
+
+        let name = attr(&attrs, "name").unwrap_or("");
+        let name = match attr(&attrs, "classname") {
+            Some(class) if !class.is_empty() => format!("{class}::{name}"),
+            _ => name.to_string(),
+        };
+        let fattrs = xml_attrs(&fcaps[1]);
+        let text = fcaps
+            .get(2)
+            .map(|m| xml_unescape(m.as_str().trim()))
+            .unwrap_or_default();
+        let message = attr(&fattrs, "message")
+            .filter(|m| !m.is_empty())
+            .map(String::from)
+            .unwrap_or_else(|| text.clone());
+
+        let mut file = attr(&attrs, "file").map(String::from);
+        let mut line = attr(&attrs, "line").and_then(|l| l.parse().ok());
+        if line.is_none() {
+            if let Some(fl) = file_line.captures(&text) {
+                file.get_or_insert_with(|| fl[1].to_string());
+                line = fl[2].parse().ok();
+            }
+        }
+        report.failures.push(TestFailure {
+            name,
+            file,
+            line,
+            message,
+        });
+    }
+    report
+}
+
+// ── TAP ─────────────────────────────────────────────────────────────────
+
+fn tap_line_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\s*(not )?ok\b(?: \d+)?(?: -)? ?([^#]*)(#.*)?$").unwrap())
+}
+
+fn tap_plan_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?m)^\s*1\.\.\d+").unwrap())
+}
+
+fn parse_tap(output: &str) -> TestReport {
+    static YAML_KV: OnceLock<Regex> = OnceLock::new();
+    let yaml_kv = YAML_KV.get_or_init(|| Regex::new(r"^\s*(\w+):\s*(.*)$").unwrap());
+
+    let mut report = TestReport::new(ReportFormat::Tap);
+    let mut lines = output.lines().peekable();
+    while let Some(line) = lines.next() {
+        let Some(caps) = tap_line_re().captures(line) else {
+            continue;
+        };
+        let directive = caps.get(3).map(|m| m.as_str().to_lowercase());
+        let skipped = directive
+            .as_deref()
+            .is_some_and(|d| d.contains("skip") || d.contains("todo"));
+        if caps.get(1).is_none() || skipped {
+            if !skipped {
+                report.passed += 1;
+            }
+            continue;
+        }
+
+        report.failed += 1;
+        let mut failure = TestFailure {
+            name: caps[2].trim().to_string(),
+            ..Default::default()
+        };
+        // Optional YAML diagnostics block: `  ---` ... `  ...`
+        if lines.peek().is_some_and(|l| l.trim() == "---") {
+            lines.next();
+            for l in lines.by_ref() {
+                if l.trim() == "..." {
+                    break;
+                }
+                let Some(kv) = yaml_kv.captures(l) else {
+                    continue;
+                };
+                let value = kv[2].trim().trim_matches(|c| c == '\'' || c == '"');
+                match &kv[1] {
+                    "message" => failure.message = value.to_string(),
+                    "file" => failure.file = Some(value.to_string()),
+                    "line" => failure.line = value.parse().ok(),
+                    "at" => {
+                        // `at: file.js:12:3` or `at: Test.<anonymous> (file.js:12:3)`
+                        let loc = value
+                            .rsplit_once('(')
+                            .map(|(_, l)| l.trim_end_matches(')'))
+                            .unwrap_or(value);
+                        let mut parts = loc.split(':');
+                        if let (Some(f), Some(l)) = (parts.next(), parts.next()) {
+                            failure.file.get_or_insert_with(|| f.to_string());
+                            failure.line = failure.line.or_else(|| l.parse().ok());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        report.failures.push(failure);
+    }
+    report
+}
+
+// ── pytest ──────────────────────────────────────────────────────────────
+
+fn pytest_totals_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?m)^=+ .*\b(\d+) (?:failed|passed).* in [\d.]+s.*=+$").unwrap())
+}
+
+fn parse_pytest(output: &str) -> TestReport {
+    static SUMMARY_LINE: OnceLock<Regex> = OnceLock::new();
+    static COUNT: OnceLock<Regex> = OnceLock::new();
+    static SECTION: OnceLock<Regex> = OnceLock::new();
+    static LOCATION: OnceLock<Regex> = OnceLock::new();
+    let summary_line = SUMMARY_LINE
+        .get_or_init(|| Regex::new(r"(?m)^(?:FAILED|ERROR) (\S+)(?: - (.*))?$").unwrap());
+    let count = COUNT.get_or_init(|| Regex::new(r"(\d+) (passed|failed|errors?)\b").unwrap());
+    let section = SECTION.get_or_init(|| Regex::new(r"(?m)^_{3,} (.+?) _{3,}$").unwrap());
+    let location = LOCATION.get_or_init(|| Regex::new(r"(?m)^([^\s:]+\.py):(\d+): (.*)$").unwrap());
+
+    let mut report = TestReport::new(ReportFormat::Pytest);
+    if let Some(totals) = pytest_totals_re().find(output) {
+        for caps in count.captures_iter(totals.as_str()) {
+            let n: u64 = caps[1].parse().unwrap_or(0);
+            match &caps[2] {
+                "passed" => report.passed = n,
+                _ => report.failed += n,
+            }
+        }
+    }
+
+    // Traceback sections, keyed by header (`test_foo` or `TestCls.test_foo`)
+    let headers: Vec<_> = section.captures_iter(output).collect();
+    let sections: Vec<(String, &str)> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, caps)| {
+            let start = caps.get(0).unwrap().end();
+            let end = headers
+                .get(i + 1)
+                .map(|c| c.get(0).unwrap().start())
+                .unwrap_or(output.len());
+            (caps[1].to_string(), &output[start..end])
+        })
+        .collect();
+
+    for caps in summary_line.captures_iter(output) {
+        let node_id = caps[1].to_string();
+        let mut failure = TestFailure {
+            file: node_id.split("::").next().map(String::from),
+            message: caps
+                .get(2)
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default(),
+            name: node_id,
+            ..Default::default()
+        };
+        let func = failure.name.rsplit("::").next().unwrap_or("");
+        let func = func.split('[').next().unwrap_or(func);
+        let body = sections
+            .iter()
+            .find(|(header, _)| {
+                let header = header.split('[').next().unwrap_or(header);
+                header == func || header.ends_with(&format!(".{func}"))
+            })
+            .map(|(_, body)| *body);
+        if let Some(loc) = body.and_then(|b| location.captures_iter(b).last()) {
+            failure.file = Some(loc[1].to_string());
+            failure.line = loc[2].parse().ok();
+            if failure.message.is_empty() {
+                failure.message = loc[3].to_string();
+            }
+        }
+        report.failures.push(failure);
+    }
+    report.failed = report.failed.max(report.failures.len() as u64);
+    report
+}
+
+/// Condense failing command output for a prompt or note.
+///
+/// Returns the structured summary when the output parses as test results
+/// with at least one failure; otherwise the first `max_raw_lines` lines of
+/// the raw output.
+pub fn failure_context(output: &str, max_raw_lines: usize) -> String {
+    match parse(output) {
+        Some(report) if !report.failures.is_empty() => report.summary(SUMMARY_MAX_FAILURES),
+        _ => output
+            .lines()
+            .take(max_raw_lines)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+// ── Storage ─────────────────────────────────────────────────────────────
+
+/// Create the test_failures table if it doesn't exist.
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS test_failures (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            bead_id     TEXT NOT NULL,
+            source      TEXT NOT NULL,
+            test_name   TEXT NOT NULL,
+            file        TEXT,
+            line        INTEGER,
+            message     TEXT NOT NULL,
+            recorded_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_test_failures_bead ON test_failures(bead_id);
+        CREATE INDEX IF NOT EXISTS idx_test_failures_test ON test_failures(test_name);",
+    )
+}
+
+/// Record the failures of one report against a bead.
+///
+/// `source` identifies where the output came from, e.g. the gate name or
+/// `integration`.
+pub fn record(
+    conn: &Connection,
+    bead_id: &str,
+    source: &str,
+    failures: &[TestFailure],
+) -> Result<()> {
+    let mut stmt = conn.prepare(
+        "INSERT INTO test_failures (bead_id, source, test_name, file, line, message)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    for f in failures {
+        stmt.execute(params![bead_id, source, f.name, f.file, f.line, f.message])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO_OUTPUT: &str = "\
+running 3 tests
+test config::tests::parses ... ok
+test auth::tests::login ... FAILED
+test auth::tests::legacy ... FAILED
+
+failures:
+
+---- auth::tests::login stdout ----
+
+thread 'auth::tests::login' panicked at src/auth.rs:42:9:
+assertion `left == right` failed
+  left: 1
+ right: 2
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+---- auth::tests::legacy stdout ----
+thread 'auth::tests::legacy' panicked at 'token expired', src/auth.rs:77:5
+
+failures:
+    auth::tests::legacy
+    auth::tests::login
+
+test result: FAILED. 1 passed; 2 failed; 0 ignored; 0 measured; 0 filtered out
+";
+
+    #[test]
+    fn parses_cargo_test_output() {
+        let report = parse(CARGO_OUTPUT).unwrap();
+        assert_eq!(report.format, ReportFormat::Libtest);
+        assert_eq!((report.passed, report.failed), (1, 2));
+        let login = &report.failures[0];
+        assert_eq!(login.name, "auth::tests::login");
+        assert_eq!(login.location().as_deref(), Some("src/auth.rs:42"));
+        assert!(login
+            .message
+            .starts_with("assertion `left == right` failed"));
+        assert!(login.message.contains("right: 2"));
+        assert!(!login.message.contains("RUST_BACKTRACE"));
+        let legacy = &report.failures[1];
+        assert_eq!(legacy.line, Some(77));
+        assert_eq!(legacy.message, "token expired");
+    }
+
+    #[test]
+    fn parses_libtest_json() {
+        let output = r#"{ "type": "suite", "event": "started", "test_count": 2 }
+{ "type": "test", "event": "started", "name": "a::ok" }
+{ "type": "test", "name": "a::ok", "event": "ok" }
+{ "type": "test", "name": "a::bad", "event": "failed", "stdout": "thread 'a::bad' panicked at src/a.rs:3:5:\nboom\n" }
+{ "type": "suite", "event": "failed", "passed": 1, "failed": 1 }"#;
+        let report = parse(output).unwrap();
+        assert_eq!(report.format, ReportFormat::LibtestJson);
+        assert_eq!((report.passed, report.failed), (1, 1));
+        assert_eq!(
+            report.failures[0],
+            TestFailure {
+                name: "a::bad".to_string(),
+                file: Some("src/a.rs".to_string()),
+                line: Some(3),
+                message: "boom".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_junit_xml() {
+        let output = r#"<?xml version="1.0"?>
+<testsuites>
+  <testsuite name="web" tests="3" failures="1" errors="1">
+    <testcase classname="LoginForm" name="renders" time="0.01"/>
+    <testcase classname="LoginForm" name="submits" file="src/login.test.ts" line="18">
+      <failure message="expected 200 to equal 401">AssertionError: expected 200 to equal 401</failure>
+    </testcase>
+    <testcase classname="Api" name="fetches">
+      <error type="TypeError">TypeError: x is undefined
+    at src/api.ts:9:3</error>
+    </testcase>
+    <testcase classname="Api" name="later"><skipped/></testcase>
+  </testsuite>
+</testsuites>"#;
+        let report = parse(output).unwrap();
+        assert_eq!(report.format, ReportFormat::JUnit);
+        assert_eq!((report.passed, report.failed), (1, 2));
+        assert_eq!(report.failures[0].name, "LoginForm::submits");
+        assert_eq!(
+            report.failures[0].location().as_deref(),
+            Some("src/login.test.ts:18")
+        );
+        assert_eq!(report.failures[0].message, "expected 200 to equal 401");
+        assert_eq!(report.failures[1].name, "Api::fetches");
+        assert_eq!(
+            report.failures[1].location().as_deref(),
+            Some("src/api.ts:9")
+        );
+        assert!(report.failures[1].message.starts_with("TypeError"));
+    }
+
+    #[test]
+    fn parses_tap() {
+        let output = "TAP version 13
+1..4
+ok 1 - adds numbers
+not ok 2 - parses dates
+  ---
+  message: 'expected 2024 to equal 2025'
+  at: Test.<anonymous> (test/date.js:14:7)
+  ...
+ok 3 - skipped thing # SKIP not on ci
+not ok 4 - todo thing # TODO later
+";
+        let report = parse(output).unwrap();
+        assert_eq!(report.format, ReportFormat::Tap);
+        assert_eq!((report.passed, report.failed), (1, 1));
+        let f = &report.failures[0];
+        assert_eq!(f.name, "parses dates");
+        assert_eq!(f.message, "expected 2024 to equal 2025");
+        assert_eq!(f.location().as_deref(), Some("test/date.js:14"));
+    }
+
+    #[test]
+    fn parses_pytest() {
+        let output = "\
+============================= test session starts ==============================
+collected 3 items
+
+tests/test_math.py .F.                                                   [100%]
+
+=================================== FAILURES ===================================
+_______________________________ TestMath.test_div ______________________________
+
+self = <tests.test_math.TestMath object>
+
+    def test_div(self):
+>       assert div(1, 0) == 0
+E       ZeroDivisionError: division by zero
+
+tests/test_math.py:12: ZeroDivisionError
+=========================== short test summary info ============================
+FAILED tests/test_math.py::TestMath::test_div - ZeroDivisionError: division by zero
+========================= 1 failed, 2 passed in 0.05s ==========================
+";
+        let report = parse(output).unwrap();
+        assert_eq!(report.format, ReportFormat::Pytest);
+        assert_eq!((report.passed, report.failed), (2, 1));
+        let f = &report.failures[0];
+        assert_eq!(f.name, "tests/test_math.py::TestMath::test_div");
+        assert_eq!(f.location().as_deref(), Some("tests/test_math.py:12"));
+        assert_eq!(f.message, "ZeroDivisionError: division by zero");
+    }
+
+    #[test]
+    fn unrecognized_output_is_none() {
+        let compiler = "error[E0425]: cannot find value `x` in this scope\n --> src/main.rs:2:5";
+        assert!(parse(compiler).is_none());
+        assert_eq!(
+            failure_context(compiler, 1),
+            "error[E0425]: cannot find value `x` in this scope"
+        );
+    }
+
+    #[test]
+    fn summary_is_compact() {
+        let report = parse(CARGO_OUTPUT).unwrap();
+        let summary = report.summary(1);
+        assert_eq!(
+            summary,
+            "2 test(s) failed, 1 passed (libtest):\n\
+             - auth::tests::login (src/auth.rs:42): assertion `left == right` failed\n\
+             ... and 1 more"
+        );
+        assert_eq!(
+            failure_context(CARGO_OUTPUT, 5),
+            report.summary(SUMMARY_MAX_FAILURES)
+        );
+    }
+
+    #[test]
+    fn record_and_load_failures() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_table(&conn).unwrap();
+        let report = parse(CARGO_OUTPUT).unwrap();
+        record(&conn, "bead-1", "test", &report.failures).unwrap();
+        record(&conn, "bead-2", "test", &report.failures[..1]).unwrap();
+
+        let count = |bead: &str| -> i64 {
+            conn.query_row(
+                "SELECT COUNT(*) FROM test_failures WHERE bead_id = ?1",
+                params![bead],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        assert_eq!(count("bead-1"), 2);
+        assert_eq!(count("bead-2"), 1);
+
+        let (name, file, line): (String, Option<String>, Option<u32>) = conn
+            .query_row(
+                "SELECT test_name, file, line FROM test_failures WHERE bead_id = 'bead-2'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(name, "auth::tests::login");
+        assert_eq!(file.as_deref(), Some("src/auth.rs"));
+        assert_eq!(line, Some(42));
+    }
+}
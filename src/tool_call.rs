@@ -0,0 +1,310 @@
+//! Per-invocation tool call records.
+//!
+//! Ingestion stores every tool call an adapter can parse out of a session
+//! transcript — tool, normalized shell command, duration, outcome and
+//! output size — so `metrics tools` can show which tools and commands an
+//! agent leans on, which are slow, and which keep failing.
+
+use std::collections::{HashMap, HashSet};
+
+use rusqlite::{params, Connection, Result};
+use serde::Serialize;
+
+use crate::adapters::ToolCall;
+
+/// Create the tool_calls table if it doesn't exist.
+pub fn create_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS tool_calls (
+            id           INTEGER PRIMARY KEY AUTOINCREMENT,
+            session      INTEGER NOT NULL,
+            turn         INTEGER NOT NULL,
+            tool         TEXT NOT NULL,
+            command      TEXT,
+            duration_ms  INTEGER,
+            success      INTEGER,
+            output_bytes INTEGER
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_tool_calls_session ON tool_calls(session);",
+    )
+}
+
+/// Replace the recorded tool calls of a session, so re-ingesting a session
+/// doesn't double count.
+pub fn record(conn: &Connection, session: i64, calls: &[ToolCall]) -> Result<()> {
+    conn.execute(
+        "DELETE FROM tool_calls WHERE session = ?1",
+        params![session],
+    )?;
+    let mut stmt = conn.prepare(
+        "INSERT INTO tool_calls (session, turn, tool, command, duration_ms, success, output_bytes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )?;
+    for call in calls {
+        stmt.execute(params![
+            session,
+            call.turn,
+            call.tool,
+            call.command,
+            call.duration_ms.map(|d| d as i64),
+            call.success,
+            call.output_bytes.map(|b| b as i64),
+        ])?;
+    }
+    Ok(())
+}
+
+/// The recorded tool calls of one session, in order.
+pub fn for_session(conn: &Connection, session: i64) -> Result<Vec<ToolCall>> {
+    let mut stmt = conn.prepare(
+        "SELECT turn, tool, command, duration_ms, success, output_bytes
+         FROM tool_calls WHERE session = ?1 ORDER BY id",
+    )?;
+    let calls = stmt
+        .query_map(params![session], |row| {
+            Ok(ToolCall {
+                turn: row.get(0)?,
+                tool: row.get(1)?,
+                command: row.get(2)?,
+                duration_ms: row.get::<_, Option<i64>>(3)?.map(|d| d as u64),
+                success: row.get(4)?,
+                output_bytes: row.get::<_, Option<i64>>(5)?.map(|b| b as u64),
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(calls)
+}
+
+/// Aggregate usage of one tool or one shell command.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolStats {
+    /// Tool name, or the normalized command for command rows.
+    pub name: String,
+    pub calls: u64,
+    pub sessions: u64,
+    /// Average calls per bead, over the beads whose sessions used it.
+    pub per_bead: Option<f64>,
+    /// Average duration of the calls that recorded one.
+    pub avg_duration_ms: Option<f64>,
+    pub errors: u64,
+    /// Fraction of calls with a known outcome that failed.
+    pub error_rate: f64,
+    pub avg_output_bytes: Option<f64>,
+}
+
+/// Tool and command stats, each most used first.
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub tools: Vec<ToolStats>,
+    pub commands: Vec<ToolStats>,
+}
+
+#[derive(Default)]
+struct Acc {
+    calls: u64,
+    sessions: HashSet<i64>,
+    bead_calls: u64,
+    beads: HashSet<String>,
+    durations: Vec<i64>,
+    outcomes: u64,
+    errors: u64,
+    outputs: Vec<i64>,
+}
+
+impl Acc {
+    fn add(&mut self, row: &Row) {
+        self.calls += 1;
+        self.sessions.insert(row.session);
+        if let Some(bead) = &row.bead {
+            self.bead_calls += 1;
+            self.beads.insert(bead.clone());
+        }
+        self.durations.extend(row.duration_ms);
+        if let Some(ok) = row.success {
+            self.outcomes += 1;
+            if !ok {
+                self.errors += 1;
+            }
+        }
+        self.outputs.extend(row.output_bytes);
+    }
+
+    fn finish(self, name: String) -> ToolStats {
+        let mean =
+            |v: &[i64]| (!v.is_empty()).then(|| v.iter().sum::<i64>() as f64 / v.len() as f64);
+        ToolStats {
+            name,
+            calls: self.calls,
+            sessions: self.sessions.len() as u64,
+            per_bead: (!self.beads.is_empty())
+                .then(|| self.bead_calls as f64 / self.beads.len() as f64),
+            avg_duration_ms: mean(&self.durations),
+            errors: self.errors,
+            error_rate: if self.outcomes == 0 {
+                0.0
+            } else {
+                self.errors as f64 / self.outcomes as f64
+            },
+            avg_output_bytes: mean(&self.outputs),
+        }
+    }
+}
+
+struct Row {
+    session: i64,
+    bead: Option<String>,
+    tool: String,
+    command: Option<String>,
+    duration_ms: Option<i64>,
+    success: Option<bool>,
+    output_bytes: Option<i64>,
+}
+
+/// Tool and command stats over the `last` most recent sessions with tool
+/// calls (all when 0). Sessions are attributed to beads through their
+/// `session.bead_id` events.
+pub fn report(conn: &Connection, last: u32) -> Result<Report> {
+    let limit: i64 = if last == 0 { -1 } else { last as i64 };
+    let mut stmt = conn.prepare(
+        "SELECT t.session,
+                (SELECT value FROM events e
+                 WHERE e.session = t.session AND e.kind = 'session.bead_id' LIMIT 1),
+                t.tool, t.command, t.duration_ms, t.success, t.output_bytes
+         FROM tool_calls t
+         WHERE t.session IN (
+            SELECT DISTINCT session FROM tool_calls ORDER BY session DESC LIMIT ?1
+         )
+         ORDER BY t.id",
+    )?;
+    let rows = stmt.query_map(params![limit], |row| {
+        Ok(Row {
+            session: row.get(0)?,
+            bead: row.get(1)?,
+            tool: row.get(2)?,
+            command: row.get(3)?,
+            duration_ms: row.get(4)?,
+            success: row.get(5)?,
+            output_bytes: row.get(6)?,
+        })
+    })?;
+
+    let mut tools: HashMap<String, Acc> = HashMap::new();
+    let mut commands: HashMap<String, Acc> = HashMap::new();
+    for row in rows {
+        let row = row?;
+        tools.entry(row.tool.clone()).or_default().add(&row);
+        if let Some(command) = row.command.as_ref().filter(|c| !c.is_empty()) {
+            commands.entry(command.clone()).or_default().add(&row);
+        }
+    }
+
+    let finish = |map: HashMap<String, Acc>| {
+        let mut stats: Vec<ToolStats> = map.into_iter().map(|(k, acc)| acc.finish(k)).collect();
+        stats.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.name.cmp(&b.name)));
+        stats
+    };
+    Ok(Report {
+        tools: finish(tools),
+        commands: finish(commands),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> (tempfile::TempDir, Connection) {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = crate::db::open_or_create(&dir.path().join("blacksmith.db")).unwrap();
+        (dir, conn)
+    }
+
+    fn call(tool: &str, command: Option<&str>, duration_ms: Option<u64>, ok: bool) -> ToolCall {
+        ToolCall {
+            turn: 0,
+            tool: tool.to_string(),
+            command: command.map(str::to_string),
+            duration_ms,
+            success: Some(ok),
+            output_bytes: Some(10),
+        }
+    }
+
+    fn attribute(conn: &Connection, session: i64, bead: &str) {
+        crate::db::insert_event(conn, session, "session.bead_id", Some(bead), None).unwrap();
+    }
+
+    #[test]
+    fn record_replaces_session_calls() {
+        let (_dir, conn) = setup_db();
+        record(&conn, 1, &[call("Bash", None, None, true)]).unwrap();
+        record(
+            &conn,
+            1,
+            &[
+                call("Read", None, None, true),
+                call("Read", None, None, true),
+            ],
+        )
+        .unwrap();
+        let report = report(&conn, 0).unwrap();
+        assert_eq!(report.tools.len(), 1);
+        assert_eq!(report.tools[0].name, "Read");
+        assert_eq!(report.tools[0].calls, 2);
+    }
+
+    #[test]
+    fn report_aggregates_tools_and_commands() {
+        let (_dir, conn) = setup_db();
+        record(
+            &conn,
+            1,
+            &[
+                call("Bash", Some("cargo test"), Some(4000), false),
+                call("Bash", Some("cargo test"), Some(2000), true),
+                call("Read", None, None, true),
+            ],
+        )
+        .unwrap();
+        record(
+            &conn,
+            2,
+            &[
+                call("Bash", Some("cargo test"), None, true),
+                call("Bash", Some("git status"), Some(100), true),
+            ],
+        )
+        .unwrap();
+        attribute(&conn, 1, "bead-a");
+        attribute(&conn, 2, "bead-b");
+
+        let report = report(&conn, 0).unwrap();
+        assert_eq!(report.tools[0].name, "Bash");
+        assert_eq!(report.tools[0].calls, 4);
+        assert_eq!(report.tools[0].sessions, 2);
+        assert_eq!(report.tools[0].per_bead, Some(2.0));
+
+        let test = &report.commands[0];
+        assert_eq!(test.name, "cargo test");
+        assert_eq!(test.calls, 3);
+        assert_eq!(test.per_bead, Some(1.5));
+        assert_eq!(test.avg_duration_ms, Some(3000.0));
+        assert_eq!(test.errors, 1);
+        assert!((test.error_rate - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(test.avg_output_bytes, Some(10.0));
+        assert_eq!(report.commands[1].name, "git status");
+    }
+
+    #[test]
+    fn report_limits_to_recent_sessions() {
+        let (_dir, conn) = setup_db();
+        record(&conn, 1, &[call("Read", None, None, true)]).unwrap();
+        record(&conn, 2, &[call("Bash", None, None, false)]).unwrap();
+
+        let report = report(&conn, 1).unwrap();
+        assert_eq!(report.tools.len(), 1);
+        assert_eq!(report.tools[0].name, "Bash");
+        assert_eq!(report.tools[0].per_bead, None);
+    }
+}
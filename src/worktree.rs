@@ -47,6 +47,23 @@ pub fn worktree_path(worktrees_dir: &Path, worker_id: u32, bead_id: &str) -> Pat
     worktrees_dir.join(worktree_name(worker_id, bead_id))
 }
 
+/// Build the directory name for a reusable worker slot.
+///
+/// Format: `worker-{worker_id}`
+pub fn slot_name(worker_id: u32) -> String {
+    format!("worker-{worker_id}")
+}
+
+/// Build the full path of a reusable worker slot under the given directory.
+pub fn slot_path(worktrees_dir: &Path, worker_id: u32) -> PathBuf {
+    worktrees_dir.join(slot_name(worker_id))
+}
+
+/// Build the per-slot `CARGO_TARGET_DIR` under the target cache directory.
+pub fn slot_target_dir(target_cache_dir: &Path, worker_id: u32) -> PathBuf {
+    target_cache_dir.join(slot_name(worker_id))
+}
+
 /// Check whether a git branch exists in the repository at `repo_dir`.
 pub fn branch_exists(repo_dir: &Path, branch: &str) -> Result<bool, WorktreeError> {
     let output = Command::new("git")
@@ -77,6 +94,74 @@ pub fn create(
         return Err(WorktreeError::AlreadyExists(wt_path));
     }
 
+    add_detached(repo_dir, &wt_path, base_branch)?;
+    Ok(wt_path)
+}
+
+/// Create (or reuse) the persistent worktree for a worker slot.
+///
+/// A fresh slot is created with `git worktree add --detach`. An existing slot
+/// is reset to `base_branch` with [`reset_to`], which keeps ignored build
+/// outputs such as `target/` or `node_modules/` warm between beads.
+///
+/// Returns the slot path and whether an existing worktree was reused.
+pub fn acquire_slot(
+    repo_dir: &Path,
+    worktrees_dir: &Path,
+    worker_id: u32,
+    base_branch: &str,
+) -> Result<(PathBuf, bool), WorktreeError> {
+    let wt_path = slot_path(worktrees_dir, worker_id);
+
+    if wt_path.join(".git").exists() {
+        reset_to(&wt_path, base_branch)?;
+        return Ok((wt_path, true));
+    }
+
+    // A directory without git metadata is a leftover from an interrupted add
+    if wt_path.exists() {
+        std::fs::remove_dir_all(&wt_path)?;
+        prune(repo_dir)?;
+    }
+
+    add_detached(repo_dir, &wt_path, base_branch)?;
+    Ok((wt_path, false))
+}
+
+/// Reset an existing worktree to a detached `base_branch` checkout.
+///
+/// Runs: `git checkout --force --detach <base_branch>` then `git clean -fd`.
+/// Untracked files are removed but ignored files are kept, so build caches
+/// survive the reset.
+pub fn reset_to(wt_path: &Path, base_branch: &str) -> Result<(), WorktreeError> {
+    if !branch_exists(wt_path, base_branch)? {
+        return Err(WorktreeError::BranchNotFound(base_branch.to_string()));
+    }
+
+    let steps: [&[&str]; 2] = [
+        &["checkout", "--force", "--detach", base_branch],
+        &["clean", "-fd"],
+    ];
+    for args in steps {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(wt_path)
+            .output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(WorktreeError::GitError(format!(
+                "{} failed: {stderr}",
+                args[0]
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Run `git worktree add --detach <path> <base_branch>` after checking that
+/// the base branch exists.
+fn add_detached(repo_dir: &Path, wt_path: &Path, base_branch: &str) -> Result<(), WorktreeError> {
     // Verify base branch exists
     if !branch_exists(repo_dir, base_branch)? {
         return Err(WorktreeError::BranchNotFound(base_branch.to_string()));
@@ -101,7 +186,53 @@ pub fn create(
         )));
     }
 
-    Ok(wt_path)
+    Ok(())
+}
+
+/// Seed a worktree with build caches copied from a template checkout.
+///
+/// Each entry in `dirs` is a path relative to both checkouts. Entries that are
+/// missing in the template or already present in the worktree are skipped.
+/// Copies use copy-on-write clones where the filesystem supports them
+/// (`cp --reflink=auto` on Linux, `cp -c` on macOS).
+///
+/// Returns the entries that were copied.
+pub fn seed(
+    template_dir: &Path,
+    wt_path: &Path,
+    dirs: &[String],
+) -> Result<Vec<String>, WorktreeError> {
+    let mut seeded = Vec::new();
+
+    for dir in dirs {
+        let src = template_dir.join(dir);
+        let dst = wt_path.join(dir);
+        if !src.exists() || dst.exists() {
+            continue;
+        }
+        if let Some(parent) = dst.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut cmd = Command::new("cp");
+        if cfg!(target_os = "macos") {
+            cmd.arg("-cR");
+        } else {
+            cmd.args(["-a", "--reflink=auto"]);
+        }
+        let output = cmd.arg(&src).arg(&dst).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(WorktreeError::Io(std::io::Error::other(format!(
+                "failed to seed {dir}: {}",
+                stderr.trim()
+            ))));
+        }
+        seeded.push(dir.clone());
+    }
+
+    Ok(seeded)
 }
 
 /// Remove a git worktree by its path.
@@ -228,7 +359,6 @@ pub fn list(repo_dir: &Path) -> Result<Vec<PathBuf>, WorktreeError> {
 /// Prune stale worktree metadata for worktrees whose directories no longer exist.
 ///
 /// Runs: `git worktree prune`
-#[allow(dead_code)]
 pub fn prune(repo_dir: &Path) -> Result<(), WorktreeError> {
     let output = Command::new("git")
         .args(["worktree", "prune"])
@@ -522,6 +652,116 @@ mod tests {
         cleanup_temp_files(Path::new("/nonexistent/path/worker-99-beads-fake"));
     }
 
+    #[test]
+    fn test_slot_path() {
+        let dir = Path::new("/tmp/worktrees");
+        assert_eq!(slot_name(2), "worker-2");
+        assert_eq!(slot_path(dir, 2), PathBuf::from("/tmp/worktrees/worker-2"));
+        assert_eq!(
+            slot_target_dir(Path::new("/tmp/target-cache"), 2),
+            PathBuf::from("/tmp/target-cache/worker-2")
+        );
+    }
+
+    #[test]
+    fn test_acquire_slot_reuses_and_resets() {
+        let dir = init_test_repo();
+        let wt_dir = dir.path().join("worktrees");
+        std::fs::create_dir_all(&wt_dir).unwrap();
+
+        let (wt_path, reused) = acquire_slot(dir.path(), &wt_dir, 0, "main").unwrap();
+        assert!(!reused);
+        assert_eq!(wt_path, slot_path(&wt_dir, 0));
+
+        // Leave the slot dirty: a tracked edit and an untracked file
+        std::fs::write(wt_path.join("README.md"), "edited").unwrap();
+        std::fs::write(wt_path.join("scratch.txt"), "junk").unwrap();
+
+        let (again, reused) = acquire_slot(dir.path(), &wt_dir, 0, "main").unwrap();
+        assert!(reused);
+        assert_eq!(again, wt_path);
+        assert_eq!(
+            std::fs::read_to_string(wt_path.join("README.md")).unwrap(),
+            "test"
+        );
+        assert!(!wt_path.join("scratch.txt").exists());
+        assert_eq!(list(dir.path()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_reset_to_keeps_ignored_files() {
+        let dir = init_test_repo();
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        Command::new("git")
+            .args(["add", ".gitignore"])
+            .current_dir(dir.path())
+            .status()
+            .unwrap();
+        Command::new("git")
+            .args(["commit", "-m", "ignore target"])
+            .current_dir(dir.path())
+            .stdout(std::process::Stdio::null())
+            .status()
+            .unwrap();
+
+        let wt_dir = dir.path().join("worktrees");
+        std::fs::create_dir_all(&wt_dir).unwrap();
+        let (wt_path, _) = acquire_slot(dir.path(), &wt_dir, 1, "main").unwrap();
+        std::fs::create_dir_all(wt_path.join("target")).unwrap();
+        std::fs::write(wt_path.join("target/cache"), "warm").unwrap();
+        std::fs::write(wt_path.join("scratch.txt"), "junk").unwrap();
+
+        reset_to(&wt_path, "main").unwrap();
+        assert!(wt_path.join("target/cache").exists());
+        assert!(!wt_path.join("scratch.txt").exists());
+    }
+
+    #[test]
+    fn test_acquire_slot_replaces_stale_dir() {
+        let dir = init_test_repo();
+        let wt_dir = dir.path().join("worktrees");
+        let stale = slot_path(&wt_dir, 0);
+        std::fs::create_dir_all(&stale).unwrap();
+        std::fs::write(stale.join("leftover"), "x").unwrap();
+
+        let (wt_path, reused) = acquire_slot(dir.path(), &wt_dir, 0, "main").unwrap();
+        assert!(!reused);
+        assert!(wt_path.join("README.md").exists());
+        assert!(!wt_path.join("leftover").exists());
+    }
+
+    #[test]
+    fn test_reset_to_branch_not_found() {
+        let dir = init_test_repo();
+        let result = reset_to(dir.path(), "nonexistent");
+        assert!(matches!(result, Err(WorktreeError::BranchNotFound(_))));
+    }
+
+    #[test]
+    fn test_seed_copies_missing_dirs() {
+        let template = TempDir::new().unwrap();
+        let wt = TempDir::new().unwrap();
+        std::fs::create_dir_all(template.path().join("target/debug")).unwrap();
+        std::fs::write(template.path().join("target/debug/app"), "bin").unwrap();
+        std::fs::create_dir_all(wt.path().join("node_modules")).unwrap();
+        std::fs::create_dir_all(template.path().join("node_modules/left-pad")).unwrap();
+
+        let dirs = vec![
+            "target".to_string(),
+            "node_modules".to_string(),
+            "missing".to_string(),
+        ];
+        let seeded = seed(template.path(), wt.path(), &dirs).unwrap();
+
+        assert_eq!(seeded, vec!["target"]);
+        assert_eq!(
+            std::fs::read_to_string(wt.path().join("target/debug/app")).unwrap(),
+            "bin"
+        );
+        // Existing directories are left alone
+        assert!(!wt.path().join("node_modules/left-pad").exists());
+    }
+
     #[test]
     fn test_worktree_error_display() {
         let e = WorktreeError::AlreadyExists(PathBuf::from("/tmp/wt"));